@@ -24,6 +24,29 @@ where
     adj
 }
 
+/// [`build_row_adjacency`] with rows split across scoped `std` threads (feature
+/// `parallel-std`). The result is identical to the sequential build.
+#[cfg(feature = "parallel-std")]
+pub fn build_row_adjacency_threaded<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+) -> Vec<Vec<usize>>
+where
+    T: nalgebra::Scalar + PartialEq + Default + Sync,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C> + Sync,
+{
+    let zero = T::default();
+    let rows: Vec<usize> = (0..mat.nrows()).collect();
+    let row = |&i: &usize| -> Result<Vec<usize>, std::convert::Infallible> {
+        Ok((0..mat.ncols()).filter(|&j| mat[(i, j)] != zero).collect())
+    };
+    match crate::scoped::scoped_map(&rows, row) {
+        Ok(adj) => adj,
+        Err(never) => match never {},
+    }
+}
+
 /// Row dependency graph used for BTF:
 /// edge i -> k if row i has a nonzero in some column matched to row k.
 pub fn build_row_dependency_graph(
@@ -35,10 +58,10 @@ pub fn build_row_dependency_graph(
 
     for (i, cols) in row_adj.iter().enumerate() {
         for &j in cols {
-            if let Some(k) = col_to_row.get(j).copied().flatten() {
-                if k != i {
-                    g[i].push(k);
-                }
+            if let Some(k) = col_to_row.get(j).copied().flatten()
+                && k != i
+            {
+                g[i].push(k);
             }
         }
         g[i].sort_unstable();
@@ -47,3 +70,64 @@ pub fn build_row_dependency_graph(
 
     g
 }
+
+/// Column dependency graph, the column-oriented mirror of [`build_row_dependency_graph`]:
+/// edge j -> l if column j has a nonzero in the row matched to column l.
+///
+/// `col_adj[j]` lists the rows of column j. Under the matching this is the row graph with
+/// every edge reversed: j -> l iff row(l) -> row(j).
+pub fn build_col_dependency_graph(
+    col_adj: &[Vec<usize>],
+    row_to_col: &[Option<usize>],
+) -> Vec<Vec<usize>> {
+    let ncols = col_adj.len();
+    let mut g = vec![Vec::new(); ncols];
+
+    for (j, rows) in col_adj.iter().enumerate() {
+        for &i in rows {
+            if let Some(l) = row_to_col.get(i).copied().flatten()
+                && l != j
+            {
+                g[j].push(l);
+            }
+        }
+        g[j].sort_unstable();
+        g[j].dedup();
+    }
+
+    g
+}
+
+/// Stable 64-bit fingerprint of the nonzero pattern of `mat` (FNV-1a over the dimensions and
+/// the sorted nonzero positions). Values do not matter, only which entries are nonzero; the
+/// result is identical across runs and platforms.
+pub fn pattern_fingerprint<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> u64
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut h = OFFSET;
+    let mut feed = |x: usize| {
+        for byte in (x as u64).to_le_bytes() {
+            h ^= u64::from(byte);
+            h = h.wrapping_mul(PRIME);
+        }
+    };
+
+    feed(mat.nrows());
+    feed(mat.ncols());
+    for (i, cols) in build_row_adjacency(mat).iter().enumerate() {
+        // Row separators keep e.g. [[0], []] and [[], [0]] distinct.
+        feed(i);
+        feed(cols.len());
+        for &j in cols {
+            feed(j);
+        }
+    }
+    h
+}
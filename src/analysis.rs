@@ -0,0 +1,96 @@
+use nalgebra::{Dyn, PermutationSequence};
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::BtfResult;
+use crate::matching::Matching;
+use crate::permutation::permutation_sequence_from_order;
+use crate::scc::Condensation;
+use crate::{
+    BtfOptions, UpperBtfStructure, condense_matching, matching_with_options, order_condensation,
+};
+
+/// The expensive, ordering-independent part of the analysis, kept for recomputing the
+/// ordering under different options: the pattern, the maximum matching, the row dependency
+/// graph and its SCCs.
+///
+/// [`Self::structure`] only reruns the ordering stages, so trying several block orders,
+/// in-block orders or unmatched placements costs one matching and one SCC decomposition in
+/// total.
+#[derive(Debug, Clone)]
+pub struct BtfAnalysis {
+    row_adj: Vec<Vec<usize>>,
+    ncols: usize,
+    matching: Matching,
+    row_graph: Vec<Vec<usize>>,
+    condensation: Condensation,
+}
+
+impl BtfAnalysis {
+    /// Matching and SCCs of `mat` under the matching options of `options` (`matching`,
+    /// `forbidden` and `priorities`); its ordering options are not used here.
+    pub fn new<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>, options: &BtfOptions) -> Self
+    where
+        T: nalgebra::Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let row_adj = build_row_adjacency(mat);
+        let ncols = mat.ncols();
+        let matching = matching_with_options(&row_adj, ncols, options);
+        let (row_graph, condensation) = condense_matching(&row_adj, &matching);
+        Self {
+            row_adj,
+            ncols,
+            matching,
+            row_graph,
+            condensation,
+        }
+    }
+
+    /// The maximum matching the analysis is based on.
+    pub fn matching(&self) -> &Matching {
+        &self.matching
+    }
+
+    /// SCCs of the row dependency graph under [`Self::matching`] (each sorted), as from
+    /// [`condensation`](crate::condensation).
+    pub fn condensation(&self) -> &Condensation {
+        &self.condensation
+    }
+
+    /// The structure under the ordering options of `options` (`in_block_order`,
+    /// `block_order`, `unmatched`, `pin_first`, `pin_last`, `min_block_size` and
+    /// `max_block_size`), reusing the matching and SCCs. Equal to
+    /// [`try_upper_block_triangular_structure_with_options`](crate::try_upper_block_triangular_structure_with_options)
+    /// with the matching options given to [`Self::new`]; the matching options of `options`
+    /// are ignored.
+    pub fn structure(&self, options: &BtfOptions) -> BtfResult<UpperBtfStructure> {
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let structure = order_condensation(
+            &self.row_adj,
+            self.ncols,
+            options,
+            &self.matching,
+            &self.row_graph,
+            &self.condensation,
+        )?;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_analysis(started.elapsed(), &structure);
+        Ok(structure)
+    }
+
+    /// Row and column permutations of [`Self::structure`], as from
+    /// [`try_upper_triangular_permutations`](crate::try_upper_triangular_permutations).
+    pub fn permutations(
+        &self,
+        options: &BtfOptions,
+    ) -> BtfResult<(PermutationSequence<Dyn>, PermutationSequence<Dyn>)> {
+        let structure = self.structure(options)?;
+        Ok((
+            permutation_sequence_from_order(&structure.row_order),
+            permutation_sequence_from_order(&structure.col_order),
+        ))
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::matching::{MatchingBudget, hopcroft_karp_budgeted, hopcroft_karp_interruptible};
+use crate::{
+    ACYCLIC_CONDENSATION, BtfOptions, UpperBtfStructure, matching_adjacency,
+    structure_from_matching,
+};
+
+/// A block triangular structure built from a possibly sub-maximal matching.
+#[derive(Debug, Clone)]
+pub struct ApproximateStructure {
+    /// Still a valid block triangular ordering of the pattern; when `exact` is false its
+    /// `matching_size` is only a lower bound on the structural rank and blocks may be split
+    /// differently than in the exact analysis.
+    pub structure: UpperBtfStructure,
+    /// Whether the matching is maximum, i.e. `structure` equals the exact analysis.
+    pub exact: bool,
+    /// Matching phases run before the budget stopped the search (or it completed).
+    pub phases: usize,
+}
+
+impl ApproximateStructure {
+    /// Whether the result may differ from the exact analysis.
+    pub fn is_approximate(&self) -> bool {
+        !self.exact
+    }
+}
+
+/// Anytime analysis of `mat`: Hopcroft–Karp limited by `budget`, then the usual ordering steps
+/// on whatever matching was reached.
+///
+/// Any matching induces a block triangular ordering, so the result is always usable for a
+/// quick look; it is just conservative about the rank (rows left unmatched by the truncated
+/// search form their own blocks). After `k` phases the matching has at least `k / (k + 1)` of
+/// the maximum size. The budget bounds only the matching, the one superlinear step; the
+/// remaining steps are linear in the number of nonzeros.
+///
+/// Panics if an internal invariant is violated (see
+/// [`crate::try_upper_block_triangular_structure`]).
+pub fn approximate_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    budget: &MatchingBudget,
+) -> ApproximateStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    try_approximate_block_triangular_structure_with_options(mat, budget, &BtfOptions::default())
+        .expect(ACYCLIC_CONDENSATION)
+}
+
+/// [`approximate_block_triangular_structure`] with explicit [`BtfOptions`].
+///
+/// The matching is always Hopcroft–Karp (the only algorithm with phase guarantees), so
+/// `options.matching` and `options.priorities` are ignored; `options.forbidden` and the
+/// ordering options apply as usual.
+pub fn try_approximate_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    budget: &MatchingBudget,
+    options: &BtfOptions,
+) -> BtfResult<ApproximateStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let row_adj = build_row_adjacency(mat);
+    let ncols = mat.ncols();
+    #[cfg(feature = "metrics")]
+    let matching_started = std::time::Instant::now();
+    let budgeted = hopcroft_karp_budgeted(&matching_adjacency(&row_adj, options), ncols, budget);
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_budgeted_matching(matching_started.elapsed(), &budgeted);
+    let structure = structure_from_matching(&row_adj, ncols, options, &budgeted.matching)?;
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_analysis(started.elapsed(), &structure);
+    Ok(ApproximateStructure {
+        structure,
+        exact: budgeted.exact,
+        phases: budgeted.phases,
+    })
+}
+
+/// Exact analysis of `mat` that gives up with [`BtfError::Cancelled`] once `cancel` is set,
+/// e.g. from another thread.
+///
+/// The flag is polled between matching phases and periodically within a phase, so
+/// cancellation takes effect within a bounded amount of matching work; the ordering steps
+/// after the matching are linear and always run to completion. As in
+/// [`try_approximate_block_triangular_structure_with_options`], the matching is always
+/// Hopcroft–Karp.
+pub fn cancellable_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &BtfOptions,
+    cancel: &AtomicBool,
+) -> BtfResult<UpperBtfStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let row_adj = build_row_adjacency(mat);
+    let ncols = mat.ncols();
+    #[cfg(feature = "metrics")]
+    let matching_started = std::time::Instant::now();
+    let budgeted = hopcroft_karp_interruptible(
+        &matching_adjacency(&row_adj, options),
+        ncols,
+        &MatchingBudget::default(),
+        || cancel.load(Ordering::Relaxed),
+    );
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_budgeted_matching(matching_started.elapsed(), &budgeted);
+    if !budgeted.exact {
+        return Err(BtfError::Cancelled);
+    }
+    let structure = structure_from_matching(&row_adj, ncols, options, &budgeted.matching)?;
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_analysis(started.elapsed(), &structure);
+    Ok(structure)
+}
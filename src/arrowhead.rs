@@ -0,0 +1,164 @@
+use std::cmp::Reverse;
+
+use crate::adjacency::build_row_adjacency;
+use crate::components::{BipartiteComponent, bipartite_components};
+
+/// Block-diagonal-plus-border ("arrowhead", doubly bordered block diagonal) form.
+///
+/// In `P A Q` the `blocks` sit on the diagonal with no entries between them; the border rows
+/// come last and the border columns are rightmost, so every coupling between blocks runs
+/// through the border. Blocks may be rectangular (a block is a connected component of the
+/// pattern without the border).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrowheadStructure {
+    /// Rows of each block in block order, then `border_rows`.
+    pub row_order: Vec<usize>,
+    /// Columns of each block in block order, then `border_cols`.
+    pub col_order: Vec<usize>,
+    /// The diagonal blocks, each with sorted original rows and columns.
+    pub blocks: Vec<BipartiteComponent>,
+    /// Border rows, sorted.
+    pub border_rows: Vec<usize>,
+    /// Border columns, sorted.
+    pub border_cols: Vec<usize>,
+}
+
+impl ArrowheadStructure {
+    /// Number of diagonal blocks.
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Number of border rows plus border columns.
+    pub fn border_size(&self) -> usize {
+        self.border_rows.len() + self.border_cols.len()
+    }
+
+    /// Whether the pattern is block diagonal without any border.
+    pub fn is_block_diagonal(&self) -> bool {
+        self.border_size() == 0
+    }
+}
+
+/// Find a small border after whose removal every connected component of the pattern has at
+/// most `max_block_size` rows and at most `max_block_size` columns.
+///
+/// Greedy heuristic: while some component is too large, the row or column with the most
+/// entries left in the largest such component moves to the border (ties: rows first, then
+/// smallest index). Border rows and columns are then returned to the interior, last picked
+/// first, wherever that keeps every component within the limit. A pattern that is already
+/// block diagonal within the limit gets an empty border; `max_block_size` 0 moves every row
+/// and column to the border.
+pub fn arrowhead_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    max_block_size: usize,
+) -> ArrowheadStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (nrows, ncols) = (row_adj.len(), mat.ncols());
+    let mut col_adj = vec![Vec::new(); ncols];
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            col_adj[j].push(i);
+        }
+    }
+
+    // Border nodes: rows `0..nrows`, columns `nrows..nrows + ncols`.
+    let mut border = vec![false; nrows + ncols];
+    let too_large = |c: &BipartiteComponent| c.rows.len().max(c.cols.len()) > max_block_size;
+    let mut picks = Vec::new();
+    loop {
+        let comps = interior_components(&row_adj, ncols, &border);
+        let Some(largest) = comps
+            .iter()
+            .filter(|c| too_large(c))
+            .max_by_key(|c| c.rows.len() + c.cols.len())
+        else {
+            break;
+        };
+        let row_degree = |i: usize| row_adj[i].iter().filter(|&&j| !border[nrows + j]).count();
+        let col_degree = |j: usize| col_adj[j].iter().filter(|&&i| !border[i]).count();
+        let pick = largest
+            .rows
+            .iter()
+            .map(|&i| (row_degree(i), Reverse(i)))
+            .chain(
+                largest
+                    .cols
+                    .iter()
+                    .map(|&j| (col_degree(j), Reverse(nrows + j))),
+            )
+            .max()
+            .map(|(_, Reverse(node))| node)
+            .expect("an oversized component is non-empty");
+        border[pick] = true;
+        picks.push(pick);
+    }
+
+    for &node in picks.iter().rev() {
+        border[node] = false;
+        if interior_components(&row_adj, ncols, &border)
+            .iter()
+            .any(too_large)
+        {
+            border[node] = true;
+        }
+    }
+
+    let blocks = interior_components(&row_adj, ncols, &border);
+    let border_rows: Vec<usize> = (0..nrows).filter(|&i| border[i]).collect();
+    let border_cols: Vec<usize> = (0..ncols).filter(|&j| border[nrows + j]).collect();
+    let row_order = blocks
+        .iter()
+        .flat_map(|b| b.rows.iter().copied())
+        .chain(border_rows.iter().copied())
+        .collect();
+    let col_order = blocks
+        .iter()
+        .flat_map(|b| b.cols.iter().copied())
+        .chain(border_cols.iter().copied())
+        .collect();
+    ArrowheadStructure {
+        row_order,
+        col_order,
+        blocks,
+        border_rows,
+        border_cols,
+    }
+}
+
+/// Connected components of the pattern without the border rows and columns.
+fn interior_components(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    border: &[bool],
+) -> Vec<BipartiteComponent> {
+    let nrows = row_adj.len();
+    let interior: Vec<Vec<usize>> = row_adj
+        .iter()
+        .enumerate()
+        .map(|(i, cols)| {
+            if border[i] {
+                Vec::new()
+            } else {
+                cols.iter()
+                    .copied()
+                    .filter(|&j| !border[nrows + j])
+                    .collect()
+            }
+        })
+        .collect();
+    bipartite_components(&interior, ncols)
+        .into_iter()
+        .filter_map(|mut c| {
+            c.rows.retain(|&i| !border[i]);
+            c.cols.retain(|&j| !border[nrows + j]);
+            (!c.rows.is_empty() || !c.cols.is_empty()).then_some(c)
+        })
+        .collect()
+}
@@ -0,0 +1,120 @@
+use crate::adjacency::build_row_adjacency;
+use crate::matching::{Matching, hopcroft_karp};
+
+/// Entries whose insertion restores full structural rank, plus the freedom in choosing them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Augmentation {
+    /// A minimal set of new `(row, col)` entries; inserting all of them raises the structural
+    /// rank to `min(nrows, ncols)`. Sorted by row.
+    pub entries: Vec<(usize, usize)>,
+    /// Rows left unmatched by some maximum matching (the overdetermined equations), sorted.
+    pub candidate_rows: Vec<usize>,
+    /// Columns left unmatched by some maximum matching (the underdetermined variables), sorted.
+    pub candidate_cols: Vec<usize>,
+}
+
+/// Suggest a minimal set of new entries making `mat` structurally full rank.
+///
+/// Each new entry can raise the structural rank by at most one, so the `min(nrows, ncols) - r`
+/// suggested entries are as few as possible. They pair the rows and columns a maximum matching
+/// leaves free; more generally, any entry `(i, j)` with `i` in `candidate_rows` and `j` in
+/// `candidate_cols` raises the rank by one, which gives modelers a choice of which equation
+/// should gain a dependency on which variable. Both candidate lists are empty for full-rank
+/// patterns.
+pub fn minimal_augmentation<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> Augmentation
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let ncols = mat.ncols();
+    let matching = hopcroft_karp(&row_adj, ncols);
+
+    let free_rows: Vec<usize> = (0..row_adj.len())
+        .filter(|&i| matching.row_to_col[i].is_none())
+        .collect();
+    let free_cols: Vec<usize> = (0..ncols)
+        .filter(|&j| matching.col_to_row[j].is_none())
+        .collect();
+    // A full-rank pattern still has free rows (or columns) when it is rectangular; only report
+    // candidates when new entries can actually raise the rank.
+    if free_rows.is_empty() || free_cols.is_empty() {
+        return Augmentation::default();
+    }
+
+    let entries = free_rows
+        .iter()
+        .zip(&free_cols)
+        .map(|(&i, &j)| (i, j))
+        .collect();
+
+    Augmentation {
+        entries,
+        candidate_rows: alternating_rows(&row_adj, &matching),
+        candidate_cols: alternating_cols(&row_adj, ncols, &matching),
+    }
+}
+
+/// Rows reachable from the free rows of the maximum `matching` by alternating paths
+/// (row -> column -> the row matched to it), i.e. rows left unmatched by some maximum
+/// matching. Sorted.
+pub(crate) fn alternating_rows(row_adj: &[Vec<usize>], matching: &Matching) -> Vec<usize> {
+    let free = (0..row_adj.len()).filter(|&i| matching.row_to_col[i].is_none());
+    alternating_rows_from(row_adj, matching, free)
+}
+
+/// Rows reachable from `roots` (free rows of the maximum `matching`) by alternating paths,
+/// roots included. Sorted.
+pub(crate) fn alternating_rows_from(
+    row_adj: &[Vec<usize>],
+    matching: &Matching,
+    roots: impl IntoIterator<Item = usize>,
+) -> Vec<usize> {
+    let mut seen = vec![false; row_adj.len()];
+    let mut stack: Vec<usize> = roots.into_iter().collect();
+    for &i in &stack {
+        seen[i] = true;
+    }
+    while let Some(i) = stack.pop() {
+        // Every column reached is matched, otherwise the matching would not be maximum.
+        for &j in &row_adj[i] {
+            if let Some(k) = matching.col_to_row[j]
+                && !seen[k]
+            {
+                seen[k] = true;
+                stack.push(k);
+            }
+        }
+    }
+    (0..row_adj.len()).filter(|&i| seen[i]).collect()
+}
+
+/// Columns reachable from the free columns of the maximum `matching` by alternating paths
+/// over the transposed pattern, i.e. columns left unmatched by some maximum matching. Sorted.
+pub(crate) fn alternating_cols(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    matching: &Matching,
+) -> Vec<usize> {
+    let mut col_adj = vec![Vec::new(); ncols];
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            col_adj[j].push(i);
+        }
+    }
+    let mut seen: Vec<bool> = matching.col_to_row.iter().map(Option::is_none).collect();
+    let mut stack: Vec<usize> = (0..ncols).filter(|&j| seen[j]).collect();
+    while let Some(j) = stack.pop() {
+        for &i in &col_adj[j] {
+            if let Some(k) = matching.row_to_col[i]
+                && !seen[k]
+            {
+                seen[k] = true;
+                stack.push(k);
+            }
+        }
+    }
+    (0..ncols).filter(|&j| seen[j]).collect()
+}
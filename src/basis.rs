@@ -0,0 +1,144 @@
+use std::fmt;
+
+use crate::adjacency::build_row_adjacency;
+use crate::matching::{augment, hopcroft_karp};
+
+/// Errors from [`repair_basis`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BasisError {
+    /// The basis does not have one column per row.
+    NotSquare { rows: usize, basis: usize },
+    /// A basis or pool column is not a column of the matrix.
+    ColumnOutOfRange { col: usize, ncols: usize },
+    /// A column appears more than once in the basis.
+    DuplicateColumn(usize),
+    /// Even using the whole pool, at most `rank` rows can be matched.
+    Unrepairable { rank: usize },
+}
+
+impl fmt::Display for BasisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSquare { rows, basis } => {
+                write!(f, "basis has {basis} columns for {rows} rows")
+            }
+            Self::ColumnOutOfRange { col, ncols } => {
+                write!(f, "column {col} out of range for {ncols} columns")
+            }
+            Self::DuplicateColumn(col) => write!(f, "column {col} appears twice in the basis"),
+            Self::Unrepairable { rank } => write!(
+                f,
+                "basis and pool have structural rank {rank}, too small to repair the basis"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BasisError {}
+
+/// A structurally nonsingular basis obtained from a candidate basis by column swaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasisRepair {
+    /// The repaired basis: the candidate with each leaving column replaced in place.
+    pub basis: Vec<usize>,
+    /// `(leaving, entering)` column pairs, by increasing leaving position in the candidate.
+    pub swaps: Vec<(usize, usize)>,
+}
+
+/// Repair a structurally singular square column subset `basis` of `mat` by swapping in columns
+/// from `pool`, using as few swaps as possible.
+///
+/// The number of swaps is `n - r`, where `r` is the structural rank of the candidate columns;
+/// no fewer can work, since at most `r` candidate columns fit in any nonsingular basis. The
+/// candidate's maximum matching is extended by augmenting paths through the pool, which keeps
+/// every matched candidate column and ends each path at a new pool column. Pool columns already
+/// in the basis are ignored; a nonsingular candidate comes back unchanged with no swaps.
+pub fn repair_basis<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    basis: &[usize],
+    pool: &[usize],
+) -> Result<BasisRepair, BasisError>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (n, ncols) = (mat.nrows(), mat.ncols());
+    if basis.len() != n {
+        return Err(BasisError::NotSquare {
+            rows: n,
+            basis: basis.len(),
+        });
+    }
+
+    // Local columns: the candidate in position order, then the new pool columns.
+    let mut local_of = vec![None; ncols];
+    let mut cols = Vec::with_capacity(n + pool.len());
+    for &j in basis {
+        if j >= ncols {
+            return Err(BasisError::ColumnOutOfRange { col: j, ncols });
+        }
+        if local_of[j].is_some() {
+            return Err(BasisError::DuplicateColumn(j));
+        }
+        local_of[j] = Some(cols.len());
+        cols.push(j);
+    }
+    for &j in pool {
+        if j >= ncols {
+            return Err(BasisError::ColumnOutOfRange { col: j, ncols });
+        }
+        if local_of[j].is_none() {
+            local_of[j] = Some(cols.len());
+            cols.push(j);
+        }
+    }
+
+    let local_adj: Vec<Vec<usize>> = build_row_adjacency(mat)
+        .iter()
+        .map(|row| row.iter().filter_map(|&j| local_of[j]).collect())
+        .collect();
+    let in_basis: Vec<Vec<usize>> = local_adj
+        .iter()
+        .map(|row| row.iter().copied().filter(|&l| l < n).collect())
+        .collect();
+
+    let start = hopcroft_karp(&in_basis, n);
+    let mut row_to_col = start.row_to_col;
+    let mut col_to_row = start.col_to_row;
+    col_to_row.resize(cols.len(), None);
+
+    let mut entering = Vec::with_capacity(n - start.size);
+    let mut rank = start.size;
+    for root in 0..n {
+        if row_to_col[root].is_none()
+            && let Some(end) = augment(root, &local_adj, &mut row_to_col, &mut col_to_row)
+        {
+            debug_assert!(
+                end >= n,
+                "augmenting path ended inside a maximum basis matching"
+            );
+            entering.push(cols[end]);
+            rank += 1;
+        }
+    }
+    if rank < n {
+        return Err(BasisError::Unrepairable { rank });
+    }
+
+    let mut repaired = basis.to_vec();
+    let leaving = (0..n).filter(|&l| col_to_row[l].is_none());
+    let swaps: Vec<(usize, usize)> = leaving
+        .zip(entering)
+        .map(|(l, j)| {
+            repaired[l] = j;
+            (basis[l], j)
+        })
+        .collect();
+
+    Ok(BasisRepair {
+        basis: repaired,
+        swaps,
+    })
+}
@@ -0,0 +1,269 @@
+use nalgebra::{Dyn, PermutationSequence};
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::orientation::Orientation;
+use crate::permutation::permutation_sequence_from_order;
+use crate::rectangular::RectangularStructure;
+use crate::scc::{scc_id_map, tarjan_scc};
+use crate::tearing::{TearingHeuristic, feedback_vertex_set};
+use crate::{BtfOptions, try_structure_from_row_adjacency};
+
+/// Bordered block triangular form: a block upper triangular interior plus a trailing border of
+/// caller-chosen columns and the rows they leave unmatched.
+///
+/// In `P A Q` the interior occupies the leading `interior_size()` rows and columns; below it
+/// are the border rows (residual equations), to its right the free interior columns and then
+/// the border columns (e.g. tearing variables). Entries in border columns create no
+/// dependencies, so once the border variables are guessed the interior is solved block by
+/// block and the border rows yield the residuals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorderedBtfStructure {
+    /// Interior rows in block order, then `border_rows`.
+    pub row_order: Vec<usize>,
+    /// Interior columns aligned with their rows, then `free_cols`, then `border_cols`.
+    pub col_order: Vec<usize>,
+    /// Sizes of the interior diagonal blocks, in solve order.
+    pub block_sizes: Vec<usize>,
+    /// Interior block DAG (forward edges, sorted).
+    pub block_dag: Vec<Vec<usize>>,
+    /// Rows left unmatched once the border columns are excluded, sorted.
+    pub border_rows: Vec<usize>,
+    /// The declared border columns, sorted and deduplicated.
+    pub border_cols: Vec<usize>,
+    /// Non-border columns left unmatched (a structurally deficient interior), sorted.
+    pub free_cols: Vec<usize>,
+}
+
+impl BorderedBtfStructure {
+    /// Number of interior rows (and columns).
+    pub fn interior_size(&self) -> usize {
+        self.block_sizes.iter().sum()
+    }
+
+    /// Row order (new position -> original row) in `orientation`.
+    pub fn oriented_row_order(&self, orientation: Orientation) -> Vec<usize> {
+        oriented(&self.row_order, self.interior_size(), orientation)
+    }
+
+    /// Column order (new position -> original column) in `orientation`; `free_cols` and
+    /// `border_cols` stay last.
+    pub fn oriented_col_order(&self, orientation: Orientation) -> Vec<usize> {
+        oriented(&self.col_order, self.interior_size(), orientation)
+    }
+
+    /// Interior block sizes along the permuted diagonal in `orientation`.
+    pub fn oriented_block_sizes(&self, orientation: Orientation) -> Vec<usize> {
+        oriented(&self.block_sizes, self.block_sizes.len(), orientation)
+    }
+
+    /// Row and column permutations `(P, Q)` such that `P A Q` is bordered block triangular
+    /// in `orientation`.
+    pub fn permutations(
+        &self,
+        orientation: Orientation,
+    ) -> (PermutationSequence<Dyn>, PermutationSequence<Dyn>) {
+        (
+            permutation_sequence_from_order(&self.oriented_row_order(orientation)),
+            permutation_sequence_from_order(&self.oriented_col_order(orientation)),
+        )
+    }
+}
+
+/// `order` with its leading `interior` entries reversed for [`Orientation::Lower`].
+fn oriented(order: &[usize], interior: usize, orientation: Orientation) -> Vec<usize> {
+    let mut order = order.to_vec();
+    if orientation == Orientation::Lower {
+        order[..interior].reverse();
+    }
+    order
+}
+
+/// Block triangularize `mat` with the columns in `border_cols` excluded from the matching and
+/// pushed to a trailing border.
+///
+/// Fails with [`BtfError::IndexOutOfRange`] if a border column is outside the matrix.
+pub fn bordered_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    border_cols: &[usize],
+) -> BtfResult<BorderedBtfStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    bordered_block_triangular_structure_with_options(mat, border_cols, &BtfOptions::default())
+}
+
+/// [`bordered_block_triangular_structure`] with explicit [`BtfOptions`] for the interior.
+pub fn bordered_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    border_cols: &[usize],
+    options: &BtfOptions,
+) -> BtfResult<BorderedBtfStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let ncols = mat.ncols();
+    let mut is_border = vec![false; ncols];
+    for &j in border_cols {
+        *is_border.get_mut(j).ok_or(BtfError::IndexOutOfRange {
+            index: j,
+            len: ncols,
+        })? = true;
+    }
+
+    // Without the border columns they are empty, hence unmatched and dependency-free.
+    let mut row_adj = build_row_adjacency(mat);
+    for cols in &mut row_adj {
+        cols.retain(|&j| !is_border[j]);
+    }
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, ncols, options)?;
+    let interior = RectangularStructure::from(&structure);
+
+    let border_cols: Vec<usize> = (0..ncols).filter(|&j| is_border[j]).collect();
+    let free_cols: Vec<usize> = interior
+        .unmatched_cols
+        .iter()
+        .copied()
+        .filter(|&j| !is_border[j])
+        .collect();
+    Ok(BorderedBtfStructure {
+        row_order: [&interior.core_rows, &interior.unmatched_rows]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect(),
+        col_order: [&interior.core_cols, &free_cols, &border_cols]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect(),
+        block_sizes: interior.block_sizes,
+        block_dag: interior.block_dag,
+        border_rows: interior.unmatched_rows,
+        border_cols,
+        free_cols,
+    })
+}
+
+/// Bordered block triangular form with the border columns chosen automatically, so that no
+/// interior block has more than `max_block_size` rows (values below 1 are treated as 1, i.e.
+/// a fully triangular interior). Read the result with [`Orientation::Lower`] for the bordered
+/// block lower triangular form; the border is the same in both orientations.
+///
+/// Each round tears, in every oversized block, the column matched to the first node picked
+/// by the greedy minimum feedback vertex set heuristic ([`TearingHeuristic::GreedyMfvs`]) on
+/// the block's dependency graph, then re-analyzes the matrix without the border. The
+/// matching is recomputed each round, so this costs one analysis per round; the result is a
+/// small, not necessarily minimum, border.
+///
+/// Blocks merged by [`BtfOptions::min_block_size`] can stay larger than `max_block_size`: the
+/// bound applies to the strongly connected parts of each block, which are torn separately.
+pub fn auto_bordered_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    max_block_size: usize,
+) -> BtfResult<BorderedBtfStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    auto_bordered_block_triangular_structure_with_options(
+        mat,
+        max_block_size,
+        &BtfOptions::default(),
+    )
+}
+
+/// [`auto_bordered_block_triangular_structure`] with explicit [`BtfOptions`] for the interior.
+pub fn auto_bordered_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    max_block_size: usize,
+    options: &BtfOptions,
+) -> BtfResult<BorderedBtfStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let max_block_size = max_block_size.max(1);
+    let row_adj = build_row_adjacency(mat);
+    let mut local_of_col = vec![usize::MAX; mat.ncols()];
+    let mut border_cols = Vec::new();
+    loop {
+        let bordered =
+            bordered_block_triangular_structure_with_options(mat, &border_cols, options)?;
+        let mut torn = false;
+        let mut start = 0;
+        for &size in &bordered.block_sizes {
+            let (rows, cols) = (
+                &bordered.row_order[start..start + size],
+                &bordered.col_order[start..start + size],
+            );
+            start += size;
+            if size <= max_block_size {
+                continue;
+            }
+
+            // Interior rows are aligned with their matched columns, so local node `l` is both
+            // `rows[l]` and `cols[l]`. Stale `local_of_col` entries from earlier blocks fail
+            // the `cols[k] == j` check.
+            for (l, &j) in cols.iter().enumerate() {
+                local_of_col[j] = l;
+            }
+            let graph: Vec<Vec<usize>> = rows
+                .iter()
+                .enumerate()
+                .map(|(l, &r)| {
+                    row_adj[r]
+                        .iter()
+                        .map(|&j| (j, local_of_col[j]))
+                        .filter(|&(j, k)| k != l && cols.get(k) == Some(&j))
+                        .map(|(_, k)| k)
+                        .collect()
+                })
+                .collect();
+            // Blocks merged by `options.min_block_size` can be reducible, so tear each SCC
+            // that is too large on its own rather than the block as a whole.
+            let sccs = tarjan_scc(&graph);
+            let comp_of = scc_id_map(&sccs, size);
+            let mut pos = vec![0; size];
+            for scc in &sccs {
+                for (i, &l) in scc.iter().enumerate() {
+                    pos[l] = i;
+                }
+            }
+            for (c, scc) in sccs.iter().enumerate() {
+                if scc.len() <= max_block_size {
+                    continue;
+                }
+                let sub: Vec<Vec<usize>> = scc
+                    .iter()
+                    .map(|&l| {
+                        graph[l]
+                            .iter()
+                            .filter(|&&k| comp_of[k] == c)
+                            .map(|&k| pos[k])
+                            .collect()
+                    })
+                    .collect();
+                let pick = *feedback_vertex_set(&sub, TearingHeuristic::GreedyMfvs)
+                    .first()
+                    .expect("a strongly connected component larger than 1x1 has a cycle");
+                border_cols.push(cols[scc[pick]]);
+                torn = true;
+            }
+        }
+        if !torn {
+            return Ok(bordered);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+
+use arrayvec::ArrayVec;
+
+use crate::UpperBtfStructure;
+use crate::kernels::{self, Containers, RowPattern};
+
+const NONE: usize = usize::MAX;
+
+/// Errors from [`upper_block_triangular_structure_bounded`]: an input exceeds the
+/// caller-provided capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapacityError {
+    /// More rows or columns than the dimension capacity `N`.
+    Dimension {
+        nrows: usize,
+        ncols: usize,
+        capacity: usize,
+    },
+    /// More structural nonzeros than the nonzero capacity `NNZ`.
+    Nonzeros { capacity: usize },
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dimension {
+                nrows,
+                ncols,
+                capacity,
+            } => write!(
+                f,
+                "{nrows} x {ncols} pattern exceeds the dimension capacity {capacity}"
+            ),
+            Self::Nonzeros { capacity } => {
+                write!(f, "pattern has more than {capacity} nonzeros")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// [`UpperBtfStructure`] with statically bounded storage: at most `N` rows and columns and
+/// `NNZ` block DAG edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedBtfStructure<const N: usize, const NNZ: usize> {
+    pub row_order: ArrayVec<usize, N>,
+    pub col_order: ArrayVec<usize, N>,
+    pub block_sizes: ArrayVec<usize, N>,
+    pub matching_size: usize,
+    /// Row -> matched column, `None` for unmatched rows.
+    pub row_to_col: ArrayVec<Option<usize>, N>,
+    /// Block DAG edges `(from, to)` in block numbering, sorted and deduplicated.
+    pub block_edges: ArrayVec<(usize, usize), NNZ>,
+}
+
+impl<const N: usize, const NNZ: usize> BoundedBtfStructure<N, NNZ> {
+    /// Heap-allocated [`UpperBtfStructure`] with the same contents.
+    pub fn to_structure(&self) -> UpperBtfStructure {
+        let mut block_dag = vec![Vec::new(); self.block_sizes.len()];
+        for &(a, b) in &self.block_edges {
+            block_dag[a].push(b);
+        }
+        UpperBtfStructure {
+            row_order: self.row_order.to_vec(),
+            col_order: self.col_order.to_vec(),
+            block_sizes: self.block_sizes.to_vec(),
+            matching_size: self.matching_size,
+            row_to_col: self.row_to_col.to_vec(),
+            block_dag,
+            block_splits: Vec::new(),
+        }
+    }
+}
+
+/// Row-major pattern with bounded storage; row `i` spans `cols[row_end[i - 1]..row_end[i]]`.
+struct BoundedCsr<const N: usize, const NNZ: usize> {
+    row_end: ArrayVec<usize, N>,
+    cols: ArrayVec<usize, NNZ>,
+    ncols: usize,
+}
+
+impl<const N: usize, const NNZ: usize> BoundedCsr<N, NNZ> {
+    fn row(&self, i: usize) -> &[usize] {
+        let start = if i == 0 { 0 } else { self.row_end[i - 1] };
+        &self.cols[start..self.row_end[i]]
+    }
+}
+
+impl<const N: usize, const NNZ: usize> RowPattern for BoundedCsr<N, NNZ> {
+    type Cursor = usize;
+
+    fn nrows(&self) -> usize {
+        self.row_end.len()
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn start(&self, _: usize) -> usize {
+        0
+    }
+
+    fn next(&self, i: usize, cursor: &mut usize) -> Option<usize> {
+        let col = *self.row(i).get(*cursor)?;
+        *cursor += 1;
+        Some(col)
+    }
+}
+
+/// Containers of capacity `N`, for the rows and columns of the bounded pattern.
+struct Bounded<const N: usize>;
+
+impl<const N: usize> Containers for Bounded<N> {
+    type Array<T: Copy> = ArrayVec<T, N>;
+
+    fn filled<T: Copy>(value: T, len: usize) -> ArrayVec<T, N> {
+        filled(value, len)
+    }
+}
+
+/// Work array of length `len` filled with `value`.
+fn filled<T: Copy, const N: usize>(value: T, len: usize) -> ArrayVec<T, N> {
+    (0..len).map(|_| value).collect()
+}
+
+/// Block triangularization with every container backed by an [`ArrayVec`]: `N` bounds the row
+/// and column counts, `NNZ` the structural nonzeros. Inputs beyond either bound are rejected
+/// with a [`CapacityError`] before any work is done, so memory use is fixed at compile time
+/// and nothing is heap-allocated.
+///
+/// Runs the same pipeline as [`crate::csr::analyze_csr`] (MC21 matching, Tarjan SCCs, min-row
+/// topological order), so the result equals the dense analysis with
+/// [`crate::MatchingAlgorithm::Mc21`]. Block selection scans the ready set, which is quadratic
+/// in the number of blocks; this targets the small systems that need static bounds.
+pub fn upper_block_triangular_structure_bounded<const N: usize, const NNZ: usize, T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+) -> Result<BoundedBtfStructure<N, NNZ>, CapacityError>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    if nrows > N || ncols > N {
+        return Err(CapacityError::Dimension {
+            nrows,
+            ncols,
+            capacity: N,
+        });
+    }
+    let zero = T::default();
+    let mut csr = BoundedCsr::<N, NNZ> {
+        row_end: ArrayVec::new(),
+        cols: ArrayVec::new(),
+        ncols,
+    };
+    for i in 0..nrows {
+        for j in (0..ncols).filter(|&j| mat[(i, j)] != zero) {
+            csr.cols
+                .try_push(j)
+                .map_err(|_| CapacityError::Nonzeros { capacity: NNZ })?;
+        }
+        csr.row_end.push(csr.cols.len());
+    }
+
+    let (row_to_col, col_to_row, matching_size) = kernels::mc21::<Bounded<N>, usize, _>(&csr);
+    let mut out = BoundedBtfStructure {
+        row_order: ArrayVec::new(),
+        col_order: ArrayVec::new(),
+        block_sizes: ArrayVec::new(),
+        matching_size,
+        row_to_col: row_to_col
+            .iter()
+            .map(|&c| (c != NONE).then_some(c))
+            .collect(),
+        block_edges: ArrayVec::new(),
+    };
+    if nrows == 0 || ncols == 0 {
+        out.row_order.extend(0..nrows);
+        out.col_order.extend(0..ncols);
+        out.matching_size = 0;
+        return Ok(out);
+    }
+
+    let (comp_of, ncomp) = kernels::tarjan::<Bounded<N>, usize, _>(&csr, &col_to_row);
+
+    // Rows grouped by component, ascending within each (counting sort over rows).
+    let mut comp_end: ArrayVec<usize, N> = filled(0, ncomp);
+    for &c in &comp_of {
+        comp_end[c] += 1;
+    }
+    for c in 1..ncomp {
+        comp_end[c] += comp_end[c - 1];
+    }
+    let mut fill: ArrayVec<usize, N> = (0..ncomp)
+        .map(|c| if c == 0 { 0 } else { comp_end[c - 1] })
+        .collect();
+    let mut rows_by_comp: ArrayVec<usize, N> = filled(0, nrows);
+    for (i, &c) in comp_of.iter().enumerate() {
+        rows_by_comp[fill[c]] = i;
+        fill[c] += 1;
+    }
+    let comp_rows = |c: usize| {
+        let start = if c == 0 { 0 } else { comp_end[c - 1] };
+        &rows_by_comp[start..comp_end[c]]
+    };
+
+    // Condensation edges, sorted and deduplicated; at most one per nonzero.
+    let mut edges: ArrayVec<(usize, usize), NNZ> = ArrayVec::new();
+    for i in 0..nrows {
+        for &col in csr.row(i) {
+            let k = col_to_row[col];
+            if k != NONE && comp_of[k] != comp_of[i] {
+                edges.push((comp_of[i], comp_of[k]));
+            }
+        }
+    }
+    edges.sort_unstable();
+    let mut kept = 0;
+    for e in 0..edges.len() {
+        if kept == 0 || edges[e] != edges[kept - 1] {
+            edges[kept] = edges[e];
+            kept += 1;
+        }
+    }
+    edges.truncate(kept);
+
+    // Kahn with the min-row tie-break of the in-memory pipeline.
+    let mut indeg: ArrayVec<usize, N> = filled(0, ncomp);
+    for &(_, b) in &edges {
+        indeg[b] += 1;
+    }
+    let mut done: ArrayVec<bool, N> = filled(false, ncomp);
+    let mut block_of_comp: ArrayVec<usize, N> = filled(0, ncomp);
+    for b in 0..ncomp {
+        let c = (0..ncomp)
+            .filter(|&c| !done[c] && indeg[c] == 0)
+            .min_by_key(|&c| comp_rows(c)[0])
+            .expect("condensation is acyclic");
+        done[c] = true;
+        block_of_comp[c] = b;
+        let from = edges.partition_point(|&(a, _)| a < c);
+        for &(_, d) in edges[from..].iter().take_while(|&&(a, _)| a == c) {
+            indeg[d] -= 1;
+        }
+        out.block_sizes.push(comp_rows(c).len());
+        out.row_order.extend(comp_rows(c).iter().copied());
+    }
+
+    for &r in &out.row_order {
+        if row_to_col[r] != NONE {
+            out.col_order.push(row_to_col[r]);
+        }
+    }
+    out.col_order
+        .extend((0..ncols).filter(|&c| col_to_row[c] == NONE));
+
+    out.block_edges.extend(
+        edges
+            .iter()
+            .map(|&(a, b)| (block_of_comp[a], block_of_comp[b])),
+    );
+    out.block_edges.sort_unstable();
+
+    Ok(out)
+}
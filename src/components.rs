@@ -0,0 +1,333 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::adjacency::build_row_adjacency;
+use crate::matching::Matching;
+use crate::ordering::{OrderingError, col_order_from_row_order_strict};
+use crate::split::split_oversized_blocks;
+use crate::{
+    BtfOptions, Pin, UnmatchedPlacement, UpperBtfStructure, place_unmatched_cols,
+    place_unmatched_rows, try_structure_from_row_adjacency,
+};
+
+/// One connected component of the bipartite row/column graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BipartiteComponent {
+    /// Original row indices, sorted.
+    pub rows: Vec<usize>,
+    /// Original column indices, sorted.
+    pub cols: Vec<usize>,
+}
+
+/// Connected components of the bipartite graph with rows `0..row_adj.len()` and columns
+/// `0..ncols`. Empty rows and empty columns form components of their own. Components are
+/// ordered by their smallest row (components without rows last, by smallest column).
+pub fn bipartite_components(row_adj: &[Vec<usize>], ncols: usize) -> Vec<BipartiteComponent> {
+    let nrows = row_adj.len();
+    // Union-find over rows (0..nrows) and columns (nrows..nrows + ncols).
+    let mut parent: Vec<usize> = (0..nrows + ncols).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            let (a, b) = (find(&mut parent, i), find(&mut parent, nrows + j));
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    // Roots are the smallest node of their set, so visiting nodes in order yields components
+    // ordered by smallest row, then column-only components by smallest column.
+    let mut comp_of_root = vec![usize::MAX; nrows + ncols];
+    let mut comps: Vec<BipartiteComponent> = Vec::new();
+    for x in 0..nrows + ncols {
+        let root = find(&mut parent, x);
+        if comp_of_root[root] == usize::MAX {
+            comp_of_root[root] = comps.len();
+            comps.push(BipartiteComponent {
+                rows: Vec::new(),
+                cols: Vec::new(),
+            });
+        }
+        let comp = &mut comps[comp_of_root[root]];
+        if x < nrows {
+            comp.rows.push(x);
+        } else {
+            comp.cols.push(x - nrows);
+        }
+    }
+    comps
+}
+
+/// Analyze each connected component of the bipartite graph independently (in parallel with
+/// the `parallel` feature, or with plain scoped threads under `parallel-std`) and stitch the
+/// results into one structure.
+///
+/// Components share no rows, columns, or dependencies, so the stitched block order merges the
+/// per-component orders by their min-row keys. With the default options this reproduces the
+/// global analysis whenever the per-component matchings coincide with the global one (always
+/// for the block partition of structurally nonsingular patterns). `options.unmatched` places
+/// unmatched rows and columns relative to the blocks of all components, as in the global
+/// analysis. Pins in `options` order the blocks within each component only.
+///
+/// `options.block_order` and `options.min_block_size` also apply per component: the block
+/// order strategy orders the blocks of each component, whose sequences are then merged by
+/// min-row key regardless of strategy, and small blocks are merged only with blocks of their
+/// own component (a component smaller than `min_block_size` stays below it). Use the global
+/// analysis when either must hold across components.
+pub fn analyze_by_components<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> Result<UpperBtfStructure, OrderingError>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let row_adj = build_row_adjacency(mat);
+    let structure = components_structure(&row_adj, mat.ncols(), options)?;
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_analysis(started.elapsed(), &structure);
+    Ok(structure)
+}
+
+fn components_structure(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    options: &BtfOptions,
+) -> Result<UpperBtfStructure, OrderingError> {
+    let nrows = row_adj.len();
+    if nrows == 0 || ncols == 0 {
+        return Ok(try_structure_from_row_adjacency(row_adj, ncols, options)?.0);
+    }
+
+    let comps = bipartite_components(row_adj, ncols);
+    let analyze = |comp: &BipartiteComponent| analyze_component(comp, row_adj, options);
+    #[cfg(feature = "parallel")]
+    let parts: Vec<ComponentResult> = comps.par_iter().map(analyze).collect::<Result<_, _>>()?;
+    #[cfg(all(feature = "parallel-std", not(feature = "parallel")))]
+    let parts: Vec<ComponentResult> = crate::scoped::scoped_map(&comps, analyze)?;
+    #[cfg(not(any(feature = "parallel", feature = "parallel-std")))]
+    let parts: Vec<ComponentResult> = comps.iter().map(analyze).collect::<Result<_, _>>()?;
+
+    let mut row_to_col = vec![None; nrows];
+    for part in &parts {
+        for &(r, col) in &part.matched {
+            row_to_col[r] = Some(col);
+        }
+    }
+
+    // k-way merge of the per-component block sequences by min-row key.
+    let mut next = vec![0usize; parts.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> = parts
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !p.blocks.is_empty())
+        .map(|(c, p)| Reverse((p.blocks[0].0, c)))
+        .collect();
+    let mut global_block = parts
+        .iter()
+        .map(|p| vec![0usize; p.blocks.len()])
+        .collect::<Vec<_>>();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    while let Some(Reverse((_, c))) = heap.pop() {
+        global_block[c][next[c]] = merged.len();
+        merged.push((c, next[c]));
+        next[c] += 1;
+        if let Some(block) = parts[c].blocks.get(next[c]) {
+            heap.push(Reverse((block.0, c)));
+        }
+    }
+
+    // Unmatched rows are placed relative to the blocks of all components, as in the global
+    // analysis.
+    let merged_dag: Vec<Vec<usize>> = merged
+        .iter()
+        .map(|&(c, b)| {
+            parts[c].block_dag[b]
+                .iter()
+                .map(|&d| global_block[c][d])
+                .collect()
+        })
+        .collect();
+    let placed = place_unmatched_rows(
+        (0..merged.len()).collect(),
+        &merged_dag,
+        |g| {
+            let (c, b) = merged[g];
+            let rows = &parts[c].blocks[b].1;
+            rows.len() == 1 && row_to_col[rows[0]].is_none()
+        },
+        options.unmatched,
+    );
+    let mut position = vec![0; merged.len()];
+    for (p, &g) in placed.iter().enumerate() {
+        position[g] = p;
+    }
+    for blocks in &mut global_block {
+        for g in blocks {
+            *g = position[*g];
+        }
+    }
+    let merged: Vec<(usize, usize)> = placed.iter().map(|&g| merged[g]).collect();
+
+    let mut row_order = Vec::with_capacity(nrows);
+    let mut block_sizes = Vec::with_capacity(merged.len());
+    let mut block_dag = Vec::with_capacity(merged.len());
+    for &(c, b) in &merged {
+        let rows = &parts[c].blocks[b].1;
+        row_order.extend_from_slice(rows);
+        block_sizes.push(rows.len());
+        let mut out: Vec<usize> = parts[c].block_dag[b]
+            .iter()
+            .map(|&d| global_block[c][d])
+            .collect();
+        out.sort_unstable();
+        block_dag.push(out);
+    }
+
+    let matching_size = parts.iter().map(|p| p.matched.len()).sum();
+    let mut col_to_row = vec![None; ncols];
+    for (r, c) in row_to_col.iter().enumerate() {
+        if let &Some(c) = c {
+            col_to_row[c] = Some(r);
+        }
+    }
+    let matching = Matching {
+        row_to_col,
+        col_to_row,
+        size: matching_size,
+    };
+    let col_order = place_unmatched_cols(
+        col_order_from_row_order_strict(&row_order, &matching.row_to_col, ncols)?,
+        &row_order,
+        &block_sizes,
+        row_adj,
+        &matching,
+        options.unmatched,
+    );
+
+    let mut structure = UpperBtfStructure {
+        row_order,
+        col_order,
+        block_sizes,
+        matching_size,
+        row_to_col: matching.row_to_col,
+        block_dag,
+        block_splits: Vec::new(),
+    };
+    split_oversized_blocks(&mut structure, row_adj, options.max_block_size);
+    Ok(structure)
+}
+
+/// Blocks as `(min row, original rows)` in component order, the component's block DAG in the
+/// same numbering, and its matched `(row, col)` pairs.
+struct ComponentResult {
+    blocks: Vec<(usize, Vec<usize>)>,
+    block_dag: Vec<Vec<usize>>,
+    matched: Vec<(usize, usize)>,
+}
+
+fn analyze_component(
+    comp: &BipartiteComponent,
+    row_adj: &[Vec<usize>],
+    options: &BtfOptions,
+) -> Result<ComponentResult, OrderingError> {
+    // A row without entries is a singleton block coupled to nothing.
+    if comp.cols.is_empty() {
+        return Ok(ComponentResult {
+            blocks: comp.rows.iter().map(|&r| (r, vec![r])).collect(),
+            block_dag: vec![Vec::new(); comp.rows.len()],
+            matched: Vec::new(),
+        });
+    }
+
+    let (local_adj, mut local_options) = component_problem(comp, row_adj, options);
+    // Blocks are split, and unmatched rows and columns placed, after stitching, where their
+    // global indices are known.
+    local_options.max_block_size = 0;
+    local_options.unmatched = UnmatchedPlacement::End;
+
+    let (structure, matching) =
+        try_structure_from_row_adjacency(&local_adj, comp.cols.len(), &local_options)?;
+
+    let mut blocks = Vec::with_capacity(structure.block_sizes.len());
+    let mut start = 0;
+    for &size in &structure.block_sizes {
+        let rows: Vec<usize> = structure.row_order[start..start + size]
+            .iter()
+            .map(|&lr| comp.rows[lr])
+            .collect();
+        let key = rows.iter().copied().min().expect("blocks are non-empty");
+        blocks.push((key, rows));
+        start += size;
+    }
+
+    let matched = matching
+        .row_to_col
+        .iter()
+        .enumerate()
+        .filter_map(|(lr, c)| c.map(|lc| (comp.rows[lr], comp.cols[lc])))
+        .collect();
+
+    Ok(ComponentResult {
+        blocks,
+        block_dag: structure.block_dag,
+        matched,
+    })
+}
+
+/// Pattern of `comp` in local indices (positions in `comp.rows` and `comp.cols`), and
+/// `options` with its entry and pin lists restricted and translated to those indices.
+pub(crate) fn component_problem(
+    comp: &BipartiteComponent,
+    row_adj: &[Vec<usize>],
+    options: &BtfOptions,
+) -> (Vec<Vec<usize>>, BtfOptions) {
+    let local_col = |j: usize| comp.cols.binary_search(&j).expect("column in component");
+    let local_adj = comp
+        .rows
+        .iter()
+        .map(|&r| row_adj[r].iter().map(|&j| local_col(j)).collect())
+        .collect();
+    let local_entry = |r: usize, j: usize| {
+        let lr = comp.rows.binary_search(&r).ok()?;
+        let lj = comp.cols.binary_search(&j).ok()?;
+        Some((lr, lj))
+    };
+    let local_pins = |pins: &[Pin]| -> Vec<Pin> {
+        pins.iter()
+            .filter_map(|&pin| match pin {
+                Pin::Row(r) => comp.rows.binary_search(&r).ok().map(Pin::Row),
+                Pin::Col(j) => comp.cols.binary_search(&j).ok().map(Pin::Col),
+            })
+            .collect()
+    };
+    let local_options = BtfOptions {
+        forbidden: options
+            .forbidden
+            .iter()
+            .filter_map(|&(r, j)| local_entry(r, j))
+            .collect(),
+        priorities: options
+            .priorities
+            .iter()
+            .filter_map(|&(r, j, class)| local_entry(r, j).map(|(lr, lj)| (lr, lj, class)))
+            .collect(),
+        pin_first: local_pins(&options.pin_first),
+        pin_last: local_pins(&options.pin_last),
+        ..options.clone()
+    };
+    (local_adj, local_options)
+}
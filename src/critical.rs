@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::adjacency::{build_row_adjacency, build_row_dependency_graph};
+use crate::matching::hopcroft_karp;
+use crate::scc::tarjan_scc;
+use crate::structure_from_row_adjacency;
+
+/// Nonzeros whose removal would change the block structure, grouped by the kind of change.
+/// Each list holds original `(row, col)` pairs sorted lexicographically.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CriticalEntries {
+    /// Entries contained in every maximum matching: removing one lowers the structural rank.
+    pub rank_reducing: Vec<(usize, usize)>,
+    /// Entries inside a diagonal block whose removal splits that block into smaller ones
+    /// (structural rank is preserved).
+    pub block_splitting: Vec<(usize, usize)>,
+    /// Above-diagonal entries that are the only coupling between their two blocks: removing
+    /// one deletes an edge of the block DAG.
+    pub coupling_breaking: Vec<(usize, usize)>,
+}
+
+/// Identify the nonzeros of `mat` whose removal would change its block structure.
+///
+/// Entries are classified by the first applicable category (rank, then block splitting,
+/// then coupling); entries not listed anywhere can be removed without changing the blocks.
+pub fn critical_entries<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> CriticalEntries
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, matching) = structure_from_row_adjacency(&row_adj, mat.ncols());
+    let row_block = structure.row_blocks();
+
+    let mut out = CriticalEntries::default();
+
+    // Rank: a matched entry lying on a cycle of its block can be swapped out along that
+    // cycle, so only singleton blocks need an explicit augmenting-path search.
+    for (i, &mate) in matching.row_to_col.iter().enumerate() {
+        if let Some(j) = mate
+            && structure.block_sizes[row_block[i]] == 1
+            && !has_augmenting_path_without(
+                &row_adj,
+                &matching.row_to_col,
+                &matching.col_to_row,
+                (i, j),
+            )
+        {
+            out.rank_reducing.push((i, j));
+        }
+    }
+
+    // Block splitting: re-analyze each non-trivial block with one entry removed.
+    let mut start = 0;
+    for &size in &structure.block_sizes {
+        let rows = &structure.row_order[start..start + size];
+        if size > 1 {
+            block_splitting_entries(
+                rows,
+                &row_adj,
+                &matching.col_to_row,
+                &mut out.block_splitting,
+            );
+        }
+        start += size;
+    }
+
+    // Coupling: off-diagonal block pairs backed by a single entry.
+    let mut couplings: BTreeMap<(usize, usize), Vec<(usize, usize)>> = BTreeMap::new();
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            if let Some(k) = matching.col_to_row[j]
+                && row_block[i] != row_block[k]
+            {
+                couplings
+                    .entry((row_block[i], row_block[k]))
+                    .or_default()
+                    .push((i, j));
+            }
+        }
+    }
+    out.coupling_breaking = couplings
+        .into_values()
+        .filter(|entries| entries.len() == 1)
+        .map(|entries| entries[0])
+        .collect();
+
+    out.rank_reducing.sort_unstable();
+    out.block_splitting.sort_unstable();
+    out.coupling_breaking.sort_unstable();
+    out
+}
+
+/// Whether the matching minus `removed` can be re-augmented to its original size in the
+/// graph without the `removed` entry (BFS over alternating paths from all free rows).
+fn has_augmenting_path_without(
+    row_adj: &[Vec<usize>],
+    row_to_col: &[Option<usize>],
+    col_to_row: &[Option<usize>],
+    removed: (usize, usize),
+) -> bool {
+    let (ri, rj) = removed;
+    let mut visited = vec![false; row_adj.len()];
+    let mut q = VecDeque::new();
+    for (u, mate) in row_to_col.iter().enumerate() {
+        if mate.is_none() || u == ri {
+            visited[u] = true;
+            q.push_back(u);
+        }
+    }
+
+    while let Some(u) = q.pop_front() {
+        for &v in &row_adj[u] {
+            if (u, v) == removed {
+                continue;
+            }
+            match col_to_row[v] {
+                Some(u2) if v != rj => {
+                    if !visited[u2] {
+                        visited[u2] = true;
+                        q.push_back(u2);
+                    }
+                }
+                _ => return true,
+            }
+        }
+    }
+
+    false
+}
+
+/// Push every entry of the (square, perfectly matched) diagonal block on `rows` whose removal
+/// splits the block.
+fn block_splitting_entries(
+    rows: &[usize],
+    row_adj: &[Vec<usize>],
+    col_to_row: &[Option<usize>],
+    out: &mut Vec<(usize, usize)>,
+) {
+    // Local numbering: block rows by position, block columns by the position of their mate.
+    let mut local_of_row = BTreeMap::new();
+    for (li, &r) in rows.iter().enumerate() {
+        local_of_row.insert(r, li);
+    }
+    let local_col = |j: usize| col_to_row[j].and_then(|k| local_of_row.get(&k).copied());
+
+    let local_adj: Vec<Vec<(usize, usize)>> = rows
+        .iter()
+        .map(|&r| {
+            row_adj[r]
+                .iter()
+                .filter_map(|&j| local_col(j).map(|lj| (lj, j)))
+                .collect()
+        })
+        .collect();
+
+    for (li, entries) in local_adj.iter().enumerate() {
+        for &(skip, j) in entries {
+            let adj: Vec<Vec<usize>> = local_adj
+                .iter()
+                .enumerate()
+                .map(|(lr, cols)| {
+                    cols.iter()
+                        .map(|&(lc, _)| lc)
+                        .filter(|&lc| (lr, lc) != (li, skip))
+                        .collect()
+                })
+                .collect();
+            let m = hopcroft_karp(&adj, rows.len());
+            let g = build_row_dependency_graph(&adj, &m.col_to_row);
+            if tarjan_scc(&g).len() > 1 {
+                out.push((rows[li], j));
+            }
+        }
+    }
+}
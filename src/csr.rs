@@ -0,0 +1,356 @@
+use std::{cmp::Reverse, collections::BinaryHeap, fmt};
+
+use crate::UpperBtfStructure;
+use crate::kernels::{self, Heap, RowPattern};
+
+const NONE: u32 = u32::MAX;
+
+/// Errors from building a [`CsrPattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsrError {
+    /// A dimension does not fit the `u32` index type.
+    TooLarge { nrows: usize, ncols: usize },
+    /// `row_ptr` has the wrong length, is not nondecreasing, or does not end at `col_idx.len()`.
+    InvalidRowPtr,
+    /// An entry lies outside the pattern.
+    OutOfRange { row: usize, col: usize },
+    /// A streamed entry is not in row-major order after the previous one.
+    Unsorted { row: usize, col: usize },
+}
+
+impl fmt::Display for CsrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { nrows, ncols } => {
+                write!(f, "{nrows} x {ncols} pattern exceeds u32 indices")
+            }
+            Self::InvalidRowPtr => write!(f, "row pointer array is inconsistent"),
+            Self::OutOfRange { row, col } => write!(f, "entry ({row}, {col}) is out of range"),
+            Self::Unsorted { row, col } => {
+                write!(f, "entry ({row}, {col}) is not in row-major order")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsrError {}
+
+/// Compressed sparse row pattern with `u32` column indices.
+///
+/// Storage is generic so the arrays can live in memory-mapped files (anything that derefs
+/// to a slice); [`CsrBuilder`] produces owned vectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrPattern<P = Vec<u64>, I = Vec<u32>> {
+    nrows: usize,
+    ncols: usize,
+    row_ptr: P,
+    col_idx: I,
+}
+
+impl<P: AsRef<[u64]>, I: AsRef<[u32]>> CsrPattern<P, I> {
+    /// Wrap existing CSR arrays: row `i` holds `col_idx[row_ptr[i]..row_ptr[i + 1]]`.
+    /// Columns within a row should be sorted and distinct.
+    pub fn from_parts(
+        nrows: usize,
+        ncols: usize,
+        row_ptr: P,
+        col_idx: I,
+    ) -> Result<Self, CsrError> {
+        if nrows >= NONE as usize || ncols >= NONE as usize {
+            return Err(CsrError::TooLarge { nrows, ncols });
+        }
+        let ptr = row_ptr.as_ref();
+        let idx = col_idx.as_ref();
+        if ptr.len() != nrows + 1
+            || ptr[0] != 0
+            || ptr.windows(2).any(|w| w[0] > w[1])
+            || ptr[nrows] != idx.len() as u64
+        {
+            return Err(CsrError::InvalidRowPtr);
+        }
+        for i in 0..nrows {
+            for &c in &idx[ptr[i] as usize..ptr[i + 1] as usize] {
+                if c as usize >= ncols {
+                    return Err(CsrError::OutOfRange {
+                        row: i,
+                        col: c as usize,
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            nrows,
+            ncols,
+            row_ptr,
+            col_idx,
+        })
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.col_idx.as_ref().len()
+    }
+
+    /// Column indices of row `i`.
+    pub fn row(&self, i: usize) -> &[u32] {
+        let ptr = self.row_ptr.as_ref();
+        &self.col_idx.as_ref()[ptr[i] as usize..ptr[i + 1] as usize]
+    }
+}
+
+impl<P: AsRef<[u64]>, I: AsRef<[u32]>> RowPattern for CsrPattern<P, I> {
+    type Cursor = u32;
+
+    fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn start(&self, _: usize) -> u32 {
+        0
+    }
+
+    fn next(&self, i: usize, cursor: &mut u32) -> Option<usize> {
+        let col = *self.row(i).get(*cursor as usize)?;
+        *cursor += 1;
+        Some(col as usize)
+    }
+}
+
+impl CsrPattern {
+    /// Pattern of a dense matrix (same nonzero convention as the rest of the crate).
+    pub fn from_matrix<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> Result<Self, CsrError>
+    where
+        T: nalgebra::Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let zero = T::default();
+        let mut builder = CsrBuilder::new(mat.nrows(), mat.ncols())?;
+        for i in 0..mat.nrows() {
+            for j in 0..mat.ncols() {
+                if mat[(i, j)] != zero {
+                    builder.push(i as u32, j as u32)?;
+                }
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+/// Streaming [`CsrPattern`] builder for nonzeros arriving in row-major order, e.g. read in
+/// chunks from disk. Only the CSR arrays are kept in memory.
+#[derive(Debug, Clone)]
+pub struct CsrBuilder {
+    nrows: usize,
+    ncols: usize,
+    row_ptr: Vec<u64>,
+    col_idx: Vec<u32>,
+    last: Option<(u32, u32)>,
+}
+
+impl CsrBuilder {
+    pub fn new(nrows: usize, ncols: usize) -> Result<Self, CsrError> {
+        if nrows >= NONE as usize || ncols >= NONE as usize {
+            return Err(CsrError::TooLarge { nrows, ncols });
+        }
+        let mut row_ptr = Vec::with_capacity(nrows + 1);
+        row_ptr.push(0);
+        Ok(Self {
+            nrows,
+            ncols,
+            row_ptr,
+            col_idx: Vec::new(),
+            last: None,
+        })
+    }
+
+    /// Append one nonzero. Repeating the previous entry is a no-op.
+    pub fn push(&mut self, row: u32, col: u32) -> Result<(), CsrError> {
+        let (r, c) = (row as usize, col as usize);
+        if r >= self.nrows || c >= self.ncols {
+            return Err(CsrError::OutOfRange { row: r, col: c });
+        }
+        match self.last {
+            Some(prev) if (row, col) == prev => return Ok(()),
+            Some(prev) if (row, col) < prev => return Err(CsrError::Unsorted { row: r, col: c }),
+            _ => {}
+        }
+        while self.row_ptr.len() <= r {
+            self.row_ptr.push(self.col_idx.len() as u64);
+        }
+        self.col_idx.push(col);
+        self.last = Some((row, col));
+        Ok(())
+    }
+
+    /// Append a chunk of nonzeros; see [`Self::push`].
+    pub fn push_chunk(&mut self, entries: &[(u32, u32)]) -> Result<(), CsrError> {
+        entries.iter().try_for_each(|&(r, c)| self.push(r, c))
+    }
+
+    pub fn finish(mut self) -> CsrPattern {
+        while self.row_ptr.len() <= self.nrows {
+            self.row_ptr.push(self.col_idx.len() as u64);
+        }
+        CsrPattern {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            row_ptr: self.row_ptr,
+            col_idx: self.col_idx,
+        }
+    }
+}
+
+/// Block triangular structure of a CSR pattern, using flat `u32` working arrays instead of
+/// nested adjacency lists.
+///
+/// The row dependency graph is never materialized, SCCs are found with an iterative Tarjan
+/// (no recursion depth limit), and the matching is MC21's. The result equals
+/// [`crate::upper_block_triangular_structure_with_options`] with
+/// [`crate::MatchingAlgorithm::Mc21`]; only the input representation differs.
+pub fn analyze_csr<P: AsRef<[u64]>, I: AsRef<[u32]>>(
+    pattern: &CsrPattern<P, I>,
+) -> UpperBtfStructure {
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let structure = csr_structure(pattern);
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_analysis(started.elapsed(), &structure);
+    structure
+}
+
+fn csr_structure<P: AsRef<[u64]>, I: AsRef<[u32]>>(
+    pattern: &CsrPattern<P, I>,
+) -> UpperBtfStructure {
+    let (nrows, ncols) = (pattern.nrows, pattern.ncols);
+    #[cfg(feature = "metrics")]
+    let matching_started = std::time::Instant::now();
+    let (row_to_col, col_to_row, matching_size) = kernels::mc21::<Heap, u32, _>(pattern);
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_matching_size(
+        "mc21",
+        matching_started.elapsed(),
+        nrows.min(ncols),
+        matching_size,
+    );
+
+    if nrows == 0 || ncols == 0 {
+        return UpperBtfStructure {
+            row_order: (0..nrows).collect(),
+            col_order: (0..ncols).collect(),
+            block_sizes: Vec::new(),
+            matching_size: 0,
+            row_to_col: vec![None; nrows],
+            block_dag: Vec::new(),
+            block_splits: Vec::new(),
+        };
+    }
+
+    let (comp_of, ncomp) = kernels::tarjan::<Heap, u32, _>(pattern, &col_to_row);
+
+    // Rows grouped by component, ascending within each (counting sort over rows).
+    let mut comp_start = vec![0u64; ncomp + 1];
+    for &c in &comp_of {
+        comp_start[c as usize + 1] += 1;
+    }
+    for c in 0..ncomp {
+        comp_start[c + 1] += comp_start[c];
+    }
+    let mut rows_by_comp = vec![0u32; nrows];
+    let mut fill = comp_start.clone();
+    for (i, &c) in comp_of.iter().enumerate() {
+        rows_by_comp[fill[c as usize] as usize] = i as u32;
+        fill[c as usize] += 1;
+    }
+    let comp_rows = |c: usize| &rows_by_comp[comp_start[c] as usize..comp_start[c + 1] as usize];
+
+    // Condensation DAG as a sorted, deduplicated edge list.
+    let mut edges: Vec<(u32, u32)> = Vec::new();
+    for i in 0..nrows {
+        for &col in pattern.row(i) {
+            let k = col_to_row[col as usize];
+            if k != NONE && comp_of[k as usize] != comp_of[i] {
+                edges.push((comp_of[i], comp_of[k as usize]));
+            }
+        }
+    }
+    edges.sort_unstable();
+    edges.dedup();
+
+    // Kahn with the min-row tie-break of the in-memory pipeline.
+    let mut indeg = vec![0u32; ncomp];
+    let mut out_start = vec![0usize; ncomp + 1];
+    for &(a, b) in &edges {
+        indeg[b as usize] += 1;
+        out_start[a as usize + 1] += 1;
+    }
+    for c in 0..ncomp {
+        out_start[c + 1] += out_start[c];
+    }
+    let mut heap: BinaryHeap<Reverse<(u32, u32)>> = (0..ncomp)
+        .filter(|&c| indeg[c] == 0)
+        .map(|c| Reverse((comp_rows(c)[0], c as u32)))
+        .collect();
+    let mut comp_order = Vec::with_capacity(ncomp);
+    while let Some(Reverse((_, c))) = heap.pop() {
+        comp_order.push(c as usize);
+        for &(_, d) in &edges[out_start[c as usize]..out_start[c as usize + 1]] {
+            indeg[d as usize] -= 1;
+            if indeg[d as usize] == 0 {
+                heap.push(Reverse((comp_rows(d as usize)[0], d)));
+            }
+        }
+    }
+    debug_assert_eq!(comp_order.len(), ncomp, "condensation must be acyclic");
+
+    let mut block_of_comp = vec![0usize; ncomp];
+    let mut row_order = Vec::with_capacity(nrows);
+    let mut block_sizes = Vec::with_capacity(ncomp);
+    for (b, &c) in comp_order.iter().enumerate() {
+        block_of_comp[c] = b;
+        block_sizes.push(comp_rows(c).len());
+        row_order.extend(comp_rows(c).iter().map(|&r| r as usize));
+    }
+
+    let mut col_order = Vec::with_capacity(ncols);
+    for &r in &row_order {
+        if row_to_col[r] != NONE {
+            col_order.push(row_to_col[r] as usize);
+        }
+    }
+    col_order.extend((0..ncols).filter(|&c| col_to_row[c] == NONE));
+
+    let mut block_dag = vec![Vec::new(); ncomp];
+    for &(a, b) in &edges {
+        block_dag[block_of_comp[a as usize]].push(block_of_comp[b as usize]);
+    }
+    for out in &mut block_dag {
+        out.sort_unstable();
+    }
+
+    UpperBtfStructure {
+        row_order,
+        col_order,
+        block_sizes,
+        matching_size,
+        row_to_col: row_to_col
+            .iter()
+            .map(|&c| (c != NONE).then_some(c as usize))
+            .collect(),
+        block_dag,
+        block_splits: Vec::new(),
+    }
+}
@@ -0,0 +1,183 @@
+use crate::adjacency::{build_row_adjacency, build_row_dependency_graph};
+use crate::scc::tarjan_scc;
+use crate::structure_from_row_adjacency;
+
+/// Bounds on cycle enumeration; the number of elementary cycles can be exponential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleLimits {
+    /// Maximum number of nodes in a reported cycle.
+    pub max_len: usize,
+    /// Stop after this many cycles.
+    pub max_cycles: usize,
+}
+
+impl Default for CycleLimits {
+    fn default() -> Self {
+        Self {
+            max_len: usize::MAX,
+            max_cycles: usize::MAX,
+        }
+    }
+}
+
+/// Johnson's algorithm: enumerate elementary cycles of a directed graph, subject to `limits`.
+///
+/// Each cycle is reported as its node sequence starting at its smallest node, e.g. `[0, 2, 1]`
+/// for `0 -> 2 -> 1 -> 0`. Output is deterministic for sorted adjacency lists.
+pub fn elementary_cycles(graph: &[Vec<usize>], limits: &CycleLimits) -> Vec<Vec<usize>> {
+    let n = graph.len();
+    let mut state = JohnsonState {
+        blocked: vec![false; n],
+        blocked_by: vec![Vec::new(); n],
+        allowed: vec![false; n],
+        stack: Vec::new(),
+        cycles: Vec::new(),
+        limits: *limits,
+    };
+
+    if limits.max_len < 2 || limits.max_cycles == 0 {
+        return state.cycles;
+    }
+
+    for s in 0..n {
+        // Restrict to the SCC containing `s` in the subgraph induced by nodes >= s.
+        let sub: Vec<Vec<usize>> = (s..n)
+            .map(|u| {
+                graph[u]
+                    .iter()
+                    .filter(|&&v| v >= s)
+                    .map(|&v| v - s)
+                    .collect()
+            })
+            .collect();
+        let Some(comp) = tarjan_scc(&sub).into_iter().find(|c| c.contains(&0)) else {
+            continue;
+        };
+        if comp.len() < 2 {
+            continue;
+        }
+
+        for &v in &comp {
+            state.allowed[v + s] = true;
+            state.blocked[v + s] = false;
+            state.blocked_by[v + s].clear();
+        }
+        state.circuit(s, s, graph);
+        for &v in &comp {
+            state.allowed[v + s] = false;
+        }
+
+        if state.cycles.len() >= limits.max_cycles {
+            break;
+        }
+    }
+
+    state.cycles
+}
+
+struct JohnsonState {
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    allowed: Vec<bool>,
+    stack: Vec<usize>,
+    cycles: Vec<Vec<usize>>,
+    limits: CycleLimits,
+}
+
+impl JohnsonState {
+    fn circuit(&mut self, v: usize, s: usize, graph: &[Vec<usize>]) -> bool {
+        let mut found = false;
+        self.stack.push(v);
+        self.blocked[v] = true;
+
+        for &w in &graph[v] {
+            if self.cycles.len() >= self.limits.max_cycles {
+                // Stop early; report "found" so nothing stays blocked on our account.
+                found = true;
+                break;
+            }
+            if !self.allowed[w] {
+                continue;
+            }
+            if w == s {
+                self.cycles.push(self.stack.clone());
+                found = true;
+            } else if !self.blocked[w] {
+                if self.stack.len() < self.limits.max_len {
+                    found |= self.circuit(w, s, graph);
+                } else {
+                    // Truncated by the length bound: cycles may still exist through `w`, so
+                    // treat it as found to keep `v` from being blocked.
+                    found = true;
+                }
+            }
+        }
+
+        if found {
+            self.unblock(v);
+        } else {
+            for &w in &graph[v] {
+                if self.allowed[w] && !self.blocked_by[w].contains(&v) {
+                    self.blocked_by[w].push(v);
+                }
+            }
+        }
+
+        self.stack.pop();
+        found
+    }
+
+    fn unblock(&mut self, u: usize) {
+        self.blocked[u] = false;
+        while let Some(w) = self.blocked_by[u].pop() {
+            if self.blocked[w] {
+                self.unblock(w);
+            }
+        }
+    }
+}
+
+/// Enumerate elementary cycles of the row dependency graph inside diagonal block `block`
+/// (in solve order). Cycles are sequences of original row indices: `i -> k` means row `i`
+/// uses the variable matched to row `k`.
+///
+/// Panics if `block` is not a valid block index.
+pub fn block_cycles<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    block: usize,
+    limits: &CycleLimits,
+) -> Vec<Vec<usize>>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, matching) = structure_from_row_adjacency(&row_adj, mat.ncols());
+    assert!(
+        block < structure.num_blocks(),
+        "block {block} out of range ({} blocks)",
+        structure.num_blocks()
+    );
+
+    let start: usize = structure.block_sizes[..block].iter().sum();
+    let rows = &structure.row_order[start..start + structure.block_sizes[block]];
+
+    // Rows within a block are sorted, so local indices preserve the original order.
+    let row_graph = build_row_dependency_graph(&row_adj, &matching.col_to_row);
+    let local: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|&r| {
+            row_graph[r]
+                .iter()
+                .filter_map(|k| rows.binary_search(k).ok())
+                .collect()
+        })
+        .collect();
+
+    elementary_cycles(&local, limits)
+        .into_iter()
+        .map(|cycle| cycle.into_iter().map(|v| rows[v]).collect())
+        .collect()
+}
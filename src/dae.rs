@@ -0,0 +1,398 @@
+use std::fmt;
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::matching::hopcroft_karp;
+use crate::ordering::OrderingError;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// Errors from [`dae_structure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaeError {
+    /// The variable and derivative patterns have different shapes.
+    ShapeMismatch {
+        vars: (usize, usize),
+        derivs: (usize, usize),
+    },
+    /// The ordering options cannot be honored, e.g. pins no block triangular order of the
+    /// incidence satisfies.
+    Ordering(OrderingError),
+}
+
+impl fmt::Display for DaeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ShapeMismatch { vars, derivs } => write!(
+                f,
+                "variable pattern is {} x {} but derivative pattern is {} x {}",
+                vars.0, vars.1, derivs.0, derivs.1
+            ),
+            Self::Ordering(err) => write!(f, "cannot order the incidence: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DaeError {}
+
+/// Structural analysis of a first-order DAE `F(x', x) = 0`.
+#[derive(Debug, Clone)]
+pub struct DaeStructure {
+    /// Per variable: whether it appears differentiated in some equation (a state). The unknown
+    /// solved for is then `x'_j`; otherwise it is the algebraic variable `x_j`.
+    pub differential: Vec<bool>,
+    /// Row adjacency of the highest-order incidence: equation `i` lists variable `j` if it
+    /// contains `x'_j` (states) or `x_j` (algebraic variables). Sorted.
+    pub incidence: Vec<Vec<usize>>,
+    /// BLT structure of `incidence`; columns are variables, standing for `x'_j` or `x_j`.
+    pub structure: UpperBtfStructure,
+}
+
+/// Analyze a DAE given the incidence of the variables (`vars`, i.e. `∂F/∂x`) and of their
+/// derivatives (`derivs`, the mass-matrix / `E` pattern `∂F/∂x'`), both `equations x variables`.
+///
+/// Matching and block ordering run over highest-order occurrences only: states are treated as
+/// known, so occurrences of `x_j` for a differentiated variable do not constrain the system.
+/// Higher-order DAEs should first be reduced to first order.
+pub fn dae_structure<T, R, C, S, T2, R2, C2, S2>(
+    vars: &nalgebra::Matrix<T, R, C, S>,
+    derivs: &nalgebra::Matrix<T2, R2, C2, S2>,
+) -> Result<DaeStructure, DaeError>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+    T2: nalgebra::Scalar + PartialEq + Default,
+    R2: nalgebra::Dim,
+    C2: nalgebra::Dim,
+    S2: nalgebra::Storage<T2, R2, C2>,
+{
+    dae_structure_with_options(vars, derivs, &BtfOptions::default())
+}
+
+/// [`dae_structure`] with explicit [`BtfOptions`] (indices refer to the combined incidence).
+///
+/// Pins the incidence cannot honor are reported as [`DaeError::Ordering`].
+pub fn dae_structure_with_options<T, R, C, S, T2, R2, C2, S2>(
+    vars: &nalgebra::Matrix<T, R, C, S>,
+    derivs: &nalgebra::Matrix<T2, R2, C2, S2>,
+    options: &BtfOptions,
+) -> Result<DaeStructure, DaeError>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+    T2: nalgebra::Scalar + PartialEq + Default,
+    R2: nalgebra::Dim,
+    C2: nalgebra::Dim,
+    S2: nalgebra::Storage<T2, R2, C2>,
+{
+    if vars.shape() != derivs.shape() {
+        return Err(DaeError::ShapeMismatch {
+            vars: vars.shape(),
+            derivs: derivs.shape(),
+        });
+    }
+    let ncols = vars.ncols();
+    let var_adj = build_row_adjacency(vars);
+    let der_adj = build_row_adjacency(derivs);
+
+    let mut differential = vec![false; ncols];
+    for cols in &der_adj {
+        for &j in cols {
+            differential[j] = true;
+        }
+    }
+
+    let incidence: Vec<Vec<usize>> = var_adj
+        .iter()
+        .zip(&der_adj)
+        .map(|(v, d)| {
+            let mut cols: Vec<usize> = v
+                .iter()
+                .copied()
+                .filter(|&j| !differential[j])
+                .chain(d.iter().copied())
+                .collect();
+            cols.sort_unstable();
+            cols.dedup();
+            cols
+        })
+        .collect();
+
+    let (structure, _) =
+        try_structure_from_row_adjacency(&incidence, ncols, options).map_err(DaeError::Ordering)?;
+
+    Ok(DaeStructure {
+        differential,
+        incidence,
+        structure,
+    })
+}
+
+/// Result of [`pantelides`]: how often each equation is differentiated and which derivative
+/// of which variable its highest derivative is solved for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexReduction {
+    /// Per equation: number of times it is differentiated (`c_i`).
+    pub equation_derivatives: Vec<usize>,
+    /// Per variable: highest derivative order in the reduced system (`d_j`).
+    pub variable_derivatives: Vec<usize>,
+    /// Per equation: `(variable, derivative order)` matched to its highest derivative. The
+    /// matching is perfect on the highest-order variables, one per variable.
+    pub assignment: Vec<(usize, usize)>,
+}
+
+impl IndexReduction {
+    /// Structural index: the largest `c_i`, plus one if some variable stays algebraic
+    /// (`d_j == 0`). 0 for an ODE in explicit form, 1 for a semi-explicit DAE whose
+    /// constraints can be solved for the algebraic variables; a purely algebraic system has
+    /// index 1.
+    pub fn structural_index(&self) -> usize {
+        let max_c = self.equation_derivatives.iter().copied().max().unwrap_or(0);
+        max_c + usize::from(self.variable_derivatives.contains(&0))
+    }
+
+    /// Equations that must be differentiated at least once, in index order.
+    pub fn differentiated_equations(&self) -> Vec<usize> {
+        (0..self.equation_derivatives.len())
+            .filter(|&i| self.equation_derivatives[i] > 0)
+            .collect()
+    }
+}
+
+/// Pantelides' structural index reduction of the first-order DAE `F(x', x) = 0` with
+/// incidence `vars` (`∂F/∂x`) and `derivs` (`∂F/∂x'`), both `equations x variables`.
+///
+/// Equations are differentiated until the highest derivatives of the variables admit a
+/// perfect matching: whenever no augmenting path reaches a free highest-order variable from an
+/// equation, every equation on the failed search is differentiated (adding the next
+/// derivative of each variable it contains) and the search continues from the derivative.
+///
+/// Fails with [`BtfError::DimensionMismatch`] if the patterns differ in shape or are not
+/// square, and with [`BtfError::StructurallySingular`] if no perfect matching exists even with
+/// all occurrences of a variable merged, so no number of differentiations makes the system
+/// solvable; otherwise the algorithm terminates.
+pub fn pantelides<T, R, C, S, T2, R2, C2, S2>(
+    vars: &nalgebra::Matrix<T, R, C, S>,
+    derivs: &nalgebra::Matrix<T2, R2, C2, S2>,
+) -> BtfResult<IndexReduction>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+    T2: nalgebra::Scalar + PartialEq + Default,
+    R2: nalgebra::Dim,
+    C2: nalgebra::Dim,
+    S2: nalgebra::Storage<T2, R2, C2>,
+{
+    if vars.shape() != derivs.shape() {
+        return Err(BtfError::DimensionMismatch {
+            expected: vars.shape(),
+            found: derivs.shape(),
+        });
+    }
+    let (n, ncols) = vars.shape();
+    if n != ncols {
+        return Err(BtfError::DimensionMismatch {
+            expected: (n, n),
+            found: (n, ncols),
+        });
+    }
+    let var_adj = build_row_adjacency(vars);
+    let der_adj = build_row_adjacency(derivs);
+
+    let merged: Vec<Vec<usize>> = var_adj
+        .iter()
+        .zip(&der_adj)
+        .map(|(v, d)| {
+            let mut cols: Vec<usize> = v.iter().chain(d).copied().collect();
+            cols.sort_unstable();
+            cols.dedup();
+            cols
+        })
+        .collect();
+    let rank = hopcroft_karp(&merged, n).size;
+    if rank < n {
+        return Err(BtfError::StructurallySingular { rank, n });
+    }
+
+    let mut graph = DerivativeGraph::new(&var_adj, &der_adj);
+    for k in 0..n {
+        let mut eq = k;
+        while !graph.augment(eq) {
+            eq = graph.differentiate_colored(eq);
+        }
+    }
+    Ok(graph.reduction(n))
+}
+
+/// Pantelides' bipartite graph of equations and variables of every derivative order.
+struct DerivativeGraph {
+    /// Equation -> variables it contains.
+    eqs: Vec<Vec<usize>>,
+    /// Per equation: `(original equation, order)`; per variable likewise.
+    eq_origin: Vec<(usize, usize)>,
+    var_origin: Vec<(usize, usize)>,
+    /// Next derivative of each equation / variable, once created.
+    eq_derivative: Vec<Option<usize>>,
+    var_derivative: Vec<Option<usize>>,
+    /// Variable -> equation it is matched to.
+    assign: Vec<Option<usize>>,
+    colored_eqs: Vec<bool>,
+    colored_vars: Vec<bool>,
+}
+
+impl DerivativeGraph {
+    fn new(var_adj: &[Vec<usize>], der_adj: &[Vec<usize>]) -> Self {
+        let n = var_adj.len();
+        let mut var_origin: Vec<(usize, usize)> = (0..n).map(|j| (j, 0)).collect();
+        let mut var_derivative = vec![None; n];
+        for &j in der_adj.iter().flatten() {
+            if var_derivative[j].is_none() {
+                var_derivative[j] = Some(var_origin.len());
+                var_origin.push((j, 1));
+            }
+        }
+        let var_derivative_len = var_origin.len();
+        var_derivative.resize(var_derivative_len, None);
+
+        let eqs = var_adj
+            .iter()
+            .zip(der_adj)
+            .map(|(v, d)| {
+                let mut cols: Vec<usize> = v
+                    .iter()
+                    .copied()
+                    .chain(d.iter().map(|&j| var_derivative[j].expect("created above")))
+                    .collect();
+                cols.sort_unstable();
+                cols
+            })
+            .collect();
+        Self {
+            eqs,
+            eq_origin: (0..n).map(|i| (i, 0)).collect(),
+            var_origin,
+            eq_derivative: vec![None; n],
+            var_derivative,
+            assign: vec![None; var_derivative_len],
+            colored_eqs: Vec::new(),
+            colored_vars: Vec::new(),
+        }
+    }
+
+    /// Only the highest derivative of each variable takes part in the matching.
+    fn active(&self, v: usize) -> bool {
+        self.var_derivative[v].is_none()
+    }
+
+    /// Search an augmenting path from `root` over active variables, coloring what it visits;
+    /// on success the matching is updated along the path.
+    fn augment(&mut self, root: usize) -> bool {
+        self.colored_eqs = vec![false; self.eqs.len()];
+        self.colored_vars = vec![false; self.var_origin.len()];
+        self.colored_eqs[root] = true;
+        // (equation, next neighbor to try); `path[l]` leads from `stack[l]` to `stack[l + 1]`.
+        let mut stack = vec![(root, 0usize)];
+        let mut path: Vec<usize> = Vec::new();
+        while let Some(&(eq, next)) = stack.last() {
+            if next == 0
+                && let Some(&v) = self.eqs[eq]
+                    .iter()
+                    .find(|&&v| self.active(v) && self.assign[v].is_none())
+            {
+                self.assign[v] = Some(eq);
+                for (level, &u) in path.iter().enumerate() {
+                    self.assign[u] = Some(stack[level].0);
+                }
+                return true;
+            }
+            let step = self.eqs[eq][next..]
+                .iter()
+                .position(|&v| self.active(v) && !self.colored_vars[v]);
+            match step {
+                Some(offset) => {
+                    let v = self.eqs[eq][next + offset];
+                    stack.last_mut().expect("non-empty").1 = next + offset + 1;
+                    self.colored_vars[v] = true;
+                    let owner = self.assign[v].expect("free variables are taken first");
+                    self.colored_eqs[owner] = true;
+                    path.push(v);
+                    stack.push((owner, 0));
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+        false
+    }
+
+    /// Differentiate every colored equation and variable of the last failed search, carry
+    /// the matching over to the derivatives, and return the derivative of `eq`.
+    fn differentiate_colored(&mut self, eq: usize) -> usize {
+        for v in 0..self.colored_vars.len() {
+            if self.colored_vars[v] {
+                let (orig, order) = self.var_origin[v];
+                self.var_derivative[v] = Some(self.var_origin.len());
+                self.var_origin.push((orig, order + 1));
+                self.var_derivative.push(None);
+                self.assign.push(None);
+            }
+        }
+        for e in 0..self.colored_eqs.len() {
+            if self.colored_eqs[e] {
+                // d/dt of an equation contains each of its variables and their derivatives.
+                let mut cols: Vec<usize> = self.eqs[e]
+                    .iter()
+                    .flat_map(|&v| {
+                        let dv = self.var_derivative[v]
+                            .expect("variables of colored equations are differentiated");
+                        [v, dv]
+                    })
+                    .collect();
+                cols.sort_unstable();
+                cols.dedup();
+                let (orig, order) = self.eq_origin[e];
+                self.eq_derivative[e] = Some(self.eqs.len());
+                self.eqs.push(cols);
+                self.eq_origin.push((orig, order + 1));
+                self.eq_derivative.push(None);
+            }
+        }
+        for v in 0..self.colored_vars.len() {
+            if self.colored_vars[v] {
+                let dv = self.var_derivative[v].expect("differentiated above");
+                let owner = self.assign[v].expect("colored variables are matched");
+                self.assign[dv] = self.eq_derivative[owner];
+            }
+        }
+        self.eq_derivative[eq].expect("the search root is colored")
+    }
+
+    fn reduction(&self, n: usize) -> IndexReduction {
+        let mut equation_derivatives = vec![0; n];
+        for &(orig, order) in &self.eq_origin {
+            equation_derivatives[orig] = equation_derivatives[orig].max(order);
+        }
+        let mut variable_derivatives = vec![0; n];
+        for &(orig, order) in &self.var_origin {
+            variable_derivatives[orig] = variable_derivatives[orig].max(order);
+        }
+        let mut assignment = vec![(0, 0); n];
+        for v in (0..self.var_origin.len()).filter(|&v| self.active(v)) {
+            if let Some(eq) = self.assign[v] {
+                assignment[self.eq_origin[eq].0] = self.var_origin[v];
+            }
+        }
+        IndexReduction {
+            equation_derivatives,
+            variable_derivatives,
+            assignment,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::adjacency::build_row_adjacency;
+use crate::augmentation::{alternating_cols, alternating_rows};
+use crate::matching::{Matching, hopcroft_karp};
+use crate::structure_from_row_adjacency;
+
+/// Rows and columns of a coarse Dulmage–Mendelsohn part, both sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DmPart {
+    pub rows: Vec<usize>,
+    pub cols: Vec<usize>,
+}
+
+/// The well-determined square part, split into its irreducible fine blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DmSquare {
+    /// Rows in block order.
+    pub rows: Vec<usize>,
+    /// `cols[k]` is matched to `rows[k]`, so the blocks lie on the diagonal.
+    pub cols: Vec<usize>,
+    /// Sizes of the fine blocks, in solve order.
+    pub block_sizes: Vec<usize>,
+    /// Fine block DAG (forward edges, sorted), as in [`crate::UpperBtfStructure::block_dag`].
+    pub block_dag: Vec<Vec<usize>>,
+}
+
+impl DmSquare {
+    /// `(rows, cols)` of every fine block, in solve order.
+    pub fn blocks(&self) -> Vec<(&[usize], &[usize])> {
+        let mut start = 0;
+        self.block_sizes
+            .iter()
+            .map(|&size| {
+                let range = start..start + size;
+                start += size;
+                (&self.rows[range.clone()], &self.cols[range])
+            })
+            .collect()
+    }
+}
+
+/// Dulmage–Mendelsohn decomposition: the coarse partition into underdetermined, square and
+/// overdetermined parts, with the square part refined into its fine blocks (the output of
+/// MATLAB's `dmperm`).
+///
+/// In the order `under`, `square`, `over` (see [`DmStructure::row_order`]) the permuted matrix
+/// is block upper triangular: square and overdetermined rows have no entries in
+/// underdetermined columns, and overdetermined rows none in square columns. The partition does
+/// not depend on which maximum matching is used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DmStructure {
+    /// Columns reachable from an unmatched column by an alternating path, and their matched
+    /// rows: more variables than equations.
+    pub under: DmPart,
+    /// Everything else: perfectly matched, split into irreducible blocks.
+    pub square: DmSquare,
+    /// Rows reachable from an unmatched row by an alternating path, and their matched
+    /// columns: more equations than variables.
+    pub over: DmPart,
+}
+
+impl DmStructure {
+    /// Structural rank: every row of `under` and `square` and every column of `over` is
+    /// matched.
+    pub fn structural_rank(&self) -> usize {
+        self.under.rows.len() + self.square.rows.len() + self.over.cols.len()
+    }
+
+    /// Rows in the order underdetermined, square (fine block order), overdetermined.
+    pub fn row_order(&self) -> Vec<usize> {
+        [&self.under.rows, &self.square.rows, &self.over.rows]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Columns in the order underdetermined, square (aligned with its rows), overdetermined.
+    pub fn col_order(&self) -> Vec<usize> {
+        [&self.under.cols, &self.square.cols, &self.over.cols]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+/// Coarse and fine Dulmage–Mendelsohn decomposition of the pattern of `mat`.
+///
+/// The fine blocks of the square part are the strongly connected components of its row
+/// dependency graph, ordered like the main analysis.
+pub fn dulmage_mendelsohn<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> DmStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp(&row_adj, mat.ncols());
+    dm_from_matching(&row_adj, mat.ncols(), &matching)
+}
+
+/// [`dulmage_mendelsohn`] of `row_adj` for a given maximum `matching`.
+pub(crate) fn dm_from_matching(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    matching: &Matching,
+) -> DmStructure {
+    let nrows = row_adj.len();
+    let under_cols = alternating_cols(row_adj, ncols, matching);
+    let over_rows = alternating_rows(row_adj, matching);
+    let mut under_rows: Vec<usize> = under_cols
+        .iter()
+        .filter_map(|&j| matching.col_to_row[j])
+        .collect();
+    under_rows.sort_unstable();
+    let mut over_cols: Vec<usize> = over_rows
+        .iter()
+        .filter_map(|&i| matching.row_to_col[i])
+        .collect();
+    over_cols.sort_unstable();
+
+    // Square part: everything outside the two coarse parts, in local indices.
+    let mut in_coarse_row = vec![false; nrows];
+    for &i in under_rows.iter().chain(&over_rows) {
+        in_coarse_row[i] = true;
+    }
+    let mut in_coarse_col = vec![false; ncols];
+    for &j in under_cols.iter().chain(&over_cols) {
+        in_coarse_col[j] = true;
+    }
+    let rows: Vec<usize> = (0..nrows).filter(|&i| !in_coarse_row[i]).collect();
+    let cols: Vec<usize> = (0..ncols).filter(|&j| !in_coarse_col[j]).collect();
+    let mut local_col = vec![None; ncols];
+    for (q, &j) in cols.iter().enumerate() {
+        local_col[j] = Some(q);
+    }
+    let sub: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|&i| row_adj[i].iter().filter_map(|&j| local_col[j]).collect())
+        .collect();
+    let (fine, _) = structure_from_row_adjacency(&sub, cols.len());
+
+    DmStructure {
+        under: DmPart {
+            rows: under_rows,
+            cols: under_cols,
+        },
+        square: DmSquare {
+            rows: fine.row_order.iter().map(|&p| rows[p]).collect(),
+            cols: fine.col_order.iter().map(|&q| cols[q]).collect(),
+            block_sizes: fine.block_sizes,
+            block_dag: fine.block_dag,
+        },
+        over: DmPart {
+            rows: over_rows,
+            cols: over_cols,
+        },
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(feature = "arrayvec")]
+use crate::bounded::CapacityError;
+#[cfg(feature = "download")]
+use crate::suitesparse::MatrixMarketError;
+use crate::{
+    basis::BasisError,
+    csr::CsrError,
+    dae::DaeError,
+    matching::MatchingError,
+    ordering::OrderingError,
+    orientation::Orientation,
+    permutation::{PermError, PermParseError},
+    snapshot::SnapshotError,
+    solve::SolveError,
+};
+
+/// Crate-wide error for fallible APIs.
+///
+/// Module-specific errors convert into it with `?`; new fallible entry points return it
+/// directly instead of panicking or silently falling back.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum BtfError {
+    /// An input does not have the shape the operation requires.
+    #[error(
+        "dimension mismatch: expected {} x {}, found {} x {}",
+        .expected.0, .expected.1, .found.0, .found.1
+    )]
+    DimensionMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// A caller-provided row or column index is outside the matrix.
+    #[error("index {index} out of range for dimension {len}")]
+    IndexOutOfRange { index: usize, len: usize },
+    /// A caller-provided index set lists the same row or column twice.
+    #[error("index {index} selected twice")]
+    DuplicateIndex { index: usize },
+    /// A row/column order or matching is not a valid permutation.
+    #[error("invalid permutation: {0}")]
+    InvalidPermutation(#[source] OrderingError),
+    /// A dependency graph that must be acyclic has a cycle; `nodes` are the nodes on or
+    /// behind it.
+    #[error("graph is not acyclic: {} node(s) on or behind a cycle", .nodes.len())]
+    NotAcyclic { nodes: Vec<usize> },
+    /// Pins in [`BtfOptions`](crate::BtfOptions) cannot be honored by any block triangular
+    /// order of the pattern.
+    #[error("pins cannot be honored: {0}")]
+    InvalidPins(#[source] OrderingError),
+    /// An explicit order is not a permutation of `0..len`.
+    #[error("invalid order: {0}")]
+    InvalidOrder(#[from] PermError),
+    /// A permutation in one-line or cycle notation could not be parsed.
+    #[error("invalid permutation notation: {0}")]
+    PermutationSyntax(#[from] PermParseError),
+    /// The operation needs a structurally nonsingular pattern.
+    #[error("structurally singular: structural rank {rank} of {n}")]
+    StructurallySingular { rank: usize, n: usize },
+    /// A block triangular form encodes a different orientation than the caller expects.
+    #[error("expected {expected:?} block triangular form, found {found:?}")]
+    OrientationMismatch {
+        expected: Orientation,
+        found: Orientation,
+    },
+    /// The caller requested cancellation.
+    #[error("analysis cancelled")]
+    Cancelled,
+    /// A caller-provided capacity bound was exceeded.
+    #[cfg(feature = "arrayvec")]
+    #[error("capacity exceeded: {0}")]
+    CapacityExceeded(#[from] CapacityError),
+    /// A CSR pattern is malformed or too large for its index type.
+    #[error("invalid pattern: {0}")]
+    InvalidPattern(#[from] CsrError),
+    /// Adjacency lists passed to a matching routine are malformed.
+    #[error("invalid adjacency: {0}")]
+    InvalidAdjacency(#[from] MatchingError),
+    /// A Matrix Market file or SuiteSparse collection name is malformed.
+    #[cfg(feature = "download")]
+    #[error("invalid Matrix Market input: {0}")]
+    MatrixMarket(#[from] MatrixMarketError),
+    /// A block factorization or solve failed.
+    #[error("solve failed: {0}")]
+    Solve(#[from] SolveError),
+    /// A candidate basis could not be repaired.
+    #[error("basis repair failed: {0}")]
+    Basis(#[from] BasisError),
+    /// A stored snapshot cannot be read back.
+    #[error("invalid snapshot: {0}")]
+    Snapshot(#[from] SnapshotError),
+    /// Reading or writing a pattern failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// `Result` with [`BtfError`].
+pub type BtfResult<T> = Result<T, BtfError>;
+
+impl From<OrderingError> for BtfError {
+    fn from(err: OrderingError) -> Self {
+        match err {
+            OrderingError::Cycle { nodes } => Self::NotAcyclic { nodes },
+            err if err.is_pin_conflict() => Self::InvalidPins(err),
+            other => Self::InvalidPermutation(other),
+        }
+    }
+}
+
+impl From<DaeError> for BtfError {
+    fn from(err: DaeError) -> Self {
+        match err {
+            DaeError::ShapeMismatch { vars, derivs } => Self::DimensionMismatch {
+                expected: vars,
+                found: derivs,
+            },
+            DaeError::Ordering(err) => err.into(),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use nalgebra::SMatrix;
+
+use crate::UpperBtfStructure;
+use crate::kernels::{self, Containers, RowPattern};
+
+/// Marker for "no row/column" in the fixed-size arrays.
+const NONE: usize = usize::MAX;
+
+/// Block triangular structure of an `N x N` pattern, stored in fixed-size arrays.
+///
+/// Same meaning as [`UpperBtfStructure`]; only the first `num_blocks` entries of
+/// `block_sizes` and `block_dag` are used (the rest are zero).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedBtfStructure<const N: usize> {
+    pub row_order: [usize; N],
+    pub col_order: [usize; N],
+    pub block_sizes: [usize; N],
+    pub num_blocks: usize,
+    pub matching_size: usize,
+    /// Row -> matched column, `None` for unmatched rows.
+    pub row_to_col: [Option<usize>; N],
+    /// Block DAG as bitsets: bit `b` of `block_dag[a]` is set iff block `a` couples to the
+    /// later block `b`.
+    pub block_dag: [u64; N],
+}
+
+impl<const N: usize> FixedBtfStructure<N> {
+    /// Sizes of the diagonal blocks, in solve order.
+    pub fn block_sizes(&self) -> &[usize] {
+        &self.block_sizes[..self.num_blocks]
+    }
+
+    /// Original rows of block `b`.
+    pub fn block_rows(&self, b: usize) -> &[usize] {
+        let start: usize = self.block_sizes[..b].iter().sum();
+        &self.row_order[start..start + self.block_sizes[b]]
+    }
+
+    /// Heap-allocated [`UpperBtfStructure`] with the same contents.
+    pub fn to_structure(&self) -> UpperBtfStructure {
+        UpperBtfStructure {
+            row_order: self.row_order.to_vec(),
+            col_order: self.col_order.to_vec(),
+            block_sizes: self.block_sizes().to_vec(),
+            matching_size: self.matching_size,
+            row_to_col: self.row_to_col.to_vec(),
+            block_dag: self.block_dag[..self.num_blocks]
+                .iter()
+                .map(|&bits| bit_indices(bits).collect())
+                .collect(),
+            block_splits: Vec::new(),
+        }
+    }
+}
+
+/// Set bits of `bits`, in increasing order.
+fn bit_indices(mut bits: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (bits != 0).then(|| {
+            let b = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            b
+        })
+    })
+}
+
+/// Pop the lowest set bit of `bits`.
+fn pop_lowest(bits: &mut u64) -> Option<usize> {
+    (*bits != 0).then(|| {
+        let b = bits.trailing_zeros() as usize;
+        *bits &= *bits - 1;
+        b
+    })
+}
+
+/// Rows as column bitsets; the cursor is the set of columns still to visit.
+impl<const N: usize> RowPattern for [u64; N] {
+    type Cursor = u64;
+
+    fn nrows(&self) -> usize {
+        N
+    }
+
+    fn ncols(&self) -> usize {
+        N
+    }
+
+    fn start(&self, i: usize) -> u64 {
+        self[i]
+    }
+
+    fn next(&self, _: usize, cursor: &mut u64) -> Option<usize> {
+        pop_lowest(cursor)
+    }
+}
+
+/// Stack arrays of length `N`, the only length the fixed-size analysis asks for.
+struct Inline<const N: usize>;
+
+impl<const N: usize> Containers for Inline<N> {
+    type Array<T: Copy> = [T; N];
+
+    fn filled<T: Copy>(value: T, len: usize) -> [T; N] {
+        debug_assert_eq!(len, N);
+        [value; N]
+    }
+}
+
+/// Allocation-free block triangularization of a small fixed-size pattern (`N <= 64`, checked
+/// at compile time).
+///
+/// Rows are `u64` column bitsets and all work arrays live on the stack, so nothing touches
+/// the heap. The pipeline is the default one with [`crate::MatchingAlgorithm::Mc21`] (MC21
+/// over ascending columns, Tarjan SCCs, min-row topological order), and the result equals
+/// [`crate::upper_block_triangular_structure_with_options`] with that matching.
+pub fn upper_block_triangular_structure_fixed<T, const N: usize>(
+    mat: &SMatrix<T, N, N>,
+) -> FixedBtfStructure<N>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+{
+    const { assert!(N <= 64, "fixed-size analysis supports at most 64 rows") };
+
+    let zero = T::default();
+    let mut rows = [0u64; N];
+    for (i, row) in rows.iter_mut().enumerate() {
+        for j in 0..N {
+            if mat[(i, j)] != zero {
+                *row |= 1 << j;
+            }
+        }
+    }
+
+    let (row_to_col, col_to_row, matching_size) = kernels::mc21::<Inline<N>, usize, _>(&rows);
+
+    // Row dependency graph: i -> k if row i touches the column matched to row k.
+    let mut graph = [0u64; N];
+    for (i, out) in graph.iter_mut().enumerate() {
+        for j in bit_indices(rows[i]) {
+            let k = col_to_row[j];
+            if k != NONE && k != i {
+                *out |= 1 << k;
+            }
+        }
+    }
+
+    let (comp_of, num_comps) = kernels::tarjan::<Inline<N>, usize, _>(&rows, &col_to_row);
+
+    // Component keys (min row) and condensation edges.
+    let mut key = [NONE; N];
+    let mut dag = [0u64; N];
+    for i in 0..N {
+        let c = comp_of[i];
+        key[c] = key[c].min(i);
+        for k in bit_indices(graph[i]) {
+            if comp_of[k] != c {
+                dag[c] |= 1 << comp_of[k];
+            }
+        }
+    }
+
+    // Kahn's algorithm, always taking the ready component with the smallest key.
+    let mut indeg = [0usize; N];
+    for &out in &dag[..num_comps] {
+        for c in bit_indices(out) {
+            indeg[c] += 1;
+        }
+    }
+    let mut ready = 0u64;
+    for (c, &d) in indeg[..num_comps].iter().enumerate() {
+        if d == 0 {
+            ready |= 1 << c;
+        }
+    }
+    let mut block_of_comp = [0usize; N];
+    let mut comp_order = [0usize; N];
+    for (b, slot) in comp_order[..num_comps].iter_mut().enumerate() {
+        let c = bit_indices(ready)
+            .min_by_key(|&c| key[c])
+            .expect("condensation is acyclic");
+        ready &= !(1 << c);
+        *slot = c;
+        block_of_comp[c] = b;
+        for d in bit_indices(dag[c]) {
+            indeg[d] -= 1;
+            if indeg[d] == 0 {
+                ready |= 1 << d;
+            }
+        }
+    }
+
+    let mut out = FixedBtfStructure {
+        row_order: [0; N],
+        col_order: [0; N],
+        block_sizes: [0; N],
+        num_blocks: num_comps,
+        matching_size,
+        row_to_col: row_to_col.map(|c| (c != NONE).then_some(c)),
+        block_dag: [0; N],
+    };
+
+    // Rows block by block, ascending within a block; matched columns follow their rows.
+    let mut pos = 0;
+    let mut used_cols = 0u64;
+    for (b, &c) in comp_order[..num_comps].iter().enumerate() {
+        for i in (0..N).filter(|&i| comp_of[i] == c) {
+            out.row_order[pos] = i;
+            pos += 1;
+            out.block_sizes[b] += 1;
+        }
+        for d in bit_indices(dag[c]) {
+            out.block_dag[b] |= 1 << block_of_comp[d];
+        }
+    }
+    let mut ncol = 0;
+    for &i in &out.row_order {
+        if row_to_col[i] != NONE {
+            out.col_order[ncol] = row_to_col[i];
+            used_cols |= 1 << row_to_col[i];
+            ncol += 1;
+        }
+    }
+    for j in 0..N {
+        if used_cols & (1 << j) == 0 {
+            out.col_order[ncol] = j;
+            ncol += 1;
+        }
+    }
+
+    out
+}
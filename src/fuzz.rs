@@ -0,0 +1,94 @@
+//! Fuzzing support (feature `arbitrary`).
+//!
+//! A `cargo-fuzz` target can drive the whole pipeline with:
+//!
+//! ```ignore
+//! fuzz_target!(|input: (ArbitraryPattern, BtfOptions)| {
+//!     let (p, options) = input;
+//!     nalgebra_block_triangularization::fuzz::check_invariants_with_options(&p.0, &options)
+//!         .unwrap();
+//! });
+//! ```
+
+use arbitrary::{Arbitrary, Unstructured};
+use nalgebra::DMatrix;
+
+use crate::permutation::validate_permutation;
+use crate::{BtfOptions, try_upper_block_triangular_structure_with_options};
+
+/// Largest dimension generated by [`ArbitraryPattern`].
+pub const MAX_DIM: usize = 48;
+
+/// A 0/1 sparsity pattern (up to `MAX_DIM x MAX_DIM`, possibly empty or rectangular)
+/// generated from fuzzer input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryPattern(pub DMatrix<u8>);
+
+impl<'a> Arbitrary<'a> for ArbitraryPattern {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let nrows = u.int_in_range(0..=MAX_DIM)?;
+        let ncols = u.int_in_range(0..=MAX_DIM)?;
+        let mut data = Vec::with_capacity(nrows * ncols);
+        for _ in 0..nrows * ncols {
+            // Sparse-ish patterns exercise more interesting block structures than dense ones.
+            data.push(u8::from(u.ratio(1u8, 4)?));
+        }
+        Ok(Self(DMatrix::from_vec(nrows, ncols, data)))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (2, None)
+    }
+}
+
+/// Run the analysis through its panic-free entry points and check the structural invariants
+/// of the result, describing the first violation found.
+pub fn check_invariants(mat: &DMatrix<u8>) -> Result<(), String> {
+    check_invariants_with_options(mat, &BtfOptions::default())
+}
+
+/// [`check_invariants`] for a given set of options (which also implement `Arbitrary`).
+pub fn check_invariants_with_options(
+    mat: &DMatrix<u8>,
+    options: &BtfOptions,
+) -> Result<(), String> {
+    let (nrows, ncols) = mat.shape();
+    let s = match try_upper_block_triangular_structure_with_options(mat, options) {
+        Ok(s) => s,
+        Err(e) if e.is_pin_conflict() => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    validate_permutation(&s.row_order).map_err(|e| format!("row_order: {e}"))?;
+    validate_permutation(&s.col_order).map_err(|e| format!("col_order: {e}"))?;
+    if s.row_order.len() != nrows || s.col_order.len() != ncols {
+        return Err("order lengths do not match the matrix".into());
+    }
+    if s.matching_size > nrows.min(ncols) {
+        return Err(format!("matching size {} too large", s.matching_size));
+    }
+    let covered: usize = s.block_sizes.iter().sum();
+    if nrows > 0 && ncols > 0 && covered != nrows {
+        return Err(format!("blocks cover {covered} of {nrows} rows"));
+    }
+    for (b, out) in s.block_dag.iter().enumerate() {
+        if out.iter().any(|&c| c <= b || c >= s.num_blocks()) {
+            return Err(format!("block_dag edge out of block {b} is not forward"));
+        }
+    }
+
+    // Every nonzero must lie in or above its row's diagonal block.
+    let row_block = s.row_blocks();
+    let col_block = s.col_blocks();
+    if nrows == ncols && s.matching_size == nrows {
+        for i in 0..nrows {
+            for j in 0..ncols {
+                if mat[(i, j)] != 0 && col_block[j].is_some_and(|cb| cb < row_block[i]) {
+                    return Err(format!("entry ({i}, {j}) lies below the block diagonal"));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::adjacency::build_row_adjacency;
+use crate::augmentation::alternating_rows_from;
+use crate::matching::hopcroft_karp;
+
+/// Certificate that the rows of a pattern cannot all be matched: a set of rows whose
+/// combined column support is smaller than the set, violating Hall's condition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HallViolator {
+    /// The violating rows, sorted.
+    pub rows: Vec<usize>,
+    /// Every column with an entry in `rows`, sorted; fewer than `rows`.
+    pub cols: Vec<usize>,
+}
+
+impl HallViolator {
+    /// How many more rows than columns the certificate has (at least 1).
+    pub fn deficiency(&self) -> usize {
+        self.rows.len() - self.cols.len()
+    }
+
+    /// Whether the certificate holds for `mat`: the support of `rows` is exactly `cols` and
+    /// is smaller than `rows`. Out-of-range or duplicate rows make it invalid.
+    pub fn verify<T, R, C, S>(&self, mat: &nalgebra::Matrix<T, R, C, S>) -> bool
+    where
+        T: nalgebra::Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        if self.rows.iter().any(|&i| i >= mat.nrows()) || !self.rows.is_sorted_by(|a, b| a < b) {
+            return false;
+        }
+        let zero = T::default();
+        let support: Vec<usize> = (0..mat.ncols())
+            .filter(|&j| self.rows.iter().any(|&i| mat[(i, j)] != zero))
+            .collect();
+        support == self.cols && support.len() < self.rows.len()
+    }
+}
+
+/// A [`HallViolator`] explaining why not every row of `mat` can be matched, or `None` when
+/// a matching covers all rows.
+///
+/// The certificate is the first row left unmatched by a maximum matching together with every
+/// row reachable from it by alternating paths (row -> column -> the row matched to it). The
+/// columns reached are all matched within the set, so the support has exactly one column
+/// fewer than the rows: the equations in `rows` constrain only the variables in `cols`. The
+/// union over all unmatched rows is the overdetermined part of
+/// [`dulmage_mendelsohn`](crate::dm::dulmage_mendelsohn); for the dual certificate (columns
+/// with too few rows) pass the transpose.
+pub fn hall_violator<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> Option<HallViolator>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp(&row_adj, mat.ncols());
+    let root = matching.row_to_col.iter().position(Option::is_none)?;
+    let rows = alternating_rows_from(&row_adj, &matching, [root]);
+    let mut cols: Vec<usize> = rows
+        .iter()
+        .filter_map(|&i| matching.row_to_col[i])
+        .collect();
+    cols.sort_unstable();
+    Some(HallViolator { rows, cols })
+}
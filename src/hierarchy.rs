@@ -0,0 +1,117 @@
+use nalgebra::{Dyn, PermutationSequence};
+
+use crate::adjacency::build_row_adjacency;
+use crate::components::{BipartiteComponent, bipartite_components, component_problem};
+use crate::error::BtfResult;
+use crate::permutation::permutation_sequence_from_order;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// One connected component of a [`HierarchicalStructure`] with its own block triangular form.
+#[derive(Debug, Clone)]
+pub struct ComponentLevel {
+    /// Original row and column indices of the component, sorted.
+    pub component: BipartiteComponent,
+    /// Position of the component's first row in the composed `row_order`.
+    pub row_offset: usize,
+    /// Position of the component's first column in the composed `col_order`.
+    pub col_offset: usize,
+    /// Analysis of the component alone, in local indices: row `l` is `component.rows[l]` and
+    /// column `l` is `component.cols[l]`.
+    pub structure: UpperBtfStructure,
+}
+
+impl ComponentLevel {
+    /// Original rows of the component's diagonal blocks, in solve order.
+    pub fn block_rows(&self) -> Vec<Vec<usize>> {
+        let mut start = 0;
+        self.structure
+            .block_sizes
+            .iter()
+            .map(|&size| {
+                let rows = self.structure.row_order[start..start + size]
+                    .iter()
+                    .map(|&l| self.component.rows[l])
+                    .collect();
+                start += size;
+                rows
+            })
+            .collect()
+    }
+}
+
+/// Two-level decomposition: connected components of the bipartite row/column graph, then the
+/// block triangular form of each component.
+///
+/// `row_order` and `col_order` compose both levels: components are laid out one after the
+/// other (ordered as by [`bipartite_components`]), each as its own BTF, so `P A Q` is block
+/// diagonal over components and block upper triangular inside each.
+#[derive(Debug, Clone)]
+pub struct HierarchicalStructure {
+    /// Composed row permutation: new position -> original row.
+    pub row_order: Vec<usize>,
+    /// Composed column permutation: new position -> original column.
+    pub col_order: Vec<usize>,
+    /// The components, in layout order.
+    pub components: Vec<ComponentLevel>,
+}
+
+impl HierarchicalStructure {
+    /// Row and column permutations of the composed ordering, as from
+    /// [`upper_triangular_permutations`](crate::upper_triangular_permutations).
+    pub fn permutations(&self) -> (PermutationSequence<Dyn>, PermutationSequence<Dyn>) {
+        (
+            permutation_sequence_from_order(&self.row_order),
+            permutation_sequence_from_order(&self.col_order),
+        )
+    }
+
+    /// Total number of diagonal blocks over all components.
+    pub fn num_blocks(&self) -> usize {
+        self.components
+            .iter()
+            .map(|c| c.structure.num_blocks())
+            .sum()
+    }
+}
+
+/// Split `mat` into connected components, analyze each with `options`, and compose the
+/// orderings.
+///
+/// `options` apply per component: `forbidden`, `priorities` and the pins are translated to its
+/// local indices, and `in_block_order` is the optional third level, e.g.
+/// [`InBlockOrder::MinDegree`](crate::options::InBlockOrder::MinDegree) for a fill-reducing
+/// order inside every diagonal block.
+pub fn hierarchical_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> BtfResult<HierarchicalStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let mut row_order = Vec::with_capacity(row_adj.len());
+    let mut col_order = Vec::with_capacity(mat.ncols());
+    let mut components = Vec::new();
+    for component in bipartite_components(&row_adj, mat.ncols()) {
+        let (local_adj, local_options) = component_problem(&component, &row_adj, options);
+        let (structure, _) =
+            try_structure_from_row_adjacency(&local_adj, component.cols.len(), &local_options)?;
+        let (row_offset, col_offset) = (row_order.len(), col_order.len());
+        row_order.extend(structure.row_order.iter().map(|&l| component.rows[l]));
+        col_order.extend(structure.col_order.iter().map(|&l| component.cols[l]));
+        components.push(ComponentLevel {
+            component,
+            row_offset,
+            col_offset,
+            structure,
+        });
+    }
+    Ok(HierarchicalStructure {
+        row_order,
+        col_order,
+        components,
+    })
+}
@@ -0,0 +1,234 @@
+//! MC21 matching and Tarjan SCCs shared by the lean backends ([`crate::csr`],
+//! [`crate::bounded`], [`crate::fixed`]), which differ only in how rows are stored, the index
+//! width and the containers backing the work arrays.
+
+/// Index type of the work arrays: `u32` keeps the CSR backend compact on huge patterns.
+pub(crate) trait Index: Copy + Ord {
+    /// Marker for an unset entry (unmatched, unvisited).
+    const NONE: Self;
+
+    fn new(i: usize) -> Self;
+
+    fn get(self) -> usize;
+}
+
+impl Index for u32 {
+    const NONE: Self = u32::MAX;
+
+    fn new(i: usize) -> Self {
+        i as u32
+    }
+
+    fn get(self) -> usize {
+        self as usize
+    }
+}
+
+impl Index for usize {
+    const NONE: Self = usize::MAX;
+
+    fn new(i: usize) -> Self {
+        i
+    }
+
+    fn get(self) -> usize {
+        self
+    }
+}
+
+/// Read access to the columns of every row, in ascending order, through a resumable cursor.
+pub(crate) trait RowPattern {
+    /// Position within a row: an entry offset, or the bitset of columns still to visit.
+    type Cursor: Copy + Default;
+
+    fn nrows(&self) -> usize;
+
+    fn ncols(&self) -> usize;
+
+    /// Cursor at the first entry of row `i`.
+    fn start(&self, i: usize) -> Self::Cursor;
+
+    /// Column at `cursor` in row `i`, advancing the cursor; `None` past the last entry.
+    fn next(&self, i: usize, cursor: &mut Self::Cursor) -> Option<usize>;
+}
+
+/// Container family backing the work arrays and results.
+pub(crate) trait Containers {
+    type Array<T: Copy>: AsRef<[T]> + AsMut<[T]>;
+
+    /// Array of length `len` filled with `value`.
+    fn filled<T: Copy>(value: T, len: usize) -> Self::Array<T>;
+}
+
+/// Heap-allocated containers.
+pub(crate) struct Heap;
+
+impl Containers for Heap {
+    type Array<T: Copy> = Vec<T>;
+
+    fn filled<T: Copy>(value: T, len: usize) -> Vec<T> {
+        vec![value; len]
+    }
+}
+
+/// MC21 maximum matching, trying columns in ascending order; mirrors
+/// [`crate::matching::mc21`]. Returns row -> column, column -> row (both [`Index::NONE`] where
+/// unmatched) and the matching size.
+pub(crate) fn mc21<C: Containers, X: Index, P: RowPattern>(
+    pattern: &P,
+) -> (C::Array<X>, C::Array<X>, usize) {
+    let (nrows, ncols) = (pattern.nrows(), pattern.ncols());
+    let mut row_to_col = C::filled(X::NONE, nrows);
+    let mut col_to_row = C::filled(X::NONE, ncols);
+    // Columns not yet tried for a cheap assignment.
+    let mut cheap = C::filled(P::Cursor::default(), nrows);
+    let mut visited = C::filled(X::NONE, nrows);
+    // DFS frames: (row, columns left to descend through, column descended through last).
+    let mut stack = C::filled((X::NONE, P::Cursor::default(), X::NONE), nrows);
+    let mut size = 0;
+    {
+        let row_to_col = row_to_col.as_mut();
+        let col_to_row = col_to_row.as_mut();
+        let cheap = cheap.as_mut();
+        let visited = visited.as_mut();
+        let stack = stack.as_mut();
+        for (i, cursor) in cheap.iter_mut().enumerate() {
+            *cursor = pattern.start(i);
+        }
+
+        for root in 0..nrows {
+            let mut depth = 1;
+            stack[0] = (X::new(root), pattern.start(root), X::NONE);
+            visited[root] = X::new(root);
+
+            while depth > 0 {
+                let u = stack[depth - 1].0.get();
+                let mut free = None;
+                while let Some(v) = pattern.next(u, &mut cheap[u]) {
+                    if col_to_row[v] == X::NONE {
+                        free = Some(v);
+                        break;
+                    }
+                }
+
+                if let Some(v) = free {
+                    row_to_col[u] = X::new(v);
+                    col_to_row[v] = X::new(u);
+                    for &(w, _, c) in &stack[..depth - 1] {
+                        row_to_col[w.get()] = c;
+                        col_to_row[c.get()] = w;
+                    }
+                    size += 1;
+                    break;
+                }
+
+                let top = &mut stack[depth - 1];
+                let mut next = None;
+                while let Some(v) = pattern.next(u, &mut top.1) {
+                    let w = col_to_row[v];
+                    if w != X::NONE && visited[w.get()] != X::new(root) {
+                        top.2 = X::new(v);
+                        next = Some(w);
+                        break;
+                    }
+                }
+                match next {
+                    Some(w) => {
+                        visited[w.get()] = X::new(root);
+                        stack[depth] = (w, pattern.start(w.get()), X::NONE);
+                        depth += 1;
+                    }
+                    None => depth -= 1,
+                }
+            }
+        }
+    }
+
+    (row_to_col, col_to_row, size)
+}
+
+/// Iterative Tarjan over the implicit row dependency graph (row `i` depends on the row matched
+/// to each of its columns). Returns row -> component and the component count.
+pub(crate) fn tarjan<C: Containers, X: Index, P: RowPattern>(
+    pattern: &P,
+    col_to_row: &[X],
+) -> (C::Array<X>, usize) {
+    let n = pattern.nrows();
+    let mut comp_of = C::filled(X::NONE, n);
+    let mut index = C::filled(X::NONE, n);
+    let mut low = C::filled(X::NONE, n);
+    let mut on_stack = C::filled(false, n);
+    let mut stack = C::filled(X::NONE, n);
+    // Call frames: (row, columns left to visit).
+    let mut call = C::filled((X::NONE, P::Cursor::default()), n);
+    let mut ncomp = 0;
+    {
+        let comp_of = comp_of.as_mut();
+        let index = index.as_mut();
+        let low = low.as_mut();
+        let on_stack = on_stack.as_mut();
+        let stack = stack.as_mut();
+        let call = call.as_mut();
+        let mut sp = 0;
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != X::NONE {
+                continue;
+            }
+            let mut depth = 0;
+            let mut enter = Some(root);
+            loop {
+                if let Some(v) = enter.take() {
+                    index[v] = X::new(next_index);
+                    low[v] = X::new(next_index);
+                    next_index += 1;
+                    stack[sp] = X::new(v);
+                    sp += 1;
+                    on_stack[v] = true;
+                    call[depth] = (X::new(v), pattern.start(v));
+                    depth += 1;
+                }
+
+                let (v, ref mut cursor) = call[depth - 1];
+                let v = v.get();
+                if let Some(c) = pattern.next(v, cursor) {
+                    let w = col_to_row[c];
+                    if w == X::NONE || w.get() == v {
+                        continue;
+                    }
+                    let w = w.get();
+                    if index[w] == X::NONE {
+                        enter = Some(w);
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                // All successors done: close the component rooted at v, then return to the
+                // parent.
+                if low[v] == index[v] {
+                    loop {
+                        sp -= 1;
+                        let w = stack[sp].get();
+                        on_stack[w] = false;
+                        comp_of[w] = X::new(ncomp);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    ncomp += 1;
+                }
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                let parent = call[depth - 1].0.get();
+                low[parent] = low[parent].min(low[v]);
+            }
+        }
+    }
+
+    (comp_of, ncomp)
+}
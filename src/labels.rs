@@ -0,0 +1,177 @@
+use std::fmt;
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// A block triangular structure together with row and column labels (equation and variable
+/// names, or any other per-index metadata).
+///
+/// Labels are indexed by original row/column, like every index in [`UpperBtfStructure`]; the
+/// helpers translate index-based results (blocks, diagnostics, entry lists) back to labels.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabeledStructure<L> {
+    pub structure: UpperBtfStructure,
+    pub row_labels: Vec<L>,
+    pub col_labels: Vec<L>,
+}
+
+impl<L> LabeledStructure<L> {
+    /// Attach labels to an existing structure; fails if the label counts do not match its
+    /// row and column counts.
+    pub fn new(
+        structure: UpperBtfStructure,
+        row_labels: Vec<L>,
+        col_labels: Vec<L>,
+    ) -> BtfResult<Self> {
+        let expected = (structure.row_order.len(), structure.col_order.len());
+        let found = (row_labels.len(), col_labels.len());
+        if expected != found {
+            return Err(BtfError::DimensionMismatch { expected, found });
+        }
+        Ok(Self {
+            structure,
+            row_labels,
+            col_labels,
+        })
+    }
+
+    /// Row labels in the permuted (solve) order.
+    pub fn ordered_row_labels(&self) -> impl Iterator<Item = &L> {
+        self.structure
+            .row_order
+            .iter()
+            .map(|&i| &self.row_labels[i])
+    }
+
+    /// Column labels in the permuted order.
+    pub fn ordered_col_labels(&self) -> impl Iterator<Item = &L> {
+        self.structure
+            .col_order
+            .iter()
+            .map(|&j| &self.col_labels[j])
+    }
+
+    /// `(row labels, column labels)` of every diagonal block, in solve order. As in
+    /// [`UpperBtfStructure::solve_order_blocks`], a block's columns are those matched to its
+    /// rows.
+    pub fn block_labels(&self) -> Vec<(Vec<&L>, Vec<&L>)> {
+        let (rows, cols) = self.structure.solve_order_blocks();
+        rows.iter()
+            .zip(&cols)
+            .map(|(rows, cols)| (self.rows(rows), self.cols(cols)))
+            .collect()
+    }
+
+    /// Labels of the given original rows (e.g. a diagnostic's row set).
+    pub fn rows(&self, rows: &[usize]) -> Vec<&L> {
+        rows.iter().map(|&i| &self.row_labels[i]).collect()
+    }
+
+    /// Labels of the given original columns.
+    pub fn cols(&self, cols: &[usize]) -> Vec<&L> {
+        cols.iter().map(|&j| &self.col_labels[j]).collect()
+    }
+
+    /// Labels of the given `(row, col)` entries (e.g. suggested or critical entries).
+    pub fn entries(&self, entries: &[(usize, usize)]) -> Vec<(&L, &L)> {
+        entries
+            .iter()
+            .map(|&(i, j)| (&self.row_labels[i], &self.col_labels[j]))
+            .collect()
+    }
+}
+
+impl<L: fmt::Display> LabeledStructure<L> {
+    /// One line describing block `block` in terms of labels, e.g.
+    /// `block 3 contains equations {f2, f7} and variables {x1, x4}`.
+    ///
+    /// Panics if `block` is out of range.
+    pub fn describe_block(&self, block: usize) -> String {
+        let (rows, cols) = self.structure.solve_order_blocks();
+        self.block_line(block, &rows[block], &cols[block])
+    }
+
+    fn block_line(&self, block: usize, rows: &[usize], cols: &[usize]) -> String {
+        let variables = if cols.is_empty() {
+            "no variables".to_string()
+        } else {
+            format!("variables {}", label_set(&self.cols(cols)))
+        };
+        format!(
+            "block {block} contains equations {} and {variables}",
+            label_set(&self.rows(rows))
+        )
+    }
+}
+
+/// Human-readable report: one [`LabeledStructure::describe_block`] line per block in solve
+/// order, then the unmatched equations and variables, if any.
+impl<L: fmt::Display> fmt::Display for LabeledStructure<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rows, cols) = self.structure.solve_order_blocks();
+        let mut lines: Vec<String> = (0..rows.len())
+            .map(|b| self.block_line(b, &rows[b], &cols[b]))
+            .collect();
+        let unmatched_rows = self.structure.unmatched_rows();
+        if !unmatched_rows.is_empty() {
+            lines.push(format!(
+                "unmatched equations {}",
+                label_set(&self.rows(&unmatched_rows))
+            ));
+        }
+        let unmatched_cols = self.structure.unmatched_cols();
+        if !unmatched_cols.is_empty() {
+            lines.push(format!(
+                "unmatched variables {}",
+                label_set(&self.cols(&unmatched_cols))
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// `{a, b, c}`.
+fn label_set<L: fmt::Display>(labels: &[&L]) -> String {
+    let items: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+    format!("{{{}}}", items.join(", "))
+}
+
+/// Analyze `mat` with the default options and attach `row_labels` and `col_labels`.
+pub fn analyze_labeled<L, T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    row_labels: Vec<L>,
+    col_labels: Vec<L>,
+) -> BtfResult<LabeledStructure<L>>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    analyze_labeled_with_options(mat, row_labels, col_labels, &BtfOptions::default())
+}
+
+/// [`analyze_labeled`] with explicit [`BtfOptions`].
+pub fn analyze_labeled_with_options<L, T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    row_labels: Vec<L>,
+    col_labels: Vec<L>,
+    options: &BtfOptions,
+) -> BtfResult<LabeledStructure<L>>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let expected = mat.shape();
+    let found = (row_labels.len(), col_labels.len());
+    if expected != found {
+        return Err(BtfError::DimensionMismatch { expected, found });
+    }
+    let row_adj = build_row_adjacency(mat);
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, mat.ncols(), options)?;
+    LabeledStructure::new(structure, row_labels, col_labels)
+}
@@ -1,16 +1,94 @@
 pub mod adjacency;
+pub mod analysis;
+pub mod approximate;
+pub mod arrowhead;
+pub mod augmentation;
+pub mod basis;
+pub mod bordered;
+#[cfg(feature = "arrayvec")]
+pub mod bounded;
+pub mod canonical;
+pub mod components;
+pub mod critical;
+pub mod csr;
+pub mod cycles;
+pub mod dae;
+pub mod dm;
+pub mod error;
+pub mod fixed;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+pub mod hall;
+pub mod hierarchy;
+mod kernels;
+pub mod labels;
 pub mod matching;
+pub mod nested;
+pub mod newton;
+pub mod nullspace;
+pub mod options;
 pub mod ordering;
+pub mod orientation;
+pub mod pencil;
 pub mod permutation;
+#[cfg(feature = "plotters")]
+pub mod plot;
+pub mod precondition;
+pub mod profile;
+pub mod prune;
+pub mod reachability;
+pub mod rectangular;
+pub mod redundancy;
+#[cfg(feature = "html")]
+pub mod report;
 pub mod scc;
+pub mod schedule;
+#[cfg(feature = "parallel-std")]
+mod scoped;
+pub mod scramble;
+pub mod semiring;
+pub mod session;
+pub mod singularity;
+pub mod snapshot;
+pub mod solve;
+#[cfg(feature = "numeric-diagnostics")]
+pub mod spectral;
+pub mod split;
+pub mod spy;
+pub mod stats;
+pub mod submatrix;
+#[cfg(feature = "download")]
+pub mod suitesparse;
+pub mod supervariable;
+pub mod symmetrize;
+pub mod tearing;
+#[cfg(feature = "metrics")]
+pub mod telemetry;
 
-use nalgebra::{Dyn, Matrix, PermutationSequence, Scalar, Storage};
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+
+use nalgebra::{
+    ClosedAddAssign, ClosedMulAssign, DMatrix, DVector, Dyn, Matrix, PermutationSequence, Scalar,
+    Storage,
+};
 
 use adjacency::{build_row_adjacency, build_row_dependency_graph};
-use matching::hopcroft_karp;
-use ordering::{col_order_from_row_order, topo_sort_with_tiebreak};
+use matching::{Matching, hopcroft_karp, hopcroft_karp_unchecked, mc21, priority_matching};
+use ordering::{
+    OrderingError, col_order_from_row_order_strict, cuthill_mckee,
+    max_adjacent_coupling_topo_order, min_coupling_topo_order, minimum_degree,
+    topo_sort_with_tiebreak,
+};
 use permutation::permutation_sequence_from_order;
-use scc::{condensation_dag, scc_id_map, tarjan_scc};
+use scc::{Condensation, condense, condense_trimmed};
+
+pub use error::{BtfError, BtfResult};
+pub use options::{
+    BlockOrder, BtfOptions, InBlockOrder, MatchingAlgorithm, Pin, UnmatchedPlacement,
+};
+pub use orientation::{BlockTriangularStructure, Orientation, block_triangular_structure};
 
 /// Return row/column permutations P, Q (as PermutationSequence) such that:
 ///     U = P * mat * Q
@@ -37,28 +115,256 @@ where
     C: nalgebra::Dim,
     S: Storage<T, R, C>,
 {
-    let structure = upper_block_triangular_structure(mat);
+    try_upper_triangular_permutations(mat).expect(ACYCLIC_CONDENSATION)
+}
+
+/// Fallible variant of [`upper_triangular_permutations`]; see
+/// [`try_upper_block_triangular_structure`].
+pub fn try_upper_triangular_permutations<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+) -> Result<(PermutationSequence<Dyn>, PermutationSequence<Dyn>), OrderingError>
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    let structure = try_upper_block_triangular_structure(mat)?;
 
     let prow = permutation_sequence_from_order(&structure.row_order);
     let pcol = permutation_sequence_from_order(&structure.col_order);
 
-    (prow, pcol)
+    Ok((prow, pcol))
+}
+
+/// Row dependency graph of `mat` under a maximum matching: edge `i -> k` if row `i` has a
+/// nonzero in the column matched to row `k` (no self-loops). Adjacency lists are sorted.
+///
+/// This is the graph whose SCCs are the diagonal blocks of
+/// [`upper_block_triangular_structure`]; the same matching is used.
+pub fn row_dependency_graph<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> Vec<Vec<usize>>
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp(&row_adj, mat.ncols());
+    build_row_dependency_graph(&row_adj, &matching.col_to_row)
+}
+
+/// SCCs of the row dependency graph (see [`row_dependency_graph`]), the row -> component map,
+/// and the condensation DAG, without the ordering and permutation stages.
+pub fn condensation<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> Condensation
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    condense(&row_dependency_graph(mat))
+}
+
+/// Whether `mat` is square and its pattern has a perfect matching (structural rank `n`).
+///
+/// Returns `false` on an empty row or column before any matching is computed.
+pub fn is_structurally_nonsingular<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> bool
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    mat.nrows() == mat.ncols() && perfect_matching(&build_row_adjacency(mat), mat.ncols()).is_some()
+}
+
+/// Whether `mat` is square, structurally nonsingular, and its block triangular form is a
+/// single diagonal block, i.e. no row and column permutation makes it block triangular.
+///
+/// Cheaper than [`upper_block_triangular_structure`]: for `n >= 2` a row or column with fewer
+/// than two entries decides the answer without a matching, and strong connectivity of the
+/// row dependency graph is checked by one forward and one backward search instead of a full
+/// SCC decomposition. The empty matrix has no blocks and is not irreducible.
+pub fn is_irreducible<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> bool
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    let n = mat.nrows();
+    if n != mat.ncols() || n == 0 {
+        return false;
+    }
+    let row_adj = build_row_adjacency(mat);
+    if n == 1 {
+        return !row_adj[0].is_empty();
+    }
+    // A row or column with a single entry is a 1x1 block of its own (none: singular).
+    let mut col_count = vec![0usize; n];
+    for cols in &row_adj {
+        if cols.len() < 2 {
+            return false;
+        }
+        for &j in cols {
+            col_count[j] += 1;
+        }
+    }
+    if col_count.iter().any(|&c| c < 2) {
+        return false;
+    }
+    let Some(matching) = perfect_matching(&row_adj, n) else {
+        return false;
+    };
+    let graph = build_row_dependency_graph(&row_adj, &matching.col_to_row);
+    let mut reversed = vec![Vec::new(); n];
+    for (i, succ) in graph.iter().enumerate() {
+        for &k in succ {
+            reversed[k].push(i);
+        }
+    }
+    reaches_all(&graph) && reaches_all(&reversed)
+}
+
+/// Whether `mat` has the strong Hall property: full structural column rank, and every set of
+/// `k` columns, `0 < k < ncols`, has entries in at least `k + 1` rows.
+///
+/// For such matrices the fill of a QR factorization (and of LU with partial pivoting) is
+/// determined by the column order alone, so a column ordering suffices; otherwise the
+/// Dulmage–Mendelsohn blocks should be factored separately. A square matrix has the
+/// property iff it [`is_irreducible`].
+///
+/// With a maximum matching `M`, a column set `S` has exactly `|S|` rows iff those are its
+/// matched rows, i.e. `S` is closed under alternating paths (column, row, column matched to
+/// that row) and no column of `S` touches an unmatched row. Columns that reach such a column
+/// lie in no tight set; every other column's alternating-path closure is tight, so the
+/// property holds iff either all columns reach an unmatched row or the alternating-path graph
+/// is strongly connected.
+pub fn has_strong_hall_property<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> bool
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    if nrows < ncols {
+        return false;
+    }
+    if ncols == 0 {
+        return true;
+    }
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp_unchecked(&row_adj, ncols);
+    if matching.size < ncols {
+        return false;
+    }
+
+    // Alternating-path graph on columns: j -> k if a row of column j is matched to k.
+    let mut graph = vec![Vec::new(); ncols];
+    let mut reversed = vec![Vec::new(); ncols];
+    let mut free_cols = Vec::new();
+    for (r, cols) in row_adj.iter().enumerate() {
+        match matching.row_to_col[r] {
+            Some(k) => {
+                for &j in cols.iter().filter(|&&j| j != k) {
+                    graph[j].push(k);
+                    reversed[k].push(j);
+                }
+            }
+            None => free_cols.extend(cols.iter().copied()),
+        }
+    }
+    if free_cols.is_empty() {
+        return reaches_all(&graph) && reaches_all(&reversed);
+    }
+    // Every column must reach a column touching an unmatched row.
+    let mut seen = vec![false; ncols];
+    let mut stack = Vec::new();
+    for j in free_cols {
+        if !std::mem::replace(&mut seen[j], true) {
+            stack.push(j);
+        }
+    }
+    while let Some(k) = stack.pop() {
+        for &j in &reversed[k] {
+            if !std::mem::replace(&mut seen[j], true) {
+                stack.push(j);
+            }
+        }
+    }
+    !seen.contains(&false)
+}
+
+/// Perfect matching of a square pattern, or `None` (early on an empty row or column).
+fn perfect_matching(row_adj: &[Vec<usize>], n: usize) -> Option<Matching> {
+    let mut col_hit = vec![false; n];
+    for cols in row_adj {
+        if cols.is_empty() {
+            return None;
+        }
+        for &j in cols {
+            col_hit[j] = true;
+        }
+    }
+    if col_hit.contains(&false) {
+        return None;
+    }
+    let matching = hopcroft_karp_unchecked(row_adj, n);
+    (matching.size == n).then_some(matching)
+}
+
+/// Whether every node of `graph` is reachable from node 0.
+fn reaches_all(graph: &[Vec<usize>]) -> bool {
+    let mut seen = vec![false; graph.len()];
+    seen[0] = true;
+    let mut count = 1;
+    let mut stack = vec![0];
+    while let Some(v) = stack.pop() {
+        for &w in &graph[v] {
+            if !seen[w] {
+                seen[w] = true;
+                count += 1;
+                if count == graph.len() {
+                    return true;
+                }
+                stack.push(w);
+            }
+        }
+    }
+    count == graph.len()
 }
 
 /// Extra structure you can print for diagnostics.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UpperBtfStructure {
     /// New position -> old row index
     pub row_order: Vec<usize>,
     /// New position -> old col index
     pub col_order: Vec<usize>,
-    /// Sizes of diagonal SCC blocks, in order.
+    /// Sizes of diagonal SCC blocks, in order: the number of rows of each block, which
+    /// partition `row_order`. For singular and rectangular patterns a block can have fewer
+    /// columns than rows; see [`Self::block_col_sizes`].
     pub block_sizes: Vec<usize>,
     /// Size of maximum matching.
     pub matching_size: usize,
+    /// The matching (transversal) the structure was built from: original row -> matched
+    /// original column, `None` for unmatched rows. Matched pairs sit on the block diagonal.
+    pub row_to_col: Vec<Option<usize>>,
+    /// Block-level dependency DAG: `block_dag[b]` lists the (later) blocks that block `b`
+    /// couples to, i.e. the nonzero off-diagonal blocks in block row `b`. Sorted.
+    pub block_dag: Vec<Vec<usize>>,
+    /// Blocks reordered into bordered form by [`BtfOptions::max_block_size`], by increasing
+    /// block index. Empty unless that option is set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_splits: Vec<split::BlockSplit>,
 }
 
 /// Compute the ordering + block sizes (useful for printing block separators).
+///
+/// Panics if an internal invariant is violated (see [`try_upper_block_triangular_structure`]).
 pub fn upper_block_triangular_structure<T, R, C, S>(mat: &Matrix<T, R, C, S>) -> UpperBtfStructure
 where
     T: Scalar + PartialEq + Default,
@@ -66,62 +372,628 @@ where
     C: nalgebra::Dim,
     S: Storage<T, R, C>,
 {
-    let nrows = mat.nrows();
-    let ncols = mat.ncols();
+    try_upper_block_triangular_structure(mat).expect(ACYCLIC_CONDENSATION)
+}
+
+/// Fallible variant of [`upper_block_triangular_structure`].
+///
+/// The condensation of the row dependency graph is acyclic by construction and the matching
+/// is consistent, so an error here means an internal invariant was violated; it is reported
+/// rather than silently producing a non-triangular ordering.
+pub fn try_upper_block_triangular_structure<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+) -> Result<UpperBtfStructure, OrderingError>
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    try_upper_block_triangular_structure_with_options(mat, &BtfOptions::default())
+}
+
+/// [`upper_block_triangular_structure`] with explicit [`BtfOptions`].
+///
+/// Panics if the pins in `options` cannot be honored (see [`BtfOptions::pin_first`]); use
+/// [`try_upper_block_triangular_structure_with_options`] to handle that as an error.
+pub fn upper_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> UpperBtfStructure
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    match try_upper_block_triangular_structure_with_options(mat, options) {
+        Ok(structure) => structure,
+        Err(err) if err.is_pin_conflict() => panic!("pins in BtfOptions cannot be honored: {err}"),
+        Err(err) => panic!("{ACYCLIC_CONDENSATION}: {err}"),
+    }
+}
+
+/// Fallible variant of [`upper_block_triangular_structure_with_options`].
+pub fn try_upper_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> Result<UpperBtfStructure, OrderingError>
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let row_adj = build_row_adjacency(mat);
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, mat.ncols(), options)?;
+    #[cfg(feature = "metrics")]
+    telemetry::record_analysis(started.elapsed(), &structure);
+    Ok(structure)
+}
+
+/// Analyze the `shape = (nrows, ncols)` submatrix of `mat` starting at `start = (row, col)` in
+/// place, through a view instead of a copy.
+///
+/// Indices in the result are local to the submatrix: add `start.0` to rows and `start.1` to
+/// columns for indices into `mat`. Panics, like [`Matrix::view`], if the submatrix does not
+/// fit in `mat`.
+pub fn submatrix_structure<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+    start: (usize, usize),
+    shape: (usize, usize),
+) -> UpperBtfStructure
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    submatrix_structure_with_options(mat, start, shape, &BtfOptions::default())
+        .expect(ACYCLIC_CONDENSATION)
+}
+
+/// [`submatrix_structure`] with explicit [`BtfOptions`] (in submatrix-local indices).
+pub fn submatrix_structure_with_options<T, R, C, S>(
+    mat: &Matrix<T, R, C, S>,
+    start: (usize, usize),
+    shape: (usize, usize),
+    options: &BtfOptions,
+) -> BtfResult<UpperBtfStructure>
+where
+    T: Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: Storage<T, R, C>,
+{
+    Ok(try_upper_block_triangular_structure_with_options(
+        &mat.view(start, shape),
+        options,
+    )?)
+}
+
+pub(crate) const ACYCLIC_CONDENSATION: &str =
+    "BTF invariant violated: condensation must be acyclic and the matching consistent";
+
+/// [`try_structure_from_row_adjacency`] with default options.
+pub(crate) fn structure_from_row_adjacency(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+) -> (UpperBtfStructure, Matching) {
+    try_structure_from_row_adjacency(row_adj, ncols, &BtfOptions::default())
+        .expect(ACYCLIC_CONDENSATION)
+}
+
+/// Run the full BTF pipeline on a row adjacency list, also returning the matching used.
+///
+/// Shared by the public entry points and by analyses of derived patterns (components,
+/// subsystems, transposes), so it records only the matching; entry points record the analysis.
+pub(crate) fn try_structure_from_row_adjacency(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    options: &BtfOptions,
+) -> Result<(UpperBtfStructure, Matching), OrderingError> {
+    let matching = matching_with_options(row_adj, ncols, options);
+    let structure = structure_from_matching(row_adj, ncols, options, &matching)?;
+    Ok((structure, matching))
+}
+
+/// The maximum matching selected by `options` (`matching`, `forbidden`, `priorities`).
+pub(crate) fn matching_with_options(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    options: &BtfOptions,
+) -> Matching {
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let matching = compute_matching(row_adj, ncols, options);
+    #[cfg(feature = "metrics")]
+    telemetry::record_matching(
+        telemetry::matching_algorithm(options),
+        started.elapsed(),
+        &matching,
+    );
+    matching
+}
+
+fn compute_matching(row_adj: &[Vec<usize>], ncols: usize, options: &BtfOptions) -> Matching {
+    let match_adj = matching_adjacency(row_adj, options);
+    if !options.priorities.is_empty() {
+        let mut classes: Vec<((usize, usize), u8)> = options
+            .priorities
+            .iter()
+            .map(|&(i, j, p)| ((i, j), p))
+            .collect();
+        classes.sort_unstable();
+        let priority = |i: usize, j: usize| {
+            let k = classes.partition_point(|&(e, _)| e < (i, j));
+            classes
+                .get(k)
+                .filter(|&&(e, _)| e == (i, j))
+                .map_or(0, |&(_, p)| p)
+        };
+        priority_matching(&match_adj, ncols, priority)
+    } else {
+        match options.matching {
+            MatchingAlgorithm::HopcroftKarp => hopcroft_karp_unchecked(&match_adj, ncols),
+            MatchingAlgorithm::Mc21 => mc21(&match_adj, ncols),
+        }
+    }
+}
+
+/// `row_adj` without the entries `options` forbids the matching to use.
+pub(crate) fn matching_adjacency<'a>(
+    row_adj: &'a [Vec<usize>],
+    options: &BtfOptions,
+) -> Cow<'a, [Vec<usize>]> {
+    if options.forbidden.is_empty() {
+        Cow::Borrowed(row_adj)
+    } else {
+        Cow::Owned(without_entries(row_adj, &options.forbidden))
+    }
+}
+
+/// The ordering steps of the pipeline (dependency graph, SCCs, block and in-block order) for
+/// a given matching of `row_adj`, which need not be maximum.
+pub(crate) fn structure_from_matching(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    options: &BtfOptions,
+    matching: &Matching,
+) -> Result<UpperBtfStructure, OrderingError> {
+    let (row_graph, condensation) = condense_matching(row_adj, matching);
+    order_condensation(row_adj, ncols, options, matching, &row_graph, &condensation)
+}
+
+/// Row dependency graph of `matching` and its condensation, with every SCC sorted.
+pub(crate) fn condense_matching(
+    row_adj: &[Vec<usize>],
+    matching: &Matching,
+) -> (Vec<Vec<usize>>, Condensation) {
+    // Row dependency graph: i -> k if row i touches a column matched to row k.
+    let row_graph = build_row_dependency_graph(row_adj, &matching.col_to_row);
+
+    // SCCs on row_graph define diagonal blocks; their condensation DAG orders the blocks.
+    let mut condensation = condense_trimmed(&row_graph);
+
+    // Deterministic in-SCC ordering: sort each component once, in place.
+    for comp in &mut condensation.sccs {
+        comp.sort_unstable();
+    }
+    (row_graph, condensation)
+}
+
+/// The ordering steps of the pipeline after [`condense_matching`]: in-block order, block
+/// order, unmatched placement, amalgamation and splitting.
+pub(crate) fn order_condensation(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    options: &BtfOptions,
+    matching: &Matching,
+    row_graph: &[Vec<usize>],
+    condensation: &Condensation,
+) -> Result<UpperBtfStructure, OrderingError> {
+    let nrows = row_adj.len();
 
     // Trivial cases.
     if nrows == 0 || ncols == 0 {
-        return UpperBtfStructure {
+        let structure = UpperBtfStructure {
             row_order: (0..nrows).collect(),
             col_order: (0..ncols).collect(),
             block_sizes: Vec::new(),
             matching_size: 0,
+            row_to_col: matching.row_to_col.clone(),
+            block_dag: Vec::new(),
+            block_splits: Vec::new(),
         };
+        return Ok(structure);
     }
 
-    let row_adj = build_row_adjacency(mat);
-    let matching = hopcroft_karp(&row_adj, ncols);
-
-    // Row dependency graph: i -> k if row i touches a column matched to row k.
-    let row_graph = build_row_dependency_graph(&row_adj, &matching.col_to_row);
-
-    // SCCs on row_graph define diagonal blocks.
-    let sccs = tarjan_scc(&row_graph);
-
-    // Condensation DAG of SCCs.
-    let comp_of = scc_id_map(&sccs, nrows);
-    let dag = condensation_dag(&row_graph, &comp_of, sccs.len());
+    let Condensation { sccs, comp_of, dag } = condensation;
+    let mut sccs = sccs.clone();
 
     // Tie-break key per SCC for deterministic topo order: min row index inside SCC.
-    let scc_key: Vec<usize> = sccs
-        .iter()
-        .map(|comp| comp.iter().copied().min().unwrap_or(usize::MAX))
-        .collect();
+    let scc_key: Vec<usize> = sccs.iter().map(|comp| comp[0]).collect();
+
+    match options.in_block_order {
+        InBlockOrder::RowIndex => {}
+        InBlockOrder::MinBandwidth => {
+            for comp in &mut sccs {
+                order_block_min_bandwidth(comp, row_graph);
+            }
+        }
+        InBlockOrder::MinDegree => {
+            for comp in &mut sccs {
+                order_block_min_degree(comp, row_graph);
+            }
+        }
+        InBlockOrder::MatchedColumn => {
+            for comp in &mut sccs {
+                comp.sort_unstable_by_key(|&r| (matching.row_to_col[r], r));
+            }
+        }
+    }
 
     // Topologically order SCC DAG so edges go "forward" -> yields upper block triangular.
-    let scc_order = topo_sort_with_tiebreak(&dag, &scc_key);
+    let scc_order = match options.block_order {
+        BlockOrder::MinRow => topo_sort_with_tiebreak(dag, &scc_key)?,
+        BlockOrder::MinCoupling => {
+            min_coupling_topo_order(&weighted_block_dag(row_graph, comp_of, dag.len()), &scc_key)?
+        }
+        BlockOrder::AdjacentCoupling => max_adjacent_coupling_topo_order(
+            &weighted_block_dag(row_graph, comp_of, dag.len()),
+            &scc_key,
+        )?,
+        BlockOrder::SmallestFirst => {
+            let key = |cid: usize| (sccs[cid].len(), scc_key[cid]);
+            topo_sort_with_tiebreak(dag, &rank_by(sccs.len(), |&a, &b| key(a).cmp(&key(b))))?
+        }
+        BlockOrder::LargestFirst => {
+            let key = |cid: usize| (Reverse(sccs[cid].len()), scc_key[cid]);
+            topo_sort_with_tiebreak(dag, &rank_by(sccs.len(), |&a, &b| key(a).cmp(&key(b))))?
+        }
+        BlockOrder::MinDisplacement => {
+            // Mean original index of the block's rows and matched columns, as (sum, count).
+            let mean: Vec<(u128, u128)> = sccs
+                .iter()
+                .map(|comp| {
+                    let cols = comp.iter().filter_map(|&r| matching.row_to_col[r]);
+                    let count = comp.len() + cols.clone().count();
+                    let sum = comp.iter().sum::<usize>() + cols.sum::<usize>();
+                    (sum as u128, count as u128)
+                })
+                .collect();
+            let rank = rank_by(sccs.len(), |&a, &b| {
+                let ((sa, na), (sb, nb)) = (mean[a], mean[b]);
+                (sa * nb).cmp(&(sb * na)).then(scc_key[a].cmp(&scc_key[b]))
+            });
+            topo_sort_with_tiebreak(dag, &rank)?
+        }
+    };
 
-    // Build row_order from SCC order, with deterministic in-SCC ordering.
+    let is_unmatched = |cid: usize| matching.row_to_col[sccs[cid][0]].is_none();
+    let scc_order = place_unmatched_rows(scc_order, dag, is_unmatched, options.unmatched);
+    let scc_order = pin_blocks(scc_order, condensation, matching, options)?;
+
+    // Build row_order from SCC order.
     let mut row_order = Vec::with_capacity(nrows);
     let mut block_sizes = Vec::with_capacity(sccs.len());
     for &cid in &scc_order {
-        let mut comp = sccs[cid].clone();
-        comp.sort_unstable();
-        block_sizes.push(comp.len());
-        row_order.extend(comp);
+        block_sizes.push(sccs[cid].len());
+        row_order.extend_from_slice(&sccs[cid]);
     }
 
     // Column order: matched columns in the same order as their rows, then unmatched columns.
-    let col_order = col_order_from_row_order(&row_order, &matching.row_to_col, ncols);
+    let col_order = col_order_from_row_order_strict(&row_order, &matching.row_to_col, ncols)?;
+    let col_order = place_unmatched_cols(
+        col_order,
+        &row_order,
+        &block_sizes,
+        row_adj,
+        matching,
+        options.unmatched,
+    );
 
-    UpperBtfStructure {
+    // Re-express the condensation DAG in block (solve order) numbering.
+    let mut block_of_scc = vec![0usize; sccs.len()];
+    for (b, &cid) in scc_order.iter().enumerate() {
+        block_of_scc[cid] = b;
+    }
+    let mut block_dag = vec![Vec::new(); sccs.len()];
+    for (cid, out) in dag.iter().enumerate() {
+        let b = block_of_scc[cid];
+        block_dag[b] = out.iter().map(|&c| block_of_scc[c]).collect();
+        block_dag[b].sort_unstable();
+    }
+
+    let (block_sizes, block_dag) =
+        merge_small_blocks(block_sizes, &block_dag, options.min_block_size);
+
+    let mut structure = UpperBtfStructure {
         row_order,
         col_order,
         block_sizes,
         matching_size: matching.size,
+        row_to_col: matching.row_to_col.clone(),
+        block_dag,
+        block_splits: Vec::new(),
+    };
+    split::split_oversized_blocks(&mut structure, row_adj, options.max_block_size);
+    Ok(structure)
+}
+
+/// SCC DAG weighted by coupling nonzeros: each row-graph edge between two SCCs counts one.
+fn weighted_block_dag(
+    row_graph: &[Vec<usize>],
+    comp_of: &[usize],
+    ncomp: usize,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut weighted: Vec<BTreeMap<usize, usize>> = vec![BTreeMap::new(); ncomp];
+    for (i, out) in row_graph.iter().enumerate() {
+        for &k in out {
+            if comp_of[i] != comp_of[k] {
+                *weighted[comp_of[i]].entry(comp_of[k]).or_default() += 1;
+            }
+        }
+    }
+    weighted
+        .into_iter()
+        .map(|m| m.into_iter().collect())
+        .collect()
+}
+
+/// Rank of each of `n` SCCs under `cmp`, for use as the tie-break key of the topological sort.
+fn rank_by(n: usize, cmp: impl FnMut(&usize, &usize) -> Ordering) -> Vec<usize> {
+    let mut sorted: Vec<usize> = (0..n).collect();
+    sorted.sort_by(cmp);
+    let mut rank = vec![0usize; n];
+    for (k, &cid) in sorted.iter().enumerate() {
+        rank[cid] = k;
+    }
+    rank
+}
+
+/// Merge consecutive blocks into groups of at least `min_size` rows (see
+/// [`BtfOptions::min_block_size`]) and re-express the block DAG on the groups. Merging
+/// consecutive blocks keeps every edge forward or inside a group.
+fn merge_small_blocks(
+    block_sizes: Vec<usize>,
+    block_dag: &[Vec<usize>],
+    min_size: usize,
+) -> (Vec<usize>, Vec<Vec<usize>>) {
+    if min_size <= 1 {
+        return (block_sizes, block_dag.to_vec());
+    }
+    let mut group_of = Vec::with_capacity(block_sizes.len());
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut open = false;
+    for &size in &block_sizes {
+        if !open {
+            sizes.push(0);
+        }
+        *sizes.last_mut().expect("a group is open") += size;
+        group_of.push(sizes.len() - 1);
+        open = *sizes.last().expect("a group is open") < min_size;
+    }
+    if open && sizes.len() > 1 {
+        let last = sizes.pop().expect("at least two groups");
+        *sizes.last_mut().expect("at least one group") += last;
+        for g in &mut group_of {
+            *g = (*g).min(sizes.len() - 1);
+        }
+    }
+
+    let mut dag = vec![Vec::new(); sizes.len()];
+    for (b, out) in block_dag.iter().enumerate() {
+        let g = group_of[b];
+        dag[g].extend(out.iter().map(|&c| group_of[c]).filter(|&h| h != g));
+    }
+    for out in &mut dag {
+        out.sort_unstable();
+        out.dedup();
+    }
+    (sizes, dag)
+}
+
+/// Move the SCCs of unmatched rows (always singletons without predecessors) within the
+/// topological `scc_order` according to `placement`.
+pub(crate) fn place_unmatched_rows(
+    scc_order: Vec<usize>,
+    dag: &[Vec<usize>],
+    is_unmatched: impl Fn(usize) -> bool,
+    placement: UnmatchedPlacement,
+) -> Vec<usize> {
+    let (unmatched, rest): (Vec<usize>, Vec<usize>) =
+        scc_order.iter().partition(|&&cid| is_unmatched(cid));
+    match placement {
+        UnmatchedPlacement::End => scc_order,
+        UnmatchedPlacement::Start => unmatched.into_iter().chain(rest).collect(),
+        UnmatchedPlacement::Adjacent => {
+            let mut pos = vec![usize::MAX; dag.len()];
+            for (p, &cid) in rest.iter().enumerate() {
+                pos[cid] = p;
+            }
+            // Anchor: position of the first successor in `rest` (all successors are matched).
+            let mut before: Vec<Vec<usize>> = vec![Vec::new(); rest.len() + 1];
+            for &cid in &unmatched {
+                let anchor = dag[cid].iter().map(|&c| pos[c]).min();
+                before[anchor.unwrap_or(rest.len())].push(cid);
+            }
+            let mut order = Vec::with_capacity(scc_order.len());
+            for (p, group) in before.into_iter().enumerate() {
+                order.extend(group);
+                order.extend(rest.get(p));
+            }
+            order
+        }
     }
 }
 
+/// Stable partition of the topological `scc_order` into the blocks pinned first, the unpinned
+/// blocks and the blocks pinned last by `options`, failing if that breaks the topological
+/// order.
+fn pin_blocks(
+    mut scc_order: Vec<usize>,
+    condensation: &Condensation,
+    matching: &Matching,
+    options: &BtfOptions,
+) -> Result<Vec<usize>, OrderingError> {
+    if options.pin_first.is_empty() && options.pin_last.is_empty() {
+        return Ok(scc_order);
+    }
+    let Condensation { sccs, comp_of, dag } = condensation;
+    let pinned = |pins: &[Pin]| -> Result<Vec<bool>, OrderingError> {
+        let mut flags = vec![false; dag.len()];
+        for &pin in pins {
+            let row = match pin {
+                Pin::Row(r) => Some(r),
+                Pin::Col(c) => match matching.col_to_row.get(c) {
+                    Some(None) => return Err(OrderingError::PinnedUnmatchedColumn { col: c }),
+                    Some(&r) => r,
+                    None => None,
+                },
+            };
+            if let Some(&cid) = row.and_then(|r| comp_of.get(r)) {
+                flags[cid] = true;
+            }
+        }
+        Ok(flags)
+    };
+    let (first, last) = (pinned(&options.pin_first)?, pinned(&options.pin_last)?);
+
+    // 0: pinned first, 1: unpinned, 2: pinned last. SCCs are sorted, so comp[0] names them.
+    let mut group = Vec::with_capacity(dag.len());
+    for cid in 0..dag.len() {
+        group.push(match (first[cid], last[cid]) {
+            (true, true) => {
+                return Err(OrderingError::ConflictingPins {
+                    block: sccs[cid][0],
+                });
+            }
+            (true, false) => 0,
+            (false, false) => 1,
+            (false, true) => 2,
+        });
+    }
+    for (u, out) in dag.iter().enumerate() {
+        if let Some(&v) = out.iter().find(|&&v| group[u] > group[v]) {
+            return Err(OrderingError::PinsAgainstOrder {
+                before: sccs[u][0],
+                after: sccs[v][0],
+            });
+        }
+    }
+    scc_order.sort_by_key(|&cid| group[cid]);
+    Ok(scc_order)
+}
+
+/// Move the unmatched columns (trailing in `col_order`) according to `placement`.
+pub(crate) fn place_unmatched_cols(
+    col_order: Vec<usize>,
+    row_order: &[usize],
+    block_sizes: &[usize],
+    row_adj: &[Vec<usize>],
+    matching: &Matching,
+    placement: UnmatchedPlacement,
+) -> Vec<usize> {
+    let (matched, unmatched) = col_order.split_at(matching.size);
+    match placement {
+        UnmatchedPlacement::End => col_order,
+        UnmatchedPlacement::Start => unmatched.iter().chain(matched).copied().collect(),
+        UnmatchedPlacement::Adjacent => {
+            let mut block_of_row = vec![0usize; row_order.len()];
+            let mut start = 0;
+            for (b, &size) in block_sizes.iter().enumerate() {
+                for &r in &row_order[start..start + size] {
+                    block_of_row[r] = b;
+                }
+                start += size;
+            }
+            // Anchor: last block with an entry in the column.
+            let mut last_block = vec![None; matching.col_to_row.len()];
+            for (i, cols) in row_adj.iter().enumerate() {
+                for &j in cols {
+                    let b = block_of_row[i];
+                    last_block[j] = Some(last_block[j].map_or(b, |l: usize| l.max(b)));
+                }
+            }
+            let mut after: Vec<Vec<usize>> = vec![Vec::new(); block_sizes.len() + 1];
+            for &j in unmatched {
+                after[last_block[j].unwrap_or(block_sizes.len())].push(j);
+            }
+            let mut order = Vec::with_capacity(col_order.len());
+            let mut start = 0;
+            for (b, &size) in block_sizes.iter().enumerate() {
+                for &r in &row_order[start..start + size] {
+                    order.extend(matching.row_to_col[r]);
+                }
+                order.extend(&after[b]);
+                start += size;
+            }
+            order.extend(&after[block_sizes.len()]);
+            order
+        }
+    }
+}
+
+/// Copy of `row_adj` with the given `(row, col)` entries removed.
+fn without_entries(row_adj: &[Vec<usize>], entries: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut sorted = entries.to_vec();
+    sorted.sort_unstable();
+    row_adj
+        .iter()
+        .enumerate()
+        .map(|(i, cols)| {
+            cols.iter()
+                .copied()
+                .filter(|&j| sorted.binary_search(&(i, j)).is_err())
+                .collect()
+        })
+        .collect()
+}
+
+/// Reorder the (sorted) rows of one SCC by Cuthill–McKee on its symmetrized induced subgraph.
+fn order_block_min_bandwidth(comp: &mut [usize], row_graph: &[Vec<usize>]) {
+    if comp.len() <= 2 {
+        return;
+    }
+    let rows: Vec<usize> = cuthill_mckee(&symmetrized_block_graph(comp, row_graph))
+        .into_iter()
+        .map(|li| comp[li])
+        .collect();
+    comp.copy_from_slice(&rows);
+}
+
+/// Reorder the (sorted) rows of one SCC by minimum degree on its symmetrized induced subgraph.
+fn order_block_min_degree(comp: &mut [usize], row_graph: &[Vec<usize>]) {
+    if comp.len() <= 2 {
+        return;
+    }
+    let rows: Vec<usize> = minimum_degree(&symmetrized_block_graph(comp, row_graph))
+        .into_iter()
+        .map(|li| comp[li])
+        .collect();
+    comp.copy_from_slice(&rows);
+}
+
+/// Induced subgraph of the (sorted) rows `comp`, symmetrized, in local indices.
+fn symmetrized_block_graph(comp: &[usize], row_graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let local = |r: usize| comp.binary_search(&r).ok();
+    let mut adj = vec![Vec::new(); comp.len()];
+    for (li, &r) in comp.iter().enumerate() {
+        for lk in row_graph[r].iter().filter_map(|&k| local(k)) {
+            adj[li].push(lk);
+            adj[lk].push(li);
+        }
+    }
+    for nbrs in &mut adj {
+        nbrs.sort_unstable();
+        nbrs.dedup();
+    }
+    adj
+}
+
 impl UpperBtfStructure {
     /// Returns the `row_order` and `col_order` partitioned into blocks according to `block_sizes`;
     /// that is, returns a vector of `(row_indices, col_indices)` for each block.
@@ -140,4 +1012,332 @@ impl UpperBtfStructure {
 
         blocks
     }
+
+    /// Original row and column indices of every block, in solve order: `(rows, cols)` with
+    /// `rows[b]` and `cols[b]` listing block `b` in permuted order.
+    ///
+    /// `cols[b]` holds the columns matched to `rows[b]`, aligned with them, so an unmatched row
+    /// forms a block with no columns and unmatched columns belong to no block. Unlike
+    /// [`Self::block_indices`], this never slices `col_order` by row positions, so it is
+    /// correct for singular and rectangular structures too.
+    pub fn solve_order_blocks(&self) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        let mut rows = Vec::with_capacity(self.num_blocks());
+        let mut cols = Vec::with_capacity(self.num_blocks());
+        let mut start = 0;
+        for &size in &self.block_sizes {
+            let block = &self.row_order[start..start + size];
+            start += size;
+            cols.push(block.iter().filter_map(|&r| self.row_to_col[r]).collect());
+            rows.push(block.to_vec());
+        }
+        (rows, cols)
+    }
+
+    /// Number of diagonal blocks.
+    pub fn num_blocks(&self) -> usize {
+        self.block_sizes.len()
+    }
+
+    /// Canonical identifier of every block, in solve order: the smallest original row index
+    /// in the block.
+    ///
+    /// Unlike the positional block index, a block's identifier depends only on its own rows,
+    /// so it survives changes to the block order and to unrelated parts of the pattern, which
+    /// makes it a stable cache key for per-block data. Rows partition the blocks, so
+    /// identifiers are unique.
+    pub fn block_ids(&self) -> Vec<usize> {
+        let mut start = 0;
+        self.block_sizes
+            .iter()
+            .map(|&size| {
+                let id = self.row_order[start..start + size]
+                    .iter()
+                    .copied()
+                    .min()
+                    .expect("blocks are non-empty");
+                start += size;
+                id
+            })
+            .collect()
+    }
+
+    /// Reverse lookup of [`Self::block_ids`]: block identifier -> block index.
+    pub fn block_id_lookup(&self) -> BTreeMap<usize, usize> {
+        self.block_ids()
+            .into_iter()
+            .enumerate()
+            .map(|(b, id)| (id, b))
+            .collect()
+    }
+
+    /// Size of the largest diagonal block (0 if there are no blocks).
+    pub fn largest_block_size(&self) -> usize {
+        self.block_sizes.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Number of 1x1 diagonal blocks.
+    pub fn singleton_block_count(&self) -> usize {
+        self.block_sizes.iter().filter(|&&size| size == 1).count()
+    }
+
+    /// Whether every diagonal block is 1x1, i.e. the permuted matrix is upper triangular.
+    /// Vacuously true when there are no blocks.
+    pub fn is_fully_triangular(&self) -> bool {
+        self.block_sizes.iter().all(|&size| size == 1)
+    }
+
+    /// Original column index -> row matched to it, or `None` for unmatched columns (inverse
+    /// of `row_to_col`).
+    pub fn col_to_row(&self) -> Vec<Option<usize>> {
+        let mut col_to_row = vec![None; self.col_order.len()];
+        for (r, &c) in self.row_to_col.iter().enumerate() {
+            if let Some(c) = c {
+                col_to_row[c] = Some(r);
+            }
+        }
+        col_to_row
+    }
+
+    /// Matched `(row, col)` pairs in solve order, i.e. the diagonal of the permuted matrix.
+    pub fn matched_pairs(&self) -> Vec<(usize, usize)> {
+        self.row_order
+            .iter()
+            .filter_map(|&r| self.row_to_col[r].map(|c| (r, c)))
+            .collect()
+    }
+
+    /// Number of rows of every block, in solve order (`block_sizes`; consecutive runs of
+    /// `row_order`).
+    pub fn block_row_sizes(&self) -> &[usize] {
+        &self.block_sizes
+    }
+
+    /// Number of columns of every block, in solve order: the columns matched to the block's
+    /// rows. Equal to [`Self::block_row_sizes`] for structurally nonsingular patterns; a block
+    /// holding unmatched rows has fewer columns than rows (none for an unmatched row on its
+    /// own), and unmatched columns belong to no block.
+    pub fn block_col_sizes(&self) -> Vec<usize> {
+        let mut start = 0;
+        self.block_sizes
+            .iter()
+            .map(|&size| {
+                let rows = &self.row_order[start..start + size];
+                start += size;
+                rows.iter()
+                    .filter(|&&r| self.row_to_col[r].is_some())
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Position in `col_order` of the first column of every block, in solve order. Together
+    /// with [`Self::block_col_sizes`] this locates the diagonal blocks of `P A Q` along the
+    /// columns, which differs from the row position once unmatched rows or columns sit
+    /// between blocks. A block without columns starts right after the previous block.
+    pub(crate) fn block_col_starts(&self) -> Vec<usize> {
+        let col_to_row = self.col_to_row();
+        let mut starts = Vec::with_capacity(self.num_blocks());
+        let mut p = 0;
+        for size in self.block_col_sizes() {
+            if size > 0 {
+                while col_to_row[self.col_order[p]].is_none() {
+                    p += 1;
+                }
+            }
+            starts.push(p);
+            p += size;
+        }
+        starts
+    }
+
+    /// Overdetermined region: rows left unmatched (surplus equations), in `row_order` order.
+    pub fn unmatched_rows(&self) -> Vec<usize> {
+        self.row_order
+            .iter()
+            .copied()
+            .filter(|&r| self.row_to_col[r].is_none())
+            .collect()
+    }
+
+    /// Underdetermined region: columns left unmatched (free variables), in `col_order`
+    /// order. Where they sit in `col_order` depends on [`BtfOptions::unmatched`].
+    pub fn unmatched_cols(&self) -> Vec<usize> {
+        let col_to_row = self.col_to_row();
+        self.col_order
+            .iter()
+            .copied()
+            .filter(|&c| col_to_row[c].is_none())
+            .collect()
+    }
+
+    /// Original row index -> index of the diagonal block containing it.
+    pub fn row_blocks(&self) -> Vec<usize> {
+        let mut block_of = vec![0usize; self.row_order.len()];
+        let mut start = 0;
+        for (b, &size) in self.block_sizes.iter().enumerate() {
+            for &r in &self.row_order[start..start + size] {
+                block_of[r] = b;
+            }
+            start += size;
+        }
+        block_of
+    }
+
+    /// Original column index -> index of the diagonal block containing it, or `None` for
+    /// columns that fall outside every block (unmatched columns).
+    ///
+    /// A matched column belongs to the block of its row, wherever
+    /// [`BtfOptions::unmatched`] placed the unmatched rows and columns.
+    pub fn col_blocks(&self) -> Vec<Option<usize>> {
+        let row_block = self.row_blocks();
+        let mut block_of = vec![None; self.col_order.len()];
+        for (r, &c) in self.row_to_col.iter().enumerate() {
+            if let Some(c) = c {
+                block_of[c] = Some(row_block[r]);
+            }
+        }
+        block_of
+    }
+
+    /// Mask in original indices: `(i, j)` is true iff position `(i, j)` lands inside a diagonal
+    /// block after permutation. Masking `A` with it gives the block-diagonal part; the remaining
+    /// nonzeros are the strictly upper coupling.
+    pub fn diagonal_block_mask(&self) -> DMatrix<bool> {
+        let row_block = self.row_blocks();
+        let col_block = self.col_blocks();
+        DMatrix::from_fn(self.row_order.len(), self.col_order.len(), |i, j| {
+            col_block[j] == Some(row_block[i])
+        })
+    }
+
+    /// `(block row, block col)` of original entry `(i, j)` in the permuted matrix.
+    ///
+    /// Unmatched trailing columns lie right of every block and map to block column
+    /// `num_blocks()`. Entries with equal components are intra-block ("loop") entries; the
+    /// rest are couplings. This is O(n) per call; use [`Self::block_pairs`] for many entries.
+    ///
+    /// Panics if `i` or `j` is out of range.
+    pub fn block_pair_of(&self, i: usize, j: usize) -> (usize, usize) {
+        let col_block = self.col_blocks()[j].unwrap_or(self.num_blocks());
+        (self.row_blocks()[i], col_block)
+    }
+
+    /// [`Self::block_pair_of`] for every nonzero of `mat`, as `((i, j), (block_row, block_col))`
+    /// in row-major order.
+    ///
+    /// Panics if `mat` has a different shape than the analyzed matrix.
+    pub fn block_pairs<T, R, C, S>(
+        &self,
+        mat: &Matrix<T, R, C, S>,
+    ) -> Vec<((usize, usize), (usize, usize))>
+    where
+        T: Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: Storage<T, R, C>,
+    {
+        assert_eq!(
+            mat.shape(),
+            (self.row_order.len(), self.col_order.len()),
+            "pattern shape does not match the structure"
+        );
+        let row_block = self.row_blocks();
+        let col_block = self.col_blocks();
+        build_row_adjacency(mat)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, cols)| {
+                let (row_block, col_block) = (&row_block, &col_block);
+                cols.iter().map(move |&j| {
+                    let bj = col_block[j].unwrap_or(self.num_blocks());
+                    ((i, j), (row_block[i], bj))
+                })
+            })
+            .collect()
+    }
+
+    /// Fill-reducing column order for the coupling region of `mat`: the entries outside the
+    /// diagonal blocks (strictly upper blocks and unmatched columns).
+    ///
+    /// Lists the original columns holding at least one coupling entry, ordered by
+    /// [`column_minimum_degree`](ordering::column_minimum_degree) on the coupling entries
+    /// alone, for a sparse QR or LU of the coupling region in bordered solvers. Complements
+    /// [`BtfOptions::in_block_order`], which orders inside the diagonal blocks.
+    ///
+    /// Panics if `mat` has a different shape than the analyzed matrix.
+    pub fn coupling_col_order<T, R, C, S>(&self, mat: &Matrix<T, R, C, S>) -> Vec<usize>
+    where
+        T: Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: Storage<T, R, C>,
+    {
+        let mut coupling = vec![Vec::new(); self.row_order.len()];
+        let mut touched = vec![false; self.col_order.len()];
+        for ((i, j), (bi, bj)) in self.block_pairs(mat) {
+            if bi != bj {
+                coupling[i].push(j);
+                touched[j] = true;
+            }
+        }
+        ordering::column_minimum_degree(&coupling, self.col_order.len())
+            .into_iter()
+            .filter(|&j| touched[j])
+            .collect()
+    }
+
+    /// `y = A x` traversed block row by block row in solve order, visiting only the diagonal
+    /// block, the coupling blocks listed in `block_dag` and the unmatched columns; the empty
+    /// blocks in between are skipped. Zero is `T::default()`, as for patterns.
+    ///
+    /// `mat` must have the pattern the structure was computed from (or a subset of it): other
+    /// entries are not visited. Panics if `mat` or `x` has a different shape than the analyzed
+    /// matrix.
+    pub fn gemv<T, R, C, S>(&self, mat: &Matrix<T, R, C, S>, x: &DVector<T>) -> DVector<T>
+    where
+        T: Scalar + Default + ClosedAddAssign + ClosedMulAssign,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: Storage<T, R, C>,
+    {
+        let (nrows, ncols) = (self.row_order.len(), self.col_order.len());
+        assert_eq!(
+            mat.shape(),
+            (nrows, ncols),
+            "matrix shape does not match the structure"
+        );
+        assert_eq!(x.len(), ncols, "vector length does not match the structure");
+
+        // Columns grouped by the block of their matched row; unmatched columns belong to none.
+        let row_block = self.row_blocks();
+        let mut block_cols = vec![Vec::new(); self.num_blocks()];
+        let mut unmatched_cols = Vec::new();
+        let col_to_row = self.col_to_row();
+        for &c in &self.col_order {
+            match col_to_row[c] {
+                Some(r) => block_cols[row_block[r]].push(c),
+                None => unmatched_cols.push(c),
+            }
+        }
+
+        let mut y = DVector::from_element(nrows, T::default());
+        let mut start = 0;
+        for (b, &size) in self.block_sizes.iter().enumerate() {
+            let cols = std::iter::once(&block_cols[b])
+                .chain(self.block_dag[b].iter().map(|&c| &block_cols[c]))
+                .chain(std::iter::once(&unmatched_cols));
+            let rows = &self.row_order[start..start + size];
+            start += size;
+            for group in cols {
+                for &r in rows {
+                    let mut acc = y[r].clone();
+                    for &c in group {
+                        acc += mat[(r, c)].clone() * x[c].clone();
+                    }
+                    y[r] = acc;
+                }
+            }
+        }
+        y
+    }
 }
@@ -1,4 +1,9 @@
-use std::collections::VecDeque;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    fmt,
+    time::{Duration, Instant},
+};
 
 #[derive(Debug, Clone)]
 pub struct Matching {
@@ -10,7 +15,106 @@ pub struct Matching {
 /// Hopcroft–Karp maximum bipartite matching.
 /// Left side: rows (0..adj.len()).
 /// Right side: columns (0..n_right).
+///
+/// Panics with a description of the first offending entry if an adjacency list names a
+/// column `>= n_right`; see [`try_hopcroft_karp`] for untrusted input.
 pub fn hopcroft_karp(adj: &[Vec<usize>], n_right: usize) -> Matching {
+    try_hopcroft_karp(adj, n_right).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// [`hopcroft_karp`] returning an error for malformed adjacency lists instead of panicking.
+pub fn try_hopcroft_karp(adj: &[Vec<usize>], n_right: usize) -> Result<Matching, MatchingError> {
+    validate_adjacency(adj, n_right)?;
+    Ok(hopcroft_karp_unchecked(adj, n_right))
+}
+
+/// [`hopcroft_karp`] without validating the adjacency lists, for callers that built them from
+/// a matrix of known shape. Out-of-range columns still panic on indexing, just without a
+/// useful message, and may do so after arbitrary work.
+pub fn hopcroft_karp_unchecked(adj: &[Vec<usize>], n_right: usize) -> Matching {
+    hopcroft_karp_budgeted(adj, n_right, &MatchingBudget::default()).matching
+}
+
+/// Why adjacency lists do not describe a bipartite graph with `n_right` columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchingError {
+    /// `adj[row]` contains `col`, which is not below `n_right`.
+    ColumnOutOfRange {
+        row: usize,
+        col: usize,
+        n_right: usize,
+    },
+}
+
+impl fmt::Display for MatchingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ColumnOutOfRange { row, col, n_right } => write!(
+                f,
+                "row {row} lists column {col}, out of range for {n_right} columns"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatchingError {}
+
+/// Check that every column in `adj` is below `n_right` (the precondition of every matching
+/// routine in this module).
+pub fn validate_adjacency(adj: &[Vec<usize>], n_right: usize) -> Result<(), MatchingError> {
+    for (row, cols) in adj.iter().enumerate() {
+        if let Some(&col) = cols.iter().find(|&&col| col >= n_right) {
+            return Err(MatchingError::ColumnOutOfRange { row, col, n_right });
+        }
+    }
+    Ok(())
+}
+
+/// Limits on the work [`hopcroft_karp_budgeted`] may do. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MatchingBudget {
+    /// Maximum number of phases (BFS layering plus a maximal set of shortest augmenting paths).
+    pub max_phases: Option<usize>,
+    /// Wall-clock limit, checked between phases and periodically within a phase.
+    pub max_time: Option<Duration>,
+}
+
+/// Result of [`hopcroft_karp_budgeted`].
+#[derive(Debug, Clone)]
+pub struct BudgetedMatching {
+    pub matching: Matching,
+    /// Whether the matching is known to be maximum (the search ran to completion).
+    pub exact: bool,
+    /// Number of phases started.
+    pub phases: usize,
+}
+
+/// [`hopcroft_karp`] stopped early once `budget` is exhausted.
+///
+/// Every intermediate matching is valid, just possibly not maximum. After `k` complete phases
+/// all augmenting paths are longer than `2k - 1` edges, so the matching has at least
+/// `k / (k + 1)` of the maximum size: one phase already gives a maximal matching, at least half
+/// the maximum. `exact` is only set when the final phase found no augmenting path.
+pub fn hopcroft_karp_budgeted(
+    adj: &[Vec<usize>],
+    n_right: usize,
+    budget: &MatchingBudget,
+) -> BudgetedMatching {
+    hopcroft_karp_interruptible(adj, n_right, budget, || false)
+}
+
+/// [`hopcroft_karp_budgeted`] that also stops as soon as `stop` returns true, polled where the
+/// clock is checked.
+pub(crate) fn hopcroft_karp_interruptible(
+    adj: &[Vec<usize>],
+    n_right: usize,
+    budget: &MatchingBudget,
+    stop: impl Fn() -> bool,
+) -> BudgetedMatching {
+    // Check the clock once per this many DFS roots within a phase.
+    const CLOCK_INTERVAL: usize = 1024;
+
     let n_left = adj.len();
     let mut row_to_col = vec![None; n_left];
     let mut col_to_row = vec![None; n_right];
@@ -18,21 +122,45 @@ pub fn hopcroft_karp(adj: &[Vec<usize>], n_right: usize) -> Matching {
     let inf = i32::MAX / 4;
     let mut dist = vec![inf; n_left];
 
+    let started = Instant::now();
+    let out_of_time = || {
+        stop()
+            || budget
+                .max_time
+                .is_some_and(|limit| started.elapsed() >= limit)
+    };
     let mut matching_size = 0;
-    while bfs(n_left, adj, &row_to_col, &col_to_row, &mut dist, inf) {
+    let mut phases = 0;
+    let mut exact = false;
+    'phases: loop {
+        if budget.max_phases.is_some_and(|max| phases >= max) || out_of_time() {
+            break;
+        }
+        if !bfs(n_left, adj, &row_to_col, &col_to_row, &mut dist, inf) {
+            exact = true;
+            break;
+        }
+        phases += 1;
         for u in 0..n_left {
-            if row_to_col[u].is_none() {
-                if dfs(u, adj, &mut row_to_col, &mut col_to_row, &mut dist, inf) {
-                    matching_size += 1;
-                }
+            if u % CLOCK_INTERVAL == CLOCK_INTERVAL - 1 && out_of_time() {
+                break 'phases;
+            }
+            if row_to_col[u].is_none()
+                && dfs(u, adj, &mut row_to_col, &mut col_to_row, &mut dist, inf)
+            {
+                matching_size += 1;
             }
         }
     }
 
-    Matching {
-        row_to_col,
-        col_to_row,
-        size: matching_size,
+    BudgetedMatching {
+        matching: Matching {
+            row_to_col,
+            col_to_row,
+            size: matching_size,
+        },
+        exact,
+        phases,
     }
 }
 
@@ -102,3 +230,417 @@ fn dfs(
     dist[u] = inf;
     false
 }
+
+/// Maximum transversal by Duff's MC21 algorithm (cheap assignment with lookahead plus
+/// depth-first augmenting paths), as in HSL MC21 and SuiteSparse `maxtrans`.
+///
+/// Rows are processed in order and their columns in adjacency order, so on sorted adjacency
+/// lists the resulting transversal is the one those codes produce. The matching size always
+/// equals that of [`hopcroft_karp`]; only the chosen transversal may differ.
+pub fn mc21(adj: &[Vec<usize>], n_right: usize) -> Matching {
+    let n_left = adj.len();
+    let mut row_to_col = vec![None; n_left];
+    let mut col_to_row = vec![None; n_right];
+
+    // Lookahead pointer: columns before `cheap[u]` were already tried for a cheap assignment.
+    let mut cheap = vec![0usize; n_left];
+    let mut visited = vec![usize::MAX; n_left];
+    // DFS stack of (row, next adjacency position to descend through).
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut matching_size = 0;
+
+    for root in 0..n_left {
+        stack.clear();
+        stack.push((root, 0));
+        visited[root] = root;
+
+        while let Some(&(u, _)) = stack.last() {
+            let cols = &adj[u];
+            let mut free = None;
+            while cheap[u] < cols.len() {
+                let v = cols[cheap[u]];
+                cheap[u] += 1;
+                if col_to_row[v].is_none() {
+                    free = Some(v);
+                    break;
+                }
+            }
+
+            if let Some(v) = free {
+                // Augment: the top row takes the free column, every row below it takes the
+                // column it descended through.
+                let (&(top, _), below) = stack.split_last().expect("stack is non-empty");
+                row_to_col[top] = Some(v);
+                col_to_row[v] = Some(top);
+                for &(w, pos) in below {
+                    let c = adj[w][pos - 1];
+                    row_to_col[w] = Some(c);
+                    col_to_row[c] = Some(w);
+                }
+                matching_size += 1;
+                break;
+            }
+
+            let top = stack.last_mut().expect("stack is non-empty");
+            let mut next = None;
+            while top.1 < cols.len() {
+                let v = cols[top.1];
+                top.1 += 1;
+                if let Some(w) = col_to_row[v]
+                    && visited[w] != root
+                {
+                    next = Some(w);
+                    break;
+                }
+            }
+            match next {
+                Some(w) => {
+                    visited[w] = root;
+                    stack.push((w, 0));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    Matching {
+        row_to_col,
+        col_to_row,
+        size: matching_size,
+    }
+}
+
+/// Number of distinct priority classes understood by [`priority_matching`].
+pub const PRIORITY_CLASSES: usize = 8;
+
+/// Lexicographic cost, most significant (highest priority class) component first.
+type LexCost = [i64; PRIORITY_CLASSES];
+
+fn lex_add(mut a: LexCost, b: &LexCost) -> LexCost {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x += y;
+    }
+    a
+}
+
+fn lex_sub(mut a: LexCost, b: &LexCost) -> LexCost {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x -= y;
+    }
+    a
+}
+
+/// Maximum matching that, among all maximum matchings, lexicographically maximizes the number
+/// of matched entries per priority class, highest class first.
+///
+/// `priority(row, col)` gives the class of each entry of `adj`; classes at or above
+/// [`PRIORITY_CLASSES`] are treated as the highest class. Runs successive shortest augmenting
+/// paths (Dijkstra with potentials) over exact lexicographic costs, so there is no weight
+/// scaling or overflow.
+pub fn priority_matching(
+    adj: &[Vec<usize>],
+    n_right: usize,
+    priority: impl Fn(usize, usize) -> u8,
+) -> Matching {
+    let n_left = adj.len();
+    // Each matched entry costs one in every class except its own, so a minimum-cost matching
+    // of fixed size maximizes the per-class counts lexicographically.
+    let cost = |i: usize, j: usize| {
+        let p = (priority(i, j) as usize).min(PRIORITY_CLASSES - 1);
+        let mut c = [1; PRIORITY_CLASSES];
+        c[PRIORITY_CLASSES - 1 - p] = 0;
+        c
+    };
+
+    let mut row_to_col = vec![None; n_left];
+    let mut col_to_row = vec![None; n_right];
+    let mut pot_r = vec![[0i64; PRIORITY_CLASSES]; n_left];
+    let mut pot_c = vec![[0i64; PRIORITY_CLASSES]; n_right];
+    let mut size = 0;
+
+    loop {
+        let mut dist_r: Vec<Option<LexCost>> = vec![None; n_left];
+        let mut dist_c: Vec<Option<LexCost>> = vec![None; n_right];
+        let mut prev_c = vec![usize::MAX; n_right];
+        let mut done_c = vec![false; n_right];
+        let mut heap = BinaryHeap::new();
+
+        let relax = |i: usize,
+                     di: LexCost,
+                     dist_c: &mut [Option<LexCost>],
+                     prev_c: &mut [usize],
+                     heap: &mut BinaryHeap<Reverse<(LexCost, usize)>>,
+                     row_to_col: &[Option<usize>]| {
+            for &j in &adj[i] {
+                if row_to_col[i] == Some(j) {
+                    continue;
+                }
+                let nd = lex_sub(lex_add(lex_add(di, &cost(i, j)), &pot_r[i]), &pot_c[j]);
+                if dist_c[j].is_none_or(|d| nd < d) {
+                    dist_c[j] = Some(nd);
+                    prev_c[j] = i;
+                    heap.push(Reverse((nd, j)));
+                }
+            }
+        };
+
+        for i in 0..n_left {
+            if row_to_col[i].is_none() {
+                dist_r[i] = Some([0; PRIORITY_CLASSES]);
+                relax(
+                    i,
+                    [0; PRIORITY_CLASSES],
+                    &mut dist_c,
+                    &mut prev_c,
+                    &mut heap,
+                    &row_to_col,
+                );
+            }
+        }
+
+        let mut target = None;
+        while let Some(Reverse((d, j))) = heap.pop() {
+            if done_c[j] || dist_c[j] != Some(d) {
+                continue;
+            }
+            done_c[j] = true;
+            match col_to_row[j] {
+                None => {
+                    target = Some((j, d));
+                    break;
+                }
+                Some(i2) => {
+                    // The matched edge has zero reduced cost.
+                    dist_r[i2] = Some(d);
+                    relax(i2, d, &mut dist_c, &mut prev_c, &mut heap, &row_to_col);
+                }
+            }
+        }
+
+        let Some((target, bound)) = target else {
+            break;
+        };
+
+        // Keep reduced costs nonnegative: add min(dist, bound) to every potential.
+        for (p, d) in pot_r.iter_mut().zip(&dist_r) {
+            *p = lex_add(*p, &d.map_or(bound, |d| d.min(bound)));
+        }
+        for (p, d) in pot_c.iter_mut().zip(&dist_c) {
+            *p = lex_add(*p, &d.map_or(bound, |d| d.min(bound)));
+        }
+
+        let mut j = target;
+        loop {
+            let i = prev_c[j];
+            let next = row_to_col[i];
+            row_to_col[i] = Some(j);
+            col_to_row[j] = Some(i);
+            match next {
+                Some(j2) => j = j2,
+                None => break,
+            }
+        }
+        size += 1;
+    }
+
+    Matching {
+        row_to_col,
+        col_to_row,
+        size,
+    }
+}
+
+/// Breadth-first augmenting path from the free row `root`; on success flips the path and
+/// returns the newly matched column.
+pub(crate) fn augment(
+    root: usize,
+    adj: &[Vec<usize>],
+    row_to_col: &mut [Option<usize>],
+    col_to_row: &mut [Option<usize>],
+) -> Option<usize> {
+    // parent_row[c]: the row from which column `c` was reached.
+    let mut parent_row = vec![None; col_to_row.len()];
+    let mut queue = VecDeque::from([root]);
+    while let Some(u) = queue.pop_front() {
+        for &c in &adj[u] {
+            if parent_row[c].is_some() {
+                continue;
+            }
+            parent_row[c] = Some(u);
+            match col_to_row[c] {
+                Some(next) => queue.push_back(next),
+                None => {
+                    let mut col = c;
+                    loop {
+                        let row = parent_row[col].expect("path column has a parent");
+                        let prev = row_to_col[row];
+                        row_to_col[row] = Some(col);
+                        col_to_row[col] = Some(row);
+                        match prev {
+                            Some(p) => col = p,
+                            None => return Some(c),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Enumerate up to `limit` distinct maximum matchings, starting with [`hopcroft_karp`]'s.
+///
+/// Binary partition over alternating structure: for the first matched entry `e` that some
+/// other maximum matching avoids (found by an augmenting search with `e` removed, i.e. an
+/// alternating cycle or even alternating path through `e`), the matchings containing `e` and
+/// those avoiding it are enumerated separately. Every branch holds at least one matching, so
+/// the work is polynomial per matching produced. A single result means the maximum matching
+/// is unique.
+pub fn enumerate_maximum_matchings(
+    adj: &[Vec<usize>],
+    n_right: usize,
+    limit: usize,
+) -> Vec<Matching> {
+    struct Branch {
+        adj: Vec<Vec<usize>>,
+        fixed: Vec<bool>,
+        matching: Matching,
+    }
+
+    let mut out = Vec::new();
+    if limit == 0 {
+        return out;
+    }
+    let mut stack = vec![Branch {
+        adj: adj.to_vec(),
+        fixed: vec![false; adj.len()],
+        matching: hopcroft_karp(adj, n_right),
+    }];
+
+    while let Some(Branch {
+        mut adj,
+        mut fixed,
+        matching,
+    }) = stack.pop()
+    {
+        let alternative = (0..adj.len()).find_map(|i| {
+            let j = matching.row_to_col[i].filter(|_| !fixed[i])?;
+            let mut without: Vec<Vec<usize>> = adj.clone();
+            without[i].retain(|&c| c != j);
+            let mut row_to_col = matching.row_to_col.clone();
+            let mut col_to_row = matching.col_to_row.clone();
+            row_to_col[i] = None;
+            col_to_row[j] = None;
+            let found = (0..without.len()).any(|r| {
+                row_to_col[r].is_none()
+                    && augment(r, &without, &mut row_to_col, &mut col_to_row).is_some()
+            });
+            found.then(|| {
+                let alt = Matching {
+                    row_to_col,
+                    col_to_row,
+                    size: matching.size,
+                };
+                (i, j, without, alt)
+            })
+        });
+
+        let Some((i, j, without, alt)) = alternative else {
+            out.push(matching);
+            if out.len() == limit {
+                break;
+            }
+            continue;
+        };
+
+        // Branch avoiding (i, j); explored after the one keeping it.
+        stack.push(Branch {
+            adj: without,
+            fixed: fixed.clone(),
+            matching: alt,
+        });
+
+        // Branch keeping (i, j): row i is settled and column j is unavailable to other rows.
+        fixed[i] = true;
+        for (r, cols) in adj.iter_mut().enumerate() {
+            if r != i {
+                cols.retain(|&c| c != j);
+            }
+        }
+        stack.push(Branch {
+            adj,
+            fixed,
+            matching,
+        });
+    }
+
+    out
+}
+
+/// Rows and columns of a bipartite vertex set, both sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VertexSet {
+    pub rows: Vec<usize>,
+    pub cols: Vec<usize>,
+}
+
+impl VertexSet {
+    /// Number of rows plus columns.
+    pub fn len(&self) -> usize {
+        self.rows.len() + self.cols.len()
+    }
+
+    /// Whether the set has no rows and no columns.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Minimum vertex cover of the bipartite graph `adj` by Kőnig's theorem: every entry has its
+/// row or its column in the cover, and the cover has exactly `matching.size` vertices.
+///
+/// With `Z` the rows reachable from the free rows by alternating paths and the columns they
+/// touch, the cover is the rows outside `Z` plus the columns inside it. Removing the cover's
+/// rows and columns leaves no entries, so it names the fewest pivot rows and columns to
+/// delete to empty a pattern. `matching` must be a maximum matching of `adj` (as from
+/// [`hopcroft_karp`]); otherwise the result is not a cover.
+pub fn minimum_vertex_cover(matching: &Matching, adj: &[Vec<usize>]) -> VertexSet {
+    let (in_z_row, in_z_col) = konig_reachable(matching, adj);
+    VertexSet {
+        rows: (0..adj.len()).filter(|&i| !in_z_row[i]).collect(),
+        cols: (0..in_z_col.len()).filter(|&j| in_z_col[j]).collect(),
+    }
+}
+
+/// Maximum independent set of the bipartite graph `adj`, the complement of
+/// [`minimum_vertex_cover`]: no entry joins one of its rows to one of its columns, and it
+/// has `adj.len() + n_right - matching.size` vertices.
+pub fn maximum_independent_set(matching: &Matching, adj: &[Vec<usize>]) -> VertexSet {
+    let (in_z_row, in_z_col) = konig_reachable(matching, adj);
+    VertexSet {
+        rows: (0..adj.len()).filter(|&i| in_z_row[i]).collect(),
+        cols: (0..in_z_col.len()).filter(|&j| !in_z_col[j]).collect(),
+    }
+}
+
+/// Rows and columns reachable from the free rows of `matching` by alternating paths.
+fn konig_reachable(matching: &Matching, adj: &[Vec<usize>]) -> (Vec<bool>, Vec<bool>) {
+    let mut in_row: Vec<bool> = matching.row_to_col.iter().map(Option::is_none).collect();
+    let mut in_col = vec![false; matching.col_to_row.len()];
+    let mut stack: Vec<usize> = (0..adj.len()).filter(|&i| in_row[i]).collect();
+    while let Some(i) = stack.pop() {
+        for &j in &adj[i] {
+            if std::mem::replace(&mut in_col[j], true) {
+                continue;
+            }
+            if let Some(k) = matching.col_to_row[j]
+                && !std::mem::replace(&mut in_row[k], true)
+            {
+                stack.push(k);
+            }
+        }
+    }
+    (in_row, in_col)
+}
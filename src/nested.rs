@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use crate::adjacency::build_row_adjacency;
+use crate::tearing::{TearingOptions, feedback_vertex_set};
+use crate::{UpperBtfStructure, structure_from_row_adjacency};
+
+/// One block of a [`NestedBtfStructure`]: a diagonal block of its parent's (reduced) pattern,
+/// possibly torn and re-triangularized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedBlock {
+    /// Original row indices of the block, in solve order.
+    pub rows: Vec<usize>,
+    /// Original column indices matched to `rows`, aligned with them. Empty for the singleton
+    /// block of an unmatched row.
+    pub cols: Vec<usize>,
+    /// Residual equations torn out of the block (original rows), sorted; empty for leaves.
+    pub residuals: Vec<usize>,
+    /// Tearing variables: the columns matched to `residuals`, in the same order.
+    pub tear_vars: Vec<usize>,
+    /// Diagonal blocks of the block without `residuals` and `tear_vars`, in solve order.
+    /// Together with `residuals` their rows partition `rows`.
+    pub children: Vec<NestedBlock>,
+}
+
+impl NestedBlock {
+    /// Whether the block was left untorn.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Number of tearing levels below this block (0 for a leaf).
+    pub fn depth(&self) -> usize {
+        self.children
+            .iter()
+            .map(|c| c.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Hierarchical BTF: the top-level structure plus, per diagonal block, a tree obtained by
+/// tearing the block and block triangularizing what is left.
+///
+/// A block is solved by guessing its `tear_vars`, solving its children in order (recursively),
+/// and driving the `residuals` to zero, which is the two-level (or deeper) iteration used by
+/// equation-oriented simulators.
+#[derive(Debug, Clone)]
+pub struct NestedBtfStructure {
+    /// Structure of the whole matrix; `blocks[b]` is its block `b`.
+    pub structure: UpperBtfStructure,
+    /// One tree per top-level diagonal block, in solve order.
+    pub blocks: Vec<NestedBlock>,
+}
+
+impl NestedBtfStructure {
+    /// Deepest tearing level over all blocks (0 when nothing was torn).
+    pub fn depth(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(NestedBlock::depth)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Analyze `mat`, then recursively tear every block with more than `max_block_size` rows.
+///
+/// Each level tears the fewest leading picks of the block's feedback vertex set heuristic
+/// (chosen by `options` for the block size) after which the remaining pattern splits into
+/// more than one diagonal block, then re-runs the analysis on that reduced pattern; children
+/// still larger than `max_block_size` are torn in turn.
+pub fn nested_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    max_block_size: usize,
+    options: &TearingOptions,
+) -> NestedBtfStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, _) = structure_from_row_adjacency(&row_adj, mat.ncols());
+    let (rows, cols) = structure.solve_order_blocks();
+    let blocks = rows
+        .into_iter()
+        .zip(cols)
+        .map(|(rows, cols)| {
+            if rows.len() == cols.len() {
+                nest(&row_adj, rows, cols, max_block_size, options)
+            } else {
+                leaf(rows, cols)
+            }
+        })
+        .collect();
+    NestedBtfStructure { structure, blocks }
+}
+
+fn leaf(rows: Vec<usize>, cols: Vec<usize>) -> NestedBlock {
+    NestedBlock {
+        rows,
+        cols,
+        residuals: Vec::new(),
+        tear_vars: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
+/// Tear the square block `rows` x `cols` (matched pairs aligned) if it is too large.
+fn nest(
+    row_adj: &[Vec<usize>],
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    max_block_size: usize,
+    options: &TearingOptions,
+) -> NestedBlock {
+    let n = rows.len();
+    if n < 2 || n <= max_block_size {
+        return leaf(rows, cols);
+    }
+
+    // Block pattern in local indices: local row `l` is matched to local column `l`.
+    let local_col: HashMap<usize, usize> = cols.iter().enumerate().map(|(l, &c)| (c, l)).collect();
+    let local_adj: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|&r| {
+            let mut local: Vec<usize> = row_adj[r]
+                .iter()
+                .filter_map(|j| local_col.get(j).copied())
+                .collect();
+            local.sort_unstable();
+            local
+        })
+        .collect();
+    let graph: Vec<Vec<usize>> = local_adj
+        .iter()
+        .enumerate()
+        .map(|(l, adj)| adj.iter().copied().filter(|&k| k != l).collect())
+        .collect();
+    let picks = feedback_vertex_set(&graph, options.heuristic_for(n));
+
+    // The whole FVS leaves an acyclic remainder (all singletons), so some prefix splits it.
+    for t in 1..=picks.len() {
+        let mut torn = vec![false; n];
+        for &l in &picks[..t] {
+            torn[l] = true;
+        }
+        let kept: Vec<usize> = (0..n).filter(|&l| !torn[l]).collect();
+        let mut new_index = vec![usize::MAX; n];
+        for (k, &l) in kept.iter().enumerate() {
+            new_index[l] = k;
+        }
+        let reduced: Vec<Vec<usize>> = kept
+            .iter()
+            .map(|&l| {
+                local_adj[l]
+                    .iter()
+                    .filter(|&&k| !torn[k])
+                    .map(|&k| new_index[k])
+                    .collect()
+            })
+            .collect();
+        let (sub, _) = structure_from_row_adjacency(&reduced, kept.len());
+        if sub.num_blocks() < 2 && t < picks.len() {
+            continue;
+        }
+
+        let mut torn_pairs: Vec<(usize, usize)> =
+            picks[..t].iter().map(|&l| (rows[l], cols[l])).collect();
+        torn_pairs.sort_unstable();
+        let (sub_rows, sub_cols) = sub.solve_order_blocks();
+        let children = sub_rows
+            .into_iter()
+            .zip(sub_cols)
+            .map(|(r, c)| {
+                let r = r.iter().map(|&k| rows[kept[k]]).collect();
+                let c = c.iter().map(|&k| cols[kept[k]]).collect();
+                nest(row_adj, r, c, max_block_size, options)
+            })
+            .collect();
+        return NestedBlock {
+            residuals: torn_pairs.iter().map(|&(r, _)| r).collect(),
+            tear_vars: torn_pairs.iter().map(|&(_, c)| c).collect(),
+            rows,
+            cols,
+            children,
+        };
+    }
+    unreachable!("an irreducible block larger than 1x1 has a nonempty feedback vertex set")
+}
@@ -0,0 +1,91 @@
+use nalgebra::{ComplexField, DVector};
+
+use crate::adjacency::pattern_fingerprint;
+use crate::solve::{BlockLu, BlockSolveOptions, SolveError};
+use crate::{UpperBtfStructure, upper_block_triangular_structure};
+
+/// Newton driver reusing the structural analysis across iterations.
+///
+/// Each [`StructuredNewton::solve_step`] refactors the diagonal blocks of the Jacobian with
+/// [`BlockLu`], but re-runs the BTF analysis only when the Jacobian's nonzero pattern
+/// (its [`pattern_fingerprint`]) differs from the previous call.
+#[derive(Debug, Clone)]
+pub struct StructuredNewton<T: ComplexField> {
+    options: BlockSolveOptions<T::RealField>,
+    symbolic: Option<(u64, UpperBtfStructure)>,
+    factor: Option<BlockLu<T>>,
+    analyses: usize,
+}
+
+impl<T: ComplexField> Default for StructuredNewton<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ComplexField> StructuredNewton<T> {
+    pub fn new() -> Self {
+        Self::with_options(BlockSolveOptions::default())
+    }
+
+    pub fn with_options(options: BlockSolveOptions<T::RealField>) -> Self {
+        Self {
+            options,
+            symbolic: None,
+            factor: None,
+            analyses: 0,
+        }
+    }
+
+    /// Newton step `dx` solving `J dx = -residual` for the given Jacobian values.
+    pub fn solve_step<R, C, S>(
+        &mut self,
+        jacobian: &nalgebra::Matrix<T, R, C, S>,
+        residual: &DVector<T>,
+    ) -> Result<DVector<T>, SolveError>
+    where
+        T: Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let fingerprint = pattern_fingerprint(jacobian);
+        let structure = match &mut self.symbolic {
+            Some((fp, structure)) if *fp == fingerprint => structure,
+            slot => {
+                self.analyses += 1;
+                &slot
+                    .insert((fingerprint, upper_block_triangular_structure(jacobian)))
+                    .1
+            }
+        };
+
+        // Drop stale factors first so a failed refactorization never leaves them behind.
+        self.factor = None;
+        let factor = BlockLu::factor_with_options(jacobian, structure, &self.options)?;
+        let step = factor.solve(&-residual)?;
+        self.factor = Some(factor);
+        Ok(step)
+    }
+
+    /// Structure from the most recent analysis, if any.
+    pub fn structure(&self) -> Option<&UpperBtfStructure> {
+        self.symbolic.as_ref().map(|(_, s)| s)
+    }
+
+    /// Factors from the most recent successful step, if any (e.g. for transpose solves).
+    pub fn factor(&self) -> Option<&BlockLu<T>> {
+        self.factor.as_ref()
+    }
+
+    /// Number of structural analyses performed so far.
+    pub fn analysis_count(&self) -> usize {
+        self.analyses
+    }
+
+    /// Forget the cached analysis, forcing the next step to re-analyze.
+    pub fn invalidate(&mut self) {
+        self.symbolic = None;
+        self.factor = None;
+    }
+}
@@ -0,0 +1,50 @@
+use crate::adjacency::build_row_adjacency;
+use crate::augmentation::{alternating_cols, alternating_rows};
+use crate::matching::hopcroft_karp;
+
+/// Which variables and equations can carry a structural null vector.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NullSpaceSupport {
+    /// Structural rank (maximum matching size).
+    pub rank: usize,
+    /// Columns that can be nonzero in some vector `x` with `A x = 0` for generic values of the
+    /// nonzeros: the underdetermined part of the Dulmage–Mendelsohn decomposition. Sorted.
+    pub right: Vec<usize>,
+    /// Rows that can be nonzero in some vector `y` with `yᵀ A = 0`: the overdetermined part.
+    /// Sorted.
+    pub left: Vec<usize>,
+}
+
+impl NullSpaceSupport {
+    /// Generic dimension of the right null space, `ncols - rank`.
+    pub fn right_nullity(&self, ncols: usize) -> usize {
+        ncols - self.rank
+    }
+
+    /// Generic dimension of the left null space, `nrows - rank`.
+    pub fn left_nullity(&self, nrows: usize) -> usize {
+        nrows - self.rank
+    }
+}
+
+/// Support pattern of the structural (generic) null spaces of `mat`.
+///
+/// A column lies in the right support iff some maximum matching leaves it unmatched, i.e. iff
+/// it is reachable from an unmatched column by an alternating path; rows are symmetric. Both
+/// lists are empty for square structurally nonsingular patterns. Variables outside `right` are
+/// determined by the equations and need no regularization.
+pub fn null_space_support<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> NullSpaceSupport
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp(&row_adj, mat.ncols());
+    NullSpaceSupport {
+        rank: matching.size,
+        right: alternating_cols(&row_adj, mat.ncols(), &matching),
+        left: alternating_rows(&row_adj, &matching),
+    }
+}
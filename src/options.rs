@@ -0,0 +1,146 @@
+/// How rows (and with them their matched columns) are ordered inside each diagonal block.
+///
+/// Every variant keeps the matching on the block diagonal; only the order within a block
+/// changes, never the block partition or the block order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum InBlockOrder {
+    /// Increasing original row index.
+    #[default]
+    RowIndex,
+    /// Cuthill–McKee on the (symmetrized) in-block dependency graph, reducing the bandwidth
+    /// of each permuted diagonal block while keeping originally adjacent rows together.
+    MinBandwidth,
+    /// Minimum degree on the (symmetrized) in-block dependency graph, reducing the fill of a
+    /// dense or sparse LU of each diagonal block pivoting along its diagonal (see
+    /// [`crate::ordering::minimum_degree`]).
+    MinDegree,
+    /// Increasing index of each row's matched column, so the block's columns keep their
+    /// original relative order and its diagonal is the matching read left to right.
+    MatchedColumn,
+}
+
+/// How the diagonal blocks are ordered (always a topological order of the block DAG).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum BlockOrder {
+    /// Among ready blocks, take the one containing the smallest original row index.
+    #[default]
+    MinRow,
+    /// Barycenter heuristic keeping strongly coupled blocks close to each other, so fewer
+    /// nonzeros sit far above the block diagonal (see
+    /// [`crate::ordering::min_coupling_topo_order`]).
+    MinCoupling,
+    /// Among ready blocks, take the smallest (ties: smallest original row index), so large
+    /// blocks are pushed as late as their dependencies allow.
+    SmallestFirst,
+    /// Among ready blocks, take the largest (ties: smallest original row index), so large
+    /// blocks are grouped as early as their dependencies allow.
+    LargestFirst,
+    /// Among ready blocks, take the one whose rows and matched columns have the smallest mean
+    /// original index (ties: smallest original row index), keeping rows and columns close to
+    /// their original positions so the permutation stays near the identity where the
+    /// structure allows. Greedy, not an exact displacement minimum. Pair it with
+    /// [`InBlockOrder::RowIndex`] or [`InBlockOrder::MatchedColumn`] for a stable order
+    /// inside blocks.
+    MinDisplacement,
+    /// Greedy clustering of coupled blocks: each block is followed, where the DAG allows, by
+    /// the block it shares the most coupling nonzeros with, so sweep-based solvers touch
+    /// coupled data back to back (see
+    /// [`crate::ordering::max_adjacent_coupling_topo_order`]). Unlike `MinCoupling`, only
+    /// consecutive block pairs count.
+    AdjacentCoupling,
+}
+
+/// Maximum transversal algorithm used by the analysis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MatchingAlgorithm {
+    /// [`crate::matching::hopcroft_karp`].
+    #[default]
+    HopcroftKarp,
+    /// [`crate::matching::mc21`], reproducing the transversal of MC21 / SuiteSparse `maxtrans`.
+    Mc21,
+}
+
+/// Where unmatched rows and columns of structurally singular or rectangular patterns go.
+///
+/// Only the positions of unmatched rows and columns change; the blocks of matched rows and
+/// their relative order stay the same. Unmatched rows never depend on another row being
+/// solved first, so every placement keeps the permuted matrix block upper triangular except
+/// [`UnmatchedPlacement::Start`] for columns, whose entries then sit left of the diagonal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum UnmatchedPlacement {
+    /// Unmatched columns after all matched ones; unmatched rows where the block order puts
+    /// them (moving them behind the rows they couple to would put their entries below the
+    /// diagonal).
+    #[default]
+    End,
+    /// Unmatched rows before all blocks and unmatched columns before all matched columns,
+    /// each in increasing index order.
+    Start,
+    /// Each unmatched row immediately before the first block it couples to, and each
+    /// unmatched column immediately after the last block with an entry in it, so the singular
+    /// parts of a spy plot sit next to their couplings. Rows and columns without couplings go
+    /// last.
+    Adjacent,
+}
+
+/// A row or a column of the analyzed pattern, for [`BtfOptions::pin_first`] and
+/// [`BtfOptions::pin_last`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Pin {
+    Row(usize),
+    /// Stands for the diagonal block of its matched row; unmatched columns belong to no block
+    /// and cannot be pinned.
+    Col(usize),
+}
+
+/// Options for [`crate::upper_block_triangular_structure_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct BtfOptions {
+    /// Secondary ordering applied within each diagonal block.
+    pub in_block_order: InBlockOrder,
+    /// Order of the diagonal blocks.
+    pub block_order: BlockOrder,
+    /// Maximum transversal algorithm.
+    pub matching: MatchingAlgorithm,
+    /// `(row, col)` entries that may not be used by the matching (e.g. numerically tiny or
+    /// semantically invalid pivots). They still count as nonzeros for the dependency graph,
+    /// so the ordering stays block triangular. Out-of-range entries are ignored.
+    pub forbidden: Vec<(usize, usize)>,
+    /// `(row, col, class)` priority classes for matching; unlisted entries have class 0. When
+    /// non-empty, [`crate::matching::priority_matching`] is used instead of `matching`: the
+    /// transversal is still maximum, and among maximum ones prefers higher classes
+    /// lexicographically. Out-of-range entries are ignored.
+    pub priorities: Vec<(usize, usize, u8)>,
+    /// Placement of unmatched rows and columns.
+    pub unmatched: UnmatchedPlacement,
+    /// Rows and columns whose diagonal blocks must come before all other blocks, e.g. the
+    /// boundary conditions of a simulation model. The pinned blocks, and separately the
+    /// unpinned ones, keep the relative order given by `block_order` and `unmatched`. The
+    /// analysis fails with an [`OrderingError`](crate::ordering::OrderingError), which
+    /// converts to [`BtfError::InvalidPins`](crate::BtfError::InvalidPins), when no block
+    /// triangular order honors the pins: a pinned block must come after an unpinned one, a
+    /// block is pinned both first and last, or a pinned column is unmatched. Out-of-range
+    /// pins are ignored.
+    pub pin_first: Vec<Pin>,
+    /// Rows and columns whose diagonal blocks must come after all other blocks, e.g. model
+    /// outputs; as `pin_first` otherwise.
+    pub pin_last: Vec<Pin>,
+    /// Supernode amalgamation: consecutive diagonal blocks are merged, in solve order, into
+    /// groups of at least this many rows (a short trailing group joins the one before it), so
+    /// per-block dense kernels see fewer, larger blocks. Merged blocks are no longer
+    /// irreducible but the permuted matrix stays block upper triangular. 0 or 1 keeps the
+    /// irreducible blocks.
+    pub min_block_size: usize,
+    /// Cap on the diagonal block size seen by dense per-block solvers: every larger block
+    /// whose rows are all matched is reordered into a bordered form (a block triangular core
+    /// with blocks of at most this size plus trailing border rows and columns), described in
+    /// [`crate::UpperBtfStructure::block_splits`]. The block partition itself is unchanged.
+    /// 0 disables the cap.
+    pub max_block_size: usize,
+}
@@ -1,15 +1,55 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashMap},
+    fmt,
+};
 
-/// Kahn topo sort with deterministic tie-break by `key[node]` (smaller first).
-pub fn topo_sort_with_tiebreak(dag: &[Vec<usize>], key: &[usize]) -> Vec<usize> {
+/// Kahn topo sort with deterministic tie-break by `key[node]` (smaller first; equal keys are
+/// broken by node index).
+///
+/// When keys are small integers (at most a constant multiple of the node count, as the
+/// min-row keys used by the BTF pipeline are) it uses a bucket queue whose operations cost
+/// O(log_64 V), for O(E + V log_64 V) overall (a handful of word operations per node at any
+/// practical size); otherwise it falls back to a binary heap, O(E + V log V).
+///
+/// Returns [`OrderingError::Cycle`] if `dag` is not acyclic.
+pub fn topo_sort_with_tiebreak(
+    dag: &[Vec<usize>],
+    key: &[usize],
+) -> Result<Vec<usize>, OrderingError> {
     let n = dag.len();
     let mut indeg = vec![0usize; n];
-    for u in 0..n {
-        for &v in &dag[u] {
+    for out in dag {
+        for &v in out {
             indeg[v] += 1;
         }
     }
 
+    let max_key = key.iter().copied().max().unwrap_or(0);
+    let order = if max_key <= BUCKET_KEY_FACTOR.saturating_mul(n) {
+        kahn_bucket(dag, key, max_key, indeg)
+    } else {
+        kahn_heap(dag, key, indeg)
+    };
+
+    if order.len() != n {
+        // Nodes never released by Kahn's algorithm lie on or behind a cycle.
+        let mut placed = vec![false; n];
+        for &u in &order {
+            placed[u] = true;
+        }
+        let nodes = (0..n).filter(|&u| !placed[u]).collect();
+        return Err(OrderingError::Cycle { nodes });
+    }
+
+    Ok(order)
+}
+
+/// Keys up to `BUCKET_KEY_FACTOR * n` use the bucket queue.
+const BUCKET_KEY_FACTOR: usize = 4;
+
+fn kahn_heap(dag: &[Vec<usize>], key: &[usize], mut indeg: Vec<usize>) -> Vec<usize> {
+    let n = dag.len();
     let mut heap: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new(); // (key, node)
     for u in 0..n {
         if indeg[u] == 0 {
@@ -27,16 +67,108 @@ pub fn topo_sort_with_tiebreak(dag: &[Vec<usize>], key: &[usize]) -> Vec<usize>
             }
         }
     }
+    order
+}
 
-    // If this triggers, something is wrong (condensation should be a DAG).
-    if order.len() != n {
-        // Fallback: identity order (still deterministic).
-        return (0..n).collect();
+fn kahn_bucket(
+    dag: &[Vec<usize>],
+    key: &[usize],
+    max_key: usize,
+    mut indeg: Vec<usize>,
+) -> Vec<usize> {
+    let n = dag.len();
+
+    // Counting sort by key (stable in node index) gives every node a distinct rank that
+    // reproduces the (key, node) order of the heap.
+    let mut start = vec![0usize; max_key + 2];
+    for &k in &key[..n] {
+        start[k + 1] += 1;
+    }
+    for k in 0..=max_key {
+        start[k + 1] += start[k];
+    }
+    let mut rank = vec![0usize; n];
+    let mut node_of_rank = vec![0usize; n];
+    for u in 0..n {
+        let r = start[key[u]];
+        start[key[u]] += 1;
+        rank[u] = r;
+        node_of_rank[r] = u;
     }
 
+    let mut queue = BucketQueue::new(n);
+    for u in 0..n {
+        if indeg[u] == 0 {
+            queue.insert(rank[u]);
+        }
+    }
+
+    let mut order = Vec::with_capacity(n);
+    while let Some(r) = queue.pop_min() {
+        let u = node_of_rank[r];
+        order.push(u);
+        for &v in &dag[u] {
+            indeg[v] -= 1;
+            if indeg[v] == 0 {
+                queue.insert(rank[v]);
+            }
+        }
+    }
     order
 }
 
+/// Min-queue over distinct integers in `0..universe`: a hierarchy of 64-bit occupancy words,
+/// so insert and pop-min cost O(log_64 universe).
+struct BucketQueue {
+    /// `levels[0]` has one bit per element; each higher level has one bit per word below it.
+    levels: Vec<Vec<u64>>,
+}
+
+impl BucketQueue {
+    fn new(universe: usize) -> Self {
+        let mut levels = Vec::new();
+        let mut len = universe.max(1);
+        loop {
+            len = len.div_ceil(64);
+            levels.push(vec![0u64; len]);
+            if len == 1 {
+                break;
+            }
+        }
+        Self { levels }
+    }
+
+    fn insert(&mut self, mut x: usize) {
+        for level in &mut self.levels {
+            level[x / 64] |= 1 << (x % 64);
+            x /= 64;
+        }
+    }
+
+    fn pop_min(&mut self) -> Option<usize> {
+        let top = self.levels.len() - 1;
+        if self.levels[top][0] == 0 {
+            return None;
+        }
+
+        let mut x = 0;
+        for level in self.levels.iter().rev() {
+            x = x * 64 + level[x].trailing_zeros() as usize;
+        }
+
+        // Clear the bit, and propagate upwards while words become empty.
+        let mut y = x;
+        for level in &mut self.levels {
+            level[y / 64] &= !(1 << (y % 64));
+            if level[y / 64] != 0 {
+                break;
+            }
+            y /= 64;
+        }
+        Some(x)
+    }
+}
+
 pub fn col_order_from_row_order(
     row_order: &[usize],
     row_to_col: &[Option<usize>],
@@ -46,19 +178,409 @@ pub fn col_order_from_row_order(
     let mut col_order = Vec::with_capacity(ncols);
 
     for &r in row_order {
-        if let Some(c) = row_to_col.get(r).copied().flatten() {
-            if c < ncols && !used[c] {
-                used[c] = true;
-                col_order.push(c);
+        if let Some(c) = row_to_col.get(r).copied().flatten()
+            && c < ncols
+            && !used[c]
+        {
+            used[c] = true;
+            col_order.push(c);
+        }
+    }
+
+    col_order.extend((0..ncols).filter(|&c| !used[c]));
+
+    col_order
+}
+
+/// Inconsistencies detected by the strict ordering helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderingError {
+    /// `row_order` names a row outside `0..row_to_col.len()`.
+    RowOutOfRange { row: usize, nrows: usize },
+    /// `row_order` lists the same row twice.
+    DuplicateRow { row: usize },
+    /// `row_order` leaves out a row of `0..row_to_col.len()`.
+    MissingRow { row: usize },
+    /// A row is assigned a column outside `0..ncols`.
+    ColumnOutOfRange {
+        row: usize,
+        col: usize,
+        ncols: usize,
+    },
+    /// Two rows are assigned the same column.
+    DuplicateColumn {
+        col: usize,
+        first_row: usize,
+        second_row: usize,
+    },
+    /// The graph passed to the topological sort has a cycle; `nodes` could not be ordered.
+    Cycle { nodes: Vec<usize> },
+    /// A diagonal block holds rows or columns pinned both first and last; `block` is its
+    /// smallest row.
+    ConflictingPins { block: usize },
+    /// The block with smallest row `before` precedes the block with smallest row `after` in
+    /// every block triangular order, but the pins put it behind.
+    PinsAgainstOrder { before: usize, after: usize },
+    /// A pinned column is unmatched, so it belongs to no diagonal block.
+    PinnedUnmatchedColumn { col: usize },
+}
+
+impl OrderingError {
+    /// Whether the error comes from pins in [`BtfOptions`](crate::BtfOptions) that the
+    /// pattern cannot honor, a caller error rather than a violated invariant.
+    pub fn is_pin_conflict(&self) -> bool {
+        matches!(
+            self,
+            Self::ConflictingPins { .. }
+                | Self::PinsAgainstOrder { .. }
+                | Self::PinnedUnmatchedColumn { .. }
+        )
+    }
+}
+
+impl fmt::Display for OrderingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowOutOfRange { row, nrows } => {
+                write!(f, "row {row} out of range (nrows = {nrows})")
+            }
+            Self::DuplicateRow { row } => write!(f, "row {row} appears more than once"),
+            Self::MissingRow { row } => write!(f, "row {row} is missing from the order"),
+            Self::ColumnOutOfRange { row, col, ncols } => {
+                write!(f, "row {row} assigned to column {col} (ncols = {ncols})")
+            }
+            Self::DuplicateColumn {
+                col,
+                first_row,
+                second_row,
+            } => write!(
+                f,
+                "column {col} assigned to both row {first_row} and row {second_row}"
+            ),
+            Self::Cycle { nodes } => write!(
+                f,
+                "graph is not acyclic: {} node(s) on or behind a cycle: {nodes:?}",
+                nodes.len()
+            ),
+            Self::ConflictingPins { block } => write!(
+                f,
+                "block with smallest row {block} is pinned both first and last"
+            ),
+            Self::PinsAgainstOrder { before, after } => write!(
+                f,
+                "pins put block with smallest row {after} before block with smallest row \
+                 {before}, which must precede it"
+            ),
+            Self::PinnedUnmatchedColumn { col } => {
+                write!(f, "pinned column {col} is unmatched and in no block")
             }
         }
     }
+}
 
-    for c in 0..ncols {
-        if !used[c] {
-            col_order.push(c);
+impl std::error::Error for OrderingError {}
+
+/// Strict variant of [`col_order_from_row_order`]: instead of skipping them, reports rows
+/// out of range, repeated in or missing from `row_order`, and out-of-range or duplicate
+/// column assignments in `row_to_col`.
+pub fn col_order_from_row_order_strict(
+    row_order: &[usize],
+    row_to_col: &[Option<usize>],
+    ncols: usize,
+) -> Result<Vec<usize>, OrderingError> {
+    let nrows = row_to_col.len();
+    let mut seen_row = vec![false; nrows];
+    for &r in row_order {
+        if r >= nrows {
+            return Err(OrderingError::RowOutOfRange { row: r, nrows });
+        }
+        if seen_row[r] {
+            return Err(OrderingError::DuplicateRow { row: r });
         }
+        seen_row[r] = true;
+    }
+    if let Some(row) = seen_row.iter().position(|&seen| !seen) {
+        return Err(OrderingError::MissingRow { row });
     }
 
-    col_order
+    let mut owner: Vec<Option<usize>> = vec![None; ncols];
+    for (r, &c) in row_to_col.iter().enumerate() {
+        let Some(c) = c else { continue };
+        if c >= ncols {
+            return Err(OrderingError::ColumnOutOfRange {
+                row: r,
+                col: c,
+                ncols,
+            });
+        }
+        if let Some(first_row) = owner[c] {
+            return Err(OrderingError::DuplicateColumn {
+                col: c,
+                first_row,
+                second_row: r,
+            });
+        }
+        owner[c] = Some(r);
+    }
+
+    Ok(col_order_from_row_order(row_order, row_to_col, ncols))
+}
+
+/// Cuthill–McKee ordering of an undirected graph (adjacency lists must be symmetric).
+///
+/// Each connected component starts from a minimum-degree node and is traversed breadth-first,
+/// visiting neighbors by increasing degree. All ties are broken by node index, so nodes that
+/// were adjacent in the input numbering tend to stay together. Returns new position -> node.
+pub fn cuthill_mckee(adj: &[Vec<usize>]) -> Vec<usize> {
+    let n = adj.len();
+    let degree: Vec<usize> = adj.iter().map(Vec::len).collect();
+    let mut starts: Vec<usize> = (0..n).collect();
+    starts.sort_by_key(|&u| (degree[u], u));
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut next = Vec::new();
+    for &s in &starts {
+        if visited[s] {
+            continue;
+        }
+        visited[s] = true;
+        let mut head = order.len();
+        order.push(s);
+        while head < order.len() {
+            let u = order[head];
+            head += 1;
+            next.clear();
+            next.extend(adj[u].iter().copied().filter(|&v| !visited[v]));
+            next.sort_unstable_by_key(|&v| (degree[v], v));
+            next.dedup();
+            for &v in &next {
+                visited[v] = true;
+                order.push(v);
+            }
+        }
+    }
+
+    order
+}
+
+/// Minimum-degree ordering of an undirected graph (adjacency lists must be symmetric).
+///
+/// Repeatedly eliminates a node of smallest degree in the current elimination graph (ties:
+/// smallest index) and joins its remaining neighbors into a clique, as in the Tinney–Walker
+/// scheme; pivoting along the diagonal in this order keeps the fill of a factorization low.
+/// Degrees are exact, so this suits diagonal blocks rather than whole large patterns.
+/// Returns new position -> node.
+pub fn minimum_degree(adj: &[Vec<usize>]) -> Vec<usize> {
+    let n = adj.len();
+    let mut graph: Vec<BTreeSet<usize>> = adj
+        .iter()
+        .enumerate()
+        .map(|(u, nbrs)| nbrs.iter().copied().filter(|&v| v != u).collect())
+        .collect();
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|u| (graph[u].len(), u)).collect();
+
+    let mut order = Vec::with_capacity(n);
+    while let Some((_, u)) = queue.pop_first() {
+        order.push(u);
+        let nbrs = std::mem::take(&mut graph[u]);
+        for &v in &nbrs {
+            queue.remove(&(graph[v].len(), v));
+            graph[v].remove(&u);
+            graph[v].extend(nbrs.iter().copied().filter(|&w| w != v));
+            queue.insert((graph[v].len(), v));
+        }
+    }
+    order
+}
+
+/// Column minimum-degree ordering of a pattern given by row adjacency lists, in the spirit
+/// of COLAMD: [`minimum_degree`] on the column intersection graph (the pattern of `AᵀA`,
+/// columns adjacent when they share a row), which bounds the fill of a QR factorization and
+/// of LU with partial pivoting.
+///
+/// As in COLAMD, dense rows (more than `max(16, 10 * sqrt(ncols))` entries) are left out of
+/// the graph, since each would make its columns a clique; they are factored last anyway.
+/// Returns new position -> column, over all `ncols` columns.
+pub fn column_minimum_degree(row_adj: &[Vec<usize>], ncols: usize) -> Vec<usize> {
+    let dense = 16usize.max((10.0 * (ncols as f64).sqrt()) as usize);
+    let mut graph: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); ncols];
+    for cols in row_adj.iter().filter(|cols| cols.len() <= dense) {
+        for &j in cols {
+            graph[j].extend(cols.iter().copied().filter(|&k| k != j));
+        }
+    }
+    let adj: Vec<Vec<usize>> = graph.into_iter().map(Vec::from_iter).collect();
+    minimum_degree(&adj)
+}
+
+/// Topological order of a weighted DAG that keeps heavily coupled nodes close together.
+///
+/// `dag[u]` lists `(v, weight)` edges. Starting from the order given by `key` (as in
+/// [`topo_sort_with_tiebreak`]), each sweep re-sorts the nodes by the weighted barycenter of
+/// their neighbors' positions and keeps the result while the total coupling distance
+/// `sum(weight * (pos[v] - pos[u] - 1))` decreases. The result is always a valid
+/// topological order; it is a heuristic, not an optimum.
+pub fn min_coupling_topo_order(
+    dag: &[Vec<(usize, usize)>],
+    key: &[usize],
+) -> Result<Vec<usize>, OrderingError> {
+    const MAX_SWEEPS: usize = 8;
+
+    let n = dag.len();
+    let plain: Vec<Vec<usize>> = dag
+        .iter()
+        .map(|out| out.iter().map(|&(v, _)| v).collect())
+        .collect();
+    let mut best = topo_sort_with_tiebreak(&plain, key)?;
+    let mut pos = positions(&best);
+    let mut best_cost = coupling_cost(dag, &pos);
+
+    for _ in 0..MAX_SWEEPS {
+        // Weighted barycenter of neighbor positions, in both edge directions.
+        let mut sum = vec![0.0f64; n];
+        let mut total = vec![0usize; n];
+        for (u, out) in dag.iter().enumerate() {
+            for &(v, w) in out {
+                sum[u] += (w * pos[v]) as f64;
+                total[u] += w;
+                sum[v] += (w * pos[u]) as f64;
+                total[v] += w;
+            }
+        }
+        let bary: Vec<f64> = (0..n)
+            .map(|u| {
+                if total[u] == 0 {
+                    pos[u] as f64
+                } else {
+                    sum[u] / total[u] as f64
+                }
+            })
+            .collect();
+
+        let mut by_bary: Vec<usize> = (0..n).collect();
+        by_bary.sort_by(|&a, &b| bary[a].total_cmp(&bary[b]).then(pos[a].cmp(&pos[b])));
+        let rank = positions(&by_bary);
+
+        let order = topo_sort_with_tiebreak(&plain, &rank)?;
+        let new_pos = positions(&order);
+        let cost = coupling_cost(dag, &new_pos);
+        if cost >= best_cost {
+            break;
+        }
+        best = order;
+        pos = new_pos;
+        best_cost = cost;
+    }
+
+    Ok(best)
+}
+
+/// Topological order of a weighted DAG maximizing the weight of edges between consecutive
+/// nodes, so heavily coupled nodes end up next to each other (cache locality of sweeps).
+///
+/// `dag[u]` lists `(v, weight)` edges. Greedy: among ready nodes, take the one with the
+/// heaviest edge from the node placed last, otherwise the smallest `key` (as in
+/// [`topo_sort_with_tiebreak`]). Sweeps then swap neighbors without an edge between them
+/// wherever that raises the consecutive weight. Unlike [`min_coupling_topo_order`], edges
+/// that cannot be made consecutive count for nothing, however long they are. Heuristic, not
+/// an optimum.
+pub fn max_adjacent_coupling_topo_order(
+    dag: &[Vec<(usize, usize)>],
+    key: &[usize],
+) -> Result<Vec<usize>, OrderingError> {
+    const MAX_SWEEPS: usize = 8;
+
+    let n = dag.len();
+    let mut weight: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut indeg = vec![0usize; n];
+    for (u, out) in dag.iter().enumerate() {
+        for &(v, w) in out {
+            *weight.entry((u.min(v), u.max(v))).or_default() += w;
+            indeg[v] += 1;
+        }
+    }
+    let between = |a: usize, b: usize| weight.get(&(a.min(b), a.max(b))).copied().unwrap_or(0);
+    // Zero-weight edges still constrain the order, so existence is checked on its own.
+    let linked = |a: usize, b: usize| weight.contains_key(&(a.min(b), a.max(b)));
+
+    let mut ready: BTreeSet<(usize, usize)> = (0..n)
+        .filter(|&u| indeg[u] == 0)
+        .map(|u| (key[u], u))
+        .collect();
+    let mut order = Vec::with_capacity(n);
+    let mut last: Option<usize> = None;
+    loop {
+        let heaviest = last.and_then(|u| {
+            dag[u]
+                .iter()
+                .filter(|&&(v, w)| w > 0 && ready.contains(&(key[v], v)))
+                .max_by_key(|&&(v, w)| (w, Reverse((key[v], v))))
+                .map(|&(v, _)| (key[v], v))
+        });
+        let Some(next) = heaviest.or_else(|| ready.first().copied()) else {
+            break;
+        };
+        ready.remove(&next);
+        let u = next.1;
+        order.push(u);
+        for &(v, _) in &dag[u] {
+            indeg[v] -= 1;
+            if indeg[v] == 0 {
+                ready.insert((key[v], v));
+            }
+        }
+        last = Some(u);
+    }
+    if order.len() != n {
+        // Not a DAG; let the plain sort report the cycle.
+        let plain: Vec<Vec<usize>> = dag
+            .iter()
+            .map(|out| out.iter().map(|&(v, _)| v).collect())
+            .collect();
+        return topo_sort_with_tiebreak(&plain, key);
+    }
+
+    // Swapping neighbors without an edge between them keeps the order topological; only the
+    // pairs with their outer neighbors change.
+    let at = |order: &[usize], p: Option<usize>| p.and_then(|p| order.get(p).copied());
+    for _ in 0..MAX_SWEEPS {
+        let mut improved = false;
+        for p in 0..n.saturating_sub(1) {
+            let (a, b) = (order[p], order[p + 1]);
+            if linked(a, b) {
+                continue;
+            }
+            let (before, after) = (at(&order, p.checked_sub(1)), at(&order, Some(p + 2)));
+            let outer = |x: Option<usize>, y: usize| x.map_or(0, |x| between(x, y));
+            let current = outer(before, a) + outer(after, b);
+            let swapped = outer(before, b) + outer(after, a);
+            if swapped > current {
+                order.swap(p, p + 1);
+                improved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    Ok(order)
+}
+
+/// Inverse of an order: node -> position.
+fn positions(order: &[usize]) -> Vec<usize> {
+    let mut pos = vec![0usize; order.len()];
+    for (p, &u) in order.iter().enumerate() {
+        pos[u] = p;
+    }
+    pos
+}
+
+/// Total weighted distance of the edges beyond adjacency, for a topological `pos`.
+fn coupling_cost(dag: &[Vec<(usize, usize)>], pos: &[usize]) -> usize {
+    dag.iter()
+        .enumerate()
+        .flat_map(|(u, out)| out.iter().map(move |&(v, w)| w * (pos[v] - pos[u] - 1)))
+        .sum()
 }
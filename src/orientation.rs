@@ -0,0 +1,132 @@
+use nalgebra::{Dyn, PermutationSequence};
+
+use crate::error::{BtfError, BtfResult};
+use crate::permutation::permutation_sequence_from_order;
+use crate::{BtfOptions, UpperBtfStructure, try_upper_block_triangular_structure_with_options};
+
+/// Which side of the block diagonal the off-diagonal entries end up on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// `P A Q` is block upper triangular, solved by back substitution from the last block.
+    #[default]
+    Upper,
+    /// `P A Q` is block lower triangular, solved by forward substitution from the first
+    /// block: the upper form with rows, columns and blocks reversed.
+    Lower,
+}
+
+/// A block triangular form together with the orientation it encodes.
+///
+/// The analysis always runs in the upper convention; `upper` is that result, and the
+/// accessors read it in `orientation`. Code that applies the orders to a matrix should
+/// check the orientation it was written for with [`Self::expect_orientation`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockTriangularStructure {
+    pub orientation: Orientation,
+    pub upper: UpperBtfStructure,
+}
+
+impl BlockTriangularStructure {
+    /// Row order (new position -> original row) in `orientation`.
+    pub fn row_order(&self) -> Vec<usize> {
+        self.oriented(&self.upper.row_order)
+    }
+
+    /// Column order (new position -> original column) in `orientation`.
+    pub fn col_order(&self) -> Vec<usize> {
+        self.oriented(&self.upper.col_order)
+    }
+
+    /// Diagonal block sizes along the permuted diagonal in `orientation`.
+    pub fn block_sizes(&self) -> Vec<usize> {
+        self.oriented(&self.upper.block_sizes)
+    }
+
+    /// Row and column permutations `(P, Q)` such that `P A Q` is block triangular in
+    /// `orientation`.
+    pub fn permutations(&self) -> (PermutationSequence<Dyn>, PermutationSequence<Dyn>) {
+        (
+            permutation_sequence_from_order(&self.row_order()),
+            permutation_sequence_from_order(&self.col_order()),
+        )
+    }
+
+    /// `self` if it encodes `expected`, otherwise [`BtfError::OrientationMismatch`].
+    pub fn expect_orientation(&self, expected: Orientation) -> BtfResult<&Self> {
+        if self.orientation == expected {
+            Ok(self)
+        } else {
+            Err(BtfError::OrientationMismatch {
+                expected,
+                found: self.orientation,
+            })
+        }
+    }
+
+    fn oriented(&self, upper: &[usize]) -> Vec<usize> {
+        match self.orientation {
+            Orientation::Upper => upper.to_vec(),
+            Orientation::Lower => upper.iter().rev().copied().collect(),
+        }
+    }
+}
+
+/// Block triangular form of `mat` in `orientation`, with the default options.
+pub fn block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    orientation: Orientation,
+) -> BlockTriangularStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    BlockTriangularStructure {
+        orientation,
+        upper: crate::upper_block_triangular_structure(mat),
+    }
+}
+
+/// [`block_triangular_structure`] with explicit [`BtfOptions`], which are interpreted in the
+/// upper convention (e.g. [`UnmatchedPlacement::Start`](crate::UnmatchedPlacement::Start)
+/// puts unmatched rows and columns last in the lower form).
+///
+/// Panics if the pins in `options` cannot be honored; use
+/// [`try_block_triangular_structure_with_options`] to handle that as an error.
+pub fn block_triangular_structure_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    orientation: Orientation,
+    options: &BtfOptions,
+) -> BlockTriangularStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    BlockTriangularStructure {
+        orientation,
+        upper: crate::upper_block_triangular_structure_with_options(mat, options),
+    }
+}
+
+/// Fallible variant of [`block_triangular_structure_with_options`].
+pub fn try_block_triangular_structure_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    orientation: Orientation,
+    options: &BtfOptions,
+) -> BtfResult<BlockTriangularStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    Ok(BlockTriangularStructure {
+        orientation,
+        upper: try_upper_block_triangular_structure_with_options(mat, options)?,
+    })
+}
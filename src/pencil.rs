@@ -0,0 +1,89 @@
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::matching::priority_matching;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// Structural analysis of a matrix pencil `λE − A`.
+#[derive(Debug, Clone)]
+pub struct PencilStructure {
+    /// Generic rank of `λE − A`: the maximum matching size of the combined pattern `E ∪ A`.
+    pub rank: usize,
+    /// Whether the pencil is square and structurally regular (`rank == n`), i.e.
+    /// `det(λE − A)` is not identically zero for generic values.
+    pub regular: bool,
+    /// Largest number of `E` entries in a maximum matching of `E ∪ A`: the generic degree of
+    /// `det(λE − A)` in `λ` for a regular pencil, i.e. its number of finite eigenvalues.
+    pub degree: usize,
+    /// `n − degree` for a regular pencil: the structurally infinite eigenvalues. `None` for
+    /// singular or non-square pencils, whose eigenvalues are not defined.
+    pub infinite_eigenvalues: Option<usize>,
+    /// BTF of the combined pattern, using a transversal with `degree` entries from `E`.
+    pub structure: UpperBtfStructure,
+}
+
+/// Analyze the pencil `λE − A` given the patterns of `e` and `a` (same shape).
+///
+/// For generic values the pencil's entries are nonzero wherever `E` or `A` is, and an entry
+/// carries `λ` iff it lies in `E`. The determinant's degree is therefore the maximum number of
+/// `E` entries over all perfect matchings, found with a two-class [`priority_matching`].
+pub fn pencil_structure<T, R, C, S, T2, R2, C2, S2>(
+    e: &nalgebra::Matrix<T, R, C, S>,
+    a: &nalgebra::Matrix<T2, R2, C2, S2>,
+) -> BtfResult<PencilStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+    T2: nalgebra::Scalar + PartialEq + Default,
+    R2: nalgebra::Dim,
+    C2: nalgebra::Dim,
+    S2: nalgebra::Storage<T2, R2, C2>,
+{
+    if e.shape() != a.shape() {
+        return Err(BtfError::DimensionMismatch {
+            expected: e.shape(),
+            found: a.shape(),
+        });
+    }
+    let (nrows, ncols) = e.shape();
+    let e_adj = build_row_adjacency(e);
+    let combined: Vec<Vec<usize>> = e_adj
+        .iter()
+        .zip(build_row_adjacency(a))
+        .map(|(ei, ai)| {
+            let mut cols: Vec<usize> = ei.iter().copied().chain(ai).collect();
+            cols.sort_unstable();
+            cols.dedup();
+            cols
+        })
+        .collect();
+
+    let in_e = |i: usize, j: usize| e_adj[i].binary_search(&j).is_ok();
+    let matching = priority_matching(&combined, ncols, |i, j| u8::from(in_e(i, j)));
+    let degree = matching
+        .row_to_col
+        .iter()
+        .enumerate()
+        .filter(|&(i, c)| c.is_some_and(|j| in_e(i, j)))
+        .count();
+
+    let options = BtfOptions {
+        priorities: e_adj
+            .iter()
+            .enumerate()
+            .flat_map(|(i, cols)| cols.iter().map(move |&j| (i, j, 1)))
+            .collect(),
+        ..Default::default()
+    };
+    let (structure, _) = try_structure_from_row_adjacency(&combined, ncols, &options)?;
+
+    let regular = nrows == ncols && matching.size == nrows;
+    Ok(PencilStructure {
+        rank: matching.size,
+        regular,
+        degree,
+        infinite_eigenvalues: regular.then(|| nrows - degree),
+        structure,
+    })
+}
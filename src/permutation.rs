@@ -1,3 +1,5 @@
+use std::fmt;
+
 use nalgebra::{Dyn, PermutationSequence};
 
 /// Convert an explicit order (new_pos -> old_index) into a nalgebra PermutationSequence<Dyn>
@@ -32,14 +34,197 @@ pub fn permutation_sequence_from_order(order: &[usize]) -> PermutationSequence<D
     p
 }
 
-fn is_valid_permutation(order: &[usize]) -> bool {
+/// Whether `order` is a permutation of `0..order.len()`.
+pub fn is_valid_permutation(order: &[usize]) -> bool {
+    validate_permutation(order).is_ok()
+}
+
+/// Check that `order` is a permutation of `0..order.len()`, reporting the first offending
+/// position otherwise.
+pub fn validate_permutation(order: &[usize]) -> Result<(), PermError> {
     let n = order.len();
-    let mut seen = vec![false; n];
-    for &x in order {
-        if x >= n || seen[x] {
-            return false;
+    let mut first_pos: Vec<Option<usize>> = vec![None; n];
+    for (pos, &x) in order.iter().enumerate() {
+        if x >= n {
+            return Err(PermError::OutOfRange {
+                position: pos,
+                value: x,
+                len: n,
+            });
         }
-        seen[x] = true;
+        if let Some(first) = first_pos[x] {
+            return Err(PermError::Duplicate {
+                value: x,
+                first,
+                second: pos,
+            });
+        }
+        first_pos[x] = Some(pos);
     }
-    seen.iter().all(|&x| x)
+    // n in-range values without duplicates cover 0..n.
+    Ok(())
+}
+
+/// One-line notation of `order`: `"[2 0 1]"` for `order = [2, 0, 1]`.
+pub fn one_line_notation(order: &[usize]) -> String {
+    let items: Vec<String> = order.iter().map(usize::to_string).collect();
+    format!("[{}]", items.join(" "))
+}
+
+/// Parse one-line notation as produced by [`one_line_notation`]. Brackets are optional and
+/// entries may be separated by whitespace and/or commas; the result must be a permutation.
+pub fn parse_one_line_notation(s: &str) -> Result<Vec<usize>, PermParseError> {
+    let trimmed = s.trim();
+    let inner = match (trimmed.strip_prefix('['), trimmed.strip_suffix(']')) {
+        (Some(_), Some(_)) if trimmed.len() >= 2 => &trimmed[1..trimmed.len() - 1],
+        (None, None) => trimmed,
+        _ => return Err(PermParseError::Unbalanced),
+    };
+    let order = parse_indices(inner)?;
+    validate_permutation(&order)?;
+    Ok(order)
 }
+
+/// Cycle notation of the map `p -> order[p]`, fixed points included: `"(0 2 1)(3)"` for
+/// `order = [2, 0, 1, 3]`, i.e. position 0 takes index 2, position 2 takes index 1 and
+/// position 1 takes index 0. Each cycle starts at its smallest element and cycles are sorted
+/// by it, so equal permutations print identically. Expects a valid permutation.
+pub fn cycle_notation(order: &[usize]) -> String {
+    debug_assert!(is_valid_permutation(order));
+    let mut seen = vec![false; order.len()];
+    let mut out = String::new();
+    for start in 0..order.len() {
+        if seen[start] {
+            continue;
+        }
+        let mut cycle = Vec::new();
+        let mut p = start;
+        while !seen[p] {
+            seen[p] = true;
+            cycle.push(p.to_string());
+            p = order[p];
+        }
+        out.push('(');
+        out.push_str(&cycle.join(" "));
+        out.push(')');
+    }
+    out
+}
+
+/// Parse cycle notation into an order of length `n`. Elements not mentioned are fixed points,
+/// so both `"(0 2 1)(3)"` and `"(0 2 1)"` give `[2, 0, 1, 3]` for `n = 4`; `"()"` and `""`
+/// are the identity. Cycles must be disjoint.
+pub fn parse_cycle_notation(s: &str, n: usize) -> Result<Vec<usize>, PermParseError> {
+    let mut order: Vec<Option<usize>> = vec![None; n];
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let body = rest.strip_prefix('(').ok_or(PermParseError::Unbalanced)?;
+        let close = body.find(')').ok_or(PermParseError::Unbalanced)?;
+        let cycle = parse_indices(&body[..close])?;
+        for (k, &a) in cycle.iter().enumerate() {
+            let b = cycle[(k + 1) % cycle.len()];
+            for x in [a, b] {
+                if x >= n {
+                    return Err(PermError::OutOfRange {
+                        position: k,
+                        value: x,
+                        len: n,
+                    }
+                    .into());
+                }
+            }
+            if order[a].replace(b).is_some() {
+                return Err(PermParseError::RepeatedElement(a));
+            }
+        }
+        rest = body[close + 1..].trim_start();
+    }
+    Ok(order
+        .iter()
+        .enumerate()
+        .map(|(p, &q)| q.unwrap_or(p))
+        .collect())
+}
+
+/// Non-negative integers separated by whitespace and/or commas.
+fn parse_indices(s: &str) -> Result<Vec<usize>, PermParseError> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| PermParseError::InvalidToken(token.to_string()))
+        })
+        .collect()
+}
+
+/// Why a permutation string could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermParseError {
+    /// A token is not a non-negative integer.
+    InvalidToken(String),
+    /// Brackets or parentheses do not match up.
+    Unbalanced,
+    /// An element appears in more than one place of the cycle notation.
+    RepeatedElement(usize),
+    /// The parsed entries are not a permutation.
+    Invalid(PermError),
+}
+
+impl From<PermError> for PermParseError {
+    fn from(err: PermError) -> Self {
+        Self::Invalid(err)
+    }
+}
+
+impl fmt::Display for PermParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidToken(token) => write!(f, "invalid index {token:?}"),
+            Self::Unbalanced => write!(f, "unbalanced brackets or parentheses"),
+            Self::RepeatedElement(x) => write!(f, "element {x} appears in more than one cycle"),
+            Self::Invalid(err) => write!(f, "not a permutation: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PermParseError {}
+
+/// Why an order is not a valid permutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermError {
+    /// `order[position] = value` is not below `len`.
+    OutOfRange {
+        position: usize,
+        value: usize,
+        len: usize,
+    },
+    /// `value` appears at both `first` and `second`.
+    Duplicate {
+        value: usize,
+        first: usize,
+        second: usize,
+    },
+}
+
+impl fmt::Display for PermError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange {
+                position,
+                value,
+                len,
+            } => write!(
+                f,
+                "index {value} at position {position} out of range for length {len}"
+            ),
+            Self::Duplicate {
+                value,
+                first,
+                second,
+            } => write!(f, "index {value} appears at positions {first} and {second}"),
+        }
+    }
+}
+
+impl std::error::Error for PermError {}
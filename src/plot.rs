@@ -0,0 +1,210 @@
+use plotters::coord::Shift;
+use plotters::prelude::{
+    Color, DrawingArea, DrawingAreaErrorKind, DrawingBackend, RGBColor, Rectangle, ShapeStyle,
+    WHITE,
+};
+
+use crate::UpperBtfStructure;
+use crate::adjacency::build_row_adjacency;
+use crate::spy::{block_color, entry_migration};
+
+/// Nonzero cells (same colors as the HTML report).
+const NONZERO: RGBColor = RGBColor(0x22, 0x44, 0x66);
+/// Diagonal block outlines.
+const BLOCK: RGBColor = RGBColor(0xdd, 0x66, 0x00);
+
+/// Result of the drawing functions; errors come from the backend.
+pub type DrawResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+/// Inclusive pixel span of cell `k` of `n` along an axis of `len` pixels.
+fn span(k: usize, n: usize, len: u32) -> (i32, i32) {
+    let at = |k: usize| (k as u64 * u64::from(len) / n.max(1) as u64) as i32;
+    (at(k), (at(k + 1) - 1).max(at(k)))
+}
+
+/// Fill the `(row, col)` cell of an `nrows x ncols` grid covering `area`.
+fn cell<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    (nrows, ncols): (usize, usize),
+    (row, col): (usize, usize),
+    style: ShapeStyle,
+) -> DrawResult<DB> {
+    let (w, h) = area.dim_in_pixel();
+    let (x0, x1) = span(col, ncols, w);
+    let (y0, y1) = span(row, nrows, h);
+    area.draw(&Rectangle::new([(x0, y0), (x1, y1)], style))
+}
+
+/// Outline the `height x width` rectangle with top-left cell `(row, col)` of an
+/// `nrows x ncols` grid (clipped to the grid).
+fn outline<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    (nrows, ncols): (usize, usize),
+    (row, col): (usize, usize),
+    (height, width): (usize, usize),
+) -> DrawResult<DB> {
+    let width = (col + width).min(ncols).saturating_sub(col);
+    let height = (row + height).min(nrows).saturating_sub(row);
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+    let (w, h) = area.dim_in_pixel();
+    let (x0, _) = span(col, ncols, w);
+    let (_, x1) = span(col + width - 1, ncols, w);
+    let (y0, _) = span(row, nrows, h);
+    let (_, y1) = span(row + height - 1, nrows, h);
+    area.draw(&Rectangle::new([(x0, y0), (x1, y1)], BLOCK.stroke_width(1)))
+}
+
+/// Outline the diagonal blocks of `P A Q`: rows and columns advance separately, as blocks of
+/// singular patterns are not square.
+fn outline_blocks<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    shape: (usize, usize),
+    structure: &UpperBtfStructure,
+) -> DrawResult<DB> {
+    let mut row = 0;
+    for ((&rows, cols), col) in structure
+        .block_sizes
+        .iter()
+        .zip(structure.block_col_sizes())
+        .zip(structure.block_col_starts())
+    {
+        outline(area, shape, (row, col), (rows, cols))?;
+        row += rows;
+    }
+    Ok(())
+}
+
+/// Draw the nonzero pattern of `mat` in its original order, filling `area`.
+pub fn draw_spy<DB, T, R, C, S>(
+    area: &DrawingArea<DB, Shift>,
+    mat: &nalgebra::Matrix<T, R, C, S>,
+) -> DrawResult<DB>
+where
+    DB: DrawingBackend,
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    area.fill(&WHITE)?;
+    let shape = mat.shape();
+    for (i, cols) in build_row_adjacency(mat).iter().enumerate() {
+        for &j in cols {
+            cell(area, shape, (i, j), NONZERO.filled())?;
+        }
+    }
+    Ok(())
+}
+
+/// Draw `P A Q` for the analyzed `structure` of `mat`, with the diagonal blocks outlined.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn draw_permuted_spy<DB, T, R, C, S>(
+    area: &DrawingArea<DB, Shift>,
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> DrawResult<DB>
+where
+    DB: DrawingBackend,
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let shape = mat.shape();
+    assert_eq!(
+        shape,
+        (structure.row_order.len(), structure.col_order.len()),
+        "pattern shape does not match the structure"
+    );
+    let mut row_pos = vec![0; shape.0];
+    for (p, &i) in structure.row_order.iter().enumerate() {
+        row_pos[i] = p;
+    }
+    let mut col_pos = vec![0; shape.1];
+    for (p, &j) in structure.col_order.iter().enumerate() {
+        col_pos[j] = p;
+    }
+
+    area.fill(&WHITE)?;
+    for (i, cols) in build_row_adjacency(mat).iter().enumerate() {
+        for &j in cols {
+            cell(area, shape, (row_pos[i], col_pos[j]), NONZERO.filled())?;
+        }
+    }
+    outline_blocks(area, shape, structure)
+}
+
+/// Draw the block-level heatmap: cell `(a, b)` is shaded by the number of nonzeros of `mat`
+/// in block `(a, b)` of `P A Q`, relative to the fullest block. Unmatched trailing columns
+/// form one extra block column on the right when present. Diagonal blocks are outlined.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn draw_block_heatmap<DB, T, R, C, S>(
+    area: &DrawingArea<DB, Shift>,
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> DrawResult<DB>
+where
+    DB: DrawingBackend,
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let pairs = structure.block_pairs(mat);
+    let nb = structure.num_blocks();
+    let trailing = pairs.iter().any(|&(_, (_, bj))| bj == nb);
+    let grid = (nb, nb + usize::from(trailing));
+
+    let mut counts = vec![0usize; grid.0 * grid.1];
+    for &(_, (bi, bj)) in &pairs {
+        counts[bi * grid.1 + bj] += 1;
+    }
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+
+    area.fill(&WHITE)?;
+    for (k, &count) in counts.iter().enumerate().filter(|&(_, &c)| c > 0) {
+        // Linear blend from white to the nonzero color.
+        let t = count as f64 / max as f64;
+        let mix = |c: u8| (255.0 - t * (255.0 - f64::from(c))).round() as u8;
+        let color = RGBColor(mix(NONZERO.0), mix(NONZERO.1), mix(NONZERO.2));
+        cell(area, grid, (k / grid.1, k % grid.1), color.filled())?;
+    }
+    for b in 0..nb {
+        outline(area, grid, (b, b), (1, 1))?;
+    }
+    Ok(())
+}
+
+/// Draw the original pattern (left half of `area`) and `P A Q` (right half) side by side, each
+/// nonzero colored by its destination block ([`crate::spy::block_color`]), with the diagonal
+/// blocks of the permuted half outlined.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn draw_spy_diff<DB, T, R, C, S>(
+    area: &DrawingArea<DB, Shift>,
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> DrawResult<DB>
+where
+    DB: DrawingBackend,
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let shape = mat.shape();
+    let moves = entry_migration(mat, structure);
+    area.fill(&WHITE)?;
+    let (before, after) = area.split_horizontally(area.dim_in_pixel().0 / 2);
+    for m in &moves {
+        let (r, g, b) = block_color(m.block);
+        let style = RGBColor(r, g, b).filled();
+        cell(&before, shape, m.from, style)?;
+        cell(&after, shape, m.to, style)?;
+    }
+    outline_blocks(&after, shape, structure)
+}
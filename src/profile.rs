@@ -0,0 +1,130 @@
+use crate::adjacency::build_row_adjacency;
+use crate::matching::{augment, hopcroft_karp};
+
+/// Structural rank of every leading `k x k` submatrix of `mat`, for `k = 1..=min(nrows, ncols)`.
+///
+/// `profile[k - 1]` is the rank of `mat[..k, ..k]`. See [`nested_rank_profile`].
+pub fn leading_rank_profile<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> Vec<usize>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    let k = nrows.min(ncols);
+    let order: Vec<usize> = (0..k).collect();
+    nested_rank_profile(mat, &order, &order)
+}
+
+/// Structural rank of the nested submatrices `mat[row_order[..k], col_order[..k]]`, for
+/// `k = 1..=min(row_order.len(), col_order.len())`.
+///
+/// The matching is maintained incrementally: growing the submatrix by one row and one column
+/// raises the rank by at most two. Adding the row and then the column as single vertices, any
+/// new augmenting path must start at the inserted vertex, so each step costs two augmenting
+/// searches instead of a fresh matching. This locates where a staircase assembly first becomes
+/// structurally deficient (see [`first_deficient`]).
+///
+/// Panics if an order repeats an index or names one outside the matrix.
+pub fn nested_rank_profile<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    row_order: &[usize],
+    col_order: &[usize],
+) -> Vec<usize>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    let row_pos = positions(row_order, nrows);
+    let col_pos = positions(col_order, ncols);
+    let steps = row_order.len().min(col_order.len());
+
+    // Adjacency of the current leading submatrix in local (position) indices, in both
+    // directions so the new column can be augmented from as well.
+    let full = build_row_adjacency(mat);
+    let mut row_adj: Vec<Vec<usize>> = vec![Vec::new(); steps];
+    let mut col_adj: Vec<Vec<usize>> = vec![Vec::new(); steps];
+    let mut col_rows: Vec<Vec<usize>> = vec![Vec::new(); ncols];
+    for (i, cols) in full.iter().enumerate() {
+        for &j in cols {
+            col_rows[j].push(i);
+        }
+    }
+
+    let mut row_to_col = vec![None; steps];
+    let mut col_to_row = vec![None; steps];
+    let mut rank = 0;
+    let mut profile = Vec::with_capacity(steps);
+    for k in 0..steps {
+        // Add the new row (entries in earlier columns), then the new column (entries in earlier
+        // rows and the corner). Each is a single vertex insertion, after which one augmenting
+        // search from the new vertex restores a maximum matching.
+        for &j in &full[row_order[k]] {
+            if let Some(q) = col_pos[j].filter(|&q| q < k) {
+                row_adj[k].push(q);
+                col_adj[q].push(k);
+            }
+        }
+        if augment(k, &row_adj, &mut row_to_col, &mut col_to_row).is_some() {
+            rank += 1;
+        }
+        for &i in &col_rows[col_order[k]] {
+            if let Some(p) = row_pos[i].filter(|&p| p <= k) {
+                row_adj[p].push(k);
+                col_adj[k].push(p);
+            }
+        }
+        if augment(k, &col_adj, &mut col_to_row, &mut row_to_col).is_some() {
+            rank += 1;
+        }
+        profile.push(rank);
+    }
+    profile
+}
+
+/// Structural rank of the submatrix of `mat` with the given rows and columns.
+///
+/// Panics if an index is outside the matrix.
+pub fn submatrix_rank<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    rows: &[usize],
+    cols: &[usize],
+) -> usize
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let full = build_row_adjacency(mat);
+    let col_pos = positions(cols, mat.ncols());
+    let adj: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|&i| full[i].iter().filter_map(|&j| col_pos[j]).collect())
+        .collect();
+    hopcroft_karp(&adj, cols.len()).size
+}
+
+/// Smallest `k` whose leading submatrix in `profile` is structurally singular (`rank < k`).
+pub fn first_deficient(profile: &[usize]) -> Option<usize> {
+    profile
+        .iter()
+        .enumerate()
+        .find(|&(k, &rank)| rank < k + 1)
+        .map(|(k, _)| k + 1)
+}
+
+/// Position of every index in `order` (`None` if absent).
+fn positions(order: &[usize], n: usize) -> Vec<Option<usize>> {
+    let mut pos = vec![None; n];
+    for (p, &i) in order.iter().enumerate() {
+        assert!(i < n, "index {i} out of range for dimension {n}");
+        assert!(pos[i].is_none(), "index {i} listed twice");
+        pos[i] = Some(p);
+    }
+    pos
+}
@@ -0,0 +1,97 @@
+use nalgebra::{ComplexField, RealField};
+
+use crate::error::BtfResult;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// Drop tolerance applied to the values of a matrix before its pattern is analyzed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropTolerance<R> {
+    /// Nonzero entries with `|a_ij| <= tolerance` are left out of the pattern.
+    pub tolerance: R,
+    /// Whether [`PruneReport::entries`] lists the pruned entries, or only counts them.
+    pub record_entries: bool,
+}
+
+impl<R: RealField> Default for DropTolerance<R> {
+    fn default() -> Self {
+        Self {
+            tolerance: R::zero(),
+            record_entries: false,
+        }
+    }
+}
+
+/// Entries removed by a [`DropTolerance`], for auditing whether it changed the structure.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Number of nonzero entries pruned.
+    pub count: usize,
+    /// The pruned `(row, col)` entries in row-major order, when
+    /// [`DropTolerance::record_entries`] is set.
+    pub entries: Option<Vec<(usize, usize)>>,
+}
+
+/// A structure computed from a pruned pattern, with the report of what was pruned.
+#[derive(Debug, Clone)]
+pub struct PrunedStructure {
+    /// Structure of the pruned pattern.
+    pub structure: UpperBtfStructure,
+    /// Entries left out of the pattern.
+    pub pruned: PruneReport,
+}
+
+/// Row adjacency of the entries of `mat` above `drop.tolerance` in modulus (sorted, as
+/// [`build_row_adjacency`](crate::adjacency::build_row_adjacency)) and the report of the
+/// nonzeros pruned. Exact zeros are never part of the pattern and are not reported.
+pub fn pruned_row_adjacency<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    drop: &DropTolerance<T::RealField>,
+) -> (Vec<Vec<usize>>, PruneReport)
+where
+    T: ComplexField,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let zero = T::zero();
+    let mut report = PruneReport {
+        count: 0,
+        entries: drop.record_entries.then(Vec::new),
+    };
+    let mut adj = vec![Vec::new(); mat.nrows()];
+    for (i, row) in adj.iter_mut().enumerate() {
+        for j in 0..mat.ncols() {
+            let a = &mat[(i, j)];
+            if *a == zero {
+                continue;
+            }
+            if a.clone().modulus() > drop.tolerance {
+                row.push(j);
+            } else {
+                report.count += 1;
+                if let Some(entries) = &mut report.entries {
+                    entries.push((i, j));
+                }
+            }
+        }
+    }
+    (adj, report)
+}
+
+/// Analyze the pattern of `mat` after pruning entries at or below `drop.tolerance`, reporting
+/// which entries were pruned.
+pub fn pruned_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    drop: &DropTolerance<T::RealField>,
+    options: &BtfOptions,
+) -> BtfResult<PrunedStructure>
+where
+    T: ComplexField,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (row_adj, pruned) = pruned_row_adjacency(mat, drop);
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, mat.ncols(), options)?;
+    Ok(PrunedStructure { structure, pruned })
+}
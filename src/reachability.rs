@@ -0,0 +1,124 @@
+use nalgebra::DMatrix;
+
+use crate::UpperBtfStructure;
+
+/// Reflexive-transitive closure of a block DAG whose edges only point to later blocks
+/// (as in `UpperBtfStructure::block_dag`): `reach[a][b]` is true iff `b` is reachable from `a`.
+pub fn block_reachability(block_dag: &[Vec<usize>]) -> Vec<Vec<bool>> {
+    let nb = block_dag.len();
+    let mut reach = vec![vec![false; nb]; nb];
+
+    // Edges go forward, so successors are complete by the time we visit a block in reverse.
+    for a in (0..nb).rev() {
+        reach[a][a] = true;
+        for &b in &block_dag[a] {
+            debug_assert!(b > a, "block_dag edge {a} -> {b} is not forward");
+            let (head, tail) = reach.split_at_mut(b);
+            for (dst, &src) in head[a].iter_mut().zip(&tail[0]) {
+                *dst |= src;
+            }
+        }
+    }
+
+    reach
+}
+
+/// Block-level structural pattern of the inverse.
+///
+/// Entry `(a, b)` is true iff block `(a, b)` of `U⁻¹` (with `U = P A Q`) can be structurally
+/// nonzero, i.e. iff block `b` is reachable from block `a` in the condensation. Blocks are
+/// numbered in solve order, so the result is upper triangular with a full diagonal.
+///
+/// Only meaningful for structurally nonsingular (square, perfectly matched) inputs.
+pub fn inverse_pattern(structure: &UpperBtfStructure) -> DMatrix<bool> {
+    let reach = block_reachability(&structure.block_dag);
+    let nb = reach.len();
+    DMatrix::from_fn(nb, nb, |a, b| reach[a][b])
+}
+
+/// Entry-level structural pattern of `A⁻¹`, in original indices.
+///
+/// The result has shape `ncols x nrows`: entry `(j, i)` is true iff `(A⁻¹)[(j, i)]` can be
+/// structurally nonzero, i.e. iff variable `j` can depend on the right-hand side of equation
+/// `i`. Diagonal blocks of an irreducible matrix have structurally full inverses, so this is
+/// the block pattern of [`inverse_pattern`] expanded to entries.
+pub fn inverse_entry_pattern(structure: &UpperBtfStructure) -> DMatrix<bool> {
+    let reach = block_reachability(&structure.block_dag);
+    let row_block = structure.row_blocks();
+    let col_block = structure.col_blocks();
+
+    DMatrix::from_fn(col_block.len(), row_block.len(), |j, i| {
+        match col_block[j] {
+            Some(bj) => reach[bj][row_block[i]],
+            None => false,
+        }
+    })
+}
+
+/// Marks every block from which some block in `targets` is reachable (including the targets).
+fn blocks_reaching(
+    block_dag: &[Vec<usize>],
+    targets: impl IntoIterator<Item = usize>,
+) -> Vec<bool> {
+    let mut reaches = vec![false; block_dag.len()];
+    let mut last = 0;
+    for t in targets {
+        reaches[t] = true;
+        last = last.max(t);
+    }
+    // Edges go forward, so every block that can reach a target comes before it.
+    for b in (0..last).rev() {
+        reaches[b] = reaches[b] || block_dag[b].iter().any(|&c| reaches[c]);
+    }
+    reaches
+}
+
+/// Rows (equations) influenced by column (variable) `col`: the equations that consume the
+/// variable directly or through variables solved from it, i.e. every row in a block that
+/// reaches the block of `col`. Sorted ascending.
+///
+/// Columns outside every diagonal block (unmatched) are not determined by any equation and
+/// yield an empty set.
+pub fn rows_influenced_by_col(structure: &UpperBtfStructure, col: usize) -> Vec<usize> {
+    let Some(target) = structure.col_blocks()[col] else {
+        return Vec::new();
+    };
+    let reaches = blocks_reaching(&structure.block_dag, [target]);
+    let row_block = structure.row_blocks();
+
+    (0..row_block.len())
+        .filter(|&i| reaches[row_block[i]])
+        .collect()
+}
+
+/// Columns (variables) influenced by row (equation) `row`: the variables whose solved value
+/// depends on the equation, i.e. every matched column in a block that reaches the block of
+/// `row`. Sorted ascending.
+pub fn cols_influenced_by_row(structure: &UpperBtfStructure, row: usize) -> Vec<usize> {
+    let target = structure.row_blocks()[row];
+    let reaches = blocks_reaching(&structure.block_dag, [target]);
+
+    structure
+        .col_blocks()
+        .iter()
+        .enumerate()
+        .filter_map(|(j, b)| b.filter(|&b| reaches[b]).map(|_| j))
+        .collect()
+}
+
+/// Columns (solution components) that can be nonzero in `x = A⁻¹ b` when `b` is nonzero only
+/// on `rhs_rows`: the union of [`cols_influenced_by_row`] over the support, computed in one
+/// sweep of the block DAG. Sorted ascending; useful to pre-allocate sparse solutions.
+///
+/// Panics if a row index is out of range.
+pub fn solution_support(structure: &UpperBtfStructure, rhs_rows: &[usize]) -> Vec<usize> {
+    let row_block = structure.row_blocks();
+    let reaches = blocks_reaching(&structure.block_dag, rhs_rows.iter().map(|&i| row_block[i]));
+
+    structure
+        .col_blocks()
+        .iter()
+        .enumerate()
+        .filter_map(|(j, b)| b.filter(|&b| reaches[b]).map(|_| j))
+        .collect()
+}
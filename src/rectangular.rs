@@ -0,0 +1,145 @@
+use crate::adjacency::build_row_adjacency;
+use crate::error::BtfResult;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// Block triangular structure of a rectangular (or structurally singular) pattern, with the
+/// square matched core separated from the unmatched rows and columns.
+///
+/// [`UpperBtfStructure`] encodes the same information in `row_order`/`col_order` by
+/// convention (unmatched rows are singleton blocks without a column, unmatched columns trail);
+/// here every part is its own field, and the core's rows and columns are aligned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RectangularStructure {
+    /// Matched rows in block order.
+    pub core_rows: Vec<usize>,
+    /// `core_cols[k]` is the column matched to `core_rows[k]`.
+    pub core_cols: Vec<usize>,
+    /// Sizes of the diagonal blocks of the square core; they sum to `core_rows.len()`.
+    pub block_sizes: Vec<usize>,
+    /// Core block DAG in core block numbering (forward edges, sorted).
+    pub block_dag: Vec<Vec<usize>>,
+    /// Rows left unmatched (surplus equations), sorted.
+    pub unmatched_rows: Vec<usize>,
+    /// Columns left unmatched (free variables), sorted.
+    pub unmatched_cols: Vec<usize>,
+}
+
+impl RectangularStructure {
+    /// Structural rank: the size of the square core.
+    pub fn rank(&self) -> usize {
+        self.core_rows.len()
+    }
+
+    /// Shape of the analyzed pattern.
+    pub fn shape(&self) -> (usize, usize) {
+        (
+            self.core_rows.len() + self.unmatched_rows.len(),
+            self.core_cols.len() + self.unmatched_cols.len(),
+        )
+    }
+
+    /// Whether every row and column is matched (square, structurally nonsingular).
+    pub fn is_square_core(&self) -> bool {
+        self.unmatched_rows.is_empty() && self.unmatched_cols.is_empty()
+    }
+
+    /// `(rows, cols)` of every core block, in solve order; both have the block's size.
+    pub fn blocks(&self) -> Vec<(&[usize], &[usize])> {
+        let mut start = 0;
+        self.block_sizes
+            .iter()
+            .map(|&size| {
+                let range = start..start + size;
+                start += size;
+                (&self.core_rows[range.clone()], &self.core_cols[range])
+            })
+            .collect()
+    }
+}
+
+impl From<&UpperBtfStructure> for RectangularStructure {
+    /// Split a structure into its core and unmatched parts. Unmatched rows are sources of the
+    /// block DAG, so dropping their blocks loses no dependency between core blocks.
+    fn from(structure: &UpperBtfStructure) -> Self {
+        let mut core_rows = Vec::with_capacity(structure.matching_size);
+        let mut core_cols = Vec::with_capacity(structure.matching_size);
+        let mut block_sizes = Vec::new();
+        // Old block -> core block, `None` for the singleton blocks of unmatched rows.
+        let mut core_block = Vec::with_capacity(structure.num_blocks());
+        let mut start = 0;
+        for &size in &structure.block_sizes {
+            let rows = &structure.row_order[start..start + size];
+            start += size;
+            let matched: Vec<(usize, usize)> = rows
+                .iter()
+                .filter_map(|&r| structure.row_to_col[r].map(|c| (r, c)))
+                .collect();
+            if matched.is_empty() {
+                core_block.push(None);
+            } else {
+                core_block.push(Some(block_sizes.len()));
+                block_sizes.push(matched.len());
+                for (r, c) in matched {
+                    core_rows.push(r);
+                    core_cols.push(c);
+                }
+            }
+        }
+        // Not from the blocks: patterns without columns have no blocks at all.
+        let unmatched_rows = (0..structure.row_to_col.len())
+            .filter(|&r| structure.row_to_col[r].is_none())
+            .collect();
+
+        let mut block_dag = vec![Vec::new(); block_sizes.len()];
+        for (a, out) in structure.block_dag.iter().enumerate() {
+            if let Some(ca) = core_block[a] {
+                block_dag[ca] = out.iter().filter_map(|&b| core_block[b]).collect();
+            }
+        }
+
+        let col_to_row = structure.col_to_row();
+        let unmatched_cols = (0..col_to_row.len())
+            .filter(|&c| col_to_row[c].is_none())
+            .collect();
+
+        Self {
+            core_rows,
+            core_cols,
+            block_sizes,
+            block_dag,
+            unmatched_rows,
+            unmatched_cols,
+        }
+    }
+}
+
+/// Analyze `mat` (any shape) and return the typed split into core and unmatched parts.
+pub fn analyze_rectangular<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+) -> BtfResult<RectangularStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    analyze_rectangular_with_options(mat, &BtfOptions::default())
+}
+
+/// [`analyze_rectangular`] with explicit [`BtfOptions`]. The placement of unmatched rows and
+/// columns does not affect the result.
+pub fn analyze_rectangular_with_options<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> BtfResult<RectangularStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, mat.ncols(), options)?;
+    Ok(RectangularStructure::from(&structure))
+}
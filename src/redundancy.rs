@@ -0,0 +1,42 @@
+use crate::adjacency::build_row_adjacency;
+use crate::augmentation::alternating_rows;
+use crate::matching::hopcroft_karp;
+
+/// Rows whose removal leaves a structurally full-rank system.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowDeletion {
+    /// Structural rank of the pattern (unchanged by the deletion).
+    pub rank: usize,
+    /// A minimal deletion set: `nrows - rank` rows, sorted.
+    pub rows: Vec<usize>,
+    /// Rows that appear in some minimal deletion set (the Dulmage–Mendelsohn overdetermined
+    /// part), sorted. Every minimal deletion set is a subset of these.
+    pub candidates: Vec<usize>,
+}
+
+/// Find a minimal set of rows whose deletion makes `mat` structurally full row rank.
+///
+/// Deleting a row lowers the row count by one and the structural rank by at most one, so at
+/// least `nrows - rank` rows must go; the rows a maximum matching leaves free achieve this.
+/// The remaining rows are all matched, i.e. they form a vertex cover of the matching's edges on
+/// the row side. Rows outside `candidates` are matched by every maximum matching and must be
+/// kept; within `candidates`, swapping a deleted row for one it reaches by an alternating path
+/// gives another minimal set, which lets modelers choose which redundant equations to drop.
+/// Both lists are empty when the pattern already has full row rank.
+pub fn minimal_row_deletion<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> RowDeletion
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let matching = hopcroft_karp(&row_adj, mat.ncols());
+    RowDeletion {
+        rank: matching.size,
+        rows: (0..row_adj.len())
+            .filter(|&i| matching.row_to_col[i].is_none())
+            .collect(),
+        candidates: alternating_rows(&row_adj, &matching),
+    }
+}
@@ -0,0 +1,269 @@
+use std::fmt::{Display, Write};
+
+use crate::adjacency::build_row_adjacency;
+use crate::augmentation::minimal_augmentation;
+use crate::error::{BtfError, BtfResult};
+use crate::nullspace::null_space_support;
+use crate::structure_from_row_adjacency;
+
+/// Largest spy plot side, in pixels; cells shrink to fit but never below one pixel.
+const SPY_PIXELS: usize = 600;
+
+/// Self-contained HTML report of the block triangular structure of `mat` (feature `html`).
+///
+/// The page has no external resources: a summary, an SVG spy plot of `P A Q` with the
+/// diagonal blocks outlined, a block table, an SVG view of the block DAG, and singularity
+/// diagnostics (structural rank, minimal augmentation, null-space support) when the pattern is
+/// not structurally full rank. `title` is HTML-escaped. The output depends only on the
+/// pattern, so reports of identical structures diff cleanly.
+pub fn html_report<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>, title: &str) -> String
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    render(mat, title, &|i| i.to_string(), &|j| j.to_string())
+}
+
+/// [`html_report`] naming rows and columns by their labels instead of indices (block table,
+/// spy plot tooltips, and diagnostics).
+///
+/// Fails with [`BtfError::DimensionMismatch`] if the label counts do not match the shape of
+/// `mat`.
+pub fn html_report_labeled<L, T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    title: &str,
+    row_labels: &[L],
+    col_labels: &[L],
+) -> BtfResult<String>
+where
+    L: Display,
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let expected = mat.shape();
+    let found = (row_labels.len(), col_labels.len());
+    if expected != found {
+        return Err(BtfError::DimensionMismatch { expected, found });
+    }
+    Ok(render(
+        mat,
+        title,
+        &|i| escape(&row_labels[i].to_string()),
+        &|j| escape(&col_labels[j].to_string()),
+    ))
+}
+
+fn render<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    title: &str,
+    row_name: &dyn Fn(usize) -> String,
+    col_name: &dyn Fn(usize) -> String,
+) -> String
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (nrows, ncols) = (row_adj.len(), mat.ncols());
+    let (structure, _) = structure_from_row_adjacency(&row_adj, ncols);
+    let nnz: usize = row_adj.iter().map(Vec::len).sum();
+    let title = escape(title);
+
+    let mut html = String::new();
+    let w = &mut html;
+    let _ = write!(
+        w,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n\
+         <style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:2px 8px;text-align:left}}\
+         .warn{{color:#b00}}</style></head><body>\n<h1>{title}</h1>\n"
+    );
+
+    // Summary.
+    let _ = write!(
+        w,
+        "<h2>Summary</h2>\n<table>\n<tr><th>Shape</th><td>{nrows} x {ncols}</td></tr>\n\
+         <tr><th>Nonzeros</th><td>{nnz}</td></tr>\n\
+         <tr><th>Structural rank</th><td>{}</td></tr>\n\
+         <tr><th>Blocks</th><td>{}</td></tr>\n\
+         <tr><th>Largest block</th><td>{}</td></tr>\n\
+         <tr><th>Singleton blocks</th><td>{}</td></tr>\n</table>\n",
+        structure.matching_size,
+        structure.num_blocks(),
+        structure.largest_block_size(),
+        structure.singleton_block_count(),
+    );
+
+    // Spy plot of the permuted matrix.
+    let mut row_pos = vec![0; nrows];
+    for (p, &i) in structure.row_order.iter().enumerate() {
+        row_pos[i] = p;
+    }
+    let mut col_pos = vec![0; ncols];
+    for (p, &j) in structure.col_order.iter().enumerate() {
+        col_pos[j] = p;
+    }
+    let cell = (SPY_PIXELS / nrows.max(ncols).max(1)).max(1);
+    let _ = write!(
+        w,
+        "<h2>Permuted pattern</h2>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" \
+         height=\"{}\"><rect width=\"100%\" height=\"100%\" fill=\"white\" stroke=\"#888\"/>\n",
+        ncols * cell,
+        nrows * cell
+    );
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            let _ = writeln!(
+                w,
+                "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" fill=\"#246\">\
+                 <title>{}, {}</title></rect>",
+                col_pos[j] * cell,
+                row_pos[i] * cell,
+                row_name(i),
+                col_name(j)
+            );
+        }
+    }
+    let mut row_start = 0;
+    for ((&rows, cols), col_start) in structure
+        .block_sizes
+        .iter()
+        .zip(structure.block_col_sizes())
+        .zip(structure.block_col_starts())
+    {
+        let _ = writeln!(
+            w,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#d60\"/>",
+            col_start * cell,
+            row_start * cell,
+            cols * cell,
+            rows * cell
+        );
+        row_start += rows;
+    }
+    w.push_str("</svg>\n");
+
+    // Block table.
+    let mut preds = vec![Vec::new(); structure.num_blocks()];
+    for (a, out) in structure.block_dag.iter().enumerate() {
+        for &b in out {
+            preds[b].push(a);
+        }
+    }
+    w.push_str(
+        "<h2>Blocks</h2>\n<table>\n<tr><th>Block</th><th>Size</th><th>Rows</th>\
+         <th>Columns</th><th>Depends on</th><th>Feeds</th></tr>\n",
+    );
+    let (block_rows, block_cols) = structure.solve_order_blocks();
+    for (b, (rows, cols)) in block_rows.iter().zip(&block_cols).enumerate() {
+        let _ = writeln!(
+            w,
+            "<tr><td>{b}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            rows.len(),
+            names(rows, row_name),
+            names(cols, col_name),
+            list(&structure.block_dag[b]),
+            list(&preds[b]),
+        );
+    }
+    w.push_str("</table>\n");
+
+    // Block DAG: blocks along a line in solve order, edges as arcs above it.
+    const STEP: usize = 40;
+    let nb = structure.num_blocks();
+    let height = 30 + STEP / 2 * nb.max(1);
+    let _ = write!(
+        w,
+        "<h2>Block DAG</h2>\n<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" \
+         height=\"{height}\">\n",
+        STEP * nb.max(1)
+    );
+    let base = height - 15;
+    for (a, out) in structure.block_dag.iter().enumerate() {
+        for &b in out {
+            let (x0, x1) = (STEP / 2 + a * STEP, STEP / 2 + b * STEP);
+            let rise = (b - a) * STEP / 2;
+            let _ = writeln!(
+                w,
+                "<path d=\"M{x0},{base} Q{},{} {x1},{base}\" fill=\"none\" stroke=\"#888\"/>",
+                (x0 + x1) / 2,
+                base.saturating_sub(rise)
+            );
+        }
+    }
+    for (b, &size) in structure.block_sizes.iter().enumerate() {
+        let x = STEP / 2 + b * STEP;
+        let fill = if size > 1 { "#d60" } else { "#246" };
+        let _ = writeln!(
+            w,
+            "<circle cx=\"{x}\" cy=\"{base}\" r=\"8\" fill=\"{fill}\"><title>block {b}, \
+             size {size}</title></circle>"
+        );
+    }
+    w.push_str("</svg>\n");
+
+    // Singularity diagnostics.
+    w.push_str("<h2>Singularity diagnostics</h2>\n");
+    if structure.matching_size == nrows.min(ncols) {
+        w.push_str("<p>Structurally full rank.</p>\n");
+    } else {
+        let aug = minimal_augmentation(mat);
+        let ns = null_space_support(mat);
+        let _ = write!(
+            w,
+            "<p class=\"warn\">Structural rank {} of {}.</p>\n<table>\n\
+             <tr><th>Suggested new entries</th><td>{}</td></tr>\n\
+             <tr><th>Overdetermined rows</th><td>{}</td></tr>\n\
+             <tr><th>Underdetermined columns</th><td>{}</td></tr>\n</table>\n",
+            structure.matching_size,
+            nrows.min(ncols),
+            aug.entries
+                .iter()
+                .map(|&(i, j)| format!("({}, {})", row_name(i), col_name(j)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            names(&ns.left, row_name),
+            names(&ns.right, col_name),
+        );
+    }
+
+    w.push_str("</body></html>\n");
+    html
+}
+
+fn list(items: &[usize]) -> String {
+    items
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn names(items: &[usize], name: &dyn Fn(usize) -> String) -> String {
+    items
+        .iter()
+        .map(|&k| name(k))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
@@ -1,5 +1,10 @@
 /// Tarjan SCC on a directed graph adjacency list.
 pub fn tarjan_scc(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    tarjan_on(graph, vec![false; graph.len()])
+}
+
+/// Tarjan on the nodes not marked `skip`, ignoring edges into skipped nodes.
+fn tarjan_on(graph: &[Vec<usize>], skip: Vec<bool>) -> Vec<Vec<usize>> {
     let n = graph.len();
     let mut state = TarjanState {
         index: 0,
@@ -8,10 +13,11 @@ pub fn tarjan_scc(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
         idx: vec![None; n],
         low: vec![0; n],
         comps: Vec::new(),
+        skip,
     };
 
     for v in 0..n {
-        if state.idx[v].is_none() {
+        if state.idx[v].is_none() && !state.skip[v] {
             strongconnect(v, graph, &mut state);
         }
     }
@@ -19,7 +25,68 @@ pub fn tarjan_scc(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
     state.comps
 }
 
+/// SCCs of `graph` in reverse topological order, like [`tarjan_scc`], after trimming.
+///
+/// Nodes with no remaining in- or out-edges (self-loops included) cannot share a cycle with
+/// any other node, so they are peeled off iteratively as singleton components; only the
+/// remaining core goes through Tarjan. On nearly triangular dependency graphs the core is a
+/// small fraction of the nodes. Trimmed sinks come first in the order they were removed, then
+/// the core's components, then trimmed sources in reverse removal order, which keeps the
+/// result reverse topological. The components equal [`tarjan_scc`]'s, possibly listed in a
+/// different (equally valid) order.
+pub fn trimmed_tarjan_scc(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = graph.len();
+    let mut preds = vec![Vec::new(); n];
+    let mut in_deg = vec![0usize; n];
+    let mut out_deg = vec![0usize; n];
+    for (u, out) in graph.iter().enumerate() {
+        out_deg[u] = out.len();
+        for &v in out {
+            preds[v].push(u);
+            in_deg[v] += 1;
+        }
+    }
+
+    let mut trimmed = vec![false; n];
+    let mut sinks = Vec::new();
+    let mut sources = Vec::new();
+    let mut work: Vec<usize> = (0..n)
+        .filter(|&v| in_deg[v] == 0 || out_deg[v] == 0)
+        .collect();
+    while let Some(v) = work.pop() {
+        if trimmed[v] {
+            continue;
+        }
+        trimmed[v] = true;
+        if out_deg[v] == 0 {
+            sinks.push(v);
+            for &u in &preds[v] {
+                out_deg[u] -= 1;
+                if out_deg[u] == 0 && !trimmed[u] {
+                    work.push(u);
+                }
+            }
+        } else {
+            sources.push(v);
+            for &w in &graph[v] {
+                in_deg[w] -= 1;
+                if in_deg[w] == 0 && !trimmed[w] {
+                    work.push(w);
+                }
+            }
+        }
+    }
+
+    let mut comps: Vec<Vec<usize>> = sinks.into_iter().map(|v| vec![v]).collect();
+    if comps.len() + sources.len() < n {
+        comps.extend(tarjan_on(graph, trimmed));
+    }
+    comps.extend(sources.into_iter().rev().map(|v| vec![v]));
+    comps
+}
+
 struct TarjanState {
+    skip: Vec<bool>,
     index: usize,
     stack: Vec<usize>,
     on_stack: Vec<bool>,
@@ -37,6 +104,9 @@ fn strongconnect(v: usize, graph: &[Vec<usize>], state: &mut TarjanState) {
     state.on_stack[v] = true;
 
     for &w in &graph[v] {
+        if state.skip[w] {
+            continue;
+        }
         if state.idx[w].is_none() {
             strongconnect(w, graph, state);
             state.low[v] = state.low[v].min(state.low[w]);
@@ -88,3 +158,31 @@ pub fn condensation_dag(graph: &[Vec<usize>], comp_of: &[usize], ncomp: usize) -
     }
     dag
 }
+
+/// SCCs of a directed graph together with the component map and condensation DAG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condensation {
+    /// Strongly connected components, in Tarjan's (reverse topological) discovery order.
+    pub sccs: Vec<Vec<usize>>,
+    /// Node -> index of its component in `sccs`.
+    pub comp_of: Vec<usize>,
+    /// Condensation DAG over component indices (sorted, deduplicated, no self-loops).
+    pub dag: Vec<Vec<usize>>,
+}
+
+/// Compute SCCs, component map and condensation DAG of `graph` in one pass.
+pub fn condense(graph: &[Vec<usize>]) -> Condensation {
+    let sccs = tarjan_scc(graph);
+    let comp_of = scc_id_map(&sccs, graph.len());
+    let dag = condensation_dag(graph, &comp_of, sccs.len());
+    Condensation { sccs, comp_of, dag }
+}
+
+/// [`condense`] using [`trimmed_tarjan_scc`]: the same components, DAG and component map up
+/// to a relabeling of the components.
+pub fn condense_trimmed(graph: &[Vec<usize>]) -> Condensation {
+    let sccs = trimmed_tarjan_scc(graph);
+    let comp_of = scc_id_map(&sccs, graph.len());
+    let dag = condensation_dag(graph, &comp_of, sccs.len());
+    Condensation { sccs, comp_of, dag }
+}
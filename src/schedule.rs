@@ -0,0 +1,66 @@
+use crate::UpperBtfStructure;
+
+/// One diagonal block of a [`Schedule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduledBlock {
+    /// Block index (solve-order numbering of the structure).
+    pub id: usize,
+    /// Original row indices (equations) of the block.
+    pub rows: Vec<usize>,
+    /// Original column indices (unknowns) matched to `rows`; empty for a block holding a
+    /// single unmatched row. Unmatched columns belong to no block.
+    pub cols: Vec<usize>,
+    /// Blocks whose solutions must be available before this block can be solved.
+    pub depends_on: Vec<usize>,
+}
+
+/// Runtime-agnostic description of the block solves: the blocks, their dependencies, and
+/// level sets of mutually independent blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schedule {
+    /// Blocks, indexed by id.
+    pub blocks: Vec<ScheduledBlock>,
+    /// `levels[l]` holds the (sorted) blocks whose dependencies all lie in earlier levels;
+    /// blocks within a level can be solved concurrently.
+    pub levels: Vec<Vec<usize>>,
+}
+
+/// Build the block-solve schedule of `structure`.
+///
+/// Back-substitution on `U = P A Q` solves block `b` after every block it couples to
+/// (`structure.block_dag[b]`), so level 0 contains the blocks without couplings.
+pub fn schedule(structure: &UpperBtfStructure) -> Schedule {
+    let nb = structure.num_blocks();
+
+    let (block_rows, block_cols) = structure.solve_order_blocks();
+    let blocks = block_rows
+        .into_iter()
+        .zip(block_cols)
+        .enumerate()
+        .map(|(id, (rows, cols))| ScheduledBlock {
+            id,
+            rows,
+            cols,
+            depends_on: structure.block_dag[id].clone(),
+        })
+        .collect();
+
+    // Dependencies point to later blocks, so a reverse sweep sees them first.
+    let mut level = vec![0usize; nb];
+    for b in (0..nb).rev() {
+        level[b] = structure.block_dag[b]
+            .iter()
+            .map(|&d| level[d] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+    let depth = level.iter().map(|&l| l + 1).max().unwrap_or(0);
+    let mut levels = vec![Vec::new(); depth];
+    for (b, &l) in level.iter().enumerate() {
+        levels[l].push(b);
+    }
+
+    Schedule { blocks, levels }
+}
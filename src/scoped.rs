@@ -0,0 +1,27 @@
+//! Minimal data parallelism on `std::thread::scope`, for builds that cannot use rayon.
+
+/// `items.iter().map(f)` on `std::thread::scope` workers, one contiguous chunk per available
+/// core; results keep the input order and the first error (in that order) is returned.
+pub(crate) fn scoped_map<A: Sync, B: Send, E: Send>(
+    items: &[A],
+    f: impl Fn(&A) -> Result<B, E> + Sync,
+) -> Result<Vec<B>, E> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = items.len().div_ceil(threads).max(1);
+    let f = &f;
+    let chunks: Vec<Result<Vec<B>, E>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk)
+            .map(|part| scope.spawn(move || part.iter().map(f).collect()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("worker thread panicked"))
+            .collect()
+    });
+    let mut out = Vec::with_capacity(items.len());
+    for part in chunks {
+        out.extend(part?);
+    }
+    Ok(out)
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use nalgebra::DMatrix;
+
+use crate::canonical::canonical_structure;
+use crate::{ACYCLIC_CONDENSATION, UpperBtfStructure};
+
+/// A matrix or pattern with its rows and columns permuted by [`scramble`] or
+/// [`scramble_row_adjacency`].
+///
+/// Orders map new position to old index, as everywhere in the crate: row `i` of `matrix` is
+/// row `row_order[i]` of the input and column `j` is column `col_order[j]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scrambled<M> {
+    pub matrix: M,
+    pub row_order: Vec<usize>,
+    pub col_order: Vec<usize>,
+}
+
+impl<M> Scrambled<M> {
+    /// `structure`, computed on the scrambled matrix, in the indices of the input. Positional
+    /// data (block sizes, the block DAG, splits) is unchanged, so the result is a block
+    /// triangular form of the input.
+    pub fn unscramble_structure(&self, structure: &UpperBtfStructure) -> UpperBtfStructure {
+        let mut row_to_col = vec![None; self.row_order.len()];
+        for (r, c) in structure.row_to_col.iter().enumerate() {
+            row_to_col[self.row_order[r]] = c.map(|c| self.col_order[c]);
+        }
+        UpperBtfStructure {
+            row_order: structure
+                .row_order
+                .iter()
+                .map(|&r| self.row_order[r])
+                .collect(),
+            col_order: structure
+                .col_order
+                .iter()
+                .map(|&c| self.col_order[c])
+                .collect(),
+            row_to_col,
+            ..structure.clone()
+        }
+    }
+}
+
+/// Deterministic pseudo-random permutation of `0..n` for `seed` (Fisher–Yates driven by
+/// SplitMix64), the same on every platform and across versions of this crate.
+pub fn random_permutation(n: usize, seed: u64) -> Vec<usize> {
+    shuffled(n, &mut SplitMix64(seed))
+}
+
+/// Permute the rows and columns of `mat` by permutations drawn from `seed`, for checking
+/// that results do not depend on how the input happens to be numbered.
+pub fn scramble<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>, seed: u64) -> Scrambled<DMatrix<T>>
+where
+    T: nalgebra::Scalar,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let mut rng = SplitMix64(seed);
+    let row_order = shuffled(mat.nrows(), &mut rng);
+    let col_order = shuffled(mat.ncols(), &mut rng);
+    let matrix = DMatrix::from_fn(mat.nrows(), mat.ncols(), |i, j| {
+        mat[(row_order[i], col_order[j])].clone()
+    });
+    Scrambled {
+        matrix,
+        row_order,
+        col_order,
+    }
+}
+
+/// [`scramble`] for a row adjacency with `ncols` columns; the result is sorted again. Draws
+/// the same permutations as [`scramble`] for the same shape and seed.
+pub fn scramble_row_adjacency(
+    row_adj: &[Vec<usize>],
+    ncols: usize,
+    seed: u64,
+) -> Scrambled<Vec<Vec<usize>>> {
+    let mut rng = SplitMix64(seed);
+    let row_order = shuffled(row_adj.len(), &mut rng);
+    let col_order = shuffled(ncols, &mut rng);
+    let mut new_col = vec![0; ncols];
+    for (j, &c) in col_order.iter().enumerate() {
+        new_col[c] = j;
+    }
+    let matrix = row_order
+        .iter()
+        .map(|&r| {
+            let mut cols: Vec<usize> = row_adj[r].iter().map(|&c| new_col[c]).collect();
+            cols.sort_unstable();
+            cols
+        })
+        .collect();
+    Scrambled {
+        matrix,
+        row_order,
+        col_order,
+    }
+}
+
+/// How the analysis of a scrambled matrix differed from that of the original, as found by
+/// [`scramble_self_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrambleMismatch {
+    /// The maximum matchings have different sizes.
+    StructuralRank { original: usize, scrambled: usize },
+    /// The diagonal blocks, as sets of original rows and columns, differ.
+    Blocks,
+    /// The blocks agree but the dependencies between them differ.
+    BlockDag,
+}
+
+impl fmt::Display for ScrambleMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StructuralRank {
+                original,
+                scrambled,
+            } => write!(
+                f,
+                "structural rank {scrambled} after scrambling, {original} before"
+            ),
+            Self::Blocks => write!(f, "scrambling changed the diagonal blocks"),
+            Self::BlockDag => write!(f, "scrambling changed the dependencies between blocks"),
+        }
+    }
+}
+
+impl std::error::Error for ScrambleMismatch {}
+
+/// Check that the [canonical form](crate::canonical::canonical_structure) of `mat` and of
+/// `mat` scrambled with `seed` describe the same decomposition: the same structural rank
+/// and, for square structurally nonsingular patterns, the same diagonal blocks (rows and
+/// columns) with the same dependencies. Block orders may differ, as they depend on the
+/// numbering. Singular patterns are only compared by rank, since their blocks depend on the
+/// maximum matching.
+pub fn scramble_self_check<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    seed: u64,
+) -> Result<(), ScrambleMismatch>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let original = canonical_structure(mat).expect(ACYCLIC_CONDENSATION);
+    let scrambled = scramble(mat, seed);
+    let other = scrambled
+        .unscramble_structure(&canonical_structure(&scrambled.matrix).expect(ACYCLIC_CONDENSATION));
+
+    if original.matching_size != other.matching_size {
+        return Err(ScrambleMismatch::StructuralRank {
+            original: original.matching_size,
+            scrambled: other.matching_size,
+        });
+    }
+    if mat.nrows() != mat.ncols() || original.matching_size != mat.nrows() {
+        return Ok(());
+    }
+    if blocks(&original) != blocks(&other) {
+        return Err(ScrambleMismatch::Blocks);
+    }
+    if dependencies(&original) != dependencies(&other) {
+        return Err(ScrambleMismatch::BlockDag);
+    }
+    Ok(())
+}
+
+/// Blocks as `(sorted rows, sorted columns)`, independent of the block order.
+fn blocks(structure: &UpperBtfStructure) -> BTreeSet<(Vec<usize>, Vec<usize>)> {
+    let (rows, cols) = structure.solve_order_blocks();
+    rows.into_iter()
+        .zip(cols)
+        .map(|(mut rows, mut cols)| {
+            rows.sort_unstable();
+            cols.sort_unstable();
+            (rows, cols)
+        })
+        .collect()
+}
+
+/// Block DAG edges named by block identifier (smallest row).
+fn dependencies(structure: &UpperBtfStructure) -> BTreeSet<(usize, usize)> {
+    let ids = structure.block_ids();
+    structure
+        .block_dag
+        .iter()
+        .enumerate()
+        .flat_map(|(b, out)| out.iter().map(|&c| (ids[b], ids[c])).collect::<Vec<_>>())
+        .collect()
+}
+
+fn shuffled(n: usize, rng: &mut SplitMix64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        order.swap(i, rng.below(i + 1));
+    }
+    order
+}
+
+/// SplitMix64 (Steele, Lea and Flood), small and good enough for shuffling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n` (multiply-shift; the bias is negligible for `n` far below 2^64).
+    fn below(&mut self, n: usize) -> usize {
+        ((u128::from(self.next()) * n as u128) >> 64) as usize
+    }
+}
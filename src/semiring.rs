@@ -0,0 +1,86 @@
+use crate::error::BtfResult;
+use crate::{BtfOptions, UpperBtfStructure, try_structure_from_row_adjacency};
+
+/// Decides which entries of a matrix are structurally nonzero.
+///
+/// The analysis only ever looks at the boolean image of the entries (the structural
+/// semiring `({0, 1}, or, and)`), so any entry type can be analyzed once it is projected
+/// there: interval matrices, symbolic expressions (nonzero iff not identically zero),
+/// probabilities of being nonzero compared against a threshold, and so on.
+///
+/// Implemented for [`ExactZero`] (the crate's default convention), [`Threshold`] and every
+/// `Fn(&T) -> bool` closure.
+pub trait StructuralSemiring<T> {
+    /// Whether `value` is a structural nonzero.
+    fn is_nonzero(&self, value: &T) -> bool;
+}
+
+/// Nonzero iff different from `T::default()`, the convention of
+/// [`build_row_adjacency`](crate::adjacency::build_row_adjacency). Interval types whose
+/// default is `[0, 0]` are nonzero unless degenerate at zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExactZero;
+
+impl<T: PartialEq + Default> StructuralSemiring<T> for ExactZero {
+    fn is_nonzero(&self, value: &T) -> bool {
+        *value != T::default()
+    }
+}
+
+/// Nonzero iff strictly above `threshold`, e.g. for matrices holding the probability that
+/// an entry is nonzero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold<T> {
+    pub threshold: T,
+}
+
+impl<T: PartialOrd> StructuralSemiring<T> for Threshold<T> {
+    fn is_nonzero(&self, value: &T) -> bool {
+        *value > self.threshold
+    }
+}
+
+impl<T, F: Fn(&T) -> bool> StructuralSemiring<T> for F {
+    fn is_nonzero(&self, value: &T) -> bool {
+        self(value)
+    }
+}
+
+/// Row adjacency of the entries of `mat` that `semiring` deems nonzero (sorted, as
+/// [`build_row_adjacency`](crate::adjacency::build_row_adjacency)).
+pub fn row_adjacency_over<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    semiring: &impl StructuralSemiring<T>,
+) -> Vec<Vec<usize>>
+where
+    T: nalgebra::Scalar,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    (0..mat.nrows())
+        .map(|i| {
+            (0..mat.ncols())
+                .filter(|&j| semiring.is_nonzero(&mat[(i, j)]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Analyze the pattern of `mat` under `semiring`. With [`ExactZero`] this equals
+/// [`try_upper_block_triangular_structure_with_options`](crate::try_upper_block_triangular_structure_with_options).
+pub fn block_triangular_structure_over<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    semiring: &impl StructuralSemiring<T>,
+    options: &BtfOptions,
+) -> BtfResult<UpperBtfStructure>
+where
+    T: nalgebra::Scalar,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = row_adjacency_over(mat, semiring);
+    let (structure, _) = try_structure_from_row_adjacency(&row_adj, mat.ncols(), options)?;
+    Ok(structure)
+}
@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::{BtfError, BtfResult};
+use crate::matching::{Matching, augment};
+use crate::{
+    BtfOptions, UpperBtfStructure, matching_adjacency, matching_with_options,
+    structure_from_matching,
+};
+
+/// Repeated analysis of a pattern that changes slightly between steps (entries appearing and
+/// disappearing at a fixed shape), warm-started from the previous step.
+///
+/// [`Self::update`] keeps the matched pairs that survive the change and repairs the matching
+/// with one augmenting search per row left free, instead of matching from scratch; the
+/// dependency graph, SCCs and ordering are then recomputed, which is linear in the number of
+/// nonzeros. The repaired matching is maximum, so block sizes and the structural rank agree
+/// with a fresh analysis; for singular patterns the blocks can differ as they do between
+/// matching algorithms. With `options.priorities` set the matching is recomputed instead.
+#[derive(Debug, Clone)]
+pub struct BtfSession {
+    options: BtfOptions,
+    row_adj: Vec<Vec<usize>>,
+    ncols: usize,
+    matching: Matching,
+    structure: UpperBtfStructure,
+}
+
+/// What changed between two steps of a [`BtfSession`]. Blocks are numbered by their position
+/// in the respective structure.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructureChange {
+    /// Entries in the new pattern but not the old one.
+    pub added_entries: usize,
+    /// Entries in the old pattern but not the new one.
+    pub removed_entries: usize,
+    /// Rows whose matched column changed (including rows becoming matched or unmatched).
+    pub rematched_rows: usize,
+    /// Old blocks whose rows ended up in several new blocks: `(old block, new blocks)`.
+    pub split: Vec<(usize, Vec<usize>)>,
+    /// New blocks whose rows came from several old blocks: `(new block, old blocks)`.
+    pub merged: Vec<(usize, Vec<usize>)>,
+    /// New blocks with exactly the rows of some old block.
+    pub unchanged_blocks: usize,
+    /// The changes block by block, for invalidating per-block caches selectively: splits and
+    /// merges first, then the rows each split or merge moved, then modified blocks.
+    pub events: Vec<BlockEvent>,
+}
+
+/// One structural change between two steps of a [`BtfSession`].
+///
+/// Blocks are named by their identifier
+/// ([`UpperBtfStructure::block_ids`], the smallest row in the block): old blocks by their
+/// identifier in the previous structure, new blocks by the one in the new structure. A block
+/// that keeps its rows keeps its identifier, so caches keyed by it stay valid unless a
+/// [`BlockEvent::Modified`] names it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockEvent {
+    /// The rows of old block `block` are now spread over the new blocks `into`.
+    Split { block: usize, into: Vec<usize> },
+    /// New block `into` holds the rows of the old blocks `blocks`.
+    Merged { blocks: Vec<usize>, into: usize },
+    /// `rows` (sorted) went from old block `from` to new block `to` as part of a split or
+    /// merge.
+    RowsMoved {
+        rows: Vec<usize>,
+        from: usize,
+        to: usize,
+    },
+    /// Block `block` kept its rows, but an entry in one of them or a matched column changed,
+    /// so its numerical data (e.g. a factorization) is stale.
+    Modified { block: usize },
+}
+
+impl StructureChange {
+    /// Whether the block partition is the same as before (the block order may still differ).
+    pub fn is_partition_unchanged(&self) -> bool {
+        self.split.is_empty() && self.merged.is_empty()
+    }
+
+    /// Identifiers of the new blocks named by some event, i.e. whose cached data must be
+    /// rebuilt; sorted. Every other block is unchanged.
+    pub fn stale_blocks(&self) -> Vec<usize> {
+        let mut stale: Vec<usize> = self
+            .events
+            .iter()
+            .flat_map(|event| match event {
+                BlockEvent::Split { into, .. } => into.clone(),
+                BlockEvent::Merged { into, .. }
+                | BlockEvent::RowsMoved { to: into, .. }
+                | BlockEvent::Modified { block: into } => vec![*into],
+            })
+            .collect();
+        stale.sort_unstable();
+        stale.dedup();
+        stale
+    }
+
+    /// No entry changed: every block of `structure` is unchanged.
+    fn default_for(structure: &UpperBtfStructure) -> Self {
+        Self {
+            unchanged_blocks: structure.num_blocks(),
+            ..Self::default()
+        }
+    }
+}
+
+impl BtfSession {
+    /// Analyze the first step.
+    pub fn new<T, R, C, S>(
+        mat: &nalgebra::Matrix<T, R, C, S>,
+        options: BtfOptions,
+    ) -> BtfResult<Self>
+    where
+        T: nalgebra::Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let row_adj = build_row_adjacency(mat);
+        let ncols = mat.ncols();
+        let matching = matching_with_options(&row_adj, ncols, &options);
+        let structure = structure_from_matching(&row_adj, ncols, &options, &matching)?;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_analysis(started.elapsed(), &structure);
+        Ok(Self {
+            options,
+            row_adj,
+            ncols,
+            matching,
+            structure,
+        })
+    }
+
+    /// Structure of the latest step.
+    pub fn structure(&self) -> &UpperBtfStructure {
+        &self.structure
+    }
+
+    /// Maximum matching of the latest step.
+    pub fn matching(&self) -> &Matching {
+        &self.matching
+    }
+
+    /// Analyze the next step, warm-started from the current one, returning the new structure
+    /// and what changed. Fails if `mat` has a different shape.
+    pub fn update<T, R, C, S>(
+        &mut self,
+        mat: &nalgebra::Matrix<T, R, C, S>,
+    ) -> BtfResult<(&UpperBtfStructure, StructureChange)>
+    where
+        T: nalgebra::Scalar + PartialEq + Default,
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let expected = (self.row_adj.len(), self.ncols);
+        if mat.shape() != expected {
+            return Err(BtfError::DimensionMismatch {
+                expected,
+                found: mat.shape(),
+            });
+        }
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let row_adj = build_row_adjacency(mat);
+        let (added_entries, removed_entries) = entry_difference(&self.row_adj, &row_adj);
+        if added_entries == 0 && removed_entries == 0 {
+            return Ok((
+                &self.structure,
+                StructureChange::default_for(&self.structure),
+            ));
+        }
+
+        let matching = if self.options.priorities.is_empty() {
+            #[cfg(feature = "metrics")]
+            let matching_started = std::time::Instant::now();
+            let match_adj = matching_adjacency(&row_adj, &self.options);
+            let matching = repair_matching(&match_adj, self.ncols, &self.matching);
+            #[cfg(feature = "metrics")]
+            crate::telemetry::record_matching("repair", matching_started.elapsed(), &matching);
+            matching
+        } else {
+            matching_with_options(&row_adj, self.ncols, &self.options)
+        };
+        let structure = structure_from_matching(&row_adj, self.ncols, &self.options, &matching)?;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_analysis(started.elapsed(), &structure);
+
+        let rematched: Vec<bool> = (0..row_adj.len())
+            .map(|r| matching.row_to_col[r] != self.matching.row_to_col[r])
+            .collect();
+        let changed_rows: Vec<bool> = (0..row_adj.len())
+            .map(|r| rematched[r] || row_adj[r] != self.row_adj[r])
+            .collect();
+        let change = StructureChange {
+            added_entries,
+            removed_entries,
+            rematched_rows: rematched.iter().filter(|&&m| m).count(),
+            ..block_changes(&self.structure, &structure, &changed_rows)
+        };
+
+        self.row_adj = row_adj;
+        self.matching = matching;
+        self.structure = structure;
+        Ok((&self.structure, change))
+    }
+}
+
+/// `(added, removed)` entries between two sorted row adjacencies of the same shape.
+fn entry_difference(old: &[Vec<usize>], new: &[Vec<usize>]) -> (usize, usize) {
+    let (mut added, mut removed) = (0, 0);
+    for (a, b) in old.iter().zip(new) {
+        let common = a.iter().filter(|c| b.binary_search(c).is_ok()).count();
+        added += b.len() - common;
+        removed += a.len() - common;
+    }
+    (added, removed)
+}
+
+/// Maximum matching of `adj` grown from the pairs of `previous` still present in `adj`.
+///
+/// A row from which no augmenting path exists keeps having none after other augmentations,
+/// so one search per free row suffices.
+fn repair_matching(adj: &[Vec<usize>], ncols: usize, previous: &Matching) -> Matching {
+    let mut row_to_col = vec![None; adj.len()];
+    let mut col_to_row = vec![None; ncols];
+    for (r, cols) in adj.iter().enumerate() {
+        if let Some(c) = previous.row_to_col[r]
+            && cols.binary_search(&c).is_ok()
+        {
+            row_to_col[r] = Some(c);
+            col_to_row[c] = Some(r);
+        }
+    }
+    for r in 0..adj.len() {
+        if row_to_col[r].is_none() {
+            augment(r, adj, &mut row_to_col, &mut col_to_row);
+        }
+    }
+    let size = row_to_col.iter().flatten().count();
+    Matching {
+        row_to_col,
+        col_to_row,
+        size,
+    }
+}
+
+/// Split, merged and unchanged blocks and the [`BlockEvent`]s between two structures of the
+/// same pattern shape; `changed_rows` flags the rows whose entries or matched column changed.
+fn block_changes(
+    old: &UpperBtfStructure,
+    new: &UpperBtfStructure,
+    changed_rows: &[bool],
+) -> StructureChange {
+    // Without columns there are no blocks; the shape is the same, so in both structures.
+    if old.num_blocks() == 0 || new.num_blocks() == 0 {
+        return StructureChange::default();
+    }
+    let (old_of, new_of) = (old.row_blocks(), new.row_blocks());
+    let (old_ids, new_ids) = (old.block_ids(), new.block_ids());
+    let mut targets = vec![BTreeSet::new(); old.num_blocks()];
+    let mut sources = vec![BTreeSet::new(); new.num_blocks()];
+    // Rows per (old block, new block) pair, ascending.
+    let mut moved: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (r, (&a, &b)) in old_of.iter().zip(&new_of).enumerate() {
+        targets[a].insert(b);
+        sources[b].insert(a);
+        moved.entry((a, b)).or_default().push(r);
+    }
+    let same = |a: usize, b: usize| targets[a].len() == 1 && sources[b].len() == 1;
+
+    let split: Vec<(usize, Vec<usize>)> = targets
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.len() > 1)
+        .map(|(a, t)| (a, t.iter().copied().collect()))
+        .collect();
+    let merged: Vec<(usize, Vec<usize>)> = sources
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.len() > 1)
+        .map(|(b, s)| (b, s.iter().copied().collect()))
+        .collect();
+    let unchanged_blocks = sources
+        .iter()
+        .filter(|s| s.len() == 1 && targets[*s.first().expect("non-empty")].len() == 1)
+        .count();
+
+    let mut events = Vec::new();
+    for (a, into) in &split {
+        events.push(BlockEvent::Split {
+            block: old_ids[*a],
+            into: into.iter().map(|&b| new_ids[b]).collect(),
+        });
+    }
+    for (b, from) in &merged {
+        events.push(BlockEvent::Merged {
+            blocks: from.iter().map(|&a| old_ids[a]).collect(),
+            into: new_ids[*b],
+        });
+    }
+    for ((a, b), rows) in moved {
+        if !same(a, b) {
+            events.push(BlockEvent::RowsMoved {
+                rows,
+                from: old_ids[a],
+                to: new_ids[b],
+            });
+        } else if rows.iter().any(|&r| changed_rows[r]) {
+            events.push(BlockEvent::Modified { block: new_ids[b] });
+        }
+    }
+
+    StructureChange {
+        split,
+        merged,
+        unchanged_blocks,
+        events,
+        ..StructureChange::default()
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::adjacency::build_row_adjacency;
+use crate::dm::{DmPart, dm_from_matching};
+use crate::matching::hopcroft_karp;
+
+/// Why a pattern is structurally singular, in terms of equations (rows) and unknowns
+/// (columns); its [`Display`](fmt::Display) is a short multi-line summary for end users.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SingularityReport {
+    pub nrows: usize,
+    pub ncols: usize,
+    /// Size of a maximum matching.
+    pub structural_rank: usize,
+    /// Rows left unmatched by the maximum matching used, sorted. Which rows these are depends
+    /// on the matching; that some row of `overdetermined` is left over does not.
+    pub unmatched_rows: Vec<usize>,
+    /// Columns left unmatched by the same matching, sorted.
+    pub unmatched_cols: Vec<usize>,
+    /// More equations than unknowns: rows unmatched by some maximum matching and the
+    /// columns they constrain (the overdetermined part of the Dulmage–Mendelsohn
+    /// decomposition).
+    pub overdetermined: DmPart,
+    /// More unknowns than equations: columns unmatched by some maximum matching and the rows
+    /// involving them.
+    pub underdetermined: DmPart,
+    /// The rest, square and perfectly matched.
+    pub well_determined: DmPart,
+}
+
+impl SingularityReport {
+    /// Whether every row and column is in the well-determined part, i.e. the pattern is
+    /// square and structurally nonsingular.
+    pub fn is_well_determined(&self) -> bool {
+        self.overdetermined.rows.is_empty() && self.underdetermined.cols.is_empty()
+    }
+}
+
+/// Structural singularity diagnosis of `mat`: unmatched rows and columns and the partition
+/// into over-, under- and well-determined subsystems.
+///
+/// The partition is the coarse Dulmage–Mendelsohn decomposition
+/// ([`dulmage_mendelsohn`](crate::dm::dulmage_mendelsohn)) and does not depend on the
+/// matching. For a minimal set of rows to point at, see
+/// [`hall_violator`](crate::hall::hall_violator); for entries that would repair the rank, see
+/// [`minimal_augmentation`](crate::augmentation::minimal_augmentation).
+pub fn diagnose_singularity<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> SingularityReport
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (nrows, ncols) = (row_adj.len(), mat.ncols());
+    let matching = hopcroft_karp(&row_adj, ncols);
+    let dm = dm_from_matching(&row_adj, ncols, &matching);
+
+    let mut square_rows = dm.square.rows;
+    let mut square_cols = dm.square.cols;
+    square_rows.sort_unstable();
+    square_cols.sort_unstable();
+    SingularityReport {
+        nrows,
+        ncols,
+        structural_rank: matching.size,
+        unmatched_rows: (0..nrows)
+            .filter(|&i| matching.row_to_col[i].is_none())
+            .collect(),
+        unmatched_cols: (0..ncols)
+            .filter(|&j| matching.col_to_row[j].is_none())
+            .collect(),
+        overdetermined: dm.over,
+        underdetermined: dm.under,
+        well_determined: DmPart {
+            rows: square_rows,
+            cols: square_cols,
+        },
+    }
+}
+
+impl fmt::Display for SingularityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "structural rank {} of a {} x {} pattern",
+            self.structural_rank, self.nrows, self.ncols
+        )?;
+        if self.is_well_determined() {
+            return write!(f, ": structurally nonsingular");
+        }
+        for (name, part) in [
+            ("overdetermined", &self.overdetermined),
+            ("underdetermined", &self.underdetermined),
+        ] {
+            if !part.rows.is_empty() || !part.cols.is_empty() {
+                write!(
+                    f,
+                    "\n{name}: {} in {}",
+                    counted(part.rows.len(), "equation", "row", &part.rows),
+                    counted(part.cols.len(), "unknown", "column", &part.cols),
+                )?;
+            }
+        }
+        if !self.unmatched_rows.is_empty() {
+            write!(f, "\nunmatched rows: {}", list(&self.unmatched_rows))?;
+        }
+        if !self.unmatched_cols.is_empty() {
+            write!(f, "\nunmatched columns: {}", list(&self.unmatched_cols))?;
+        }
+        Ok(())
+    }
+}
+
+/// `"2 equations (rows 1, 3)"`.
+fn counted(n: usize, noun: &str, index_noun: &str, indices: &[usize]) -> String {
+    let plural = if n == 1 { "" } else { "s" };
+    if indices.is_empty() {
+        format!("{n} {noun}{plural}")
+    } else {
+        format!(
+            "{n} {noun}{plural} ({index_noun}{plural} {})",
+            list(indices)
+        )
+    }
+}
+
+fn list(indices: &[usize]) -> String {
+    let items: Vec<String> = indices.iter().map(usize::to_string).collect();
+    items.join(", ")
+}
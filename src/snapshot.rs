@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::UpperBtfStructure;
+use crate::permutation::{PermError, validate_permutation};
+
+/// Layout version written by [`BtfSnapshot::from_structure`].
+///
+/// Version 1 is the layout documented on [`BtfSnapshot`]. A later layout gets a new version;
+/// snapshots of every earlier version stay readable.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Coarse classification of an analyzed pattern, stored in a [`BtfSnapshot`].
+///
+/// Serialized as the snake_case variant name (`"irreducible"`, ...). Frozen: variants are
+/// never renamed or removed, so archived snapshots keep their meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum StructureClass {
+    /// No rows or no columns.
+    Empty,
+    /// Not square (and not empty).
+    Rectangular,
+    /// Square but structurally singular.
+    Singular,
+    /// Square, nonsingular and a single diagonal block.
+    Irreducible,
+    /// Square, nonsingular and every diagonal block `1 x 1`.
+    Triangular,
+    /// Square, nonsingular, with several blocks of which at least one is larger than `1 x 1`.
+    Reducible,
+}
+
+impl StructureClass {
+    /// Classification of `structure`.
+    pub fn of(structure: &UpperBtfStructure) -> Self {
+        let (nrows, ncols) = (structure.row_order.len(), structure.col_order.len());
+        if nrows == 0 || ncols == 0 {
+            Self::Empty
+        } else if nrows != ncols {
+            Self::Rectangular
+        } else if structure.matching_size < nrows {
+            Self::Singular
+        } else if structure.num_blocks() == 1 {
+            Self::Irreducible
+        } else if structure.is_fully_triangular() {
+            Self::Triangular
+        } else {
+            Self::Reducible
+        }
+    }
+}
+
+/// Frozen, versioned record of a block triangular analysis for long-term storage.
+///
+/// Unlike [`UpperBtfStructure`], which may gain fields and change representation between
+/// releases, the fields below are a stable contract: in layout version 1 they are exactly
+/// these, with these names, types and meanings, and with the `serde` feature they serialize
+/// under these names. Indices are 0-based original row and column indices.
+///
+/// - `version`: the layout version ([`SNAPSHOT_VERSION`] when written by this crate).
+/// - `nrows`, `ncols`: shape of the analyzed pattern.
+/// - `row_order`, `col_order`: new position -> original row / column.
+/// - `block_sizes`: sizes of the diagonal blocks in solve order; they cover `row_order` from
+///   the start.
+/// - `block_dag`: for every block, the later blocks it couples to (sorted).
+/// - `row_to_col`: original row -> matched original column, `null`/`None` if unmatched.
+/// - `matching_size`: number of matched rows.
+/// - `class`: the [`StructureClass`].
+///
+/// Amalgamation is part of the stored blocks; bordered splits of oversized blocks are not
+/// stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BtfSnapshot {
+    pub version: u32,
+    pub nrows: usize,
+    pub ncols: usize,
+    pub row_order: Vec<usize>,
+    pub col_order: Vec<usize>,
+    pub block_sizes: Vec<usize>,
+    pub block_dag: Vec<Vec<usize>>,
+    pub row_to_col: Vec<Option<usize>>,
+    pub matching_size: usize,
+    pub class: StructureClass,
+}
+
+impl BtfSnapshot {
+    /// Freeze `structure` in the current layout.
+    pub fn from_structure(structure: &UpperBtfStructure) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            nrows: structure.row_order.len(),
+            ncols: structure.col_order.len(),
+            row_order: structure.row_order.clone(),
+            col_order: structure.col_order.clone(),
+            block_sizes: structure.block_sizes.clone(),
+            block_dag: structure.block_dag.clone(),
+            row_to_col: structure.row_to_col.clone(),
+            matching_size: structure.matching_size,
+            class: StructureClass::of(structure),
+        }
+    }
+
+    /// Check that the snapshot has a readable version and is internally consistent (orders
+    /// are permutations of the right length, blocks and matching agree, `class` matches).
+    pub fn validate(&self) -> Result<(), SnapshotError> {
+        use SnapshotError::Inconsistent;
+
+        if self.version == 0 || self.version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: self.version,
+                supported: SNAPSHOT_VERSION,
+            });
+        }
+        if self.row_order.len() != self.nrows || self.row_to_col.len() != self.nrows {
+            return Err(Inconsistent(
+                "row_order and row_to_col must have nrows entries",
+            ));
+        }
+        if self.col_order.len() != self.ncols {
+            return Err(Inconsistent("col_order must have ncols entries"));
+        }
+        validate_permutation(&self.row_order).map_err(SnapshotError::RowOrder)?;
+        validate_permutation(&self.col_order).map_err(SnapshotError::ColOrder)?;
+
+        let mut col_used = vec![false; self.ncols];
+        for &c in self.row_to_col.iter().flatten() {
+            if c >= self.ncols || std::mem::replace(&mut col_used[c], true) {
+                return Err(Inconsistent(
+                    "row_to_col must match distinct in-range columns",
+                ));
+            }
+        }
+        if self.row_to_col.iter().flatten().count() != self.matching_size {
+            return Err(Inconsistent("matching_size must count the matched rows"));
+        }
+
+        let covered: usize = self.block_sizes.iter().sum();
+        if self.block_sizes.contains(&0) || covered > self.nrows {
+            return Err(Inconsistent(
+                "block_sizes must be positive and fit in nrows",
+            ));
+        }
+        let nblocks = self.block_sizes.len();
+        if self.block_dag.len() != nblocks
+            || self.block_dag.iter().enumerate().any(|(b, out)| {
+                !out.is_sorted_by(|x, y| x < y) || out.iter().any(|&c| c <= b || c >= nblocks)
+            })
+        {
+            return Err(Inconsistent(
+                "block_dag must list later blocks of block_sizes, sorted",
+            ));
+        }
+        if self.class != StructureClass::of(&self.structure_unchecked()) {
+            return Err(Inconsistent("class does not match the stored structure"));
+        }
+        Ok(())
+    }
+
+    /// The in-memory structure, after [`Self::validate`].
+    pub fn to_structure(&self) -> Result<UpperBtfStructure, SnapshotError> {
+        self.validate()?;
+        Ok(self.structure_unchecked())
+    }
+
+    fn structure_unchecked(&self) -> UpperBtfStructure {
+        UpperBtfStructure {
+            row_order: self.row_order.clone(),
+            col_order: self.col_order.clone(),
+            block_sizes: self.block_sizes.clone(),
+            matching_size: self.matching_size,
+            row_to_col: self.row_to_col.clone(),
+            block_dag: self.block_dag.clone(),
+            block_splits: Vec::new(),
+        }
+    }
+}
+
+impl From<&UpperBtfStructure> for BtfSnapshot {
+    fn from(structure: &UpperBtfStructure) -> Self {
+        Self::from_structure(structure)
+    }
+}
+
+/// Why a [`BtfSnapshot`] cannot be read back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The snapshot was written in a layout this version of the crate does not know.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// `row_order` is not a permutation.
+    RowOrder(PermError),
+    /// `col_order` is not a permutation.
+    ColOrder(PermError),
+    /// Fields contradict each other; the message names the violated invariant.
+    Inconsistent(&'static str),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "snapshot layout version {found} is not supported (up to {supported})"
+            ),
+            Self::RowOrder(err) => write!(f, "row_order: {err}"),
+            Self::ColOrder(err) => write!(f, "col_order: {err}"),
+            Self::Inconsistent(what) => write!(f, "inconsistent snapshot: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
@@ -0,0 +1,617 @@
+use std::fmt;
+
+use nalgebra::{ComplexField, DMatrix, DVector, Dyn, PermutationSequence, RealField};
+
+use crate::UpperBtfStructure;
+
+/// Errors from block factorization and solves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The matrix is not square.
+    NotSquare { nrows: usize, ncols: usize },
+    /// The structure has no perfect matching, so some diagonal block is singular.
+    StructurallySingular { matching_size: usize, n: usize },
+    /// Matrix or right-hand side dimensions disagree with the structure.
+    DimensionMismatch { expected: usize, found: usize },
+    /// A nonzero lies below the block diagonal of the structure (it was computed for a
+    /// different pattern).
+    PatternMismatch { row: usize, col: usize },
+    /// The LU factor of a diagonal block has a zero pivot.
+    SingularBlock { block: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSquare { nrows, ncols } => {
+                write!(f, "matrix is not square ({nrows} x {ncols})")
+            }
+            Self::StructurallySingular { matching_size, n } => write!(
+                f,
+                "matrix is structurally singular (matching size {matching_size} < {n})"
+            ),
+            Self::DimensionMismatch { expected, found } => {
+                write!(f, "dimension mismatch: expected {expected}, found {found}")
+            }
+            Self::PatternMismatch { row, col } => write!(
+                f,
+                "entry ({row}, {col}) lies below the block diagonal of the structure"
+            ),
+            Self::SingularBlock { block } => write!(f, "diagonal block {block} is singular"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Options for [`BlockLu::factor_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSolveOptions<R> {
+    /// A block whose LU pivot ratio `min |u_ii| / max |u_ii|` falls below this threshold is
+    /// refactored with column-pivoted QR. Zero (the default) disables the fallback.
+    pub qr_fallback_threshold: R,
+    /// QR diagonal entries with `|r_ii| <= rank_tolerance * |r_00|` are treated as zero; the
+    /// block is then solved in the basic least-squares sense on its numerical rank.
+    pub rank_tolerance: R,
+}
+
+impl<R: RealField> Default for BlockSolveOptions<R> {
+    fn default() -> Self {
+        Self {
+            qr_fallback_threshold: R::zero(),
+            rank_tolerance: R::default_epsilon() * nalgebra::convert::<f64, R>(1e3),
+        }
+    }
+}
+
+/// Which kernel factored a diagonal block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockFactorKind {
+    /// Dense LU with partial pivoting.
+    Lu,
+    /// Column-pivoted QR; `rank` is the numerical rank used by the solves.
+    Qr { rank: usize },
+}
+
+/// Dense factors of one diagonal block.
+#[derive(Debug, Clone)]
+enum BlockFactor<T: ComplexField> {
+    /// `P A = L U`.
+    Lu {
+        p: PermutationSequence<Dyn>,
+        l: DMatrix<T>,
+        u: DMatrix<T>,
+    },
+    /// `A Π = Q R`, truncated to the leading `rank` columns of `R` in solves.
+    Qr {
+        q: DMatrix<T>,
+        r: DMatrix<T>,
+        p: PermutationSequence<Dyn>,
+        rank: usize,
+    },
+}
+
+impl<T: ComplexField> BlockFactor<T> {
+    fn new(block: DMatrix<T>, options: &BlockSolveOptions<T::RealField>) -> Option<Self> {
+        let zero = nalgebra::zero::<T::RealField>();
+        let (p, l, u) = block.clone().lu().unpack();
+        let moduli: Vec<T::RealField> = u.diagonal().iter().map(|d| d.clone().modulus()).collect();
+        let max = moduli.iter().cloned().fold(zero.clone(), T::RealField::max);
+        let min = moduli.iter().cloned().fold(max.clone(), T::RealField::min);
+
+        let threshold: T::RealField = options.qr_fallback_threshold.clone();
+        if min > threshold.clone() * max {
+            return Some(Self::Lu { p, l, u });
+        }
+        if threshold == zero {
+            return None;
+        }
+
+        let (q, r, p) = block.col_piv_qr().unpack();
+        let r00 = r[(0, 0)].clone().modulus();
+        let rank = (0..r.nrows())
+            .take_while(|&i| {
+                r[(i, i)].clone().modulus() > options.rank_tolerance.clone() * r00.clone()
+            })
+            .count();
+        Some(Self::Qr { q, r, p, rank })
+    }
+
+    /// The triangular factor: `U` of the LU, `R` of the QR.
+    fn upper(&self) -> &DMatrix<T> {
+        match self {
+            Self::Lu { u, .. } => u,
+            Self::Qr { r, .. } => r,
+        }
+    }
+
+    fn kind(&self) -> BlockFactorKind {
+        match self {
+            Self::Lu { .. } => BlockFactorKind::Lu,
+            Self::Qr { rank, .. } => BlockFactorKind::Qr { rank: *rank },
+        }
+    }
+
+    /// Solve `A z = rhs` in place.
+    fn solve_mut(&self, rhs: &mut DVector<T>) -> bool {
+        match self {
+            Self::Lu { p, l, u } => {
+                p.permute_rows(rhs);
+                l.solve_lower_triangular_mut(rhs) && u.solve_upper_triangular_mut(rhs)
+            }
+            Self::Qr { q, r, p, rank } => {
+                let mut y = q.ad_mul(rhs);
+                let k = *rank;
+                let ok = r
+                    .view((0, 0), (k, k))
+                    .solve_upper_triangular_mut(&mut y.rows_mut(0, k));
+                y.rows_mut(k, y.len() - k).fill(T::zero());
+                p.inv_permute_rows(&mut y);
+                *rhs = y;
+                ok
+            }
+        }
+    }
+
+    /// Solve `Aᵀ z = rhs` in place.
+    fn tr_solve_mut(&self, rhs: &mut DVector<T>) -> bool {
+        match self {
+            // A = Pᵀ L U, so Aᵀ = Uᵀ Lᵀ P.
+            Self::Lu { p, l, u } => {
+                let ok =
+                    u.tr_solve_upper_triangular_mut(rhs) && l.tr_solve_lower_triangular_mut(rhs);
+                p.inv_permute_rows(rhs);
+                ok
+            }
+            // A = Q R Πᵀ, so Aᵀ = Π Rᵀ Qᵀ.
+            Self::Qr { q, r, p, rank } => {
+                let k = *rank;
+                p.permute_rows(rhs);
+                let ok = r
+                    .view((0, 0), (k, k))
+                    .tr_solve_upper_triangular_mut(&mut rhs.rows_mut(0, k));
+                let n = rhs.len();
+                rhs.rows_mut(k, n - k).fill(T::zero());
+                *rhs = q.conjugate() * &*rhs;
+                ok
+            }
+        }
+    }
+}
+
+/// Which entries outside the diagonal blocks [`BlockLu::factor_split`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OffDiagonal {
+    /// Keep the couplings above the block diagonal and reject entries below it.
+    Exact,
+    /// Keep the couplings above the block diagonal and ignore entries below it.
+    Upper,
+    /// Ignore every entry outside the diagonal blocks.
+    Dropped,
+}
+
+/// The diagonal blocks and selected couplings of a matrix, cut along its structure.
+struct Assembled<T> {
+    /// Permuted position where each block starts; `block_starts[nb] == n`.
+    block_starts: Vec<usize>,
+    blocks: Vec<DMatrix<T>>,
+    /// Per block: `(row position, col position, value)` of entries right of the block.
+    coupling: Vec<Vec<(usize, usize, T)>>,
+}
+
+impl<T: ComplexField> Assembled<T> {
+    fn new<R, C, S>(
+        mat: &nalgebra::Matrix<T, R, C, S>,
+        structure: &UpperBtfStructure,
+        off: OffDiagonal,
+    ) -> Result<Self, SolveError>
+    where
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let (nrows, ncols) = mat.shape();
+        if nrows != ncols {
+            return Err(SolveError::NotSquare { nrows, ncols });
+        }
+        let n = nrows;
+        if structure.row_order.len() != n || structure.col_order.len() != n {
+            return Err(SolveError::DimensionMismatch {
+                expected: structure.row_order.len(),
+                found: n,
+            });
+        }
+        if structure.matching_size != n {
+            return Err(SolveError::StructurallySingular {
+                matching_size: structure.matching_size,
+                n,
+            });
+        }
+
+        let mut block_starts = Vec::with_capacity(structure.num_blocks() + 1);
+        let mut start = 0;
+        for &size in &structure.block_sizes {
+            block_starts.push(start);
+            start += size;
+        }
+        block_starts.push(start);
+
+        let mut col_pos = vec![0usize; n];
+        for (q, &c) in structure.col_order.iter().enumerate() {
+            col_pos[c] = q;
+        }
+
+        let zero = T::zero();
+        let mut blocks = Vec::with_capacity(structure.num_blocks());
+        let mut coupling = vec![Vec::new(); structure.num_blocks()];
+        for (b, window) in block_starts.windows(2).enumerate() {
+            let (lo, hi) = (window[0], window[1]);
+            let mut block = DMatrix::zeros(hi - lo, hi - lo);
+            for p in lo..hi {
+                let r = structure.row_order[p];
+                for c in 0..n {
+                    let v = mat[(r, c)].clone();
+                    if v == zero {
+                        continue;
+                    }
+                    let q = col_pos[c];
+                    if q < lo {
+                        if off == OffDiagonal::Exact {
+                            return Err(SolveError::PatternMismatch { row: r, col: c });
+                        }
+                    } else if q < hi {
+                        block[(p - lo, q - lo)] = v;
+                    } else if off != OffDiagonal::Dropped {
+                        coupling[b].push((p, q, v));
+                    }
+                }
+            }
+            blocks.push(block);
+        }
+
+        Ok(Assembled {
+            block_starts,
+            blocks,
+            coupling,
+        })
+    }
+}
+
+/// The diagonal blocks of `P A Q` cut along `structure` (rows aligned with their matched
+/// columns) and the permuted position where each starts; entries outside them are ignored.
+#[cfg(feature = "numeric-diagnostics")]
+pub(crate) fn diagonal_blocks<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> Result<(Vec<usize>, Vec<DMatrix<T>>), SolveError>
+where
+    T: ComplexField,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let assembled = Assembled::new(mat, structure, OffDiagonal::Dropped)?;
+    Ok((assembled.block_starts, assembled.blocks))
+}
+
+/// Block LU factorization of a square matrix along its upper BTF structure: each diagonal
+/// block is factored densely, the coupling blocks above the diagonal are kept as entries.
+///
+/// With `U = P A Q` block upper triangular, `A x = b` is solved by block back-substitution
+/// and `Aᵀ x = b` by block forward substitution with the transposed block factors.
+#[derive(Debug, Clone)]
+pub struct BlockLu<T: ComplexField> {
+    row_order: Vec<usize>,
+    col_order: Vec<usize>,
+    /// Permuted position where each block starts; `block_starts[nb] == n`.
+    block_starts: Vec<usize>,
+    factors: Vec<BlockFactor<T>>,
+    /// Per block: `(row position, col position, value)` of entries right of the block.
+    coupling: Vec<Vec<(usize, usize, T)>>,
+}
+
+impl<T: ComplexField> BlockLu<T> {
+    /// Factor the diagonal blocks of `mat` according to `structure` (which must come from the
+    /// pattern of `mat`, or a superset of it) with LU only.
+    pub fn factor<R, C, S>(
+        mat: &nalgebra::Matrix<T, R, C, S>,
+        structure: &UpperBtfStructure,
+    ) -> Result<Self, SolveError>
+    where
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        Self::factor_with_options(mat, structure, &BlockSolveOptions::default())
+    }
+
+    /// Like [`BlockLu::factor`], falling back to column-pivoted QR for blocks whose LU is
+    /// ill-conditioned according to `options`.
+    pub fn factor_with_options<R, C, S>(
+        mat: &nalgebra::Matrix<T, R, C, S>,
+        structure: &UpperBtfStructure,
+        options: &BlockSolveOptions<T::RealField>,
+    ) -> Result<Self, SolveError>
+    where
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        Self::factor_split(mat, structure, options, OffDiagonal::Exact)
+    }
+
+    /// Factor the diagonal blocks, keeping the off-diagonal entries selected by `off`.
+    pub(crate) fn factor_split<R, C, S>(
+        mat: &nalgebra::Matrix<T, R, C, S>,
+        structure: &UpperBtfStructure,
+        options: &BlockSolveOptions<T::RealField>,
+        off: OffDiagonal,
+    ) -> Result<Self, SolveError>
+    where
+        R: nalgebra::Dim,
+        C: nalgebra::Dim,
+        S: nalgebra::Storage<T, R, C>,
+    {
+        let Assembled {
+            block_starts,
+            blocks,
+            coupling,
+        } = Assembled::new(mat, structure, off)?;
+        let mut factors = Vec::with_capacity(blocks.len());
+        for (b, block) in blocks.into_iter().enumerate() {
+            match BlockFactor::new(block, options) {
+                Some(f) => factors.push(f),
+                None => return Err(SolveError::SingularBlock { block: b }),
+            }
+        }
+
+        Ok(Self {
+            row_order: structure.row_order.clone(),
+            col_order: structure.col_order.clone(),
+            block_starts,
+            factors,
+            coupling,
+        })
+    }
+
+    /// Kernel used for each diagonal block, in block order.
+    pub fn block_factor_kinds(&self) -> Vec<BlockFactorKind> {
+        self.factors.iter().map(BlockFactor::kind).collect()
+    }
+
+    /// Dimension of the factored matrix.
+    pub fn dim(&self) -> usize {
+        self.row_order.len()
+    }
+
+    /// Solve `A x = b`.
+    pub fn solve(&self, b: &DVector<T>) -> Result<DVector<T>, SolveError> {
+        self.check_rhs(b)?;
+        let n = self.dim();
+
+        // U y = c with c = P b; blocks from last to first.
+        let mut y = DVector::from_fn(n, |p, _| b[self.row_order[p]].clone());
+        for k in (0..self.factors.len()).rev() {
+            for (p, q, v) in &self.coupling[k] {
+                let delta = v.clone() * y[*q].clone();
+                y[*p] -= delta;
+            }
+            let (lo, hi) = (self.block_starts[k], self.block_starts[k + 1]);
+            let mut rhs = y.rows(lo, hi - lo).into_owned();
+            if !self.factors[k].solve_mut(&mut rhs) {
+                return Err(SolveError::SingularBlock { block: k });
+            }
+            y.rows_mut(lo, hi - lo).copy_from(&rhs);
+        }
+
+        // x = Q y.
+        let mut x = DVector::zeros(n);
+        for (q, &c) in self.col_order.iter().enumerate() {
+            x[c] = y[q].clone();
+        }
+        Ok(x)
+    }
+
+    /// Solve `Aᵀ x = b` with the same factors (blocks traversed first to last).
+    pub fn solve_transpose(&self, b: &DVector<T>) -> Result<DVector<T>, SolveError> {
+        self.check_rhs(b)?;
+        let n = self.dim();
+
+        // Uᵀ w = d with d = Qᵀ b, then x = Pᵀ w.
+        let mut w = DVector::from_fn(n, |q, _| b[self.col_order[q]].clone());
+        for k in 0..self.factors.len() {
+            let (lo, hi) = (self.block_starts[k], self.block_starts[k + 1]);
+            let mut rhs = w.rows(lo, hi - lo).into_owned();
+            if !self.factors[k].tr_solve_mut(&mut rhs) {
+                return Err(SolveError::SingularBlock { block: k });
+            }
+            w.rows_mut(lo, hi - lo).copy_from(&rhs);
+
+            // Push this block's contribution into the later blocks it couples to.
+            for (p, q, v) in &self.coupling[k] {
+                let delta = v.clone() * w[*p].clone();
+                w[*q] -= delta;
+            }
+        }
+
+        let mut x = DVector::zeros(n);
+        for (p, &r) in self.row_order.iter().enumerate() {
+            x[r] = w[p].clone();
+        }
+        Ok(x)
+    }
+
+    fn check_rhs(&self, b: &DVector<T>) -> Result<(), SolveError> {
+        if b.len() != self.dim() {
+            return Err(SolveError::DimensionMismatch {
+                expected: self.dim(),
+                found: b.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one diagonal block in a [`SolveReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockStatus {
+    /// Factored and solved.
+    Solved,
+    /// The block is numerically singular: its factorization or triangular solve hit a zero
+    /// pivot.
+    Singular,
+    /// Factored but not solved because it couples to an unsolved block; `by` is the smallest
+    /// singular block it depends on, directly or through other blocks.
+    Blocked { by: usize },
+}
+
+/// Diagnostics of one diagonal block in a [`SolveReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDiagnostics<R> {
+    /// Original rows (equations) of the block, in permuted order.
+    pub rows: Vec<usize>,
+    /// Original columns (unknowns) of the block, aligned with `rows`.
+    pub cols: Vec<usize>,
+    /// Kernel that factored the block, or `None` if the factorization failed.
+    pub kind: Option<BlockFactorKind>,
+    /// Pivot growth `max |u_ij| / max |a_ij|` of the factored block (`R` for QR), or `None`
+    /// if the factorization failed or the block is all zeros.
+    pub pivot_growth: Option<R>,
+    /// Whether the block was solved.
+    pub status: BlockStatus,
+}
+
+/// Result of [`solve_with_report`]: the solution as far as it could be computed plus
+/// per-block diagnostics.
+#[derive(Debug, Clone)]
+pub struct SolveReport<T: ComplexField> {
+    /// Solution of `A x = b`. Unknowns of blocks that were not solved are zero.
+    pub x: DVector<T>,
+    /// One entry per diagonal block, in block (solve structure) order.
+    pub blocks: Vec<BlockDiagnostics<T::RealField>>,
+}
+
+impl<T: ComplexField> SolveReport<T> {
+    /// Whether every block was solved, so `x` solves the whole system.
+    pub fn is_solved(&self) -> bool {
+        self.blocks.iter().all(|b| b.status == BlockStatus::Solved)
+    }
+
+    /// Indices of the singular blocks, in block order.
+    pub fn singular_blocks(&self) -> Vec<usize> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.status == BlockStatus::Singular)
+            .map(|(k, _)| k)
+            .collect()
+    }
+}
+
+/// Factor and solve `A x = b` block by block without stopping at the first failing block.
+///
+/// Every diagonal block is factored (with the QR fallback of `options`) and every block
+/// that does not depend on a singular one is solved, so a failure mid-way still yields the
+/// unknowns of the independent blocks and names the failing blocks with their original
+/// equations. Only inputs that cannot be cut into blocks at all are errors: a non-square or
+/// structurally singular matrix, mismatched dimensions, or entries below the block diagonal.
+pub fn solve_with_report<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+    b: &DVector<T>,
+    options: &BlockSolveOptions<T::RealField>,
+) -> Result<SolveReport<T>, SolveError>
+where
+    T: ComplexField,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let Assembled {
+        block_starts,
+        blocks,
+        coupling,
+    } = Assembled::new(mat, structure, OffDiagonal::Exact)?;
+    let n = structure.row_order.len();
+    if b.len() != n {
+        return Err(SolveError::DimensionMismatch {
+            expected: n,
+            found: b.len(),
+        });
+    }
+
+    let nb = blocks.len();
+    let mut block_of_pos = vec![0usize; n];
+    for k in 0..nb {
+        block_of_pos[block_starts[k]..block_starts[k + 1]].fill(k);
+    }
+    let zero = nalgebra::zero::<T::RealField>();
+    let max_modulus = |m: &DMatrix<T>| {
+        m.iter()
+            .map(|v| v.clone().modulus())
+            .fold(zero.clone(), T::RealField::max)
+    };
+    let mut diagnostics = Vec::with_capacity(nb);
+    let mut factors = Vec::with_capacity(nb);
+    for (k, block) in blocks.into_iter().enumerate() {
+        let (lo, hi) = (block_starts[k], block_starts[k + 1]);
+        let scale = max_modulus(&block);
+        let factor = BlockFactor::new(block, options);
+        diagnostics.push(BlockDiagnostics {
+            rows: structure.row_order[lo..hi].to_vec(),
+            cols: structure.col_order[lo..hi].to_vec(),
+            kind: factor.as_ref().map(BlockFactor::kind),
+            pivot_growth: factor
+                .as_ref()
+                .filter(|_| scale != zero)
+                .map(|f| max_modulus(f.upper()) / scale.clone()),
+            status: BlockStatus::Singular,
+        });
+        factors.push(factor);
+    }
+
+    // Block back-substitution as in `BlockLu::solve`, skipping what a failure reaches.
+    let mut y = DVector::from_fn(n, |p, _| b[structure.row_order[p]].clone());
+    for k in (0..nb).rev() {
+        let (lo, hi) = (block_starts[k], block_starts[k + 1]);
+        let blocked_by = coupling[k]
+            .iter()
+            .filter_map(|&(_, q, _)| match diagnostics[block_of_pos[q]].status {
+                BlockStatus::Solved => None,
+                BlockStatus::Singular => Some(block_of_pos[q]),
+                BlockStatus::Blocked { by } => Some(by),
+            })
+            .min();
+        let solved = match (&factors[k], blocked_by) {
+            (_, Some(by)) => {
+                diagnostics[k].status = BlockStatus::Blocked { by };
+                false
+            }
+            (None, None) => false,
+            (Some(factor), None) => {
+                for (p, q, v) in &coupling[k] {
+                    let delta = v.clone() * y[*q].clone();
+                    y[*p] -= delta;
+                }
+                let mut rhs = y.rows(lo, hi - lo).into_owned();
+                let ok = factor.solve_mut(&mut rhs);
+                y.rows_mut(lo, hi - lo).copy_from(&rhs);
+                ok
+            }
+        };
+        if solved {
+            diagnostics[k].status = BlockStatus::Solved;
+        } else {
+            y.rows_mut(lo, hi - lo).fill(T::zero());
+        }
+    }
+
+    let mut x = DVector::zeros(n);
+    for (q, &c) in structure.col_order.iter().enumerate() {
+        x[c] = y[q].clone();
+    }
+    Ok(SolveReport {
+        x,
+        blocks: diagnostics,
+    })
+}
@@ -0,0 +1,141 @@
+use nalgebra::{ComplexField, DMatrix, DVector, RealField};
+
+use crate::UpperBtfStructure;
+use crate::solve::{SolveError, diagonal_blocks};
+
+/// Options for [`block_spectra`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralOptions<R> {
+    /// Power iteration steps per block before giving up on convergence.
+    pub max_iterations: usize,
+    /// The iteration has converged once `|B x - λ x| <= tolerance * |B x|` for the unit
+    /// iterate `x` and its Rayleigh quotient `λ`.
+    pub tolerance: R,
+}
+
+impl<R: RealField> Default for SpectralOptions<R> {
+    fn default() -> Self {
+        Self {
+            max_iterations: 500,
+            tolerance: nalgebra::convert(1e-10),
+        }
+    }
+}
+
+/// Spectral estimate of one diagonal block in [`block_spectra`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSpectrum<T: ComplexField> {
+    /// Original rows of the block, in permuted order.
+    pub rows: Vec<usize>,
+    /// Original columns of the block, aligned with `rows`.
+    pub cols: Vec<usize>,
+    /// Estimated spectral radius: `|dominant_eigenvalue|` when the power iteration
+    /// converged, otherwise the geometric mean growth `|B x| / |x|` over the second half of
+    /// the iterations (which still tends to the spectral radius when several eigenvalues
+    /// share the largest modulus, e.g. a complex pair of a real block).
+    pub spectral_radius: T::RealField,
+    /// The eigenvalue of largest modulus, if the power iteration converged to it.
+    pub dominant_eigenvalue: Option<T>,
+    /// Power iteration steps taken.
+    pub iterations: usize,
+}
+
+impl<T: ComplexField> BlockSpectrum<T> {
+    /// Whether the estimate is below one, i.e. a fixed-point iteration with this block as
+    /// its iteration matrix converges locally.
+    pub fn is_contractive(&self) -> bool {
+        self.spectral_radius < nalgebra::one::<T::RealField>()
+    }
+}
+
+/// Spectral radius estimate of every diagonal block of `P A Q`, in block order, by power
+/// iteration on the dense block.
+///
+/// Meant for the iteration matrices of fixed-point and waveform relaxation schemes set up
+/// along `structure`: row `i` of `mat` holds the derivatives of the update of the unknown
+/// matched to equation `i`, so each diagonal block is the iteration matrix of one loop and a
+/// block with spectral radius at or above one destabilizes the iteration over it (see
+/// [`destabilizing_blocks`]). Entries outside the diagonal blocks are ignored; `structure`
+/// must be square and structurally nonsingular.
+pub fn block_spectra<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+    options: &SpectralOptions<T::RealField>,
+) -> Result<Vec<BlockSpectrum<T>>, SolveError>
+where
+    T: ComplexField,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (block_starts, blocks) = diagonal_blocks(mat, structure)?;
+    Ok(blocks
+        .iter()
+        .enumerate()
+        .map(|(k, block)| {
+            let (lo, hi) = (block_starts[k], block_starts[k + 1]);
+            let (spectral_radius, dominant_eigenvalue, iterations) =
+                power_iteration(block, options);
+            BlockSpectrum {
+                rows: structure.row_order[lo..hi].to_vec(),
+                cols: structure.col_order[lo..hi].to_vec(),
+                spectral_radius,
+                dominant_eigenvalue,
+                iterations,
+            }
+        })
+        .collect())
+}
+
+/// Indices of the blocks of `spectra` that are not contractive, in block order.
+pub fn destabilizing_blocks<T: ComplexField>(spectra: &[BlockSpectrum<T>]) -> Vec<usize> {
+    spectra
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.is_contractive())
+        .map(|(k, _)| k)
+        .collect()
+}
+
+/// `(spectral radius, dominant eigenvalue if converged, iterations)` of `block`.
+fn power_iteration<T: ComplexField>(
+    block: &DMatrix<T>,
+    options: &SpectralOptions<T::RealField>,
+) -> (T::RealField, Option<T>, usize) {
+    let n = block.nrows();
+    // Deterministic start with no symmetry (golden-ratio fractions), so it is not orthogonal
+    // to the dominant eigenvector of structured blocks such as permutations.
+    let mut x = DVector::from_fn(n, |i, _| {
+        let phase = (i as f64 * 0.618_033_988_749_895).fract();
+        T::from_real(nalgebra::convert(1.0 + phase))
+    });
+    x.normalize_mut();
+
+    let mut growth: Vec<T::RealField> = Vec::with_capacity(options.max_iterations);
+    for step in 1..=options.max_iterations {
+        let y = block * &x;
+        let g = y.norm();
+        if g == nalgebra::zero::<T::RealField>() {
+            return (g, Some(T::zero()), step);
+        }
+        let lambda = x.dotc(&y);
+        if (&y - &x * lambda.clone()).norm() <= options.tolerance.clone() * g.clone() {
+            return (lambda.clone().modulus(), Some(lambda), step);
+        }
+        x = y.unscale(g.clone());
+        growth.push(g);
+    }
+
+    let tail = &growth[growth.len() / 2..];
+    let radius = if tail.is_empty() {
+        nalgebra::zero::<T::RealField>()
+    } else {
+        let sum = tail
+            .iter()
+            .fold(nalgebra::zero::<T::RealField>(), |acc, g| {
+                acc + g.clone().ln()
+            });
+        (sum / nalgebra::convert::<f64, T::RealField>(tail.len() as f64)).exp()
+    };
+    (radius, None, options.max_iterations)
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::matching::Matching;
+use crate::tearing::{TearingHeuristic, feedback_vertex_set};
+use crate::{ACYCLIC_CONDENSATION, BtfOptions, UpperBtfStructure, structure_from_matching};
+
+/// Bordered layout of a diagonal block larger than [`BtfOptions::max_block_size`].
+///
+/// The block's rows are reordered to its core rows (in the order of `core_block_sizes`)
+/// followed by `border_size` border rows, with the matched columns aligned: core columns,
+/// then the border (tearing) columns. The core is block upper triangular with diagonal blocks
+/// of at most `max_block_size`, so a dense per-block solver only factors those and a
+/// `border_size` Schur complement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockSplit {
+    /// Index of the split block in `block_sizes`.
+    pub block: usize,
+    /// Sizes of the core's diagonal blocks, in solve order.
+    pub core_block_sizes: Vec<usize>,
+    /// Number of trailing border rows (and columns) of the block.
+    pub border_size: usize,
+}
+
+impl BlockSplit {
+    /// Number of core rows (and columns).
+    pub fn core_size(&self) -> usize {
+        self.core_block_sizes.iter().sum()
+    }
+}
+
+/// Reorder every block with more than `max_size` rows, all of them matched, into bordered
+/// form and record it in `structure.block_splits`. The matching is kept: each tear removes a
+/// matched `(row, col)` pair picked by the greedy feedback vertex set heuristic from an
+/// oversized core block, until no core block is oversized. 0 disables splitting.
+pub(crate) fn split_oversized_blocks(
+    structure: &mut UpperBtfStructure,
+    row_adj: &[Vec<usize>],
+    max_size: usize,
+) {
+    if max_size == 0 {
+        return;
+    }
+    let mut col_pos = vec![0usize; structure.col_order.len()];
+    for (q, &c) in structure.col_order.iter().enumerate() {
+        col_pos[c] = q;
+    }
+
+    let mut start = 0;
+    for b in 0..structure.block_sizes.len() {
+        let n = structure.block_sizes[b];
+        let range = start..start + n;
+        start += n;
+        let rows = &structure.row_order[range.clone()];
+        if n <= max_size || rows.iter().any(|&r| structure.row_to_col[r].is_none()) {
+            continue;
+        }
+
+        let cols: Vec<usize> = rows
+            .iter()
+            .map(|&r| structure.row_to_col[r].expect("checked above"))
+            .collect();
+        let local_col: HashMap<usize, usize> =
+            cols.iter().enumerate().map(|(l, &c)| (c, l)).collect();
+        let local_adj: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|&r| {
+                let mut local: Vec<usize> = row_adj[r]
+                    .iter()
+                    .filter_map(|j| local_col.get(j).copied())
+                    .collect();
+                local.sort_unstable();
+                local
+            })
+            .collect();
+        let (core, border) = split_block(&local_adj, max_size);
+
+        // The block's matched-column slots in `col_order`, which need not be consecutive:
+        // `UnmatchedPlacement::Adjacent` can put unmatched columns between blocks that
+        // `min_block_size` merges.
+        let mut slots: Vec<usize> = cols.iter().map(|&c| col_pos[c]).collect();
+        slots.sort_unstable();
+        let new_rows: Vec<usize> = core
+            .row_order
+            .iter()
+            .chain(&border)
+            .map(|&l| rows[l])
+            .collect();
+        for (p, &r) in new_rows.iter().enumerate() {
+            structure.row_order[range.start + p] = r;
+            let c = structure.row_to_col[r].expect("checked above");
+            structure.col_order[slots[p]] = c;
+            col_pos[c] = slots[p];
+        }
+        structure.block_splits.push(BlockSplit {
+            block: b,
+            core_block_sizes: core.block_sizes,
+            border_size: border.len(),
+        });
+    }
+}
+
+/// Tear the block with local pattern `local_adj` (row `l` matched to column `l`) until its
+/// core has no block larger than `max_size`; returns the core structure (in indices into the
+/// kept local rows, translated back) and the torn local rows, sorted.
+fn split_block(local_adj: &[Vec<usize>], max_size: usize) -> (UpperBtfStructure, Vec<usize>) {
+    let n = local_adj.len();
+    let mut torn = vec![false; n];
+    loop {
+        let kept: Vec<usize> = (0..n).filter(|&l| !torn[l]).collect();
+        let mut new_index = vec![usize::MAX; n];
+        for (k, &l) in kept.iter().enumerate() {
+            new_index[l] = k;
+        }
+        let reduced: Vec<Vec<usize>> = kept
+            .iter()
+            .map(|&l| {
+                local_adj[l]
+                    .iter()
+                    .filter(|&&k| !torn[k])
+                    .map(|&k| new_index[k])
+                    .collect()
+            })
+            .collect();
+        let m = kept.len();
+        let identity = Matching {
+            row_to_col: (0..m).map(Some).collect(),
+            col_to_row: (0..m).map(Some).collect(),
+            size: m,
+        };
+        let mut core = structure_from_matching(&reduced, m, &BtfOptions::default(), &identity)
+            .expect(ACYCLIC_CONDENSATION);
+
+        let mut grew = false;
+        let mut sub_start = 0;
+        for &size in &core.block_sizes {
+            let sub = &core.row_order[sub_start..sub_start + size];
+            sub_start += size;
+            if size <= max_size {
+                continue;
+            }
+            // Sub-block rows are sorted, so local indices follow `binary_search`.
+            let graph: Vec<Vec<usize>> = sub
+                .iter()
+                .map(|&k| {
+                    reduced[k]
+                        .iter()
+                        .filter(|&&j| j != k)
+                        .filter_map(|j| sub.binary_search(j).ok())
+                        .collect()
+                })
+                .collect();
+            let pick = *feedback_vertex_set(&graph, TearingHeuristic::GreedyMfvs)
+                .first()
+                .expect("an irreducible block larger than 1x1 has a cycle");
+            torn[kept[sub[pick]]] = true;
+            grew = true;
+        }
+        if !grew {
+            for r in &mut core.row_order {
+                *r = kept[*r];
+            }
+            return (core, (0..n).filter(|&l| torn[l]).collect());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt::Write;
+
+use crate::UpperBtfStructure;
+use crate::adjacency::build_row_adjacency;
+
+/// Categorical palette for block colors, cycled for more blocks.
+const PALETTE: [(u8, u8, u8); 10] = [
+    (0x1f, 0x77, 0xb4),
+    (0xff, 0x7f, 0x0e),
+    (0x2c, 0xa0, 0x2c),
+    (0xd6, 0x27, 0x28),
+    (0x94, 0x67, 0xbd),
+    (0x8c, 0x56, 0x4b),
+    (0xe3, 0x77, 0xc2),
+    (0x7f, 0x7f, 0x7f),
+    (0xbc, 0xbd, 0x22),
+    (0x17, 0xbe, 0xcf),
+];
+
+/// Symbols for blocks in the text view, cycled for more blocks.
+const SYMBOLS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Largest SVG side per panel, in pixels; cells never shrink below one pixel.
+const PANEL_PIXELS: usize = 300;
+
+/// RGB color of block `b` in the spy views.
+pub fn block_color(b: usize) -> (u8, u8, u8) {
+    PALETTE[b % PALETTE.len()]
+}
+
+/// One nonzero's move from the original pattern to the permuted one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryMove {
+    /// Position in the original pattern.
+    pub from: (usize, usize),
+    /// Position in `P A Q`.
+    pub to: (usize, usize),
+    /// Diagonal block row the entry belongs to.
+    pub block: usize,
+}
+
+/// Where every nonzero of `mat` moves under the analyzed permutations, sorted by original
+/// position. Entries move with their row, so their destination block is their row's block.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn entry_migration<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> Vec<EntryMove>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    assert_eq!(
+        (nrows, ncols),
+        (structure.row_order.len(), structure.col_order.len()),
+        "pattern shape does not match the structure"
+    );
+    let row_pos = inverse(&structure.row_order);
+    let col_pos = inverse(&structure.col_order);
+    let block_of = structure.row_blocks();
+    build_row_adjacency(mat)
+        .iter()
+        .enumerate()
+        .flat_map(|(i, cols)| cols.iter().map(move |&j| (i, j)))
+        .map(|(i, j)| EntryMove {
+            from: (i, j),
+            to: (row_pos[i], col_pos[j]),
+            block: block_of[i],
+        })
+        .collect()
+}
+
+/// Original and permuted patterns side by side as text, each nonzero shown as the symbol of
+/// its destination block (`0`-`9`, `a`-`z`, `A`-`Z`, then repeating) and zeros as `.`.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn spy_diff_text<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> String
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    let mut before = vec![vec!['.'; ncols]; nrows];
+    let mut after = vec![vec!['.'; ncols]; nrows];
+    for m in entry_migration(mat, structure) {
+        let symbol = char::from(SYMBOLS[m.block % SYMBOLS.len()]);
+        before[m.from.0][m.from.1] = symbol;
+        after[m.to.0][m.to.1] = symbol;
+    }
+    let line = |row: &[char]| {
+        row.iter()
+            .map(char::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut out = String::new();
+    for (b, a) in before.iter().zip(&after) {
+        let _ = writeln!(out, "{}   |   {}", line(b), line(a));
+    }
+    out
+}
+
+/// Original (left) and permuted (right) patterns as one standalone SVG, each nonzero filled
+/// with the color of its destination block ([`block_color`]) and the diagonal blocks of the
+/// permuted panel outlined. Entries carry a `<title>` with their original position.
+///
+/// Panics if `mat` has a different shape than the analyzed matrix.
+pub fn spy_diff_svg<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    structure: &UpperBtfStructure,
+) -> String
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let (nrows, ncols) = mat.shape();
+    let cell = (PANEL_PIXELS / nrows.max(ncols).max(1)).max(1);
+    let (width, height) = (ncols * cell, nrows * cell);
+    let gap = 2 * cell.max(10);
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{height}\">",
+        2 * width + gap
+    );
+    for x0 in [0, width + gap] {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x0}\" y=\"0\" width=\"{width}\" height=\"{height}\" fill=\"white\" \
+             stroke=\"#888\"/>"
+        );
+    }
+    for m in entry_migration(mat, structure) {
+        let (r, g, b) = block_color(m.block);
+        for (x0, (row, col)) in [(0, m.from), (width + gap, m.to)] {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" \
+                 fill=\"#{r:02x}{g:02x}{b:02x}\"><title>({}, {}) block {}</title></rect>",
+                x0 + col * cell,
+                row * cell,
+                m.from.0,
+                m.from.1,
+                m.block
+            );
+        }
+    }
+    let mut row_start = 0;
+    for ((&rows, cols), col_start) in structure
+        .block_sizes
+        .iter()
+        .zip(structure.block_col_sizes())
+        .zip(structure.block_col_starts())
+    {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#000\"/>",
+            width + gap + col_start * cell,
+            row_start * cell,
+            cols * cell,
+            rows * cell
+        );
+        row_start += rows;
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn inverse(order: &[usize]) -> Vec<usize> {
+    let mut pos = vec![0; order.len()];
+    for (p, &k) in order.iter().enumerate() {
+        pos[k] = p;
+    }
+    pos
+}
@@ -0,0 +1,129 @@
+use crate::adjacency::build_row_adjacency;
+use crate::structure_from_row_adjacency;
+
+/// Quantitative measures of how much a pattern is reduced by the block triangular form.
+///
+/// All fractions are in `[0, 1]` and are `0.0` for empty inputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReducibilityMetrics {
+    /// Number of rows.
+    pub n: usize,
+    /// Number of structural nonzeros.
+    pub nnz: usize,
+    /// Fraction of rows that lie in diagonal blocks larger than 1x1.
+    pub nonsingleton_row_fraction: f64,
+    /// Fraction of nonzeros inside diagonal blocks.
+    pub diagonal_nnz_fraction: f64,
+    /// Fraction of nonzeros above the diagonal blocks (including entries in unmatched
+    /// trailing columns). Together with `diagonal_nnz_fraction` this sums to 1.
+    pub above_diagonal_nnz_fraction: f64,
+    /// Largest block size divided by `n`.
+    pub max_block_fraction: f64,
+}
+
+/// Compute [`ReducibilityMetrics`] for `mat` (same nonzero convention as the rest of the crate).
+///
+/// A fully triangular pattern has `nonsingleton_row_fraction == 0`; an irreducible one has
+/// `max_block_fraction == 1`.
+pub fn reducibility_metrics<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> ReducibilityMetrics
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, _) = structure_from_row_adjacency(&row_adj, mat.ncols());
+    let n = row_adj.len();
+
+    let row_block = structure.row_blocks();
+    let col_block = structure.col_blocks();
+
+    let mut nnz = 0;
+    let mut diagonal = 0;
+    for (i, cols) in row_adj.iter().enumerate() {
+        nnz += cols.len();
+        diagonal += cols
+            .iter()
+            .filter(|&&j| col_block[j] == Some(row_block[i]))
+            .count();
+    }
+
+    let nonsingleton_rows: usize = structure.block_sizes.iter().filter(|&&size| size > 1).sum();
+
+    let ratio = |num: usize, den: usize| {
+        if den == 0 {
+            0.0
+        } else {
+            num as f64 / den as f64
+        }
+    };
+
+    ReducibilityMetrics {
+        n,
+        nnz,
+        nonsingleton_row_fraction: ratio(nonsingleton_rows, n),
+        diagonal_nnz_fraction: ratio(diagonal, nnz),
+        above_diagonal_nnz_fraction: ratio(nnz - diagonal, nnz),
+        max_block_fraction: ratio(structure.largest_block_size(), n),
+    }
+}
+
+/// Structural symmetry of a pattern, see [`structural_symmetry`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymmetryReport {
+    /// Number of off-diagonal structural nonzeros.
+    pub offdiag_nnz: usize,
+    /// Fraction of off-diagonal nonzeros `(i, j)` whose transpose position `(j, i)` is also
+    /// nonzero; `1.0` when there are no off-diagonal nonzeros.
+    pub ratio: f64,
+    /// Off-diagonal nonzeros without a transpose partner, sorted by `(row, col)`.
+    pub asymmetric: Vec<(usize, usize)>,
+}
+
+impl SymmetryReport {
+    /// Whether the pattern is structurally symmetric.
+    pub fn is_symmetric(&self) -> bool {
+        self.asymmetric.is_empty()
+    }
+}
+
+/// Measure how far the pattern of `mat` is from structural symmetry.
+///
+/// Diagonal entries are symmetric by definition and not counted. For rectangular inputs,
+/// entries whose transpose position lies outside the matrix count as asymmetric. A (nearly)
+/// symmetric pattern with a zero-free diagonal can skip matching and go straight to the SCCs
+/// of its graph; otherwise the bipartite matching path is needed.
+pub fn structural_symmetry<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> SymmetryReport
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let nrows = row_adj.len();
+
+    let mut offdiag_nnz = 0;
+    let mut asymmetric = Vec::new();
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols.iter().filter(|&&j| j != i) {
+            offdiag_nnz += 1;
+            if j >= nrows || row_adj[j].binary_search(&i).is_err() {
+                asymmetric.push((i, j));
+            }
+        }
+    }
+
+    let ratio = if offdiag_nnz == 0 {
+        1.0
+    } else {
+        (offdiag_nnz - asymmetric.len()) as f64 / offdiag_nnz as f64
+    };
+
+    SymmetryReport {
+        offdiag_nnz,
+        ratio,
+        asymmetric,
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::csr::{CsrBuilder, CsrPattern};
+use crate::error::BtfResult;
+
+/// Root of the SuiteSparse Matrix Collection's Matrix Market tree.
+pub const SUITESPARSE_MM_URL: &str = "https://sparse.tamu.edu/MM";
+
+/// Most entries reserved up front from a size line; larger files grow the buffer as they are
+/// read, so a bogus header cannot trigger a huge allocation.
+const MAX_PREALLOCATED_ENTRIES: usize = 1 << 22;
+
+/// Errors from parsing a Matrix Market file or naming a collection matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixMarketError {
+    /// The `%%MatrixMarket` banner is missing, malformed or not a supported kind.
+    Header(String),
+    /// The size line is missing or malformed.
+    Size { line: usize },
+    /// An entry line is malformed or its indices lie outside the declared shape.
+    Entry { line: usize },
+    /// The file holds a different number of entries than the size line declares.
+    EntryCount { expected: usize, found: usize },
+    /// A collection name is not of the form `Group/Name`.
+    InvalidName(String),
+}
+
+impl fmt::Display for MatrixMarketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header(msg) => write!(f, "bad Matrix Market header: {msg}"),
+            Self::Size { line } => write!(f, "bad size line at line {line}"),
+            Self::Entry { line } => write!(f, "bad entry at line {line}"),
+            Self::EntryCount { expected, found } => {
+                write!(f, "expected {expected} entries, found {found}")
+            }
+            Self::InvalidName(name) => {
+                write!(f, "matrix name {name:?} is not of the form Group/Name")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatrixMarketError {}
+
+/// Treatment of stored entries whose value is zero.
+///
+/// Tools disagree on whether an explicitly stored zero is part of the sparsity pattern, so
+/// the choice is left to the caller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StoredZeros {
+    /// Every stored entry is structural (pattern semantics).
+    #[default]
+    Structural,
+    /// Entries whose stored value is zero are dropped (value semantics). `pattern` files
+    /// store no values, so nothing is dropped from them.
+    Dropped,
+}
+
+/// A Matrix Market pattern together with the number of stored zeros left out of it.
+#[derive(Debug, Clone)]
+pub struct MatrixMarketPattern {
+    /// The structural pattern.
+    pub pattern: CsrPattern,
+    /// Stored entries (as listed in the file, before mirroring) dropped because their value
+    /// is zero; always 0 with [`StoredZeros::Structural`].
+    pub dropped_zeros: usize,
+}
+
+/// Read the pattern of a Matrix Market `coordinate` file.
+///
+/// Every stored entry is structural, including explicit zeros, and values are ignored (see
+/// [`read_matrix_market_with`] to drop explicit zeros instead). `symmetric`,
+/// `skew-symmetric` and `hermitian` files store one triangle; the mirrored entries are added.
+/// Dense `array` files are rejected.
+pub fn read_matrix_market<R: BufRead>(reader: R) -> BtfResult<CsrPattern> {
+    Ok(read_matrix_market_with(reader, StoredZeros::Structural)?.pattern)
+}
+
+/// [`read_matrix_market`] with an explicit treatment of stored zeros, also reporting how many
+/// were dropped. With [`StoredZeros::Dropped`] every entry line of a valued file must carry a
+/// parsable value.
+pub fn read_matrix_market_with<R: BufRead>(
+    reader: R,
+    stored_zeros: StoredZeros,
+) -> BtfResult<MatrixMarketPattern> {
+    let mut lines = reader.lines().enumerate();
+    let banner = lines
+        .next()
+        .ok_or_else(|| MatrixMarketError::Header("empty file".into()))?
+        .1?;
+    let fields: Vec<String> = banner
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let [tag, object, format, field, symmetry] = fields.as_slice() else {
+        return Err(MatrixMarketError::Header(banner).into());
+    };
+    if tag != "%%matrixmarket" || object != "matrix" {
+        return Err(MatrixMarketError::Header(banner).into());
+    }
+    if format != "coordinate" {
+        return Err(MatrixMarketError::Header(format!("unsupported format {format}")).into());
+    }
+    // Number of value tokens to read per entry; values are only looked at to drop zeros.
+    let values = match (stored_zeros, field.as_str()) {
+        (StoredZeros::Structural, _) | (StoredZeros::Dropped, "pattern") => 0,
+        (StoredZeros::Dropped, "real" | "integer") => 1,
+        (StoredZeros::Dropped, "complex") => 2,
+        (StoredZeros::Dropped, other) => {
+            return Err(MatrixMarketError::Header(format!("unsupported field {other}")).into());
+        }
+    };
+    let mirrored = match symmetry.as_str() {
+        "general" => false,
+        "symmetric" | "skew-symmetric" | "hermitian" => true,
+        other => {
+            return Err(MatrixMarketError::Header(format!("unsupported symmetry {other}")).into());
+        }
+    };
+
+    let mut data = lines.filter_map(|(k, line)| match line {
+        Ok(l) if l.trim().is_empty() || l.starts_with('%') => None,
+        other => Some((k + 1, other)),
+    });
+    let (size_line, size) = data.next().ok_or(MatrixMarketError::Size { line: 0 })?;
+    let size: Vec<usize> = size?
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| MatrixMarketError::Size { line: size_line })?;
+    let &[nrows, ncols, nnz] = size.as_slice() else {
+        return Err(MatrixMarketError::Size { line: size_line }.into());
+    };
+
+    let declared = if mirrored {
+        nnz.checked_mul(2)
+    } else {
+        Some(nnz)
+    };
+    let capacity = declared
+        .unwrap_or(usize::MAX)
+        .min(nrows.saturating_mul(ncols))
+        .min(MAX_PREALLOCATED_ENTRIES);
+    let mut entries: Vec<(u32, u32)> = Vec::with_capacity(capacity);
+    let (mut found, mut dropped_zeros) = (0, 0);
+    for (k, line) in data {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        let mut index = |len: usize| {
+            tokens
+                .next()
+                .and_then(|t| t.parse::<usize>().ok())
+                .filter(|&i| (1..=len).contains(&i))
+                .and_then(|i| u32::try_from(i - 1).ok())
+                .ok_or(MatrixMarketError::Entry { line: k })
+        };
+        let i = index(nrows)?;
+        let j = index(ncols)?;
+        found += 1;
+        if values > 0 {
+            let mut is_zero = true;
+            for _ in 0..values {
+                let value = tokens
+                    .next()
+                    .and_then(|t| t.parse::<f64>().ok())
+                    .ok_or(MatrixMarketError::Entry { line: k })?;
+                is_zero &= value == 0.0;
+            }
+            if is_zero {
+                dropped_zeros += 1;
+                continue;
+            }
+        }
+        entries.push((i, j));
+        if mirrored && i != j {
+            entries.push((j, i));
+        }
+    }
+    if found != nnz {
+        return Err(MatrixMarketError::EntryCount {
+            expected: nnz,
+            found,
+        }
+        .into());
+    }
+
+    entries.sort_unstable();
+    let mut builder = CsrBuilder::new(nrows, ncols)?;
+    builder.push_chunk(&entries)?;
+    Ok(MatrixMarketPattern {
+        pattern: builder.finish(),
+        dropped_zeros,
+    })
+}
+
+/// Read `Group/Name` from a local mirror of the collection's Matrix Market tree without
+/// touching the network.
+///
+/// The mirror follows the website layout: either the archive `<mirror>/Group/Name.tar.gz`
+/// or its extracted `<mirror>/Group/Name/Name.mtx`. The extracted file is preferred.
+pub fn read_suitesparse_mirror(mirror: &Path, name: &str) -> BtfResult<CsrPattern> {
+    Ok(read_suitesparse_mirror_with(mirror, name, StoredZeros::Structural)?.pattern)
+}
+
+/// [`read_suitesparse_mirror`] with an explicit treatment of stored zeros; the collection
+/// keeps explicit zeros in many of its matrices.
+pub fn read_suitesparse_mirror_with(
+    mirror: &Path,
+    name: &str,
+    stored_zeros: StoredZeros,
+) -> BtfResult<MatrixMarketPattern> {
+    let (group, matrix) = split_name(name)?;
+    let extracted = mirror
+        .join(group)
+        .join(matrix)
+        .join(format!("{matrix}.mtx"));
+    if extracted.is_file() {
+        return read_matrix_market_with(BufReader::new(File::open(extracted)?), stored_zeros);
+    }
+    let archive = archive_path(mirror, group, matrix);
+    read_archive(File::open(archive)?, matrix, stored_zeros)
+}
+
+/// Fetch `Group/Name` (e.g. `"HB/west0479"`) from the SuiteSparse Matrix Collection.
+///
+/// With a `mirror` directory the matrix is read from it when present (see
+/// [`read_suitesparse_mirror`]); otherwise the archive is downloaded from
+/// [`SUITESPARSE_MM_URL`] and saved there, so later calls work offline. Without a mirror
+/// the archive is streamed and nothing is written to disk. Network failures surface as
+/// [`BtfError::Io`](crate::error::BtfError::Io).
+pub fn fetch_suitesparse(name: &str, mirror: Option<&Path>) -> BtfResult<CsrPattern> {
+    Ok(fetch_suitesparse_with(name, mirror, StoredZeros::Structural)?.pattern)
+}
+
+/// [`fetch_suitesparse`] with an explicit treatment of stored zeros.
+pub fn fetch_suitesparse_with(
+    name: &str,
+    mirror: Option<&Path>,
+    stored_zeros: StoredZeros,
+) -> BtfResult<MatrixMarketPattern> {
+    let (group, matrix) = split_name(name)?;
+    let Some(mirror) = mirror else {
+        return read_archive(download(group, matrix)?, matrix, stored_zeros);
+    };
+    match read_suitesparse_mirror_with(mirror, name, stored_zeros) {
+        Err(crate::error::BtfError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {}
+        cached => return cached,
+    }
+    let archive = archive_path(mirror, group, matrix);
+    fs::create_dir_all(mirror.join(group))?;
+    // Write to a temporary name first so an interrupted download is not mistaken for a
+    // cached archive.
+    let partial = archive.with_extension("part");
+    io::copy(&mut download(group, matrix)?, &mut File::create(&partial)?)?;
+    fs::rename(&partial, &archive)?;
+    read_archive(File::open(archive)?, matrix, stored_zeros)
+}
+
+fn split_name(name: &str) -> Result<(&str, &str), MatrixMarketError> {
+    match name.split_once('/') {
+        Some((group, matrix))
+            if !group.is_empty()
+                && !matrix.is_empty()
+                && ![group, matrix]
+                    .iter()
+                    .any(|s| s.contains(['/', '\\']) || *s == "." || *s == "..") =>
+        {
+            Ok((group, matrix))
+        }
+        _ => Err(MatrixMarketError::InvalidName(name.to_owned())),
+    }
+}
+
+fn archive_path(mirror: &Path, group: &str, matrix: &str) -> PathBuf {
+    mirror.join(group).join(format!("{matrix}.tar.gz"))
+}
+
+fn download(group: &str, matrix: &str) -> io::Result<impl Read> {
+    let url = format!("{SUITESPARSE_MM_URL}/{group}/{matrix}.tar.gz");
+    let response = ureq::get(&url).call().map_err(io::Error::other)?;
+    Ok(response.into_body().into_reader())
+}
+
+/// Parse `Name/Name.mtx` out of a collection archive; other members (right-hand sides,
+/// coordinates, ...) are skipped.
+fn read_archive<R: Read>(
+    archive: R,
+    matrix: &str,
+    stored_zeros: StoredZeros,
+) -> BtfResult<MatrixMarketPattern> {
+    let wanted = Path::new(matrix).join(format!("{matrix}.mtx"));
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar.entries()? {
+        let entry = entry?;
+        if entry.path()? == wanted {
+            return read_matrix_market_with(BufReader::new(entry), stored_zeros);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("archive has no member {}", wanted.display()),
+    )
+    .into())
+}
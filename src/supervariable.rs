@@ -0,0 +1,249 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::adjacency::build_row_adjacency;
+use crate::error::BtfResult;
+use crate::matching::Matching;
+use crate::{BtfOptions, UpperBtfStructure, matching_adjacency, structure_from_matching};
+
+/// Indistinguishable rows and columns: rows with identical patterns, and columns with
+/// identical patterns, grouped into supervariables.
+///
+/// Groups are ordered by their smallest member and list their members ascending. Matched
+/// members of a group always end up in the same diagonal block (any two of them reach each
+/// other through each other's matched column), so a group is a unit of the block structure
+/// as well as of the matching.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupervariableMap {
+    pub row_groups: Vec<Vec<usize>>,
+    pub col_groups: Vec<Vec<usize>>,
+}
+
+impl SupervariableMap {
+    /// Original row index -> index of its group.
+    pub fn row_group_of(&self) -> Vec<usize> {
+        group_of(&self.row_groups)
+    }
+
+    /// Original column index -> index of its group.
+    pub fn col_group_of(&self) -> Vec<usize> {
+        group_of(&self.col_groups)
+    }
+
+    /// Whether every group is a single row or column, i.e. there is nothing to compress.
+    pub fn is_trivial(&self) -> bool {
+        self.row_groups.iter().all(|g| g.len() == 1) && self.col_groups.iter().all(|g| g.len() == 1)
+    }
+}
+
+/// A structure computed on the supervariable-compressed pattern, with the grouping used.
+#[derive(Debug, Clone)]
+pub struct SupervariableStructure {
+    /// Structure of the original pattern, in original indices.
+    pub structure: UpperBtfStructure,
+    pub map: SupervariableMap,
+}
+
+/// Supervariables of `mat`'s pattern.
+pub fn supervariable_map<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> SupervariableMap
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    map_of_adjacency(&build_row_adjacency(mat), mat.ncols())
+}
+
+/// Analyze `mat` with the maximum matching computed on the pattern compressed by
+/// [`supervariable_map`], then expanded back to rows and columns.
+///
+/// Between supervariables the matching is a transportation problem (a row group of `k` rows
+/// can take up to `k` columns), solved by augmenting whole groups at once, so patterns with
+/// many indistinguishable rows and columns, as from finite elements with several unknowns per
+/// node, need far fewer augmentations than the plain matching. The remaining steps run on the
+/// original pattern and are linear in the number of nonzeros. Entries in `options.forbidden`
+/// are removed before grouping; `options.matching` and `options.priorities` are ignored.
+/// Structurally nonsingular patterns get the same blocks as
+/// [`crate::try_upper_block_triangular_structure_with_options`].
+pub fn supervariable_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &BtfOptions,
+) -> BtfResult<SupervariableStructure>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    let row_adj = build_row_adjacency(mat);
+    let ncols = mat.ncols();
+    let match_adj = matching_adjacency(&row_adj, options);
+    #[cfg(feature = "metrics")]
+    let matching_started = std::time::Instant::now();
+    let map = map_of_adjacency(&match_adj, ncols);
+    let matching = grouped_matching(&match_adj, ncols, &map);
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_matching("supervariable", matching_started.elapsed(), &matching);
+    let structure = structure_from_matching(&row_adj, ncols, options, &matching)?;
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_analysis(started.elapsed(), &structure);
+    Ok(SupervariableStructure { structure, map })
+}
+
+fn group_of(groups: &[Vec<usize>]) -> Vec<usize> {
+    let mut of = vec![0; groups.iter().map(Vec::len).sum()];
+    for (g, members) in groups.iter().enumerate() {
+        for &m in members {
+            of[m] = g;
+        }
+    }
+    of
+}
+
+/// Group the indices of `lists` by identical (sorted) lists, in order of first occurrence.
+fn groups_by_pattern(lists: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut index: HashMap<&[usize], usize> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, list) in lists.iter().enumerate() {
+        let g = *index.entry(list).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[g].push(i);
+    }
+    groups
+}
+
+fn map_of_adjacency(row_adj: &[Vec<usize>], ncols: usize) -> SupervariableMap {
+    let mut col_adj = vec![Vec::new(); ncols];
+    for (i, cols) in row_adj.iter().enumerate() {
+        for &j in cols {
+            col_adj[j].push(i);
+        }
+    }
+    SupervariableMap {
+        row_groups: groups_by_pattern(row_adj),
+        col_groups: groups_by_pattern(&col_adj),
+    }
+}
+
+/// Maximum matching of `row_adj` from a maximum flow between the groups of `map`: source ->
+/// row group (capacity: its size) -> column group (unbounded) -> sink (capacity: its size),
+/// expanded by pairing the members of each group in order.
+fn grouped_matching(row_adj: &[Vec<usize>], ncols: usize, map: &SupervariableMap) -> Matching {
+    let col_group_of = map.col_group_of();
+    let (nr, nc) = (map.row_groups.len(), map.col_groups.len());
+
+    // Group-level pattern (a group's rows share one pattern) and its column side.
+    let qadj: Vec<Vec<usize>> = map
+        .row_groups
+        .iter()
+        .map(|rows| {
+            let mut cols: Vec<usize> = row_adj[rows[0]].iter().map(|&j| col_group_of[j]).collect();
+            cols.sort_unstable();
+            cols.dedup();
+            cols
+        })
+        .collect();
+    let mut qcol: Vec<Vec<(usize, usize)>> = vec![Vec::new(); nc];
+    for (g, cols) in qadj.iter().enumerate() {
+        for (k, &c) in cols.iter().enumerate() {
+            qcol[c].push((g, k));
+        }
+    }
+
+    let mut flow: Vec<Vec<usize>> = qadj.iter().map(|cols| vec![0; cols.len()]).collect();
+    let mut supply: Vec<usize> = map.row_groups.iter().map(Vec::len).collect();
+    let mut spare: Vec<usize> = map.col_groups.iter().map(Vec::len).collect();
+
+    // Augment along shortest residual paths, each by its bottleneck: forward edges from row
+    // to column groups are unbounded, backward edges carry the current flow.
+    loop {
+        // col_parent[c]: the forward edge `(row group, edge)` column group `c` was reached by.
+        // row_parent[g]: the edge `(g, edge)` whose flow `g` was reached back through.
+        let mut col_parent: Vec<Option<(usize, usize)>> = vec![None; nc];
+        let mut row_parent: Vec<Option<usize>> = vec![None; nr];
+        let mut reached = vec![false; nr];
+        let mut queue: VecDeque<usize> = (0..nr).filter(|&g| supply[g] > 0).collect();
+        for &g in &queue {
+            reached[g] = true;
+        }
+        let mut end = None;
+        'search: while let Some(g) = queue.pop_front() {
+            for (k, &c) in qadj[g].iter().enumerate() {
+                if col_parent[c].is_some() {
+                    continue;
+                }
+                col_parent[c] = Some((g, k));
+                if spare[c] > 0 {
+                    end = Some(c);
+                    break 'search;
+                }
+                for &(h, kh) in &qcol[c] {
+                    if !reached[h] && flow[h][kh] > 0 {
+                        reached[h] = true;
+                        row_parent[h] = Some(kh);
+                        queue.push_back(h);
+                    }
+                }
+            }
+        }
+        let Some(end) = end else { break };
+
+        // Collect the path from `end` back to a row group with supply.
+        let mut forward = Vec::new();
+        let mut backward = Vec::new();
+        let mut c = end;
+        let start = loop {
+            let (g, k) = col_parent[c].expect("reached column groups have a parent");
+            forward.push((g, k));
+            match row_parent[g] {
+                Some(kh) => {
+                    backward.push((g, kh));
+                    c = qadj[g][kh];
+                }
+                None => break g,
+            }
+        };
+        let delta = backward
+            .iter()
+            .map(|&(g, k)| flow[g][k])
+            .fold(supply[start].min(spare[end]), usize::min);
+        for (g, k) in forward {
+            flow[g][k] += delta;
+        }
+        for (g, k) in backward {
+            flow[g][k] -= delta;
+        }
+        supply[start] -= delta;
+        spare[end] -= delta;
+    }
+
+    // Pair the members of each group in order along the group-level flow.
+    let mut row_to_col = vec![None; row_adj.len()];
+    let mut col_to_row = vec![None; ncols];
+    let mut next_col = vec![0; nc];
+    for (g, rows) in map.row_groups.iter().enumerate() {
+        let mut rows = rows.iter();
+        for (k, &c) in qadj[g].iter().enumerate() {
+            for _ in 0..flow[g][k] {
+                let r = *rows
+                    .next()
+                    .expect("flow out of a group is at most its size");
+                let col = map.col_groups[c][next_col[c]];
+                next_col[c] += 1;
+                row_to_col[r] = Some(col);
+                col_to_row[col] = Some(r);
+            }
+        }
+    }
+    let size = row_to_col.iter().flatten().count();
+    Matching {
+        row_to_col,
+        col_to_row,
+        size,
+    }
+}
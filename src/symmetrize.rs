@@ -0,0 +1,110 @@
+use nalgebra::DMatrix;
+
+use crate::adjacency::build_row_adjacency;
+use crate::matching::hopcroft_karp;
+use crate::stats::{SymmetryReport, structural_symmetry};
+
+/// Improvement sweeps over all diagonal-preserving column swaps.
+const MAX_SWEEPS: usize = 8;
+
+/// Result of [`symmetrizing_permutation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symmetrization {
+    /// Column permutation: column `k` of `B = A[:, col_order]` is column `col_order[k]` of `A`.
+    pub col_order: Vec<usize>,
+    /// Number of structurally nonzero diagonal entries of `B` (the structural rank).
+    pub matching_size: usize,
+    /// Structural symmetry of `B`.
+    pub symmetry: SymmetryReport,
+}
+
+/// Heuristic column permutation that maximizes the structural symmetry of the permuted matrix
+/// while keeping a maximum transversal on its diagonal.
+///
+/// Starts from a maximum matching placed on the diagonal, then repeatedly applies the column
+/// swap with the largest symmetry gain among swaps that keep the diagonal zero-free (both
+/// swapped columns must hit the other row), for at most a few sweeps. Rows without a matched
+/// column get the unmatched columns in increasing order. Since a symmetric permutation
+/// `P B Pᵀ` preserves symmetry, only columns are permuted; any fill-reducing symmetric
+/// ordering can follow.
+pub fn symmetrizing_permutation<T, R, C, S>(mat: &nalgebra::Matrix<T, R, C, S>) -> Symmetrization
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (nrows, ncols) = (row_adj.len(), mat.ncols());
+    let matching = hopcroft_karp(&row_adj, ncols);
+
+    let mut free = (0..ncols).filter(|&j| matching.col_to_row[j].is_none());
+    let mut col_order: Vec<usize> = matching
+        .row_to_col
+        .iter()
+        .filter_map(|&c| c.or_else(|| free.next()))
+        .collect();
+    col_order.extend(free);
+
+    // Only the leading square part of `B` has transpose positions.
+    let m = nrows.min(ncols);
+    let has = |i: usize, j: usize| row_adj[i].binary_search(&j).is_ok();
+    let b = |q: &[usize], a: usize, p: usize| has(a, q[p]);
+    // Symmetric unordered pairs {a, p} of `B` with p in `touch`, each counted once.
+    let touching = |q: &[usize], touch: [usize; 2]| {
+        let mut count = 0;
+        for (t, &p) in touch.iter().enumerate() {
+            for a in (0..m).filter(|&a| a != p && (t == 0 || a != touch[0])) {
+                if b(q, a, p) && b(q, p, a) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    };
+
+    let mut pos = vec![usize::MAX; ncols];
+    for (p, &j) in col_order.iter().enumerate() {
+        pos[j] = p;
+    }
+    for _ in 0..MAX_SWEEPS {
+        let mut improved = false;
+        for i in 0..m {
+            if matching.row_to_col[i].is_none() {
+                continue;
+            }
+            // Best swap of column positions i and k keeping both diagonal entries.
+            let mut best: Option<(usize, usize)> = None;
+            for &c in &row_adj[i] {
+                let k = pos[c];
+                if k >= m || k == i || !has(k, col_order[i]) {
+                    continue;
+                }
+                let before = touching(&col_order, [i, k]);
+                col_order.swap(i, k);
+                let after = touching(&col_order, [i, k]);
+                col_order.swap(i, k);
+                if after > before && best.is_none_or(|(g, _)| after - before > g) {
+                    best = Some((after - before, k));
+                }
+            }
+            if let Some((_, k)) = best {
+                col_order.swap(i, k);
+                pos[col_order[i]] = i;
+                pos[col_order[k]] = k;
+                improved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    let permuted = DMatrix::from_fn(nrows, ncols, |i, k| has(i, col_order[k]));
+    let symmetry = structural_symmetry(&permuted);
+    Symmetrization {
+        col_order,
+        matching_size: matching.size,
+        symmetry,
+    }
+}
@@ -0,0 +1,335 @@
+use crate::adjacency::{build_row_adjacency, build_row_dependency_graph};
+use crate::{UpperBtfStructure, structure_from_row_adjacency};
+
+/// Rule used to pick the tear set (a feedback vertex set of the in-block dependency graph).
+///
+/// Non-exhaustive because the set of variants depends on enabled features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TearingHeuristic {
+    /// Cellier's heuristic: repeatedly tear the variable used by the most remaining equations
+    /// (largest in-degree), breaking ties by the equation using the most unknowns.
+    Cellier,
+    /// Greedy minimum feedback vertex set: repeatedly tear the node maximizing
+    /// `in-degree * out-degree`, after pruning nodes that cannot lie on a cycle.
+    GreedyMfvs,
+    /// Minimum feedback vertex set by exhaustive search over subsets of increasing size, up
+    /// to the size of the [`GreedyMfvs`](Self::GreedyMfvs) set. No ILP solver is involved;
+    /// the search is exponential in the block size, so pair it with a small size threshold.
+    #[cfg(feature = "exact-tearing")]
+    ExhaustiveSearch,
+}
+
+/// Per-block heuristic selection for [`tear`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TearingOptions {
+    /// `(max_block_size, heuristic)` rules; a block uses the first rule whose
+    /// `max_block_size` is at least its size.
+    pub by_size: Vec<(usize, TearingHeuristic)>,
+    /// Heuristic for blocks not covered by any rule.
+    pub default: TearingHeuristic,
+}
+
+impl Default for TearingOptions {
+    fn default() -> Self {
+        Self {
+            by_size: Vec::new(),
+            default: TearingHeuristic::Cellier,
+        }
+    }
+}
+
+impl TearingOptions {
+    /// Heuristic applied to a block of `size` rows.
+    pub fn heuristic_for(&self, size: usize) -> TearingHeuristic {
+        self.by_size
+            .iter()
+            .find(|&&(max, _)| size <= max)
+            .map_or(self.default, |&(_, h)| h)
+    }
+}
+
+/// Tear set of one diagonal block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTearing {
+    /// Block index (in solve order).
+    pub block: usize,
+    /// Heuristic that produced this tear set.
+    pub heuristic: TearingHeuristic,
+    /// Residual equations (original row indices), sorted.
+    pub residuals: Vec<usize>,
+    /// Tearing variables: the columns matched to `residuals`, in the same order.
+    pub tear_vars: Vec<usize>,
+    /// Alternative to tearing whole equations: `(row, col)` entries of the block (a feedback
+    /// edge set of its dependency graph, chosen greedily) whose removal leaves the block
+    /// triangular under the same matching. Sorted.
+    pub cut_entries: Vec<(usize, usize)>,
+}
+
+/// Block triangular structure together with the tear set of every non-trivial block.
+#[derive(Debug, Clone)]
+pub struct TornBtfStructure {
+    /// The structure the blocks refer to.
+    pub structure: UpperBtfStructure,
+    /// One entry per diagonal block larger than 1x1, in block order.
+    pub blocks: Vec<BlockTearing>,
+}
+
+/// Compute a tear set for every diagonal block larger than 1x1.
+///
+/// Guessing the tearing variables and dropping the residual equations leaves each block
+/// solvable by forward substitution (its remaining dependency graph is acyclic).
+pub fn tear<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &TearingOptions,
+) -> Vec<BlockTearing>
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    torn_block_triangular_structure(mat, options).blocks
+}
+
+/// Analyze `mat` and [`tear`] its irreducible blocks in one pass, keeping the structure the
+/// tear sets refer to.
+pub fn torn_block_triangular_structure<T, R, C, S>(
+    mat: &nalgebra::Matrix<T, R, C, S>,
+    options: &TearingOptions,
+) -> TornBtfStructure
+where
+    T: nalgebra::Scalar + PartialEq + Default,
+    R: nalgebra::Dim,
+    C: nalgebra::Dim,
+    S: nalgebra::Storage<T, R, C>,
+{
+    let row_adj = build_row_adjacency(mat);
+    let (structure, matching) = structure_from_row_adjacency(&row_adj, mat.ncols());
+    let row_graph = build_row_dependency_graph(&row_adj, &matching.col_to_row);
+    let matched_col =
+        |r: usize| matching.row_to_col[r].expect("rows of a non-singleton block are matched");
+
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for (block, &size) in structure.block_sizes.iter().enumerate() {
+        let rows = &structure.row_order[start..start + size];
+        start += size;
+        if size < 2 {
+            continue;
+        }
+
+        // Rows within a block are sorted, so local indices preserve the original order.
+        let local: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|&r| {
+                row_graph[r]
+                    .iter()
+                    .filter_map(|k| rows.binary_search(k).ok())
+                    .collect()
+            })
+            .collect();
+
+        let heuristic = options.heuristic_for(size);
+        let mut fvs = feedback_vertex_set(&local, heuristic);
+        fvs.sort_unstable();
+
+        let residuals: Vec<usize> = fvs.iter().map(|&l| rows[l]).collect();
+        let tear_vars = residuals.iter().map(|&r| matched_col(r)).collect();
+        // Arc u -> v is the entry of row u in the column matched to row v.
+        let mut cut_entries: Vec<(usize, usize)> = feedback_arc_set(&local)
+            .into_iter()
+            .map(|(u, v)| (rows[u], matched_col(rows[v])))
+            .collect();
+        cut_entries.sort_unstable();
+        blocks.push(BlockTearing {
+            block,
+            heuristic,
+            residuals,
+            tear_vars,
+            cut_entries,
+        });
+    }
+    TornBtfStructure { structure, blocks }
+}
+
+/// Feedback arc set of a directed graph without self-loops: arcs `(u, v)` whose removal leaves
+/// it acyclic, sorted.
+///
+/// Greedy heuristic of Eades, Lin and Smyth: peel sinks to the back and sources to the front
+/// of a linear order, otherwise move the node maximizing `out-degree − in-degree` (ties:
+/// smallest index) to the front; the arcs pointing backwards in the order are the set.
+pub fn feedback_arc_set(graph: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let n = graph.len();
+    let mut rev = vec![Vec::new(); n];
+    for (u, out) in graph.iter().enumerate() {
+        for &v in out {
+            rev[v].push(u);
+        }
+    }
+    let mut indeg: Vec<usize> = rev.iter().map(Vec::len).collect();
+    let mut outdeg: Vec<usize> = graph.iter().map(Vec::len).collect();
+    let mut alive = vec![true; n];
+    let (mut front, mut back) = (Vec::with_capacity(n), Vec::new());
+
+    for _ in 0..n {
+        let live = || (0..n).filter(|&u| alive[u]);
+        let u = if let Some(u) = live().find(|&u| outdeg[u] == 0) {
+            back.push(u);
+            u
+        } else {
+            let u = live()
+                .find(|&u| indeg[u] == 0)
+                .or_else(|| {
+                    live().max_by_key(|&u| {
+                        (outdeg[u] as isize - indeg[u] as isize, std::cmp::Reverse(u))
+                    })
+                })
+                .expect("a live node remains");
+            front.push(u);
+            u
+        };
+        alive[u] = false;
+        for &v in &graph[u] {
+            indeg[v] -= 1;
+        }
+        for &w in &rev[u] {
+            outdeg[w] -= 1;
+        }
+    }
+
+    let mut pos = vec![0usize; n];
+    for (p, &u) in front.iter().chain(back.iter().rev()).enumerate() {
+        pos[u] = p;
+    }
+    let mut fas: Vec<(usize, usize)> = graph
+        .iter()
+        .enumerate()
+        .flat_map(|(u, out)| out.iter().map(move |&v| (u, v)))
+        .filter(|&(u, v)| pos[v] < pos[u])
+        .collect();
+    fas.sort_unstable();
+    fas
+}
+
+/// Feedback vertex set of a directed graph without self-loops, chosen by `heuristic`.
+pub fn feedback_vertex_set(graph: &[Vec<usize>], heuristic: TearingHeuristic) -> Vec<usize> {
+    match heuristic {
+        TearingHeuristic::Cellier => greedy_fvs(graph, |indeg, outdeg| (indeg, outdeg)),
+        TearingHeuristic::GreedyMfvs => greedy_fvs(graph, |indeg, outdeg| (indeg * outdeg, 0)),
+        #[cfg(feature = "exact-tearing")]
+        TearingHeuristic::ExhaustiveSearch => exhaustive_fvs(graph),
+    }
+}
+
+/// Whether `graph` restricted to the nodes with `alive[u]` is acyclic.
+#[cfg(feature = "exact-tearing")]
+fn is_acyclic_without(graph: &[Vec<usize>], alive: &[bool]) -> bool {
+    let n = graph.len();
+    let mut indeg = vec![0usize; n];
+    for u in (0..n).filter(|&u| alive[u]) {
+        for &v in graph[u].iter().filter(|&&v| alive[v]) {
+            indeg[v] += 1;
+        }
+    }
+    let mut stack: Vec<usize> = (0..n).filter(|&u| alive[u] && indeg[u] == 0).collect();
+    let mut seen = 0;
+    while let Some(u) = stack.pop() {
+        seen += 1;
+        for &v in graph[u].iter().filter(|&&v| alive[v]) {
+            indeg[v] -= 1;
+            if indeg[v] == 0 {
+                stack.push(v);
+            }
+        }
+    }
+    seen == alive.iter().filter(|&&a| a).count()
+}
+
+/// Greedy FVS: prune nodes with no live predecessor or successor (they lie on no cycle), then
+/// remove the live node with the largest `score(indeg, outdeg)` (ties: smallest index).
+fn greedy_fvs<K: Ord>(graph: &[Vec<usize>], score: impl Fn(usize, usize) -> K) -> Vec<usize> {
+    let n = graph.len();
+    let mut rev = vec![Vec::new(); n];
+    for (u, out) in graph.iter().enumerate() {
+        for &v in out {
+            rev[v].push(u);
+        }
+    }
+    let mut indeg: Vec<usize> = rev.iter().map(Vec::len).collect();
+    let mut outdeg: Vec<usize> = graph.iter().map(Vec::len).collect();
+    let mut alive = vec![true; n];
+    let mut fvs = Vec::new();
+
+    let mut pending: Vec<usize> = (0..n).collect();
+    loop {
+        // Prune until every live node has both a live predecessor and a live successor.
+        while let Some(u) = pending.pop() {
+            if !alive[u] || (indeg[u] > 0 && outdeg[u] > 0) {
+                continue;
+            }
+            alive[u] = false;
+            for &v in &graph[u] {
+                indeg[v] -= 1;
+                pending.push(v);
+            }
+            for &w in &rev[u] {
+                outdeg[w] -= 1;
+                pending.push(w);
+            }
+        }
+
+        let Some(best) = (0..n)
+            .filter(|&u| alive[u])
+            .max_by_key(|&u| (score(indeg[u], outdeg[u]), std::cmp::Reverse(u)))
+        else {
+            break;
+        };
+
+        fvs.push(best);
+        alive[best] = false;
+        for &v in &graph[best] {
+            indeg[v] -= 1;
+            pending.push(v);
+        }
+        for &w in &rev[best] {
+            outdeg[w] -= 1;
+            pending.push(w);
+        }
+    }
+
+    fvs
+}
+
+/// Smallest FVS, trying subsets in increasing size (lexicographic within a size).
+#[cfg(feature = "exact-tearing")]
+fn exhaustive_fvs(graph: &[Vec<usize>]) -> Vec<usize> {
+    let n = graph.len();
+    let upper = greedy_fvs(graph, |indeg, outdeg| (indeg * outdeg, 0));
+    let mut alive = vec![true; n];
+    for k in 0..upper.len() {
+        let mut pick: Vec<usize> = (0..k).collect();
+        loop {
+            for &u in &pick {
+                alive[u] = false;
+            }
+            let ok = is_acyclic_without(graph, &alive);
+            for &u in &pick {
+                alive[u] = true;
+            }
+            if ok {
+                return pick;
+            }
+
+            // Next k-combination of 0..n.
+            let Some(i) = (0..k).rev().find(|&i| pick[i] < n - k + i) else {
+                break;
+            };
+            pick[i] += 1;
+            for j in i + 1..k {
+                pick[j] = pick[j - 1] + 1;
+            }
+        }
+    }
+    upper
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use crate::matching::{BudgetedMatching, Matching};
+use crate::{BtfOptions, MatchingAlgorithm, UpperBtfStructure};
+
+/// Counter: analyses completed by the BTF pipeline.
+pub const ANALYSES: &str = "btf_analyses_total";
+/// Histogram: wall time of one analysis, in seconds.
+pub const ANALYSIS_SECONDS: &str = "btf_analysis_seconds";
+/// Histogram: wall time of the matching phase, in seconds; labeled by `algorithm`
+/// (`hopcroft_karp`, `mc21`, `priority`, `repair` for warm-started session updates, or
+/// `supervariable`).
+pub const MATCHING_SECONDS: &str = "btf_matching_seconds";
+/// Histogram: Hopcroft–Karp phases run by the budgeted and cancellable analyses
+/// ([`crate::approximate`]).
+pub const MATCHING_PHASES: &str = "btf_matching_phases";
+/// Histogram: structural rank deficiency (`min(nrows, ncols)` minus the matching size).
+pub const STRUCTURAL_DEFICIENCY: &str = "btf_structural_deficiency";
+/// Histogram: number of diagonal blocks.
+pub const BLOCKS: &str = "btf_blocks";
+/// Histogram: size of the largest diagonal block.
+pub const LARGEST_BLOCK: &str = "btf_largest_block";
+
+/// Label of the matching algorithm `options` select.
+pub(crate) fn matching_algorithm(options: &BtfOptions) -> &'static str {
+    if !options.priorities.is_empty() {
+        "priority"
+    } else {
+        match options.matching {
+            MatchingAlgorithm::HopcroftKarp => "hopcroft_karp",
+            MatchingAlgorithm::Mc21 => "mc21",
+        }
+    }
+}
+
+/// Record the matching phase of one analysis.
+pub(crate) fn record_matching(algorithm: &'static str, elapsed: Duration, matching: &Matching) {
+    record_matching_size(
+        algorithm,
+        elapsed,
+        matching.row_to_col.len().min(matching.col_to_row.len()),
+        matching.size,
+    );
+}
+
+/// [`record_matching`] for matchings not held in a [`Matching`].
+pub(crate) fn record_matching_size(
+    algorithm: &'static str,
+    elapsed: Duration,
+    rank_bound: usize,
+    size: usize,
+) {
+    metrics::histogram!(MATCHING_SECONDS, "algorithm" => algorithm).record(elapsed.as_secs_f64());
+    metrics::histogram!(STRUCTURAL_DEFICIENCY).record((rank_bound - size) as f64);
+}
+
+/// [`record_matching`] for a Hopcroft–Karp search that may have stopped early, also recording
+/// its phases.
+pub(crate) fn record_budgeted_matching(elapsed: Duration, budgeted: &BudgetedMatching) {
+    record_matching("hopcroft_karp", elapsed, &budgeted.matching);
+    metrics::histogram!(MATCHING_PHASES).record(budgeted.phases as f64);
+}
+
+/// Record a completed analysis.
+pub(crate) fn record_analysis(elapsed: Duration, structure: &UpperBtfStructure) {
+    metrics::counter!(ANALYSES).increment(1);
+    metrics::histogram!(ANALYSIS_SECONDS).record(elapsed.as_secs_f64());
+    metrics::histogram!(BLOCKS).record(structure.num_blocks() as f64);
+    metrics::histogram!(LARGEST_BLOCK).record(structure.largest_block_size() as f64);
+}
@@ -1,6 +1,7 @@
 use nalgebra::DMatrix;
 use nalgebra_block_triangularization::adjacency::{
-    build_row_adjacency, build_row_dependency_graph,
+    build_col_dependency_graph, build_row_adjacency, build_row_dependency_graph,
+    pattern_fingerprint,
 };
 
 #[test]
@@ -68,8 +69,8 @@ fn adjacency_identity_matrix() {
     let m: DMatrix<f64> = DMatrix::identity(5, 5);
     let adj = build_row_adjacency(&m);
     assert_eq!(adj.len(), 5);
-    for i in 0..5 {
-        assert_eq!(adj[i], vec![i]);
+    for (i, cols) in adj.iter().enumerate() {
+        assert_eq!(cols, &vec![i]);
     }
 }
 
@@ -203,3 +204,56 @@ fn dependency_graph_unmatched_columns_ignored() {
     // Row 1 has no dependencies (col 1 unmatched, col 3 -> row 1 self)
     assert!(dep_graph[1].is_empty());
 }
+
+#[test]
+fn col_dependency_graph_with_dependencies() {
+    // Columns of [[1, 1, 0], [0, 1, 1], [0, 0, 1]] with the diagonal matching.
+    let col_adj = vec![vec![0], vec![0, 1], vec![1, 2]];
+    let row_to_col = vec![Some(0), Some(1), Some(2)];
+    let g = build_col_dependency_graph(&col_adj, &row_to_col);
+    assert_eq!(g, vec![vec![], vec![0], vec![1]]);
+}
+
+#[test]
+fn col_dependency_graph_ignores_unmatched_rows_and_self_loops() {
+    // Row 2 is unmatched; column 1 is unmatched.
+    let col_adj = vec![vec![0, 1, 2], vec![0, 2], vec![1, 1]];
+    let row_to_col = vec![Some(0), Some(2), None];
+    let g = build_col_dependency_graph(&col_adj, &row_to_col);
+    assert_eq!(g, vec![vec![2], vec![0], vec![]]);
+}
+
+#[test]
+fn fingerprint_ignores_values() {
+    let a = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 2.5, 3.0]);
+    let b = DMatrix::from_row_slice(2, 2, &[-7.0, 0.0, 1.0, 0.1]);
+    assert_eq!(pattern_fingerprint(&a), pattern_fingerprint(&b));
+}
+
+#[test]
+fn fingerprint_distinguishes_patterns() {
+    let a = DMatrix::from_row_slice(2, 2, &[1, 0, 0, 0]);
+    let b = DMatrix::from_row_slice(2, 2, &[0, 0, 1, 0]);
+    let c = DMatrix::from_row_slice(1, 4, &[1, 0, 0, 0]);
+    assert_ne!(pattern_fingerprint(&a), pattern_fingerprint(&b));
+    assert_ne!(pattern_fingerprint(&a), pattern_fingerprint(&c));
+}
+
+#[test]
+fn fingerprint_is_stable() {
+    let m: DMatrix<u8> = DMatrix::identity(3, 3);
+    assert_eq!(pattern_fingerprint(&m), pattern_fingerprint(&m.clone()));
+    let empty: DMatrix<u8> = DMatrix::zeros(0, 0);
+    assert_ne!(pattern_fingerprint(&empty), pattern_fingerprint(&m));
+}
+
+#[cfg(feature = "parallel-std")]
+#[test]
+fn threaded_adjacency_matches_sequential() {
+    use nalgebra_block_triangularization::adjacency::build_row_adjacency_threaded;
+
+    let m = DMatrix::from_fn(37, 23, |i, j| u8::from((i * 7 + j * 3) % 5 == 0));
+    assert_eq!(build_row_adjacency_threaded(&m), build_row_adjacency(&m));
+    let empty = DMatrix::<u8>::zeros(0, 4);
+    assert!(build_row_adjacency_threaded(&empty).is_empty());
+}
@@ -0,0 +1,123 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::analysis::BtfAnalysis;
+use nalgebra_block_triangularization::{
+    BlockOrder, BtfOptions, InBlockOrder, MatchingAlgorithm, UnmatchedPlacement, condensation,
+    try_upper_block_triangular_structure_with_options, try_upper_triangular_permutations,
+};
+
+fn system() -> DMatrix<u8> {
+    DMatrix::from_row_slice(6, 6, &[
+        1, 1, 0, 0, 0, 1,
+        0, 1, 1, 0, 0, 0,
+        0, 1, 1, 0, 1, 0,
+        0, 0, 0, 1, 1, 0,
+        0, 0, 0, 1, 1, 0,
+        1, 0, 0, 0, 0, 1,
+    ])
+}
+
+#[test]
+fn reordering_matches_the_full_pipeline() {
+    let m = system();
+    let analysis = BtfAnalysis::new(&m, &BtfOptions::default());
+    for block_order in [
+        BlockOrder::MinRow,
+        BlockOrder::MinCoupling,
+        BlockOrder::SmallestFirst,
+        BlockOrder::LargestFirst,
+        BlockOrder::MinDisplacement,
+        BlockOrder::AdjacentCoupling,
+    ] {
+        for in_block_order in [
+            InBlockOrder::RowIndex,
+            InBlockOrder::MinBandwidth,
+            InBlockOrder::MinDegree,
+            InBlockOrder::MatchedColumn,
+        ] {
+            let options = BtfOptions { block_order, in_block_order, ..Default::default() };
+            let reordered = analysis.structure(&options).unwrap();
+            let full = try_upper_block_triangular_structure_with_options(&m, &options).unwrap();
+            assert_eq!(reordered.row_order, full.row_order, "{block_order:?} {in_block_order:?}");
+            assert_eq!(reordered.col_order, full.col_order);
+            assert_eq!(reordered.block_sizes, full.block_sizes);
+            assert_eq!(reordered.block_dag, full.block_dag);
+            assert_eq!(reordered.row_to_col, full.row_to_col);
+        }
+    }
+}
+
+#[test]
+fn permutations_match_the_full_pipeline() {
+    let m = system();
+    let analysis = BtfAnalysis::new(&m, &BtfOptions::default());
+    let (pr, pc) = analysis.permutations(&BtfOptions::default()).unwrap();
+    let (full_pr, full_pc) = try_upper_triangular_permutations(&m).unwrap();
+    let (mut a, mut b) = (m.clone(), m.clone());
+    pr.permute_rows(&mut a);
+    pc.permute_columns(&mut a);
+    full_pr.permute_rows(&mut b);
+    full_pc.permute_columns(&mut b);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn exposes_the_shared_matching_and_sccs() {
+    let m = system();
+    let analysis = BtfAnalysis::new(&m, &BtfOptions::default());
+    assert_eq!(analysis.matching().size, 6);
+    let mut blocks: Vec<Vec<usize>> = analysis.condensation().sccs.clone();
+    blocks.sort();
+    let mut expected: Vec<Vec<usize>> = condensation(&m)
+        .sccs
+        .into_iter()
+        .map(|mut c| {
+            c.sort_unstable();
+            c
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(blocks, expected);
+}
+
+#[test]
+fn matching_options_are_fixed_at_construction() {
+    // Singular: row 2 is empty, so one row and one column stay unmatched.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 1,
+        0, 0, 0,
+    ]);
+    let build = BtfOptions { matching: MatchingAlgorithm::Mc21, ..Default::default() };
+    let analysis = BtfAnalysis::new(&m, &build);
+    for unmatched in [UnmatchedPlacement::End, UnmatchedPlacement::Adjacent] {
+        let options = BtfOptions { unmatched, ..build.clone() };
+        let full = try_upper_block_triangular_structure_with_options(&m, &options).unwrap();
+        // The matching options given here are ignored in favor of the analysis's.
+        let ignored = BtfOptions { matching: MatchingAlgorithm::HopcroftKarp, ..options };
+        let reordered = analysis.structure(&ignored).unwrap();
+        assert_eq!(reordered.row_order, full.row_order);
+        assert_eq!(reordered.col_order, full.col_order);
+        assert_eq!(reordered.row_to_col, analysis.matching().row_to_col);
+    }
+}
+
+#[test]
+fn empty_and_rectangular_patterns() {
+    let empty = DMatrix::<u8>::zeros(0, 3);
+    let s = BtfAnalysis::new(&empty, &BtfOptions::default())
+        .structure(&BtfOptions::default())
+        .unwrap();
+    assert_eq!(s.col_order, vec![0, 1, 2]);
+    assert!(s.block_sizes.is_empty());
+
+    let wide = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 1,
+        0, 1, 1,
+    ]);
+    let options = BtfOptions { min_block_size: 2, ..Default::default() };
+    let s = BtfAnalysis::new(&wide, &options).structure(&options).unwrap();
+    let full = try_upper_block_triangular_structure_with_options(&wide, &options).unwrap();
+    assert_eq!(s.row_order, full.row_order);
+    assert_eq!(s.col_order, full.col_order);
+    assert_eq!(s.block_sizes, full.block_sizes);
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::AtomicBool;
+
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::approximate::{
+    approximate_block_triangular_structure, cancellable_block_triangular_structure,
+    try_approximate_block_triangular_structure_with_options,
+};
+use nalgebra_block_triangularization::matching::MatchingBudget;
+use nalgebra_block_triangularization::{BtfError, BtfOptions, upper_block_triangular_structure};
+
+fn one_phase() -> MatchingBudget {
+    MatchingBudget {
+        max_phases: Some(1),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn unlimited_budget_is_exact() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let approx = approximate_block_triangular_structure(&m, &MatchingBudget::default());
+    let exact = upper_block_triangular_structure(&m);
+    assert!(approx.exact);
+    assert!(!approx.is_approximate());
+    assert_eq!(approx.structure.row_order, exact.row_order);
+    assert_eq!(approx.structure.col_order, exact.col_order);
+    assert_eq!(approx.structure.block_sizes, exact.block_sizes);
+    assert_eq!(approx.structure.matching_size, 3);
+}
+
+#[test]
+fn truncated_matching_is_flagged_and_still_triangular() {
+    // One phase matches (0, 0) only; row 1 needs the augmenting path through row 0.
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 0,
+    ]);
+    let approx = approximate_block_triangular_structure(&m, &one_phase());
+    assert!(approx.is_approximate());
+    assert_eq!(approx.phases, 1);
+    assert_eq!(approx.structure.matching_size, 1);
+    // The unmatched row forms its own block, ahead of the row it depends on.
+    assert_eq!(approx.structure.row_order, vec![1, 0]);
+    assert_eq!(approx.structure.block_sizes, vec![1, 1]);
+    // Row 1 touches column 0, matched to the later row 0.
+    let blocks = approx.structure.row_blocks();
+    assert!(blocks[1] < blocks[0]);
+}
+
+#[test]
+fn forbidden_entries_apply() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 1,
+    ]);
+    let options = BtfOptions {
+        forbidden: vec![(0, 0), (1, 1)],
+        ..Default::default()
+    };
+    let approx =
+        try_approximate_block_triangular_structure_with_options(&m, &MatchingBudget::default(), &options)
+            .unwrap();
+    assert!(approx.exact);
+    assert_eq!(approx.structure.col_order, vec![1, 0]);
+}
+
+#[test]
+fn cancellation_flag_stops_the_analysis() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let options = BtfOptions::default();
+
+    let running = AtomicBool::new(false);
+    let structure = cancellable_block_triangular_structure(&m, &options, &running).unwrap();
+    let exact = upper_block_triangular_structure(&m);
+    assert_eq!(structure.row_order, exact.row_order);
+    assert_eq!(structure.col_order, exact.col_order);
+
+    let cancelled = AtomicBool::new(true);
+    assert!(matches!(
+        cancellable_block_triangular_structure(&m, &options, &cancelled),
+        Err(BtfError::Cancelled)
+    ));
+}
@@ -0,0 +1,72 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::arrowhead::arrowhead_structure;
+use nalgebra_block_triangularization::components::BipartiteComponent;
+
+#[test]
+fn scrambled_arrowhead_is_recovered() {
+    // Diagonal plus a full row and column 2.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 0, 1, 0, 0,
+        0, 1, 1, 0, 0,
+        1, 1, 1, 1, 1,
+        0, 0, 1, 1, 0,
+        0, 0, 1, 0, 1,
+    ]);
+    let a = arrowhead_structure(&m, 1);
+    assert_eq!(a.border_rows, vec![2]);
+    assert_eq!(a.border_cols, vec![2]);
+    assert_eq!(a.num_blocks(), 4);
+    assert_eq!(a.row_order, vec![0, 1, 3, 4, 2]);
+    assert_eq!(a.col_order, vec![0, 1, 3, 4, 2]);
+    assert!(!a.is_block_diagonal());
+}
+
+#[test]
+fn larger_blocks_need_a_smaller_border() {
+    // Two 2x2 blocks {0, 1} and {3, 4} coupled only through row and column 2.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 1, 0, 0,
+        1, 1, 1, 0, 0,
+        1, 1, 1, 1, 1,
+        0, 0, 1, 1, 1,
+        0, 0, 1, 1, 1,
+    ]);
+    let a = arrowhead_structure(&m, 2);
+    assert_eq!(a.border_size(), 2);
+    assert_eq!((a.border_rows.clone(), a.border_cols.clone()), (vec![2], vec![2]));
+    assert_eq!(
+        a.blocks,
+        vec![
+            BipartiteComponent { rows: vec![0, 1], cols: vec![0, 1] },
+            BipartiteComponent { rows: vec![3, 4], cols: vec![3, 4] },
+        ]
+    );
+    // A limit above the pattern size needs no border at all.
+    let whole = arrowhead_structure(&m, 5);
+    assert!(whole.is_block_diagonal());
+    assert_eq!(whole.num_blocks(), 1);
+}
+
+#[test]
+fn block_diagonal_pattern_has_empty_border() {
+    let m = DMatrix::from_row_slice(4, 3, &[
+        1, 1, 0,
+        1, 0, 0,
+        0, 0, 1,
+        0, 0, 0,
+    ]);
+    let a = arrowhead_structure(&m, 2);
+    assert!(a.is_block_diagonal());
+    assert_eq!(a.num_blocks(), 3);
+    assert_eq!(a.row_order, vec![0, 1, 2, 3]);
+    assert_eq!(a.col_order, vec![0, 1, 2]);
+}
+
+#[test]
+fn zero_limit_borders_everything() {
+    let m = DMatrix::from_element(2, 3, 1u8);
+    let a = arrowhead_structure(&m, 0);
+    assert_eq!(a.num_blocks(), 0);
+    assert_eq!(a.border_rows, vec![0, 1]);
+    assert_eq!(a.border_cols, vec![0, 1, 2]);
+}
@@ -0,0 +1,73 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::augmentation::{Augmentation, minimal_augmentation};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+fn with_entries(m: &DMatrix<u8>, entries: &[(usize, usize)]) -> DMatrix<u8> {
+    let mut out = m.clone();
+    for &(i, j) in entries {
+        out[(i, j)] = 1;
+    }
+    out
+}
+
+#[test]
+fn full_rank_needs_nothing() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 1,
+    ]);
+    assert_eq!(minimal_augmentation(&m), Augmentation::default());
+}
+
+#[test]
+fn rectangular_full_rank_needs_nothing() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 1,
+        0, 1, 0,
+    ]);
+    assert_eq!(minimal_augmentation(&m), Augmentation::default());
+}
+
+#[test]
+fn overdetermined_rows_and_underdetermined_columns() {
+    // Rows 0 and 1 both only use column 0; column 2 is unused.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let aug = minimal_augmentation(&m);
+    assert_eq!(aug.entries.len(), 1);
+    assert_eq!(aug.candidate_rows, vec![0, 1]);
+    assert_eq!(aug.candidate_cols, vec![1, 2]);
+
+    let repaired = upper_block_triangular_structure(&with_entries(&m, &aug.entries));
+    assert_eq!(repaired.matching_size, 3);
+}
+
+#[test]
+fn any_candidate_pair_raises_rank() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 0, 0, 0,
+        1, 0, 0, 0,
+        0, 1, 1, 1,
+        0, 1, 1, 1,
+    ]);
+    let aug = minimal_augmentation(&m);
+    assert_eq!(aug.entries.len(), 1);
+    for &i in &aug.candidate_rows {
+        for &j in &aug.candidate_cols {
+            let s = upper_block_triangular_structure(&with_entries(&m, &[(i, j)]));
+            assert_eq!(s.matching_size, 4, "entry ({i}, {j})");
+        }
+    }
+}
+
+#[test]
+fn empty_pattern_needs_a_diagonal() {
+    let m = DMatrix::<u8>::zeros(3, 3);
+    let aug = minimal_augmentation(&m);
+    assert_eq!(aug.entries, vec![(0, 0), (1, 1), (2, 2)]);
+    assert_eq!(aug.candidate_rows, vec![0, 1, 2]);
+    assert_eq!(aug.candidate_cols, vec![0, 1, 2]);
+}
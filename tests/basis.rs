@@ -0,0 +1,83 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::basis::{BasisError, repair_basis};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+fn columns(m: &DMatrix<u8>, cols: &[usize]) -> DMatrix<u8> {
+    m.select_columns(cols)
+}
+
+#[test]
+fn nonsingular_basis_is_unchanged() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 1,
+        0, 1, 1,
+    ]);
+    let repair = repair_basis(&m, &[1, 0], &[2]).unwrap();
+    assert_eq!(repair.basis, vec![1, 0]);
+    assert!(repair.swaps.is_empty());
+}
+
+#[test]
+fn singular_basis_swaps_in_pool_column() {
+    // Columns 0 and 1 only touch row 0; column 2 covers row 1.
+    let m = DMatrix::from_row_slice(2, 4, &[
+        1, 1, 1, 0,
+        0, 0, 1, 0,
+    ]);
+    let repair = repair_basis(&m, &[0, 1], &[3, 2]).unwrap();
+    assert_eq!(repair.swaps, vec![(1, 2)]);
+    assert_eq!(repair.basis, vec![0, 2]);
+    let s = upper_block_triangular_structure(&columns(&m, &repair.basis));
+    assert_eq!(s.matching_size, 2);
+}
+
+#[test]
+fn repair_reroutes_through_matched_columns() {
+    // Basis {0, 1} leaves row 2 uncovered; pool column 3 only helps after column 1 moves from
+    // row 1 to row 2.
+    let m = DMatrix::from_row_slice(3, 4, &[
+        1, 0, 0, 0,
+        0, 1, 0, 1,
+        0, 1, 0, 0,
+    ]);
+    let repair = repair_basis(&m, &[0, 1, 2], &[3]).unwrap();
+    assert_eq!(repair.swaps, vec![(2, 3)]);
+    assert_eq!(repair.basis, vec![0, 1, 3]);
+}
+
+#[test]
+fn swap_count_equals_rank_deficiency() {
+    let m = DMatrix::from_row_slice(3, 6, &[
+        1, 1, 1, 1, 0, 0,
+        0, 0, 0, 0, 1, 0,
+        0, 0, 0, 0, 0, 1,
+    ]);
+    let repair = repair_basis(&m, &[0, 1, 2], &[3, 4, 5]).unwrap();
+    assert_eq!(repair.swaps.len(), 2);
+    assert_eq!(repair.basis[0], 0);
+    let s = upper_block_triangular_structure(&columns(&m, &repair.basis));
+    assert_eq!(s.matching_size, 3);
+}
+
+#[test]
+fn insufficient_pool_is_reported() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 1, 1,
+        0, 0, 0,
+    ]);
+    assert_eq!(repair_basis(&m, &[0, 1], &[2]), Err(BasisError::Unrepairable { rank: 1 }));
+}
+
+#[test]
+fn invalid_inputs_are_rejected() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        0, 1,
+    ]);
+    assert_eq!(repair_basis(&m, &[0], &[]), Err(BasisError::NotSquare { rows: 2, basis: 1 }));
+    assert_eq!(
+        repair_basis(&m, &[0, 5], &[]),
+        Err(BasisError::ColumnOutOfRange { col: 5, ncols: 2 })
+    );
+    assert_eq!(repair_basis(&m, &[1, 1], &[0]), Err(BasisError::DuplicateColumn(1)));
+}
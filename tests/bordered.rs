@@ -0,0 +1,191 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::{BtfError, BtfOptions};
+use nalgebra_block_triangularization::orientation::Orientation;
+use nalgebra_block_triangularization::bordered::{
+    BorderedBtfStructure, auto_bordered_block_triangular_structure,
+    auto_bordered_block_triangular_structure_with_options, bordered_block_triangular_structure,
+};
+
+fn cycle() -> DMatrix<u8> {
+    // One irreducible 3x3 block.
+    DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ])
+}
+
+#[test]
+fn empty_border_is_the_plain_analysis() {
+    let b = bordered_block_triangular_structure(&cycle(), &[]).unwrap();
+    assert_eq!(b.block_sizes, vec![3]);
+    assert_eq!(b.interior_size(), 3);
+    assert!(b.border_rows.is_empty() && b.border_cols.is_empty() && b.free_cols.is_empty());
+}
+
+#[test]
+fn border_column_breaks_the_cycle() {
+    let m = cycle();
+    let b = bordered_block_triangular_structure(&m, &[0, 0]).unwrap();
+    assert_eq!(b.border_cols, vec![0]);
+    assert_eq!(b.border_rows.len(), 1);
+    assert_eq!(b.block_sizes, vec![1, 1]);
+    assert_eq!(b.row_order.len(), 3);
+    assert_eq!(b.row_order[2], b.border_rows[0]);
+    assert_eq!(b.col_order, vec![b.col_order[0], b.col_order[1], 0]);
+
+    // The interior is upper triangular: no interior entry left of its row's diagonal.
+    let n = b.interior_size();
+    for p in 0..n {
+        for q in 0..n {
+            if m[(b.row_order[p], b.col_order[q])] != 0 {
+                assert!(q >= p, "interior entry at ({p}, {q}) below the diagonal");
+            }
+        }
+    }
+}
+
+#[test]
+fn wide_border_leaves_free_columns() {
+    // Column 2 only appears in the border row.
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 1, 0,
+        1, 0, 1,
+    ]);
+    let b = bordered_block_triangular_structure(&m, &[0]).unwrap();
+    assert_eq!(b.interior_size(), 2);
+    assert!(b.border_rows.is_empty());
+    assert!(b.free_cols.is_empty());
+    assert_eq!(b.col_order[2], 0);
+
+    let b = bordered_block_triangular_structure(&DMatrix::<u8>::identity(2, 3), &[1]).unwrap();
+    assert_eq!(b.border_rows, vec![1]);
+    assert_eq!(b.free_cols, vec![2]);
+    assert_eq!(b.col_order, vec![0, 2, 1]);
+}
+
+#[test]
+fn out_of_range_border_column_is_an_error() {
+    let err = bordered_block_triangular_structure(&cycle(), &[3]).unwrap_err();
+    assert!(matches!(err, BtfError::IndexOutOfRange { index: 3, len: 3 }));
+    assert_eq!(err.to_string(), "index 3 out of range for dimension 3");
+}
+
+fn assert_interior_upper_triangular(m: &DMatrix<u8>, b: &BorderedBtfStructure) {
+    let n = b.interior_size();
+    let mut start = 0;
+    for &size in &b.block_sizes {
+        for p in start..start + size {
+            for q in 0..start {
+                assert_eq!(m[(b.row_order[p], b.col_order[q])], 0, "entry left of block at ({p}, {q})");
+            }
+        }
+        start += size;
+    }
+    assert_eq!(start, n);
+}
+
+fn two_coupled_cycles() -> DMatrix<u8> {
+    // Two 3-cycles, the second also using a column of the first.
+    DMatrix::from_row_slice(6, 6, &[
+        1, 1, 0, 0, 0, 0,
+        0, 1, 1, 0, 0, 0,
+        1, 0, 1, 0, 0, 0,
+        0, 0, 1, 1, 1, 0,
+        0, 0, 0, 0, 1, 1,
+        0, 0, 0, 1, 0, 1,
+    ])
+}
+
+#[test]
+fn auto_border_makes_the_interior_triangular() {
+    let m = cycle();
+    let b = auto_bordered_block_triangular_structure(&m, 1).unwrap();
+    assert_eq!(b.border_cols.len(), 1);
+    assert_eq!(b.block_sizes, vec![1, 1]);
+    assert_interior_upper_triangular(&m, &b);
+    assert_eq!(b, bordered_block_triangular_structure(&m, &b.border_cols).unwrap());
+
+    let m = two_coupled_cycles();
+    let b = auto_bordered_block_triangular_structure(&m, 0).unwrap();
+    assert_eq!(b.border_cols.len(), 2);
+    assert_eq!(b.border_rows.len(), 2);
+    assert!(b.block_sizes.iter().all(|&s| s == 1));
+    assert_interior_upper_triangular(&m, &b);
+}
+
+#[test]
+fn auto_border_respects_the_block_size_bound() {
+    let m = two_coupled_cycles();
+    let b = auto_bordered_block_triangular_structure(&m, 3).unwrap();
+    assert!(b.border_cols.is_empty());
+    assert_eq!(b.block_sizes, vec![3, 3]);
+
+    let dense = DMatrix::from_element(5, 5, 1u8);
+    for max in 1..=5 {
+        let b = auto_bordered_block_triangular_structure(&dense, max).unwrap();
+        assert!(b.block_sizes.iter().all(|&s| s <= max), "max {max}: {:?}", b.block_sizes);
+        assert_eq!(b.border_cols.len(), 5 - b.interior_size());
+        assert_interior_upper_triangular(&dense, &b);
+    }
+}
+
+#[test]
+fn auto_border_tears_merged_blocks_per_component() {
+    let merged = BtfOptions {
+        min_block_size: 3,
+        ..Default::default()
+    };
+    // Three singletons merged into one reducible block: nothing to tear.
+    let identity = DMatrix::<u8>::identity(3, 3);
+    let b = auto_bordered_block_triangular_structure_with_options(&identity, 1, &merged).unwrap();
+    assert!(b.border_cols.is_empty());
+    assert_eq!(b.block_sizes, vec![3]);
+
+    // Both cycles are torn even though they share a merged block.
+    let merged = BtfOptions {
+        min_block_size: 6,
+        ..Default::default()
+    };
+    let m = two_coupled_cycles();
+    let b = auto_bordered_block_triangular_structure_with_options(&m, 1, &merged).unwrap();
+    assert_eq!(b.border_cols.len(), 2);
+    assert_eq!(b.interior_size(), 4);
+    assert_interior_upper_triangular(&m, &b);
+}
+
+#[test]
+fn auto_border_reads_as_block_lower_triangular() {
+    let m = two_coupled_cycles();
+    let b = auto_bordered_block_triangular_structure(&m, 2).unwrap();
+    let rows = b.oriented_row_order(Orientation::Lower);
+    let cols = b.oriented_col_order(Orientation::Lower);
+    let sizes = b.oriented_block_sizes(Orientation::Lower);
+    let n = b.interior_size();
+    assert_eq!(sizes, b.block_sizes.iter().rev().copied().collect::<Vec<_>>());
+    // The border keeps its trailing place.
+    assert_eq!(rows[n..], b.row_order[n..]);
+    assert_eq!(cols[n..], b.col_order[n..]);
+
+    // No interior entry right of its block.
+    let mut start = 0;
+    for &size in &sizes {
+        for p in start..start + size {
+            for q in start + size..n {
+                assert_eq!(m[(rows[p], cols[q])], 0, "entry right of block at ({p}, {q})");
+            }
+        }
+        start += size;
+    }
+
+    let (pr, pc) = b.permutations(Orientation::Lower);
+    let mut permuted = m.clone();
+    pr.permute_rows(&mut permuted);
+    pc.permute_columns(&mut permuted);
+    for p in 0..6 {
+        for q in 0..6 {
+            assert_eq!(permuted[(p, q)], m[(rows[p], cols[q])]);
+        }
+    }
+    assert_eq!(b.oriented_row_order(Orientation::Upper), b.row_order);
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "arrayvec")]
+
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::bounded::{
+    CapacityError, upper_block_triangular_structure_bounded,
+};
+use nalgebra_block_triangularization::{
+    BtfOptions, MatchingAlgorithm, upper_block_triangular_structure_with_options,
+};
+
+fn mc21_options() -> BtfOptions {
+    BtfOptions {
+        matching: MatchingAlgorithm::Mc21,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn bounded_matches_dynamic_analysis() {
+    let m = DMatrix::from_row_slice(5, 6, &[
+        1, 1, 0, 0, 1, 0,
+        1, 1, 0, 0, 0, 0,
+        0, 0, 1, 1, 0, 0,
+        0, 0, 1, 1, 1, 1,
+        0, 0, 0, 0, 1, 0,
+    ]);
+    let bounded = upper_block_triangular_structure_bounded::<8, 16, _, _, _, _>(&m).unwrap();
+    assert_eq!(bounded.block_sizes.as_slice(), &[2, 2, 1]);
+
+    let s = bounded.to_structure();
+    let dynamic = upper_block_triangular_structure_with_options(&m, &mc21_options());
+    assert_eq!(s.row_order, dynamic.row_order);
+    assert_eq!(s.col_order, dynamic.col_order);
+    assert_eq!(s.block_sizes, dynamic.block_sizes);
+    assert_eq!(s.block_dag, dynamic.block_dag);
+    assert_eq!(s.matching_size, dynamic.matching_size);
+}
+
+#[test]
+fn bounded_singular_pattern() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let s = upper_block_triangular_structure_bounded::<3, 4, _, _, _, _>(&m)
+        .unwrap()
+        .to_structure();
+    let dynamic = upper_block_triangular_structure_with_options(&m, &mc21_options());
+    assert_eq!(s.matching_size, 2);
+    assert_eq!(s.row_order, dynamic.row_order);
+    assert_eq!(s.col_order, dynamic.col_order);
+    assert_eq!(s.block_dag, dynamic.block_dag);
+}
+
+#[test]
+fn bounded_rejects_oversized_inputs() {
+    let m = DMatrix::<u8>::identity(4, 4);
+    assert_eq!(
+        upper_block_triangular_structure_bounded::<3, 16, _, _, _, _>(&m),
+        Err(CapacityError::Dimension { nrows: 4, ncols: 4, capacity: 3 })
+    );
+    assert_eq!(
+        upper_block_triangular_structure_bounded::<4, 3, _, _, _, _>(&m),
+        Err(CapacityError::Nonzeros { capacity: 3 })
+    );
+    assert!(upper_block_triangular_structure_bounded::<4, 4, _, _, _, _>(&m).is_ok());
+}
+
+#[test]
+fn bounded_empty_pattern() {
+    let m = DMatrix::<u8>::zeros(0, 2);
+    let s = upper_block_triangular_structure_bounded::<2, 1, _, _, _, _>(&m).unwrap();
+    assert!(s.row_order.is_empty());
+    assert_eq!(s.col_order.as_slice(), &[0, 1]);
+    assert!(s.block_sizes.is_empty());
+}
+
+#[test]
+fn bounded_matches_dynamic_analysis_on_pseudorandom_patterns() {
+    let mut state = 0x9e37_79b9u32;
+    for (nrows, ncols) in (1..12).flat_map(|n| [(n, n), (n, n + 2), (n + 2, n)]) {
+        let m = DMatrix::from_fn(nrows, ncols, |_, _| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            u8::from(state.is_multiple_of(4))
+        });
+        let s = upper_block_triangular_structure_bounded::<16, 256, _, _, _, _>(&m)
+            .unwrap()
+            .to_structure();
+        let dynamic = upper_block_triangular_structure_with_options(&m, &mc21_options());
+        assert_eq!(s.row_order, dynamic.row_order, "{m}");
+        assert_eq!(s.col_order, dynamic.col_order, "{m}");
+        assert_eq!(s.block_dag, dynamic.block_dag, "{m}");
+        assert_eq!(s.row_to_col, dynamic.row_to_col, "{m}");
+    }
+}
@@ -0,0 +1,72 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::canonical::{
+    canonical_structure, canonical_structure_with_options,
+};
+use nalgebra_block_triangularization::{BtfOptions, MatchingAlgorithm, UpperBtfStructure};
+
+fn check_zero_free_diagonal(m: &DMatrix<u8>, s: &UpperBtfStructure) {
+    for (&r, &c) in s.row_order.iter().zip(&s.col_order) {
+        assert_ne!(m[(r, c)], 0, "({r}, {c}) on the diagonal");
+    }
+}
+
+#[test]
+fn full_block_gets_the_identity_matching() {
+    let full = DMatrix::from_element(3, 3, 1u8);
+    let s = canonical_structure(&full).unwrap();
+    assert_eq!(s.row_order, vec![0, 1, 2]);
+    assert_eq!(s.col_order, vec![0, 1, 2]);
+}
+
+#[test]
+fn canonical_matching_is_lexicographically_smallest() {
+    // Row 0 takes column 1; row 1 cannot take column 0 as row 2 would be left without a
+    // column, so it takes column 2.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        0, 1, 1,
+        1, 1, 1,
+        1, 1, 0,
+    ]);
+    let s = canonical_structure(&m).unwrap();
+    assert_eq!(s.block_sizes, vec![3]);
+    assert_eq!(s.row_to_col, vec![Some(1), Some(2), Some(0)]);
+    assert_eq!(s.row_order, vec![0, 1, 2]);
+    assert_eq!(s.col_order, vec![1, 2, 0]);
+    check_zero_free_diagonal(&m, &s);
+}
+
+#[test]
+fn canonical_form_ignores_the_matching_algorithm() {
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 0, 1,
+        1, 1, 1, 0, 0,
+        0, 1, 1, 0, 0,
+        0, 0, 1, 1, 1,
+        1, 0, 0, 1, 1,
+    ]);
+    let hk = canonical_structure(&m).unwrap();
+    let mc21 = canonical_structure_with_options(
+        &m,
+        &BtfOptions {
+            matching: MatchingAlgorithm::Mc21,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(hk.row_order, mc21.row_order);
+    assert_eq!(hk.col_order, mc21.col_order);
+    assert_eq!(hk.block_sizes, mc21.block_sizes);
+    check_zero_free_diagonal(&m, &hk);
+}
+
+#[test]
+fn forbidden_entries_are_never_matched() {
+    let full = DMatrix::from_element(2, 2, 1u8);
+    let options = BtfOptions {
+        forbidden: vec![(0, 0)],
+        ..Default::default()
+    };
+    let s = canonical_structure_with_options(&full, &options).unwrap();
+    assert_eq!(s.row_to_col, vec![Some(1), Some(0)]);
+    assert_eq!(s.col_order, vec![1, 0]);
+}
@@ -0,0 +1,146 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::adjacency::build_row_adjacency;
+use nalgebra_block_triangularization::components::{
+    BipartiteComponent, analyze_by_components, bipartite_components,
+};
+use nalgebra_block_triangularization::permutation::is_valid_permutation;
+use nalgebra_block_triangularization::{
+    BlockOrder, BtfOptions, UnmatchedPlacement, upper_block_triangular_structure,
+    upper_block_triangular_structure_with_options,
+};
+
+#[test]
+fn components_of_block_diagonal_pattern() {
+    // {rows 0,2; cols 0,2}, {row 1; col 1}, empty row 3, empty column 3.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 0, 1, 0,
+        0, 1, 0, 0,
+        1, 0, 0, 0,
+        0, 0, 0, 0,
+    ]);
+    let comps = bipartite_components(&build_row_adjacency(&m), 4);
+    assert_eq!(
+        comps,
+        vec![
+            BipartiteComponent { rows: vec![0, 2], cols: vec![0, 2] },
+            BipartiteComponent { rows: vec![1], cols: vec![1] },
+            BipartiteComponent { rows: vec![3], cols: vec![] },
+            BipartiteComponent { rows: vec![], cols: vec![3] },
+        ]
+    );
+}
+
+#[test]
+fn stitched_structure_matches_global() {
+    // Two interleaved independent subsystems: even rows/cols form a 3-cycle, odd ones a chain.
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1, 0, 1, 0, 0, 0,
+        0, 1, 0, 1, 0, 0,
+        0, 0, 1, 0, 1, 0,
+        0, 0, 0, 1, 0, 1,
+        1, 0, 0, 0, 1, 0,
+        0, 0, 0, 0, 0, 1,
+    ]);
+    let global = upper_block_triangular_structure(&m);
+    let stitched = analyze_by_components(&m, &BtfOptions::default()).unwrap();
+    assert_eq!(stitched.row_order, global.row_order);
+    assert_eq!(stitched.col_order, global.col_order);
+    assert_eq!(stitched.block_sizes, global.block_sizes);
+    assert_eq!(stitched.block_dag, global.block_dag);
+    assert_eq!(stitched.matching_size, global.matching_size);
+}
+
+#[test]
+fn stitched_structure_matches_global_on_pseudorandom_patterns() {
+    let mut state = 0x9e37_79b9u32;
+    for n in 1..25 {
+        let m = DMatrix::from_fn(n, n, |i, j| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            u8::from(i == j || state.is_multiple_of(11))
+        });
+        let global = upper_block_triangular_structure(&m);
+        let stitched = analyze_by_components(&m, &BtfOptions::default()).unwrap();
+        assert_eq!(stitched.row_order, global.row_order);
+        assert_eq!(stitched.block_sizes, global.block_sizes);
+        assert_eq!(stitched.block_dag, global.block_dag);
+        assert_eq!(stitched.matching_size, n);
+        assert!(is_valid_permutation(&stitched.col_order));
+    }
+}
+
+#[test]
+fn stitched_structure_handles_empty_rows_and_columns() {
+    let m = DMatrix::from_row_slice(3, 4, &[
+        0, 1, 0, 0,
+        0, 0, 0, 0,
+        0, 1, 0, 1,
+    ]);
+    let s = analyze_by_components(&m, &BtfOptions::default()).unwrap();
+    let global = upper_block_triangular_structure(&m);
+    assert_eq!(s.row_order, global.row_order);
+    assert_eq!(s.block_sizes, global.block_sizes);
+    assert_eq!(s.matching_size, 2);
+    assert!(is_valid_permutation(&s.col_order));
+
+    let empty = analyze_by_components(&DMatrix::<u8>::zeros(0, 3), &BtfOptions::default()).unwrap();
+    assert_eq!(empty.col_order, vec![0, 1, 2]);
+}
+
+#[test]
+fn stitched_structure_places_unmatched_rows_and_columns_globally() {
+    // Rows 0 and 3 compete for column 0; row 1 and column 4 form a chain with an unmatched
+    // column; row 2 is a singleton on column 2.
+    let m = DMatrix::from_row_slice(4, 5, &[
+        1, 0, 0, 0, 0,
+        0, 1, 0, 1, 1,
+        0, 0, 1, 0, 0,
+        1, 0, 0, 0, 0,
+    ]);
+    for unmatched in [
+        UnmatchedPlacement::End,
+        UnmatchedPlacement::Start,
+        UnmatchedPlacement::Adjacent,
+    ] {
+        let options = BtfOptions {
+            unmatched,
+            ..Default::default()
+        };
+        let global = upper_block_triangular_structure_with_options(&m, &options);
+        let stitched = analyze_by_components(&m, &options).unwrap();
+        assert_eq!(stitched.row_order, global.row_order, "{unmatched:?}");
+        assert_eq!(stitched.col_order, global.col_order, "{unmatched:?}");
+        assert_eq!(stitched.block_sizes, global.block_sizes, "{unmatched:?}");
+    }
+}
+
+#[test]
+fn block_order_and_min_block_size_apply_per_component() {
+    // A 1x1 component before a 2x2 one.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        0, 1, 1,
+        0, 1, 1,
+    ]);
+    let largest_first = BtfOptions {
+        block_order: BlockOrder::LargestFirst,
+        ..Default::default()
+    };
+    let global = upper_block_triangular_structure_with_options(&m, &largest_first);
+    assert_eq!(global.block_sizes, vec![2, 1]);
+    // Each component has one block; the merge goes by min row.
+    let stitched = analyze_by_components(&m, &largest_first).unwrap();
+    assert_eq!(stitched.block_sizes, vec![1, 2]);
+
+    let min_two = BtfOptions {
+        min_block_size: 2,
+        ..Default::default()
+    };
+    let identity = DMatrix::<u8>::identity(2, 2);
+    let global = upper_block_triangular_structure_with_options(&identity, &min_two);
+    assert_eq!(global.block_sizes, vec![2]);
+    // Blocks are never merged across components.
+    let stitched = analyze_by_components(&identity, &min_two).unwrap();
+    assert_eq!(stitched.block_sizes, vec![1, 1]);
+}
@@ -0,0 +1,106 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::critical::critical_entries;
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+#[test]
+fn identity_diagonal_is_rank_critical() {
+    let m: DMatrix<u8> = DMatrix::identity(3, 3);
+    let crit = critical_entries(&m);
+    assert_eq!(crit.rank_reducing, vec![(0, 0), (1, 1), (2, 2)]);
+    assert!(crit.block_splitting.is_empty());
+    assert!(crit.coupling_breaking.is_empty());
+}
+
+#[test]
+fn single_coupling_entry_is_reported() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1u8, 1,
+        0, 1,
+    ]);
+    let crit = critical_entries(&m);
+    assert_eq!(crit.rank_reducing, vec![(0, 0), (1, 1)]);
+    assert_eq!(crit.coupling_breaking, vec![(0, 1)]);
+}
+
+#[test]
+fn redundant_couplings_are_not_critical() {
+    // Block {0,1} couples to block {2} through two entries.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1u8, 1, 1,
+        1, 1, 1,
+        0, 0, 1,
+    ]);
+    let crit = critical_entries(&m);
+    assert!(crit.coupling_breaking.is_empty());
+    assert_eq!(crit.rank_reducing, vec![(2, 2)]);
+}
+
+#[test]
+fn every_entry_of_a_simple_cycle_splits_the_block() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1u8, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let crit = critical_entries(&m);
+    assert!(crit.rank_reducing.is_empty());
+    assert_eq!(
+        crit.block_splitting,
+        vec![(0, 0), (0, 1), (1, 1), (1, 2), (2, 0), (2, 2)]
+    );
+}
+
+#[test]
+fn dense_block_has_no_critical_entries() {
+    let m = DMatrix::from_element(3, 3, 1u8);
+    assert_eq!(critical_entries(&m), Default::default());
+}
+
+#[test]
+fn rank_reducing_in_rectangular_pattern() {
+    // Column 0 is shared by rows 0 and 1, so (0,0) can be swapped for (1,0);
+    // column 1 is only reachable from row 2.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1u8, 0,
+        1, 0,
+        0, 1,
+    ]);
+    let crit = critical_entries(&m);
+    assert_eq!(crit.rank_reducing, vec![(2, 1)]);
+}
+
+#[test]
+fn matches_brute_force_on_small_patterns() {
+    let mut state = 0x2545_f491_u64;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..40 {
+        let n = 5;
+        let m = DMatrix::from_fn(n, n, |i, j| u8::from(i == j || next() % 3 == 0));
+        let base = upper_block_triangular_structure(&m);
+        let crit = critical_entries(&m);
+
+        for i in 0..n {
+            for j in 0..n {
+                if m[(i, j)] == 0 {
+                    continue;
+                }
+                let mut reduced = m.clone();
+                reduced[(i, j)] = 0;
+                let s = upper_block_triangular_structure(&reduced);
+
+                let rank_drop = s.matching_size < base.matching_size;
+                assert_eq!(crit.rank_reducing.contains(&(i, j)), rank_drop, "{m}");
+                if !rank_drop {
+                    let split = s.block_sizes.len() > base.block_sizes.len();
+                    assert_eq!(crit.block_splitting.contains(&(i, j)), split, "{m}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::csr::{CsrBuilder, CsrError, CsrPattern, analyze_csr};
+use nalgebra_block_triangularization::{
+    BtfOptions, MatchingAlgorithm, upper_block_triangular_structure_with_options,
+};
+
+fn mc21_options() -> BtfOptions {
+    BtfOptions {
+        matching: MatchingAlgorithm::Mc21,
+        ..Default::default()
+    }
+}
+
+fn assert_same_as_dense(m: &DMatrix<u8>) {
+    let csr = CsrPattern::from_matrix(m).unwrap();
+    let a = analyze_csr(&csr);
+    let b = upper_block_triangular_structure_with_options(m, &mc21_options());
+    assert_eq!(a.row_order, b.row_order);
+    assert_eq!(a.col_order, b.col_order);
+    assert_eq!(a.block_sizes, b.block_sizes);
+    assert_eq!(a.matching_size, b.matching_size);
+    assert_eq!(a.block_dag, b.block_dag);
+    assert_eq!(a.row_to_col, b.row_to_col);
+}
+
+#[test]
+fn csr_matches_dense_analysis() {
+    assert_same_as_dense(&DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 0, 0,
+        1, 1, 0, 1, 0,
+        0, 0, 1, 1, 0,
+        0, 0, 1, 1, 1,
+        1, 0, 0, 0, 1,
+    ]));
+    assert_same_as_dense(&DMatrix::from_row_slice(3, 4, &[
+        1, 0, 1, 0,
+        1, 0, 0, 0,
+        0, 1, 1, 1,
+    ]));
+    assert_same_as_dense(&DMatrix::from_row_slice(4, 2, &[
+        1, 0,
+        1, 1,
+        0, 1,
+        0, 0,
+    ]));
+    assert_same_as_dense(&DMatrix::<u8>::zeros(0, 0));
+    assert_same_as_dense(&DMatrix::<u8>::zeros(3, 0));
+}
+
+#[test]
+fn csr_matches_dense_on_pseudorandom_patterns() {
+    let mut state = 0x2545_f491u32;
+    for n in 1..30 {
+        let m = DMatrix::from_fn(n, n, |i, j| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            u8::from(i == j || state.is_multiple_of(7))
+        });
+        assert_same_as_dense(&m);
+    }
+}
+
+#[test]
+fn csr_long_chain_does_not_recurse() {
+    // A single 20_000-row cycle: recursion-based SCC would need one frame per row.
+    let n = 20_000u32;
+    let mut builder = CsrBuilder::new(n as usize, n as usize).unwrap();
+    for i in 0..n {
+        let mut cols = [i, (i + 1) % n];
+        cols.sort_unstable();
+        builder.push_chunk(&[(i, cols[0]), (i, cols[1])]).unwrap();
+    }
+    let s = analyze_csr(&builder.finish());
+    assert_eq!(s.block_sizes, vec![n as usize]);
+    assert_eq!(s.matching_size, n as usize);
+}
+
+#[test]
+fn builder_streams_chunks() {
+    let mut builder = CsrBuilder::new(3, 3).unwrap();
+    builder.push_chunk(&[(0, 0), (0, 2)]).unwrap();
+    builder.push_chunk(&[(0, 2), (2, 1)]).unwrap();
+    let csr = builder.finish();
+    assert_eq!(csr.nnz(), 3);
+    assert_eq!(csr.row(0), &[0, 2]);
+    assert!(csr.row(1).is_empty());
+    assert_eq!(csr.row(2), &[1]);
+}
+
+#[test]
+fn builder_rejects_bad_input() {
+    let mut builder = CsrBuilder::new(2, 2).unwrap();
+    assert_eq!(builder.push(2, 0), Err(CsrError::OutOfRange { row: 2, col: 0 }));
+    builder.push(1, 1).unwrap();
+    assert_eq!(builder.push(1, 0), Err(CsrError::Unsorted { row: 1, col: 0 }));
+    assert_eq!(builder.push(0, 1), Err(CsrError::Unsorted { row: 0, col: 1 }));
+}
+
+#[test]
+fn from_parts_validates_and_borrows() {
+    let row_ptr: &[u64] = &[0, 2, 3];
+    let col_idx: &[u32] = &[0, 1, 1];
+    let csr = CsrPattern::from_parts(2, 2, row_ptr, col_idx).unwrap();
+    assert_eq!(analyze_csr(&csr).block_sizes, vec![1, 1]);
+
+    assert_eq!(
+        CsrPattern::from_parts(2, 2, &[0u64, 2][..], col_idx).unwrap_err(),
+        CsrError::InvalidRowPtr
+    );
+    assert_eq!(
+        CsrPattern::from_parts(2, 1, row_ptr, col_idx).unwrap_err(),
+        CsrError::OutOfRange { row: 0, col: 1 }
+    );
+}
@@ -0,0 +1,97 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::cycles::{CycleLimits, block_cycles, elementary_cycles};
+
+#[test]
+fn no_cycles_in_dag() {
+    let graph = vec![vec![1, 2], vec![2], vec![]];
+    assert!(elementary_cycles(&graph, &CycleLimits::default()).is_empty());
+}
+
+#[test]
+fn two_cycle_and_three_cycle() {
+    // 0 <-> 1, 1 -> 2 -> 0
+    let graph = vec![vec![1], vec![0, 2], vec![0]];
+    let cycles = elementary_cycles(&graph, &CycleLimits::default());
+    assert_eq!(cycles, vec![vec![0, 1], vec![0, 1, 2]]);
+}
+
+#[test]
+fn complete_graph_cycle_count() {
+    // K4 has 6 two-cycles, 8 three-cycles and 6 four-cycles.
+    let graph: Vec<Vec<usize>> = (0..4)
+        .map(|u| (0..4).filter(|&v| v != u).collect())
+        .collect();
+    let cycles = elementary_cycles(&graph, &CycleLimits::default());
+    assert_eq!(cycles.len(), 20);
+    for len in 2..=4 {
+        let count = cycles.iter().filter(|c| c.len() == len).count();
+        assert_eq!(count, [0, 0, 6, 8, 6][len]);
+    }
+}
+
+#[test]
+fn length_limit() {
+    let graph: Vec<Vec<usize>> = (0..4)
+        .map(|u| (0..4).filter(|&v| v != u).collect())
+        .collect();
+    let limits = CycleLimits {
+        max_len: 3,
+        ..Default::default()
+    };
+    let cycles = elementary_cycles(&graph, &limits);
+    assert_eq!(cycles.len(), 14);
+    assert!(cycles.iter().all(|c| c.len() <= 3));
+}
+
+#[test]
+fn count_limit() {
+    let graph: Vec<Vec<usize>> = (0..5)
+        .map(|u| (0..5).filter(|&v| v != u).collect())
+        .collect();
+    let limits = CycleLimits {
+        max_cycles: 7,
+        ..Default::default()
+    };
+    assert_eq!(elementary_cycles(&graph, &limits).len(), 7);
+}
+
+#[test]
+fn cycles_are_elementary_and_closed() {
+    let graph = vec![vec![1, 3], vec![2], vec![0, 3], vec![1]];
+    for cycle in elementary_cycles(&graph, &CycleLimits::default()) {
+        let mut sorted = cycle.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), cycle.len());
+        for (k, &u) in cycle.iter().enumerate() {
+            let v = cycle[(k + 1) % cycle.len()];
+            assert!(graph[u].contains(&v));
+        }
+    }
+}
+
+#[test]
+fn block_cycles_in_original_rows() {
+    // Rows 1 and 3 form a loop; rows 0 and 2 are singleton blocks.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1u8, 1, 0, 0,
+        0, 1, 0, 1,
+        0, 0, 1, 0,
+        0, 1, 1, 1,
+    ]);
+    let structure = nalgebra_block_triangularization::upper_block_triangular_structure(&m);
+    let block = structure
+        .block_sizes
+        .iter()
+        .position(|&s| s == 2)
+        .unwrap();
+    let cycles = block_cycles(&m, block, &CycleLimits::default());
+    assert_eq!(cycles, vec![vec![1, 3]]);
+}
+
+#[test]
+#[should_panic]
+fn block_cycles_out_of_range() {
+    let m: DMatrix<u8> = DMatrix::identity(2, 2);
+    block_cycles(&m, 2, &CycleLimits::default());
+}
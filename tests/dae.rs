@@ -0,0 +1,208 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::dae::{
+    DaeError, dae_structure, dae_structure_with_options, pantelides,
+};
+use nalgebra_block_triangularization::ordering::OrderingError;
+use nalgebra_block_triangularization::{BtfError, BtfOptions, Pin};
+
+#[test]
+fn states_use_derivative_occurrences() {
+    // x0' = f(x0, x1)
+    // x1' = g(x0)
+    // 0   = h(x0, x2)
+    let vars = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 0,
+        1, 0, 1,
+    ]);
+    let derivs = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+        0, 0, 0,
+    ]);
+    let dae = dae_structure(&vars, &derivs).unwrap();
+    assert_eq!(dae.differential, vec![true, true, false]);
+    // States are known, so only x0', x1' and the algebraic x2 are unknowns.
+    assert_eq!(dae.incidence, vec![vec![0], vec![1], vec![2]]);
+    assert_eq!(dae.structure.block_sizes, vec![1, 1, 1]);
+    assert_eq!(dae.structure.matching_size, 3);
+}
+
+#[test]
+fn algebraic_loop_forms_a_block() {
+    // x0' = f(x1); 0 = g(x1, x2); 0 = h(x1, x2)
+    let vars = DMatrix::from_row_slice(3, 3, &[
+        0, 1, 0,
+        0, 1, 1,
+        0, 1, 1,
+    ]);
+    let derivs = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        0, 0, 0,
+        0, 0, 0,
+    ]);
+    let dae = dae_structure(&vars, &derivs).unwrap();
+    assert_eq!(dae.incidence, vec![vec![0, 1], vec![1, 2], vec![1, 2]]);
+    let rb = dae.structure.row_blocks();
+    assert_eq!(rb[1], rb[2]);
+    assert_ne!(rb[0], rb[1]);
+    assert!(rb[0] < rb[1]);
+}
+
+#[test]
+fn merged_pattern_would_hide_singularity() {
+    // x0' = f(x0); 0 = g(x0, x1): nonsingular.
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        1, 1,
+    ]);
+    let derivs = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        0, 0,
+    ]);
+    let dae = dae_structure(&vars, &derivs).unwrap();
+    assert_eq!(dae.structure.matching_size, 2);
+
+    // x0' = f(x0, x1); 0 = g(x0): the merged pattern [[1, 1], [1, 0]] is nonsingular, but
+    // the constraint only involves the state x0, so no equation is left for x0' and x1.
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 0,
+    ]);
+    let dae = dae_structure(&vars, &derivs).unwrap();
+    assert_eq!(dae.incidence, vec![vec![0, 1], vec![]]);
+    assert_eq!(dae.structure.matching_size, 1);
+}
+
+#[test]
+fn shape_mismatch_is_an_error() {
+    let vars: DMatrix<u8> = DMatrix::zeros(2, 3);
+    let derivs: DMatrix<u8> = DMatrix::zeros(3, 2);
+    assert_eq!(
+        dae_structure(&vars, &derivs).unwrap_err(),
+        DaeError::ShapeMismatch { vars: (2, 3), derivs: (3, 2) }
+    );
+}
+
+#[test]
+fn pins_the_incidence_cannot_honor_are_an_error() {
+    // 0 = f(x0); 0 = g(x1); x2 appears nowhere, so its column stays unmatched.
+    let vars = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let derivs: DMatrix<u8> = DMatrix::zeros(2, 3);
+    let options = BtfOptions {
+        pin_first: vec![Pin::Col(2)],
+        ..Default::default()
+    };
+    let err = dae_structure_with_options(&vars, &derivs, &options).unwrap_err();
+    assert_eq!(
+        err,
+        DaeError::Ordering(OrderingError::PinnedUnmatchedColumn { col: 2 })
+    );
+    assert!(matches!(BtfError::from(err), BtfError::InvalidPins(_)));
+}
+
+#[test]
+fn pantelides_leaves_an_ode_alone() {
+    // x0' = f(x0, x1); x1' = g(x0)
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 0,
+    ]);
+    let derivs = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        0, 1,
+    ]);
+    let reduction = pantelides(&vars, &derivs).unwrap();
+    assert_eq!(reduction.equation_derivatives, vec![0, 0]);
+    assert_eq!(reduction.variable_derivatives, vec![1, 1]);
+    assert_eq!(reduction.assignment, vec![(0, 1), (1, 1)]);
+    assert_eq!(reduction.structural_index(), 0);
+}
+
+#[test]
+fn pantelides_semi_explicit_index_one_and_two() {
+    let derivs = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        0, 0,
+    ]);
+    // x' = f(x, y); 0 = g(x, y): the constraint is solved for y.
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 1,
+    ]);
+    let reduction = pantelides(&vars, &derivs).unwrap();
+    assert!(reduction.differentiated_equations().is_empty());
+    assert_eq!(reduction.assignment, vec![(0, 1), (1, 0)]);
+    assert_eq!(reduction.structural_index(), 1);
+
+    // x' = f(x, y); 0 = g(x): the constraint must be differentiated once.
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 0,
+    ]);
+    let reduction = pantelides(&vars, &derivs).unwrap();
+    assert_eq!(reduction.equation_derivatives, vec![0, 1]);
+    assert_eq!(reduction.variable_derivatives, vec![1, 0]);
+    assert_eq!(reduction.assignment, vec![(1, 0), (0, 1)]);
+    assert_eq!(reduction.structural_index(), 2);
+}
+
+#[test]
+fn pantelides_pendulum_has_index_three() {
+    // Variables x, y, u, v, lambda.
+    // x' = u; y' = v; u' = lambda x; v' = lambda y - g; x^2 + y^2 = 1
+    let vars = DMatrix::from_row_slice(5, 5, &[
+        0, 0, 1, 0, 0,
+        0, 0, 0, 1, 0,
+        1, 0, 0, 0, 1,
+        0, 1, 0, 0, 1,
+        1, 1, 0, 0, 0,
+    ]);
+    let derivs = DMatrix::from_row_slice(5, 5, &[
+        1, 0, 0, 0, 0,
+        0, 1, 0, 0, 0,
+        0, 0, 1, 0, 0,
+        0, 0, 0, 1, 0,
+        0, 0, 0, 0, 0,
+    ]);
+    let reduction = pantelides(&vars, &derivs).unwrap();
+    assert_eq!(reduction.equation_derivatives, vec![1, 1, 0, 0, 2]);
+    assert_eq!(reduction.variable_derivatives, vec![2, 2, 1, 1, 0]);
+    assert_eq!(reduction.differentiated_equations(), vec![0, 1, 4]);
+    assert_eq!(reduction.structural_index(), 3);
+    // One highest-order variable per equation.
+    let mut assigned = reduction.assignment.clone();
+    assigned.sort_unstable();
+    assigned.dedup();
+    assert_eq!(assigned.len(), 5);
+    for (j, order) in assigned {
+        assert_eq!(order, reduction.variable_derivatives[j]);
+    }
+}
+
+#[test]
+fn pantelides_rejects_unsolvable_systems() {
+    let vars = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        1, 0,
+    ]);
+    let derivs: DMatrix<u8> = DMatrix::zeros(2, 2);
+    assert!(matches!(
+        pantelides(&vars, &derivs),
+        Err(BtfError::StructurallySingular { rank: 1, n: 2 })
+    ));
+
+    let vars: DMatrix<u8> = DMatrix::zeros(2, 3);
+    assert!(matches!(
+        pantelides(&vars, &vars),
+        Err(BtfError::DimensionMismatch { expected: (2, 2), found: (2, 3) })
+    ));
+    let derivs: DMatrix<u8> = DMatrix::zeros(3, 2);
+    assert!(matches!(
+        pantelides(&vars, &derivs),
+        Err(BtfError::DimensionMismatch { expected: (2, 3), found: (3, 2) })
+    ));
+}
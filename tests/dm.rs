@@ -0,0 +1,89 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::dm::dulmage_mendelsohn;
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+#[test]
+fn nonsingular_pattern_is_all_square() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 1,
+    ]);
+    let dm = dulmage_mendelsohn(&m);
+    let s = upper_block_triangular_structure(&m);
+    assert!(dm.under.rows.is_empty() && dm.under.cols.is_empty());
+    assert!(dm.over.rows.is_empty() && dm.over.cols.is_empty());
+    assert_eq!(dm.structural_rank(), 4);
+    assert_eq!(dm.square.rows, s.row_order);
+    assert_eq!(dm.square.cols, s.col_order);
+    assert_eq!(dm.square.block_sizes, s.block_sizes);
+    assert_eq!(dm.square.block_dag, s.block_dag);
+}
+
+#[test]
+fn coarse_and_fine_parts() {
+    // Row 0 spans columns 0 and 1 (underdetermined); rows 3 and 4 both only use column 4
+    // (overdetermined); rows 1 and 2 form a 2x2 irreducible square block that row 0 and row
+    // 3 also touch.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 1, 0, 0,
+        0, 0, 1, 1, 0,
+        0, 0, 1, 1, 1,
+        0, 0, 0, 0, 1,
+        0, 0, 0, 0, 1,
+    ]);
+    let dm = dulmage_mendelsohn(&m);
+    assert_eq!(dm.under.rows, vec![0]);
+    assert_eq!(dm.under.cols, vec![0, 1]);
+    assert_eq!(dm.over.rows, vec![3, 4]);
+    assert_eq!(dm.over.cols, vec![4]);
+    assert_eq!(dm.square.block_sizes, vec![2]);
+    let (rows, cols) = dm.square.blocks()[0];
+    assert_eq!(rows, [1, 2]);
+    assert_eq!(cols.len(), 2);
+    assert_eq!(dm.structural_rank(), 4);
+    assert_eq!(dm.row_order(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(dm.col_order()[..2], [0, 1]);
+    assert_eq!(dm.col_order()[4], 4);
+}
+
+#[test]
+fn permuted_pattern_is_block_upper_triangular() {
+    let m = DMatrix::from_row_slice(5, 6, &[
+        0, 1, 0, 0, 1, 0,
+        1, 0, 0, 0, 0, 0,
+        1, 1, 0, 0, 0, 0,
+        0, 0, 1, 1, 0, 0,
+        0, 0, 0, 1, 0, 1,
+    ]);
+    let dm = dulmage_mendelsohn(&m);
+    // Part index of every row and column: under 0, square 1.., over last.
+    let nfine = dm.square.block_sizes.len();
+    let mut row_part = [0; 5];
+    let mut col_part = [0; 6];
+    for &i in &dm.over.rows {
+        row_part[i] = nfine + 1;
+    }
+    for &j in &dm.over.cols {
+        col_part[j] = nfine + 1;
+    }
+    for (b, (rows, cols)) in dm.square.blocks().into_iter().enumerate() {
+        for &i in rows {
+            row_part[i] = b + 1;
+        }
+        for &j in cols {
+            col_part[j] = b + 1;
+        }
+    }
+    for i in 0..5 {
+        for j in 0..6 {
+            if m[(i, j)] != 0 {
+                assert!(col_part[j] >= row_part[i], "entry ({i}, {j}) below the diagonal");
+            }
+        }
+    }
+    assert_eq!(dm.structural_rank(), 5);
+    assert_eq!(dm.under.cols, vec![2, 3, 5]);
+    assert_eq!(dm.square.rows.len(), 3);
+}
@@ -0,0 +1,89 @@
+use std::error::Error;
+
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::basis::repair_basis;
+use nalgebra_block_triangularization::csr::CsrPattern;
+use nalgebra_block_triangularization::dae::dae_structure;
+use nalgebra_block_triangularization::matching::try_hopcroft_karp;
+use nalgebra_block_triangularization::ordering::{
+    col_order_from_row_order_strict, topo_sort_with_tiebreak,
+};
+use nalgebra_block_triangularization::permutation::{parse_cycle_notation, validate_permutation};
+use nalgebra_block_triangularization::solve::SolveError;
+use nalgebra_block_triangularization::{BtfError, BtfResult};
+
+fn repaired_len(m: &DMatrix<u8>) -> BtfResult<usize> {
+    Ok(repair_basis(m, &[0, 1], &[])?.basis.len())
+}
+
+#[test]
+fn module_errors_convert_with_question_mark() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 0,
+    ]);
+    assert!(matches!(repaired_len(&m), Err(BtfError::Basis(_))));
+
+    let ordering = || -> BtfResult<Vec<usize>> {
+        Ok(col_order_from_row_order_strict(&[0, 0], &[None, None], 2)?)
+    };
+    assert!(matches!(ordering(), Err(BtfError::InvalidPermutation(_))));
+
+    let csr = || -> BtfResult<usize> {
+        Ok(CsrPattern::from_parts(1, 1, vec![0u64, 2], vec![0u32])?.nnz())
+    };
+    assert!(matches!(csr(), Err(BtfError::InvalidPattern(_))));
+
+    let order = || -> BtfResult<()> { Ok(validate_permutation(&[1, 1])?) };
+    assert!(matches!(order(), Err(BtfError::InvalidOrder(_))));
+
+    let parsed = || -> BtfResult<Vec<usize>> { Ok(parse_cycle_notation("(0 1", 2)?) };
+    assert!(matches!(parsed(), Err(BtfError::PermutationSyntax(_))));
+
+    let solve: BtfError = SolveError::SingularBlock { block: 1 }.into();
+    assert_eq!(solve.to_string(), "solve failed: diagonal block 1 is singular");
+}
+
+#[test]
+fn cycles_are_not_reported_as_invalid_permutations() {
+    let sorted = || -> BtfResult<Vec<usize>> {
+        Ok(topo_sort_with_tiebreak(&[vec![1], vec![0]], &[0, 1])?)
+    };
+    let err = sorted().unwrap_err();
+    assert!(matches!(&err, BtfError::NotAcyclic { nodes } if nodes.len() == 2));
+    assert_eq!(err.to_string(), "graph is not acyclic: 2 node(s) on or behind a cycle");
+}
+
+#[test]
+fn dae_shape_mismatch_is_a_dimension_mismatch() {
+    let vars = DMatrix::<u8>::zeros(2, 2);
+    let derivs = DMatrix::<u8>::zeros(2, 3);
+    let err: BtfError = dae_structure(&vars, &derivs).unwrap_err().into();
+    assert!(matches!(
+        err,
+        BtfError::DimensionMismatch { expected: (2, 2), found: (2, 3) }
+    ));
+    assert_eq!(err.to_string(), "dimension mismatch: expected 2 x 2, found 2 x 3");
+}
+
+#[test]
+fn errors_keep_their_source_message() {
+    let io: BtfError = std::io::Error::other("disk gone").into();
+    assert_eq!(io.to_string(), "I/O error: disk gone");
+    assert!(io.source().is_some());
+
+    assert_eq!(BtfError::Cancelled.to_string(), "analysis cancelled");
+    assert_eq!(
+        BtfError::StructurallySingular { rank: 2, n: 3 }.to_string(),
+        "structurally singular: structural rank 2 of 3"
+    );
+}
+
+#[test]
+fn malformed_adjacency_is_an_error() {
+    let err: BtfError = try_hopcroft_karp(&[vec![0], vec![1, 5]], 2).unwrap_err().into();
+    assert_eq!(
+        err.to_string(),
+        "invalid adjacency: row 1 lists column 5, out of range for 2 columns"
+    );
+}
@@ -0,0 +1,81 @@
+use nalgebra::{Matrix3, SMatrix};
+use nalgebra_block_triangularization::fixed::upper_block_triangular_structure_fixed;
+use nalgebra_block_triangularization::{
+    BtfOptions, MatchingAlgorithm, upper_block_triangular_structure_with_options,
+};
+
+fn mc21_options() -> BtfOptions {
+    BtfOptions {
+        matching: MatchingAlgorithm::Mc21,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn fixed_triangular() {
+    let m = Matrix3::new(
+        1, 0, 0,
+        1, 1, 0,
+        1, 1, 1,
+    );
+    let s = upper_block_triangular_structure_fixed(&m);
+    assert_eq!(s.block_sizes(), &[1, 1, 1]);
+    assert_eq!(s.matching_size, 3);
+    assert_eq!(s.to_structure().row_order, vec![2, 1, 0]);
+}
+
+#[test]
+fn fixed_matches_dynamic_analysis() {
+    let m = SMatrix::<u8, 5, 5>::from_row_slice(&[
+        1, 1, 0, 0, 1,
+        1, 1, 0, 0, 0,
+        0, 0, 1, 1, 0,
+        0, 0, 1, 1, 1,
+        0, 0, 0, 0, 1,
+    ]);
+    let fixed = upper_block_triangular_structure_fixed(&m).to_structure();
+    let dynamic = upper_block_triangular_structure_with_options(&m, &mc21_options());
+    assert_eq!(fixed.row_order, dynamic.row_order);
+    assert_eq!(fixed.col_order, dynamic.col_order);
+    assert_eq!(fixed.block_sizes, dynamic.block_sizes);
+    assert_eq!(fixed.block_dag, dynamic.block_dag);
+    assert_eq!(fixed.matching_size, dynamic.matching_size);
+}
+
+#[test]
+fn fixed_block_rows_and_dag_bits() {
+    let m = Matrix3::new(
+        1, 1, 1,
+        1, 1, 0,
+        0, 0, 1,
+    );
+    let s = upper_block_triangular_structure_fixed(&m);
+    assert_eq!(s.num_blocks, 2);
+    assert_eq!(s.block_rows(0), &[0, 1]);
+    assert_eq!(s.block_rows(1), &[2]);
+    assert_eq!(s.block_dag[0], 0b10);
+    assert_eq!(s.block_dag[1], 0);
+}
+
+#[test]
+fn fixed_singular_pattern() {
+    let m = Matrix3::new(
+        1, 0, 0,
+        1, 0, 0,
+        0, 0, 0,
+    );
+    let fixed = upper_block_triangular_structure_fixed(&m).to_structure();
+    let dynamic = upper_block_triangular_structure_with_options(&m, &mc21_options());
+    assert_eq!(fixed.matching_size, 1);
+    assert_eq!(fixed.row_order, dynamic.row_order);
+    assert_eq!(fixed.col_order, dynamic.col_order);
+    assert_eq!(fixed.block_dag, dynamic.block_dag);
+}
+
+#[test]
+fn fixed_largest_size() {
+    let m = SMatrix::<u8, 64, 64>::from_fn(|i, j| u8::from(j == i || j == (i + 1) % 64));
+    let s = upper_block_triangular_structure_fixed(&m);
+    assert_eq!(s.block_sizes(), &[64]);
+    assert_eq!(s.matching_size, 64);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::BtfOptions;
+use nalgebra_block_triangularization::fuzz::{
+    ArbitraryPattern, MAX_DIM, check_invariants, check_invariants_with_options,
+};
+
+#[test]
+fn arbitrary_pattern_respects_bounds() {
+    let bytes: Vec<u8> = (0..4096u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect();
+    let mut u = Unstructured::new(&bytes);
+    for _ in 0..20 {
+        let Ok(p) = ArbitraryPattern::arbitrary(&mut u) else {
+            break;
+        };
+        assert!(p.0.nrows() <= MAX_DIM && p.0.ncols() <= MAX_DIM);
+        assert!(p.0.iter().all(|&x| x <= 1));
+    }
+}
+
+#[test]
+fn invariants_hold_on_generated_patterns() {
+    for seed in 0..64u32 {
+        let bytes: Vec<u8> = (0..2048u32)
+            .map(|i| ((i ^ seed).wrapping_mul(0x9e37_79b9) >> 7) as u8)
+            .collect();
+        let p = ArbitraryPattern::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert_eq!(check_invariants(&p.0), Ok(()), "{}", p.0);
+    }
+}
+
+#[test]
+fn invariants_hold_on_edge_cases() {
+    assert_eq!(check_invariants(&DMatrix::zeros(0, 0)), Ok(()));
+    assert_eq!(check_invariants(&DMatrix::zeros(3, 0)), Ok(()));
+    assert_eq!(check_invariants(&DMatrix::zeros(0, 3)), Ok(()));
+    assert_eq!(check_invariants(&DMatrix::from_element(4, 2, 1)), Ok(()));
+}
+
+#[test]
+fn invariants_hold_with_arbitrary_options() {
+    for seed in 0..32u32 {
+        let bytes: Vec<u8> = (0..2048u32)
+            .map(|i| ((i ^ seed).wrapping_mul(0x85eb_ca6b) >> 9) as u8)
+            .collect();
+        let mut u = Unstructured::new(&bytes);
+        let options = BtfOptions::arbitrary(&mut u).unwrap();
+        let p = ArbitraryPattern::arbitrary(&mut u).unwrap();
+        assert_eq!(check_invariants_with_options(&p.0, &options), Ok(()), "{:?}", options);
+    }
+}
@@ -0,0 +1,74 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::dm::dulmage_mendelsohn;
+use nalgebra_block_triangularization::hall::{HallViolator, hall_violator};
+
+#[test]
+fn matchable_rows_have_no_violator() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    assert_eq!(hall_violator(&m), None);
+    // Wide patterns can match every row.
+    assert_eq!(hall_violator(&DMatrix::from_element(2, 3, 1u8)), None);
+    assert_eq!(hall_violator(&DMatrix::<u8>::zeros(0, 0)), None);
+}
+
+#[test]
+fn three_equations_in_two_variables() {
+    // Rows 0, 2 and 3 only mention columns 1 and 3.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        0, 1, 0, 1,
+        1, 1, 1, 0,
+        0, 1, 0, 0,
+        0, 0, 0, 1,
+    ]);
+    let v = hall_violator(&m).unwrap();
+    assert_eq!(v.rows, vec![0, 2, 3]);
+    assert_eq!(v.cols, vec![1, 3]);
+    assert_eq!(v.deficiency(), 1);
+    assert!(v.verify(&m));
+}
+
+#[test]
+fn empty_row_is_its_own_certificate() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 0,
+    ]);
+    let v = hall_violator(&m).unwrap();
+    assert_eq!(v, HallViolator { rows: vec![1], cols: vec![] });
+    assert!(v.verify(&m));
+}
+
+#[test]
+fn certificate_lies_in_the_overdetermined_part() {
+    let m = DMatrix::from_row_slice(5, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+        0, 1, 0,
+        0, 0, 1,
+    ]);
+    let v = hall_violator(&m).unwrap();
+    assert!(v.verify(&m));
+    let over = dulmage_mendelsohn(&m).over;
+    assert!(v.rows.iter().all(|i| over.rows.contains(i)));
+    assert!(v.cols.iter().all(|j| over.cols.contains(j)));
+}
+
+#[test]
+fn verify_rejects_wrong_certificates() {
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        1, 0,
+        0, 1,
+    ]);
+    assert!(HallViolator { rows: vec![0, 1], cols: vec![0] }.verify(&m));
+    // Too few rows, a missing column, unsorted or out-of-range rows.
+    assert!(!HallViolator { rows: vec![0], cols: vec![0] }.verify(&m));
+    assert!(!HallViolator { rows: vec![0, 1, 2], cols: vec![0] }.verify(&m));
+    assert!(!HallViolator { rows: vec![1, 0], cols: vec![0] }.verify(&m));
+    assert!(!HallViolator { rows: vec![0, 3], cols: vec![0] }.verify(&m));
+}
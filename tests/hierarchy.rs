@@ -0,0 +1,122 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::hierarchy::hierarchical_structure;
+use nalgebra_block_triangularization::options::InBlockOrder;
+use nalgebra_block_triangularization::permutation::is_valid_permutation;
+use nalgebra_block_triangularization::{BtfOptions, upper_block_triangular_structure};
+
+fn two_components() -> DMatrix<u8> {
+    // Component A: rows/cols {0, 2, 4}, lower triangular chain plus a 2-cycle between 2 and 4.
+    // Component B: rows/cols {1, 3}, a 2x2 irreducible block.
+    DMatrix::from_row_slice(5, 5, &[
+        1, 0, 0, 0, 0,
+        0, 1, 0, 1, 0,
+        1, 0, 1, 0, 1,
+        0, 1, 0, 1, 0,
+        0, 0, 1, 0, 1,
+    ])
+}
+
+#[test]
+fn components_are_laid_out_contiguously() {
+    let m = two_components();
+    let h = hierarchical_structure(&m, &BtfOptions::default()).unwrap();
+    assert!(is_valid_permutation(&h.row_order));
+    assert!(is_valid_permutation(&h.col_order));
+    assert_eq!(h.components.len(), 2);
+    assert_eq!(h.components[0].component.rows, vec![0, 2, 4]);
+    assert_eq!(h.components[1].component.rows, vec![1, 3]);
+    assert_eq!((h.components[1].row_offset, h.components[1].col_offset), (3, 3));
+
+    let mut sorted = h.row_order[..3].to_vec();
+    sorted.sort_unstable();
+    assert_eq!(sorted, vec![0, 2, 4]);
+    assert_eq!(h.components[0].structure.block_sizes, vec![2, 1]);
+    assert_eq!(h.components[0].block_rows(), vec![vec![2, 4], vec![0]]);
+    assert_eq!(h.components[1].block_rows(), vec![vec![1, 3]]);
+    assert_eq!(h.num_blocks(), 3);
+}
+
+#[test]
+fn composed_permutation_is_block_upper_triangular() {
+    let m = two_components();
+    let h = hierarchical_structure(&m, &BtfOptions::default()).unwrap();
+    let (pr, pc) = h.permutations();
+    let mut u = m.clone();
+    pr.permute_rows(&mut u);
+    pc.permute_columns(&mut u);
+
+    let sizes: Vec<usize> = h
+        .components
+        .iter()
+        .flat_map(|c| c.structure.block_sizes.iter().copied())
+        .collect();
+    let mut block_of = Vec::new();
+    for (b, &size) in sizes.iter().enumerate() {
+        block_of.extend(std::iter::repeat_n(b, size));
+    }
+    for i in 0..5 {
+        assert_ne!(u[(i, i)], 0);
+        for j in 0..5 {
+            if u[(i, j)] != 0 {
+                assert!(block_of[i] <= block_of[j], "entry ({i}, {j}) below the block diagonal");
+            }
+        }
+    }
+    assert_eq!(sizes.len(), upper_block_triangular_structure(&m).num_blocks());
+}
+
+#[test]
+fn min_degree_orders_inside_blocks_only() {
+    // Irreducible arrow pattern: row 0 couples to every column and every row to column 0.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 1, 1,
+        1, 1, 0, 0,
+        1, 0, 1, 0,
+        1, 0, 0, 1,
+    ]);
+    let options = BtfOptions {
+        in_block_order: InBlockOrder::MinDegree,
+        ..Default::default()
+    };
+    let h = hierarchical_structure(&m, &options).unwrap();
+    assert_eq!(h.components.len(), 1);
+    assert_eq!(h.components[0].structure.block_sizes, vec![4]);
+    // Eliminating the hub row first would fill the whole block; it is ordered next to last.
+    assert_eq!(h.row_order, vec![1, 2, 0, 3]);
+    assert_eq!(h.col_order, vec![1, 2, 0, 3]);
+}
+
+#[test]
+fn forbidden_entries_are_translated_per_component() {
+    // Two 2x2 full components; forbidding (3, 3) forces the anti-diagonal matching in the
+    // second one.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    let options = BtfOptions {
+        forbidden: vec![(3, 3)],
+        ..Default::default()
+    };
+    let h = hierarchical_structure(&m, &options).unwrap();
+    let second = &h.components[1].structure;
+    assert_eq!(second.row_to_col, vec![Some(1), Some(0)]);
+    let pos = h.row_order.iter().position(|&r| r == 3).unwrap();
+    assert_eq!(h.col_order[pos], 2);
+}
+
+#[test]
+fn empty_rows_and_columns_form_their_own_components() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        0, 0, 0,
+        1, 0, 0,
+    ]);
+    let h = hierarchical_structure(&m, &BtfOptions::default()).unwrap();
+    assert!(is_valid_permutation(&h.row_order));
+    assert!(is_valid_permutation(&h.col_order));
+    let matched: usize = h.components.iter().map(|c| c.structure.matching_size).sum();
+    assert_eq!(matched, 1);
+}
@@ -0,0 +1,84 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::labels::{LabeledStructure, analyze_labeled};
+use nalgebra_block_triangularization::nullspace::null_space_support;
+use nalgebra_block_triangularization::{BtfError, upper_block_triangular_structure};
+
+fn names(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn labels_follow_the_permutation() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 1, 0,
+        1, 1, 1,
+    ]);
+    let labeled = analyze_labeled(&m, names(&["f", "g", "h"]), names(&["x", "y", "z"])).unwrap();
+    assert_eq!(labeled.ordered_row_labels().collect::<Vec<_>>(), vec!["h", "g", "f"]);
+    assert_eq!(labeled.ordered_col_labels().collect::<Vec<_>>(), vec!["z", "y", "x"]);
+    let blocks = labeled.block_labels();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0], (vec![&"h".to_string()], vec![&"z".to_string()]));
+}
+
+#[test]
+fn labels_translate_diagnostics() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 0,
+    ]);
+    let labeled = analyze_labeled(&m, vec!["flow", "pressure"], vec!["q", "p"]).unwrap();
+    let ns = null_space_support(&m);
+    assert_eq!(labeled.rows(&ns.left), vec![&"pressure"]);
+    assert_eq!(labeled.cols(&ns.right), vec![&"q", &"p"]);
+    assert_eq!(labeled.entries(&[(1, 0)]), vec![(&"pressure", &"q")]);
+}
+
+#[test]
+fn label_count_mismatch_is_an_error() {
+    let m = DMatrix::<u8>::identity(2, 2);
+    let err = analyze_labeled(&m, vec!["a"], vec!["x", "y"]).unwrap_err();
+    assert!(matches!(
+        err,
+        BtfError::DimensionMismatch { expected: (2, 2), found: (1, 2) }
+    ));
+
+    let structure = upper_block_triangular_structure(&m);
+    assert!(LabeledStructure::new(structure, vec![1, 2], vec![3]).is_err());
+}
+
+#[test]
+fn report_names_equations_and_variables() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 1,
+        0, 0, 1,
+    ]);
+    let labeled = analyze_labeled(&m, names(&["f1", "f2", "f3"]), names(&["x1", "x2", "x3"])).unwrap();
+    assert_eq!(labeled.describe_block(0), "block 0 contains equations {f1, f2} and variables {x1, x2}");
+    assert_eq!(labeled.describe_block(1), "block 1 contains equations {f3} and variables {x3}");
+    assert_eq!(
+        labeled.to_string(),
+        "block 0 contains equations {f1, f2} and variables {x1, x2}\n\
+         block 1 contains equations {f3} and variables {x3}"
+    );
+}
+
+#[test]
+fn report_lists_unmatched_equations_and_variables() {
+    // f2 repeats f1's only variable; y appears in no equation.
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        1, 0,
+    ]);
+    let labeled = analyze_labeled(&m, vec!["f1", "f2"], vec!["x", "y"]).unwrap();
+    let unmatched = labeled.structure.unmatched_rows()[0];
+    let text = labeled.to_string();
+    assert!(text.contains(&format!("contains equations {{{}}} and no variables", labeled.row_labels[unmatched])));
+    assert!(text.contains(&format!("unmatched equations {{{}}}", labeled.row_labels[unmatched])));
+    assert!(text.ends_with("unmatched variables {y}"));
+    // Block columns follow the matching, so the unmatched row's block has none.
+    let blocks = labeled.block_labels();
+    assert_eq!(blocks.iter().map(|(_, cols)| cols.len()).sum::<usize>(), 1);
+}
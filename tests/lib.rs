@@ -1,6 +1,12 @@
 use nalgebra::{DMatrix, Dyn, PermutationSequence, Scalar};
+use nalgebra_block_triangularization::ordering::OrderingError;
 use nalgebra_block_triangularization::{
-    upper_block_triangular_structure, upper_triangular_permutations,
+    BtfError, UpperBtfStructure,
+    BlockOrder, BtfOptions, InBlockOrder, MatchingAlgorithm, Pin, UnmatchedPlacement,
+    upper_block_triangular_structure_with_options,
+    condensation, has_strong_hall_property, is_irreducible, is_structurally_nonsingular, row_dependency_graph,
+    try_upper_block_triangular_structure, try_upper_block_triangular_structure_with_options,
+    try_upper_triangular_permutations, upper_block_triangular_structure, upper_triangular_permutations,
 };
 
 fn apply_perms<T: Scalar + Copy>(
@@ -340,3 +346,949 @@ fn permutations_are_invertible() {
     assert_eq!(structure.row_order.len(), 4);
     assert_eq!(structure.col_order.len(), 4);
 }
+
+#[test]
+fn row_dependency_graph_follows_matching() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    let g = row_dependency_graph(&m);
+    assert_eq!(g, vec![vec![1], vec![2], vec![]]);
+}
+
+#[test]
+fn row_dependency_graph_sccs_are_blocks() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    let g = row_dependency_graph(&m);
+    let sccs = nalgebra_block_triangularization::scc::tarjan_scc(&g);
+    let structure = upper_block_triangular_structure(&m);
+    assert_eq!(sccs.len(), structure.block_sizes.len());
+    assert!(g.iter().enumerate().all(|(i, out)| !out.contains(&i)));
+}
+
+#[test]
+fn row_dependency_graph_empty() {
+    let m: DMatrix<u8> = DMatrix::zeros(0, 3);
+    assert!(row_dependency_graph(&m).is_empty());
+}
+
+#[test]
+fn condensation_matches_structure() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    let c = condensation(&m);
+    assert_eq!(c.sccs.len(), 2);
+    assert_eq!(c.comp_of.len(), 4);
+    assert_eq!(c.comp_of[0], c.comp_of[1]);
+    assert_eq!(c.comp_of[2], c.comp_of[3]);
+    // Rows 2,3 touch column 1 (matched into block {0,1}).
+    assert_eq!(c.dag[c.comp_of[2]], vec![c.comp_of[0]]);
+    assert!(c.dag[c.comp_of[0]].is_empty());
+}
+
+#[test]
+fn try_variants_agree_with_infallible() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let tried = try_upper_block_triangular_structure(&m).unwrap();
+    assert_eq!(tried.row_order, structure.row_order);
+    assert_eq!(tried.col_order, structure.col_order);
+    assert_eq!(tried.block_sizes, structure.block_sizes);
+
+    let (pr, pc) = try_upper_triangular_permutations(&m).unwrap();
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &structure.block_sizes));
+}
+
+#[test]
+fn structure_accessors() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 1,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.num_blocks(), 3);
+    assert_eq!(s.largest_block_size(), 2);
+    assert_eq!(s.singleton_block_count(), 2);
+    assert!(!s.is_fully_triangular());
+
+    let t = upper_block_triangular_structure(&DMatrix::<u8>::identity(3, 3));
+    assert_eq!(t.num_blocks(), 3);
+    assert_eq!(t.largest_block_size(), 1);
+    assert_eq!(t.singleton_block_count(), 3);
+    assert!(t.is_fully_triangular());
+}
+
+#[test]
+fn structure_accessors_empty() {
+    let s = upper_block_triangular_structure(&DMatrix::<u8>::zeros(0, 0));
+    assert_eq!(s.num_blocks(), 0);
+    assert_eq!(s.largest_block_size(), 0);
+    assert_eq!(s.singleton_block_count(), 0);
+    assert!(s.is_fully_triangular());
+}
+
+#[test]
+fn block_pair_of_classifies_entries() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 1,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let rb = s.row_blocks();
+    assert_eq!(s.block_pair_of(0, 1), (rb[0], rb[1]));
+    assert_eq!(rb[0], rb[1]);
+    let (a, b) = s.block_pair_of(1, 3);
+    assert!(a < b);
+
+    let pairs = s.block_pairs(&m);
+    assert_eq!(pairs.len(), 8);
+    for &((i, j), pair) in &pairs {
+        assert_eq!(pair, s.block_pair_of(i, j));
+        assert!(pair.0 <= pair.1);
+    }
+    let intra = pairs.iter().filter(|(_, (a, b))| a == b).count();
+    assert_eq!(intra, 6);
+}
+
+#[test]
+fn block_pair_of_trailing_column() {
+    // Column 2 stays unmatched and is appended after every block.
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 1,
+        0, 1, 0,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.block_pair_of(0, 2), (s.row_blocks()[0], s.num_blocks()));
+}
+
+#[test]
+fn diagonal_block_mask_splits_matrix() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 1,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let mask = s.diagonal_block_mask();
+    assert_eq!(mask.shape(), (4, 4));
+    // Block {0,1} plus the two singleton diagonals.
+    assert_eq!(mask.iter().filter(|&&b| b).count(), 4 + 1 + 1);
+    assert!(mask[(0, 1)] && mask[(1, 0)] && mask[(2, 2)] && mask[(3, 3)]);
+    assert!(!mask[(1, 3)] && !mask[(2, 3)]);
+
+    let diag = m.zip_map(&mask, |x, keep| if keep { x } else { 0 });
+    let coupling = &m - &diag;
+    assert_eq!(diag.iter().filter(|&&x| x != 0).count(), 6);
+    assert_eq!(coupling.iter().filter(|&&x| x != 0).count(), 2);
+}
+
+#[test]
+fn diagonal_block_mask_empty() {
+    let s = upper_block_triangular_structure(&DMatrix::<u8>::zeros(0, 2));
+    assert_eq!(s.diagonal_block_mask().shape(), (0, 2));
+}
+
+#[test]
+fn tall_structure_separates_row_and_col_block_sizes() {
+    // Rows 0 and 1 compete for column 0; one of them stays unmatched.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        1, 0,
+        0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.block_row_sizes(), &s.block_sizes[..]);
+    assert_eq!(s.block_row_sizes().iter().sum::<usize>(), 3);
+    assert_eq!(s.block_col_sizes().iter().sum::<usize>(), 2);
+    assert_eq!(s.block_col_sizes().iter().filter(|&&n| n == 0).count(), 1);
+    let unmatched = s.unmatched_rows();
+    assert_eq!(unmatched.len(), 1);
+    assert!(unmatched[0] < 2);
+    assert!(s.unmatched_cols().is_empty());
+}
+
+#[test]
+fn wide_structure_lists_unmatched_columns() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 1, 1,
+        0, 1, 0,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.block_col_sizes(), s.block_sizes);
+    assert!(s.unmatched_rows().is_empty());
+    assert_eq!(s.unmatched_cols().len(), 1);
+    assert_eq!(s.col_blocks()[s.unmatched_cols()[0]], None);
+}
+
+#[test]
+fn col_blocks_follow_the_matching_under_every_placement() {
+    let m = DMatrix::from_row_slice(3, 4, &[
+        1, 0, 0, 1,
+        1, 0, 0, 0,
+        0, 1, 1, 0,
+    ]);
+    for unmatched in [UnmatchedPlacement::End, UnmatchedPlacement::Start, UnmatchedPlacement::Adjacent] {
+        let options = BtfOptions { unmatched, ..Default::default() };
+        let s = upper_block_triangular_structure_with_options(&m, &options);
+        let (rb, cb) = (s.row_blocks(), s.col_blocks());
+        for (r, c) in s.matched_pairs() {
+            assert_eq!(cb[c], Some(rb[r]));
+        }
+        assert_eq!(s.unmatched_cols().len(), 1);
+        assert!(s.unmatched_cols().iter().all(|&c| cb[c].is_none()));
+        let sizes = s.block_col_sizes();
+        for (b, &n) in sizes.iter().enumerate() {
+            assert_eq!(cb.iter().filter(|&&x| x == Some(b)).count(), n);
+        }
+    }
+}
+
+/// Largest |row position - col position| over nonzeros inside diagonal blocks of P A Q.
+fn in_block_bandwidth(m: &DMatrix<u8>, s: &UpperBtfStructure) -> usize {
+    let mut row_pos = vec![0; m.nrows()];
+    let mut col_pos = vec![0; m.ncols()];
+    for (p, &r) in s.row_order.iter().enumerate() {
+        row_pos[r] = p;
+    }
+    for (p, &c) in s.col_order.iter().enumerate() {
+        col_pos[c] = p;
+    }
+    let mask = s.diagonal_block_mask();
+    let mut bw = 0;
+    for i in 0..m.nrows() {
+        for j in 0..m.ncols() {
+            if m[(i, j)] != 0 && mask[(i, j)] {
+                bw = bw.max(row_pos[i].abs_diff(col_pos[j]));
+            }
+        }
+    }
+    bw
+}
+
+#[test]
+fn min_bandwidth_in_block_order() {
+    // Irreducible tridiagonal system on the scrambled path 0 - 3 - 1 - 4 - 2.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 0, 0, 1, 0,
+        0, 1, 0, 1, 1,
+        0, 0, 1, 0, 1,
+        1, 1, 0, 1, 0,
+        0, 1, 1, 0, 1,
+    ]);
+    let default = upper_block_triangular_structure(&m);
+    let options = BtfOptions {
+        in_block_order: InBlockOrder::MinBandwidth,
+        ..Default::default()
+    };
+    let banded = upper_block_triangular_structure_with_options(&m, &options);
+
+    assert_eq!(banded.block_sizes, vec![5]);
+    assert_eq!(banded.block_sizes, default.block_sizes);
+    assert_eq!(banded.row_order, vec![0, 3, 1, 4, 2]);
+    assert!(in_block_bandwidth(&m, &banded) < in_block_bandwidth(&m, &default));
+}
+
+#[test]
+fn min_bandwidth_preserves_block_structure() {
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1, 0, 1, 0, 0, 1,
+        0, 1, 0, 1, 0, 0,
+        1, 0, 1, 0, 0, 0,
+        0, 1, 0, 1, 1, 0,
+        0, 0, 0, 0, 1, 0,
+        0, 0, 0, 0, 1, 1,
+    ]);
+    let default = upper_block_triangular_structure(&m);
+    let options = BtfOptions {
+        in_block_order: InBlockOrder::MinBandwidth,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.block_sizes, default.block_sizes);
+    assert_eq!(s.row_blocks(), default.row_blocks());
+    assert_eq!(s.block_dag, default.block_dag);
+
+    let (pr, pc) = (
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.row_order),
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.col_order),
+    );
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &s.block_sizes));
+    for k in 0..6 {
+        assert_ne!(u[(k, k)], 0);
+    }
+}
+
+#[test]
+fn matched_column_in_block_order() {
+    // One irreducible block with rows listed out of column order.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        0, 1, 1,
+        1, 0, 1,
+        1, 1, 0,
+    ]);
+    let options = BtfOptions {
+        in_block_order: InBlockOrder::MatchedColumn,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.block_sizes, vec![3]);
+    assert_eq!(s.col_order, vec![0, 1, 2]);
+    for k in 0..3 {
+        assert_ne!(m[(s.row_order[k], s.col_order[k])], 0);
+    }
+    assert_eq!(s.row_blocks(), upper_block_triangular_structure(&m).row_blocks());
+}
+
+#[test]
+fn forbidden_entries_are_not_matched() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 1,
+        0, 1, 1,
+    ]);
+    let options = BtfOptions {
+        forbidden: vec![(0, 0), (1, 1)],
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.matching_size, 3);
+    for k in 0..3 {
+        let (r, c) = (s.row_order[k], s.col_order[k]);
+        assert_ne!(m[(r, c)], 0);
+        assert!(!options.forbidden.contains(&(r, c)));
+    }
+    let (pr, pc) = (
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.row_order),
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.col_order),
+    );
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &s.block_sizes));
+}
+
+#[test]
+fn forbidden_entries_still_couple_blocks() {
+    // Forbidding the only pivot of row 0 makes the pattern structurally singular, but
+    // (0, 0) is still a nonzero: column 0 is matched to row 1, so row 0 must come first.
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        1, 1,
+    ]);
+    let options = BtfOptions {
+        forbidden: vec![(0, 0), (7, 7)],
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.matching_size, 1);
+    assert_eq!(s.row_order, vec![0, 1]);
+    assert_eq!(s.col_order, vec![0, 1]);
+    assert_eq!(s.block_dag, vec![vec![1], vec![]]);
+}
+
+#[test]
+fn mc21_backend_produces_valid_structure() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 0, 0, 1,
+        0, 0, 1, 1,
+        0, 1, 0, 1,
+    ]);
+    let options = BtfOptions {
+        matching: MatchingAlgorithm::Mc21,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    let default = upper_block_triangular_structure(&m);
+    assert_eq!(s.matching_size, default.matching_size);
+    // The block partition does not depend on which perfect matching is used.
+    assert_eq!(s.block_sizes, default.block_sizes);
+    let (pr, pc) = (
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.row_order),
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.col_order),
+    );
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &s.block_sizes));
+}
+
+#[test]
+fn min_coupling_block_order() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 0, 0, 1,
+        0, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ]);
+    let default = upper_block_triangular_structure(&m);
+    assert_eq!(default.row_order, vec![0, 1, 2, 3]);
+
+    let options = BtfOptions {
+        block_order: BlockOrder::MinCoupling,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    // Both couplings end up directly above the diagonal.
+    for &((i, j), (bi, bj)) in &s.block_pairs(&m) {
+        assert!(bj == bi || bj == bi + 1, "entry ({i}, {j}) far from the diagonal");
+    }
+    let (pr, pc) = (
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.row_order),
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.col_order),
+    );
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &s.block_sizes));
+}
+
+#[test]
+fn size_block_orders_respect_the_dag() {
+    // Blocks {0, 1}, {2, 3}, {4}, {5}; the entry (2, 4) forces {2, 3} before {4}.
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1, 1, 0, 0, 0, 0,
+        1, 1, 0, 0, 0, 0,
+        0, 0, 1, 1, 1, 0,
+        0, 0, 1, 1, 0, 0,
+        0, 0, 0, 0, 1, 0,
+        0, 0, 0, 0, 0, 1,
+    ]);
+    assert_eq!(upper_block_triangular_structure(&m).row_order, vec![0, 1, 2, 3, 4, 5]);
+
+    let options = BtfOptions {
+        block_order: BlockOrder::SmallestFirst,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    // {5} is free to go first; {4} is as small but must wait for {2, 3}.
+    assert_eq!(s.row_order, vec![5, 0, 1, 2, 3, 4]);
+    assert_eq!(s.block_sizes, vec![1, 2, 2, 1]);
+    let (pr, pc) = (
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.row_order),
+        nalgebra_block_triangularization::permutation::permutation_sequence_from_order(&s.col_order),
+    );
+    let u = apply_perms(m.clone(), &pr, &pc);
+    assert!(is_upper_block_triangular_u8(&u, &s.block_sizes));
+}
+
+#[test]
+fn largest_first_groups_big_blocks_early() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 0, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 1,
+        0, 1, 0, 1,
+    ]);
+    assert_eq!(upper_block_triangular_structure(&m).block_sizes, vec![1, 3]);
+    let options = BtfOptions {
+        block_order: BlockOrder::LargestFirst,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.block_sizes, vec![3, 1]);
+    assert_eq!(s.row_order, vec![1, 2, 3, 0]);
+}
+
+#[test]
+fn min_displacement_keeps_rows_near_their_positions() {
+    // Cycle 0 -> 4 -> 5 -> 0 plus independent singletons 1, 2, 3.
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1, 0, 0, 0, 1, 0,
+        0, 1, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 0,
+        0, 0, 0, 1, 0, 0,
+        0, 0, 0, 0, 1, 1,
+        1, 0, 0, 0, 0, 1,
+    ]);
+    let displacement = |order: &[usize]| -> usize {
+        order.iter().enumerate().map(|(p, &r)| p.abs_diff(r)).sum()
+    };
+    let default = upper_block_triangular_structure(&m);
+    assert_eq!(default.row_order, vec![0, 4, 5, 1, 2, 3]);
+
+    let options = BtfOptions {
+        block_order: BlockOrder::MinDisplacement,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    // The cycle's mean index is 3: it goes after rows 1 and 2 and ties with row 3.
+    assert_eq!(s.row_order, vec![1, 2, 0, 4, 5, 3]);
+    assert_eq!(s.col_order, s.row_order);
+    assert_eq!(displacement(&s.row_order), 8);
+    assert_eq!(displacement(&default.row_order), 12);
+    assert_eq!(s.block_sizes, vec![1, 1, 3, 1]);
+}
+
+#[test]
+fn min_displacement_is_identity_on_triangular_patterns() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 1,
+        0, 1, 1, 0,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let options = BtfOptions {
+        block_order: BlockOrder::MinDisplacement,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.row_order, vec![0, 1, 2, 3]);
+    assert_eq!(s.col_order, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn block_ids_are_stable_under_reordering() {
+    // Blocks {0, 1}, {2, 3}, {4}, {5}; see `size_block_orders_respect_the_dag`.
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1, 1, 0, 0, 0, 0,
+        1, 1, 0, 0, 0, 0,
+        0, 0, 1, 1, 1, 0,
+        0, 0, 1, 1, 0, 0,
+        0, 0, 0, 0, 1, 0,
+        0, 0, 0, 0, 0, 1,
+    ]);
+    let default = upper_block_triangular_structure(&m);
+    assert_eq!(default.block_ids(), vec![0, 2, 4, 5]);
+
+    let options = BtfOptions {
+        block_order: BlockOrder::SmallestFirst,
+        ..Default::default()
+    };
+    let reordered = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(reordered.block_ids(), vec![5, 0, 2, 4]);
+    let lookup = reordered.block_id_lookup();
+    assert_eq!(lookup.len(), 4);
+    let (rows, _) = default.solve_order_blocks();
+    let (moved, _) = reordered.solve_order_blocks();
+    for (b, id) in default.block_ids().into_iter().enumerate() {
+        assert_eq!(moved[lookup[&id]], rows[b]);
+    }
+    assert_eq!(lookup.get(&1), None);
+}
+
+#[test]
+fn priorities_steer_the_matching() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 1,
+    ]);
+    let options = BtfOptions {
+        priorities: vec![(0, 1, 3), (1, 0, 3)],
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.row_order, vec![0, 1]);
+    assert_eq!(s.col_order, vec![1, 0]);
+    assert_eq!(upper_block_triangular_structure(&m).col_order, vec![0, 1]);
+}
+
+#[test]
+fn structure_exposes_its_matching() {
+    // Rows 1 and 2 compete for column 0; column 2 is unreachable.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 0,
+        1, 0, 0,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.row_to_col.iter().flatten().count(), s.matching_size);
+    assert_eq!(s.row_to_col[0], Some(1));
+    let col_to_row = s.col_to_row();
+    assert_eq!(col_to_row[2], None);
+    for (r, c) in s.matched_pairs() {
+        assert_eq!(col_to_row[c], Some(r));
+        assert_ne!(m[(r, c)], 0);
+    }
+    // Matched pairs sit on the diagonal of the permuted matrix, in solve order.
+    let pairs = s.matched_pairs();
+    assert_eq!(pairs.len(), 2);
+    let pos = |order: &[usize], k: usize| order.iter().position(|&x| x == k).unwrap();
+    for (d, &(r, c)) in pairs.iter().enumerate() {
+        assert_eq!(pos(&s.col_order, c), d);
+        assert!(pos(&s.row_order, r) >= d);
+    }
+}
+
+#[test]
+fn unmatched_placement_moves_singular_parts() {
+    // Forbidding row 0's entries leaves it and column 2 unmatched; row 0 couples to row 2 via
+    // column 1.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        0, 1, 1,
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let with = |unmatched| {
+        let options = BtfOptions {
+            forbidden: vec![(0, 1), (0, 2)],
+            unmatched,
+            ..Default::default()
+        };
+        let s = upper_block_triangular_structure_with_options(&m, &options);
+        assert_eq!(s.matching_size, 2);
+        assert_eq!(s.block_sizes, vec![1, 1, 1]);
+        (s.row_order, s.col_order)
+    };
+    assert_eq!(with(UnmatchedPlacement::End), (vec![0, 1, 2], vec![0, 1, 2]));
+    assert_eq!(with(UnmatchedPlacement::Start), (vec![0, 1, 2], vec![2, 0, 1]));
+    // Row 0 right before row 2's block; column 2 right after row 0's block.
+    assert_eq!(with(UnmatchedPlacement::Adjacent), (vec![1, 0, 2], vec![0, 2, 1]));
+}
+
+#[test]
+fn adjacent_placement_sends_uncoupled_parts_last() {
+    // Row 2 and column 2 are empty.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 0,
+        0, 0, 0,
+    ]);
+    let options = BtfOptions {
+        unmatched: UnmatchedPlacement::Adjacent,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.row_order, vec![0, 1, 2]);
+    assert_eq!(s.col_order, vec![0, 1, 2]);
+    let start = BtfOptions {
+        unmatched: UnmatchedPlacement::Start,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &start);
+    assert_eq!(s.row_order, vec![2, 0, 1]);
+    assert_eq!(s.col_order, vec![2, 0, 1]);
+}
+
+#[test]
+fn gemv_visits_only_nonempty_blocks() {
+    // Blocks {0,1} and {2} with a coupling from the first to the second.
+    let a = DMatrix::from_row_slice(3, 3, &[
+        2.0, 1.0, 0.5,
+        1.0, 3.0, 0.0,
+        0.0, 0.0, 4.0,
+    ]);
+    let s = upper_block_triangular_structure(&a);
+    assert_eq!(s.num_blocks(), 2);
+    let x = nalgebra::DVector::from_vec(vec![1.0, -1.0, 2.0]);
+    assert_eq!(s.gemv(&a, &x), &a * &x);
+
+    // Entries outside the analyzed pattern are not visited.
+    let mut b = a.clone();
+    b[(2, 0)] = 10.0;
+    assert_eq!(s.gemv(&b, &x), &a * &x);
+}
+
+#[test]
+fn solve_order_blocks_lists_original_indices() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 1, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let (rows, cols) = s.solve_order_blocks();
+    assert_eq!(rows.len(), s.num_blocks());
+    let blocks: Vec<(Vec<usize>, Vec<usize>)> = rows.into_iter().zip(cols).collect();
+    assert_eq!(blocks, s.block_indices());
+    assert_eq!(blocks.iter().map(|(r, _)| r.len()).collect::<Vec<_>>(), s.block_sizes);
+
+    // Singular and tall: the zero row is a block without columns, and the columns stay
+    // aligned with their matched rows.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        0, 0,
+        1, 1,
+        0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let (rows, cols) = s.solve_order_blocks();
+    assert_eq!(rows.concat(), s.row_order);
+    for (r, c) in rows.iter().zip(&cols) {
+        let matched: Vec<usize> = r.iter().filter_map(|&i| s.row_to_col[i]).collect();
+        assert_eq!(c, &matched);
+    }
+    assert!(rows.iter().zip(&cols).any(|(r, c)| r == &vec![0] && c.is_empty()));
+    assert_eq!(cols.concat().len(), 2);
+}
+
+#[test]
+fn min_block_size_merges_consecutive_blocks() {
+    // Upper bidiagonal: six 1x1 blocks.
+    let m = DMatrix::from_fn(6, 6, |i, j| u8::from(j == i || j == i + 1));
+    let plain = upper_block_triangular_structure(&m);
+    assert_eq!(plain.block_sizes, vec![1; 6]);
+
+    for (min, expected) in [(0, vec![1; 6]), (1, vec![1; 6]), (2, vec![2, 2, 2]), (4, vec![6]), (7, vec![6])] {
+        let options = BtfOptions { min_block_size: min, ..Default::default() };
+        let s = upper_block_triangular_structure_with_options(&m, &options);
+        assert_eq!(s.block_sizes, expected, "min_block_size {min}");
+        assert_eq!(s.row_order, plain.row_order);
+        let (pr, pc) = try_upper_triangular_permutations(&m).unwrap();
+        assert!(is_upper_block_triangular_u8(&apply_perms(m.clone(), &pr, &pc), &s.block_sizes));
+        assert_eq!(s.block_dag.len(), s.num_blocks());
+        for (b, out) in s.block_dag.iter().enumerate() {
+            assert!(out.iter().all(|&c| c > b));
+        }
+    }
+
+    // A block already large enough closes its group; a short tail joins the last group.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 0, 0,
+        0, 1, 1, 0, 0,
+        0, 1, 1, 1, 0,
+        0, 0, 0, 1, 1,
+        0, 0, 0, 0, 1,
+    ]);
+    assert_eq!(upper_block_triangular_structure(&m).block_sizes, vec![1, 2, 1, 1]);
+    let options = BtfOptions { min_block_size: 3, ..Default::default() };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.block_sizes, vec![5]);
+    let options = BtfOptions { min_block_size: 2, ..Default::default() };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.block_sizes, vec![3, 2]);
+    assert_eq!(s.block_dag, vec![vec![1], vec![]]);
+}
+
+#[test]
+fn structural_predicates() {
+    let cycle = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    assert!(is_structurally_nonsingular(&cycle));
+    assert!(is_irreducible(&cycle));
+
+    // Nonsingular but reducible: upper triangular.
+    let upper = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    assert!(is_structurally_nonsingular(&upper));
+    assert!(!is_irreducible(&upper));
+
+    // Every row and column has two entries, but two 2x2 blocks only touch one way.
+    let two_blocks = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    assert!(is_structurally_nonsingular(&two_blocks));
+    assert!(!is_irreducible(&two_blocks));
+
+    // Full rows and columns, yet structurally singular (columns 1 and 2 share row 0 only).
+    let singular = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        1, 0, 0,
+        1, 0, 0,
+    ]);
+    assert!(!is_structurally_nonsingular(&singular));
+    assert!(!is_irreducible(&singular));
+
+    assert!(is_irreducible(&DMatrix::from_element(1, 1, 1u8)));
+    assert!(!is_irreducible(&DMatrix::from_element(1, 1, 0u8)));
+    assert!(!is_structurally_nonsingular(&DMatrix::from_element(2, 3, 1u8)));
+    assert!(!is_irreducible(&DMatrix::from_element(2, 3, 1u8)));
+    assert!(is_structurally_nonsingular(&DMatrix::<u8>::zeros(0, 0)));
+    assert!(!is_irreducible(&DMatrix::<u8>::zeros(0, 0)));
+}
+
+#[test]
+fn coupling_col_order_covers_the_coupling_region() {
+    // Upper triangular: column 4 couples to every earlier row, column 0 to none.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 0, 1,
+        0, 1, 1, 0, 1,
+        0, 0, 1, 1, 1,
+        0, 0, 0, 1, 1,
+        0, 0, 0, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.coupling_col_order(&m), vec![1, 2, 3, 4]);
+
+    // Irreducible: no coupling at all.
+    let cycle = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&cycle);
+    assert!(s.coupling_col_order(&cycle).is_empty());
+}
+
+#[test]
+fn strong_hall_property() {
+    // Tall and connected: every k < 3 columns reach k + 1 rows.
+    let tall = DMatrix::from_row_slice(4, 3, &[
+        1, 0, 0,
+        1, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    assert!(has_strong_hall_property(&tall));
+
+    // Column 2 alone reaches only row 3.
+    let tight = DMatrix::from_row_slice(4, 3, &[
+        1, 1, 0,
+        1, 1, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    assert!(!has_strong_hall_property(&tight));
+
+    // Two column blocks with their own surplus rows: columns {0} reach rows {0, 1} only but
+    // columns {1, 2} reach rows {2, 3, 4}, so the property holds without any coupling.
+    let split = DMatrix::from_row_slice(5, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    assert!(has_strong_hall_property(&split));
+
+    // Square: the property is irreducibility.
+    let cycle = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    assert!(has_strong_hall_property(&cycle));
+    assert!(!has_strong_hall_property(&DMatrix::<u8>::identity(3, 3)));
+
+    assert!(!has_strong_hall_property(&DMatrix::from_element(2, 3, 1u8)));
+    assert!(!has_strong_hall_property(&DMatrix::<u8>::zeros(3, 1)));
+    assert!(has_strong_hall_property(&DMatrix::<u8>::zeros(3, 0)));
+}
+
+#[test]
+fn adjacent_coupling_clusters_coupled_blocks() {
+    // Four singleton blocks; only rows 0 and 3 are coupled.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 0, 0, 1,
+        0, 1, 0, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ]);
+    assert_eq!(upper_block_triangular_structure(&m).row_order, vec![0, 1, 2, 3]);
+    let options = BtfOptions {
+        block_order: BlockOrder::AdjacentCoupling,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    assert_eq!(s.row_order, vec![0, 3, 1, 2]);
+    assert_eq!(s.col_order, vec![0, 3, 1, 2]);
+    assert_eq!(s.block_dag, vec![vec![1], vec![], vec![], vec![]]);
+}
+
+fn diagonal_with_coupling() -> DMatrix<u8> {
+    // Singleton blocks; the entry (0, 3) forces {0} before {3}.
+    DMatrix::from_row_slice(4, 4, &[
+        1, 0, 0, 1,
+        0, 1, 0, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ])
+}
+
+#[test]
+fn pinned_blocks_lead_and_trail() {
+    let m = diagonal_with_coupling();
+    let first = BtfOptions {
+        pin_first: vec![Pin::Row(2)],
+        ..Default::default()
+    };
+    let s = try_upper_block_triangular_structure_with_options(&m, &first).unwrap();
+    assert_eq!(s.row_order, vec![2, 0, 1, 3]);
+    assert_eq!(s.col_order, vec![2, 0, 1, 3]);
+
+    // Pinned blocks keep their relative order among themselves.
+    let last = BtfOptions {
+        pin_last: vec![Pin::Col(3), Pin::Row(1), Pin::Row(9)],
+        ..Default::default()
+    };
+    let s = try_upper_block_triangular_structure_with_options(&m, &last).unwrap();
+    assert_eq!(s.row_order, vec![0, 2, 1, 3]);
+}
+
+#[test]
+fn pins_against_the_block_order_are_rejected() {
+    let m = diagonal_with_coupling();
+    let options = BtfOptions {
+        pin_last: vec![Pin::Col(0)],
+        ..Default::default()
+    };
+    assert_eq!(
+        try_upper_block_triangular_structure_with_options(&m, &options).unwrap_err(),
+        OrderingError::PinsAgainstOrder { before: 0, after: 3 }
+    );
+
+    let options = BtfOptions {
+        pin_first: vec![Pin::Row(1)],
+        pin_last: vec![Pin::Col(1)],
+        ..Default::default()
+    };
+    assert_eq!(
+        try_upper_block_triangular_structure_with_options(&m, &options).unwrap_err(),
+        OrderingError::ConflictingPins { block: 1 }
+    );
+}
+
+#[test]
+fn pinned_unmatched_column_is_rejected() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let options = BtfOptions {
+        pin_first: vec![Pin::Col(2)],
+        ..Default::default()
+    };
+    assert_eq!(
+        try_upper_block_triangular_structure_with_options(&m, &options).unwrap_err(),
+        OrderingError::PinnedUnmatchedColumn { col: 2 }
+    );
+}
+
+#[test]
+fn pin_errors_convert_to_invalid_pins() {
+    let m = diagonal_with_coupling();
+    let options = BtfOptions {
+        pin_last: vec![Pin::Col(0)],
+        ..Default::default()
+    };
+    let err: BtfError = try_upper_block_triangular_structure_with_options(&m, &options)
+        .unwrap_err()
+        .into();
+    assert!(matches!(err, BtfError::InvalidPins(OrderingError::PinsAgainstOrder { .. })));
+}
+
+#[test]
+#[should_panic(expected = "pins in BtfOptions cannot be honored")]
+fn infallible_analysis_panics_on_pins_it_cannot_honor() {
+    let options = BtfOptions {
+        pin_first: vec![Pin::Row(1)],
+        pin_last: vec![Pin::Col(1)],
+        ..Default::default()
+    };
+    upper_block_triangular_structure_with_options(&diagonal_with_coupling(), &options);
+}
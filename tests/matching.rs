@@ -1,4 +1,10 @@
-use nalgebra_block_triangularization::matching::hopcroft_karp;
+use std::time::Duration;
+
+use nalgebra_block_triangularization::matching::{
+    MatchingBudget, MatchingError, VertexSet, enumerate_maximum_matchings, hopcroft_karp,
+    hopcroft_karp_budgeted, hopcroft_karp_unchecked, maximum_independent_set, mc21,
+    minimum_vertex_cover, priority_matching, try_hopcroft_karp, validate_adjacency,
+};
 
 #[test]
 fn matching_empty_graph() {
@@ -190,3 +196,201 @@ fn matching_hall_violation() {
     // Can only match 2 out of 3 rows
     assert_eq!(matching.size, 2);
 }
+
+#[test]
+fn mc21_cheap_assignment_takes_first_free_column() {
+    let adj = vec![vec![0, 1, 2], vec![0, 1, 2], vec![0, 1, 2]];
+    let m = mc21(&adj, 3);
+    assert_eq!(m.size, 3);
+    assert_eq!(m.row_to_col, vec![Some(0), Some(1), Some(2)]);
+}
+
+#[test]
+fn mc21_augments_depth_first() {
+    // Row 1 can only use column 0, which row 0 took cheaply; row 0 moves to column 1.
+    let adj = vec![vec![0, 1], vec![0]];
+    let m = mc21(&adj, 2);
+    assert_eq!(m.size, 2);
+    assert_eq!(m.row_to_col, vec![Some(1), Some(0)]);
+    assert_eq!(m.col_to_row, vec![Some(1), Some(0)]);
+}
+
+#[test]
+fn mc21_long_augmenting_path() {
+    // Cheap pass matches i -> i for rows 0..3; row 3 forces a chain of reassignments.
+    let adj = vec![vec![0, 1], vec![1, 2], vec![2, 3], vec![0]];
+    let m = mc21(&adj, 4);
+    assert_eq!(m.size, 4);
+    assert_eq!(m.row_to_col, vec![Some(1), Some(2), Some(3), Some(0)]);
+}
+
+#[test]
+fn mc21_deficient_and_rectangular() {
+    let adj = vec![vec![0], vec![0], vec![]];
+    let m = mc21(&adj, 2);
+    assert_eq!(m.size, 1);
+    assert_eq!(m.row_to_col, vec![Some(0), None, None]);
+    assert_eq!(m.col_to_row, vec![Some(0), None]);
+    assert_eq!(mc21(&[], 3).size, 0);
+}
+
+#[test]
+fn priority_matching_prefers_high_class_diagonal() {
+    let adj = vec![vec![0, 1], vec![0, 1]];
+    let anti = |i: usize, j: usize| u8::from(i != j);
+    let m = priority_matching(&adj, 2, anti);
+    assert_eq!(m.size, 2);
+    assert_eq!(m.row_to_col, vec![Some(1), Some(0)]);
+
+    let diag = |i: usize, j: usize| u8::from(i == j);
+    assert_eq!(priority_matching(&adj, 2, diag).row_to_col, vec![Some(0), Some(1)]);
+}
+
+#[test]
+fn priority_matching_is_lexicographic() {
+    // Either (0,0) alone at class 2 with (1,1),(2,2) at class 0, or the three class-1
+    // entries (0,1),(1,2),(2,0): one class-2 entry beats three class-1 entries.
+    let adj = vec![vec![0, 1], vec![1, 2], vec![0, 2]];
+    let class = |i: usize, j: usize| match (i, j) {
+        (0, 0) => 2,
+        (0, 1) | (1, 2) | (2, 0) => 1,
+        _ => 0,
+    };
+    let m = priority_matching(&adj, 3, class);
+    assert_eq!(m.size, 3);
+    assert_eq!(m.row_to_col, vec![Some(0), Some(1), Some(2)]);
+}
+
+#[test]
+fn priority_matching_keeps_cardinality_first() {
+    // Taking the class-7 entry (0,0) would leave row 1 unmatched.
+    let adj = vec![vec![0, 1], vec![0]];
+    let m = priority_matching(&adj, 2, |i, j| if (i, j) == (0, 0) { 7 } else { 0 });
+    assert_eq!(m.size, 2);
+    assert_eq!(m.row_to_col, vec![Some(1), Some(0)]);
+}
+
+#[test]
+fn enumerate_unique_matching() {
+    // Lower triangular: the diagonal is the only perfect matching.
+    let adj = vec![vec![0], vec![0, 1], vec![0, 1, 2]];
+    let all = enumerate_maximum_matchings(&adj, 3, 10);
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].row_to_col, vec![Some(0), Some(1), Some(2)]);
+}
+
+#[test]
+fn enumerate_full_block() {
+    let adj = vec![vec![0, 1, 2]; 3];
+    let all = enumerate_maximum_matchings(&adj, 3, 100);
+    assert_eq!(all.len(), 6);
+    assert_eq!(all[0].row_to_col, hopcroft_karp(&adj, 3).row_to_col);
+    let mut perms: Vec<_> = all.iter().map(|m| m.row_to_col.clone()).collect();
+    perms.sort();
+    perms.dedup();
+    assert_eq!(perms.len(), 6);
+}
+
+#[test]
+fn enumerate_respects_limit() {
+    let adj = vec![vec![0, 1, 2]; 3];
+    assert_eq!(enumerate_maximum_matchings(&adj, 3, 4).len(), 4);
+    assert!(enumerate_maximum_matchings(&adj, 3, 0).is_empty());
+}
+
+#[test]
+fn enumerate_deficient_matchings() {
+    // Both rows compete for column 0; either may be left unmatched.
+    let adj = vec![vec![0], vec![0]];
+    let all = enumerate_maximum_matchings(&adj, 1, 10);
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(|m| m.size == 1));
+}
+
+#[test]
+fn budgeted_phase_limit_stops_before_augmenting() {
+    // Phase 1 matches (0, 0); the augmenting path 1 -> 0 -> 0 -> 1 needs phase 2.
+    let adj = vec![vec![0, 1], vec![0]];
+    let budget = MatchingBudget {
+        max_phases: Some(1),
+        ..Default::default()
+    };
+    let partial = hopcroft_karp_budgeted(&adj, 2, &budget);
+    assert_eq!(partial.matching.size, 1);
+    assert_eq!(partial.phases, 1);
+    assert!(!partial.exact);
+
+    let full = hopcroft_karp_budgeted(&adj, 2, &MatchingBudget::default());
+    assert_eq!(full.matching.size, 2);
+    assert_eq!(full.phases, 2);
+    assert!(full.exact);
+    assert_eq!(full.matching.row_to_col, hopcroft_karp(&adj, 2).row_to_col);
+}
+
+#[test]
+fn budgeted_zero_budgets_return_empty_matching() {
+    let adj = vec![vec![0]];
+    for budget in [
+        MatchingBudget {
+            max_phases: Some(0),
+            ..Default::default()
+        },
+        MatchingBudget {
+            max_time: Some(Duration::ZERO),
+            ..Default::default()
+        },
+    ] {
+        let result = hopcroft_karp_budgeted(&adj, 1, &budget);
+        assert_eq!(result.matching.size, 0);
+        assert_eq!(result.phases, 0);
+        assert!(!result.exact);
+    }
+}
+
+#[test]
+fn validated_matching_rejects_out_of_range_columns() {
+    let adj = vec![vec![0, 1], vec![3]];
+    assert_eq!(
+        try_hopcroft_karp(&adj, 2).unwrap_err(),
+        MatchingError::ColumnOutOfRange { row: 1, col: 3, n_right: 2 }
+    );
+    assert!(validate_adjacency(&adj, 4).is_ok());
+    let m = try_hopcroft_karp(&adj, 4).unwrap();
+    assert_eq!(m.size, 2);
+    assert_eq!(m.row_to_col, hopcroft_karp_unchecked(&adj, 4).row_to_col);
+}
+
+#[test]
+#[should_panic(expected = "row 0 lists column 1, out of range for 1 columns")]
+fn hopcroft_karp_panics_with_description() {
+    hopcroft_karp(&[vec![1]], 1);
+}
+
+#[test]
+fn vertex_cover_of_a_star_is_its_center() {
+    // Row 0 touches every column; rows 1 and 2 only column 0.
+    let adj = vec![vec![0, 1, 2], vec![0], vec![0]];
+    let m = hopcroft_karp(&adj, 3);
+    assert_eq!(m.size, 2);
+    let cover = minimum_vertex_cover(&m, &adj);
+    assert_eq!(cover, VertexSet { rows: vec![0], cols: vec![0] });
+    let independent = maximum_independent_set(&m, &adj);
+    assert_eq!(independent, VertexSet { rows: vec![1, 2], cols: vec![1, 2] });
+    assert_eq!(cover.len() + independent.len(), 6);
+}
+
+#[test]
+fn vertex_cover_of_perfect_and_empty_graphs() {
+    let adj = vec![vec![0, 1], vec![0, 1]];
+    let m = hopcroft_karp(&adj, 2);
+    let cover = minimum_vertex_cover(&m, &adj);
+    assert_eq!(cover.len(), 2);
+    assert!(adj.iter().enumerate().all(|(i, cols)| {
+        cols.iter().all(|j| cover.rows.contains(&i) || cover.cols.contains(j))
+    }));
+
+    let adj: Vec<Vec<usize>> = vec![vec![], vec![]];
+    let m = hopcroft_karp(&adj, 3);
+    assert!(minimum_vertex_cover(&m, &adj).is_empty());
+    assert_eq!(maximum_independent_set(&m, &adj), VertexSet { rows: vec![0, 1], cols: vec![0, 1, 2] });
+}
@@ -0,0 +1,115 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::nested::{NestedBlock, nested_block_triangular_structure};
+use nalgebra_block_triangularization::tearing::{TearingHeuristic, TearingOptions};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+/// Two 3-cycles {0,1,2} and {3,4,5} joined into one irreducible 6x6 block through rows 2
+/// and 5, plus a trailing singleton.
+fn linked_cycles() -> DMatrix<u8> {
+    DMatrix::from_row_slice(7, 7, &[
+        1, 1, 0, 0, 0, 0, 0,
+        0, 1, 1, 0, 0, 0, 0,
+        1, 0, 1, 1, 0, 0, 1,
+        0, 0, 0, 1, 1, 0, 0,
+        0, 0, 0, 0, 1, 1, 0,
+        1, 0, 0, 1, 0, 1, 0,
+        0, 0, 0, 0, 0, 0, 1,
+    ])
+}
+
+/// Checks the tree invariants of `block` against `m` and returns its leaves' sizes.
+fn check(m: &DMatrix<u8>, block: &NestedBlock, max: usize, sizes: &mut Vec<usize>) {
+    assert_eq!(block.rows.len(), block.cols.len());
+    for (&r, &c) in block.rows.iter().zip(&block.cols) {
+        assert_ne!(m[(r, c)], 0, "matched pair ({r}, {c}) is not an entry");
+    }
+    if block.is_leaf() {
+        assert!(block.residuals.is_empty() && block.tear_vars.is_empty());
+        sizes.push(block.rows.len());
+        return;
+    }
+    assert!(block.rows.len() > max);
+    assert_eq!(block.residuals.len(), block.tear_vars.len());
+    for (r, c) in block.residuals.iter().zip(&block.tear_vars) {
+        let p = block.rows.iter().position(|x| x == r).unwrap();
+        assert_eq!(block.cols[p], *c);
+    }
+
+    // Children and residuals partition the rows; the children are block upper triangular
+    // once the tear variables are removed.
+    let mut rows: Vec<usize> = block.children.iter().flat_map(|c| c.rows.clone()).collect();
+    rows.extend(&block.residuals);
+    rows.sort_unstable();
+    let mut expected = block.rows.clone();
+    expected.sort_unstable();
+    assert_eq!(rows, expected);
+    for (a, earlier) in block.children.iter().enumerate() {
+        for later in &block.children[a + 1..] {
+            for &r in &later.rows {
+                for &c in &earlier.cols {
+                    assert_eq!(m[(r, c)], 0, "row {r} reaches back into column {c}");
+                }
+            }
+        }
+    }
+    for child in &block.children {
+        check(m, child, max, sizes);
+    }
+}
+
+#[test]
+fn large_block_is_torn_recursively_to_singletons() {
+    let m = linked_cycles();
+    let nested = nested_block_triangular_structure(&m, 1, &TearingOptions::default());
+    assert_eq!(nested.structure.block_sizes, upper_block_triangular_structure(&m).block_sizes);
+    assert_eq!(nested.blocks.len(), 2);
+    let mut sizes = Vec::new();
+    for block in &nested.blocks {
+        check(&m, block, 1, &mut sizes);
+    }
+    assert!(sizes.iter().all(|&s| s == 1));
+    assert!(nested.depth() >= 1);
+}
+
+#[test]
+fn first_level_splits_the_linked_cycles() {
+    let m = linked_cycles();
+    let options = TearingOptions {
+        by_size: Vec::new(),
+        default: TearingHeuristic::GreedyMfvs,
+    };
+    let nested = nested_block_triangular_structure(&m, 3, &options);
+    let big = nested.blocks.iter().find(|b| b.rows.len() == 6).unwrap();
+    let mut sizes = Vec::new();
+    check(&m, big, 3, &mut sizes);
+    assert!(sizes.iter().all(|&s| s <= 3));
+    assert!(big.children.len() >= 2);
+    assert_eq!(sizes.iter().sum::<usize>() + big.residuals.len(), 6);
+}
+
+#[test]
+fn small_blocks_are_left_alone() {
+    let m = linked_cycles();
+    let nested = nested_block_triangular_structure(&m, 6, &TearingOptions::default());
+    assert_eq!(nested.depth(), 0);
+    assert!(nested.blocks.iter().all(NestedBlock::is_leaf));
+    let (rows, cols) = nested.structure.solve_order_blocks();
+    let flat: Vec<(Vec<usize>, Vec<usize>)> =
+        nested.blocks.iter().map(|b| (b.rows.clone(), b.cols.clone())).collect();
+    assert_eq!(flat, rows.into_iter().zip(cols).collect::<Vec<_>>());
+}
+
+#[test]
+fn unmatched_rows_are_leaves() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 0,
+        0, 0, 0,
+    ]);
+    let nested = nested_block_triangular_structure(&m, 1, &TearingOptions::default());
+    let zero_row = nested.blocks.iter().find(|b| b.rows == vec![2]).unwrap();
+    assert!(zero_row.is_leaf() && zero_row.cols.is_empty());
+    let torn = nested.blocks.iter().find(|b| b.rows.len() == 2).unwrap();
+    assert_eq!(torn.residuals.len(), 1);
+    assert_eq!(torn.children.len(), 1);
+}
@@ -0,0 +1,72 @@
+use nalgebra::{DMatrix, DVector};
+use nalgebra_block_triangularization::newton::StructuredNewton;
+
+/// F(x) = [x0^2 + x1 - 3, x1^3 - 1, x2 + x0 - 2] with root (sqrt(2), 1, 2 - sqrt(2)).
+fn residual(x: &DVector<f64>) -> DVector<f64> {
+    DVector::from_vec(vec![
+        x[0] * x[0] + x[1] - 3.0,
+        x[1] * x[1] * x[1] - 1.0,
+        x[2] + x[0] - 2.0,
+    ])
+}
+
+fn jacobian(x: &DVector<f64>) -> DMatrix<f64> {
+    DMatrix::from_row_slice(3, 3, &[
+        2.0 * x[0], 1.0, 0.0,
+        0.0, 3.0 * x[1] * x[1], 0.0,
+        1.0, 0.0, 1.0,
+    ])
+}
+
+#[test]
+fn newton_converges_with_single_analysis() {
+    let mut newton = StructuredNewton::new();
+    let mut x = DVector::from_vec(vec![1.0, 2.0, 0.0]);
+    for _ in 0..30 {
+        let dx = newton.solve_step(&jacobian(&x), &residual(&x)).unwrap();
+        x += &dx;
+        if dx.norm() < 1e-13 {
+            break;
+        }
+    }
+    assert!(residual(&x).norm() < 1e-10);
+    assert!((x[0] - 2f64.sqrt()).abs() < 1e-10);
+    assert_eq!(newton.analysis_count(), 1);
+    assert_eq!(newton.structure().unwrap().block_sizes, vec![1, 1, 1]);
+    assert!(newton.factor().is_some());
+}
+
+#[test]
+fn pattern_change_triggers_reanalysis() {
+    let mut newton = StructuredNewton::new();
+    let r = DVector::from_vec(vec![1.0, 1.0]);
+
+    let diag = DMatrix::from_row_slice(2, 2, &[2.0, 0.0, 0.0, 2.0]);
+    newton.solve_step(&diag, &r).unwrap();
+    // Same pattern, different values.
+    newton.solve_step(&(&diag * 3.0), &r).unwrap();
+    assert_eq!(newton.analysis_count(), 1);
+
+    let coupled = DMatrix::from_row_slice(2, 2, &[2.0, 1.0, 1.0, 2.0]);
+    let dx = newton.solve_step(&coupled, &r).unwrap();
+    assert_eq!(newton.analysis_count(), 2);
+    assert!((&coupled * dx + &r).norm() < 1e-12);
+
+    newton.invalidate();
+    newton.solve_step(&coupled, &r).unwrap();
+    assert_eq!(newton.analysis_count(), 3);
+}
+
+#[test]
+fn failed_step_clears_factor() {
+    let mut newton = StructuredNewton::new();
+    let r = DVector::from_vec(vec![1.0, 1.0]);
+    newton
+        .solve_step(&DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 2.0]), &r)
+        .unwrap();
+    assert!(newton.factor().is_some());
+
+    let singular = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+    assert!(newton.solve_step(&singular, &r).is_err());
+    assert!(newton.factor().is_none());
+}
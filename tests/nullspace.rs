@@ -0,0 +1,56 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::nullspace::null_space_support;
+
+#[test]
+fn nonsingular_has_empty_support() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let ns = null_space_support(&m);
+    assert_eq!(ns.rank, 3);
+    assert!(ns.right.is_empty());
+    assert!(ns.left.is_empty());
+}
+
+#[test]
+fn underdetermined_part_forms_right_support() {
+    // Row 0 couples columns 0 and 1; row 1 only uses column 2; row 2 is empty.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 0, 1,
+        0, 0, 0,
+    ]);
+    let ns = null_space_support(&m);
+    assert_eq!(ns.rank, 2);
+    assert_eq!(ns.right, vec![0, 1]);
+    assert_eq!(ns.left, vec![2]);
+    assert_eq!(ns.right_nullity(3), 1);
+    assert_eq!(ns.left_nullity(3), 1);
+}
+
+#[test]
+fn wide_full_rank_pattern_has_right_null_space() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let ns = null_space_support(&m);
+    assert_eq!(ns.rank, 2);
+    assert_eq!(ns.right, vec![1, 2]);
+    assert!(ns.left.is_empty());
+}
+
+#[test]
+fn tall_pattern_has_left_null_space() {
+    let m = DMatrix::from_row_slice(3, 1, &[
+        1,
+        1,
+        0,
+    ]);
+    let ns = null_space_support(&m);
+    assert_eq!(ns.rank, 1);
+    assert!(ns.right.is_empty());
+    assert_eq!(ns.left, vec![0, 1, 2]);
+}
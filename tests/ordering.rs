@@ -1,10 +1,15 @@
-use nalgebra_block_triangularization::ordering::{topo_sort_with_tiebreak, col_order_from_row_order};
+use nalgebra_block_triangularization::ordering::{
+    OrderingError, col_order_from_row_order, col_order_from_row_order_strict, cuthill_mckee,
+    column_minimum_degree, max_adjacent_coupling_topo_order, min_coupling_topo_order,
+    minimum_degree,
+    topo_sort_with_tiebreak,
+};
 
 #[test]
 fn topo_empty_dag() {
     let dag: Vec<Vec<usize>> = vec![];
     let key: Vec<usize> = vec![];
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     assert_eq!(order.len(), 0);
 }
 
@@ -12,7 +17,7 @@ fn topo_empty_dag() {
 fn topo_single_node() {
     let dag = vec![vec![]];
     let key = vec![0];
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     assert_eq!(order, vec![0]);
 }
 
@@ -21,7 +26,7 @@ fn topo_two_nodes_no_edges() {
     let dag = vec![vec![], vec![]];
     // Both have same in-degree; key determines order
     let key = vec![1, 0];  // Node 1 has lower key
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     assert_eq!(order, vec![1, 0]);  // Should be sorted by key
 }
 
@@ -30,7 +35,7 @@ fn topo_two_nodes_with_edge() {
     // 0 -> 1
     let dag = vec![vec![1], vec![]];
     let key = vec![0, 0];  // Keys don't matter when topology constrains
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     assert_eq!(order, vec![0, 1]);
 }
 
@@ -44,7 +49,7 @@ fn topo_linear_chain() {
         vec![],
     ];
     let key = vec![3, 2, 1, 0];  // Reverse order keys
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     // Topology forces 0, 1, 2, 3 order regardless of keys
     assert_eq!(order, vec![0, 1, 2, 3]);
 }
@@ -64,7 +69,7 @@ fn topo_diamond() {
         vec![],
     ];
     let key = vec![0, 2, 1, 3];  // Node 2 has lower key than node 1
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     // Must be 0 first, 3 last
     // Between 1 and 2, key=1 < key=2, so 2 should come before 1
     assert_eq!(order[0], 0);
@@ -83,7 +88,7 @@ fn topo_parallel_branches() {
         vec![],
     ];
     let key = vec![1, 0, 3, 2];  // 1<0, 2<3
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     // Node 1 should come before 0 (lower key, both in-degree 0)
     // Node 3 should come after 1
     // Node 2 should come after 0
@@ -97,29 +102,55 @@ fn topo_tiebreak_by_key() {
     // All nodes have in-degree 0, so only key matters
     let dag = vec![vec![], vec![], vec![], vec![]];
     let key = vec![3, 1, 2, 0];
-    let order = topo_sort_with_tiebreak(&dag, &key);
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
     // Should be sorted by key: [3, 1, 2, 0]
     assert_eq!(order, vec![3, 1, 2, 0]);
 }
 
 #[test]
-fn topo_cycle_fallback() {
+fn topo_equal_keys_break_by_node() {
+    let dag = vec![vec![], vec![], vec![]];
+    let key = vec![5, 2, 2];
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
+    assert_eq!(order, vec![1, 2, 0]);
+}
+
+#[test]
+fn topo_large_keys() {
+    // Keys far larger than the node count take the heap path.
+    let dag = vec![vec![], vec![2], vec![]];
+    let key = vec![usize::MAX, 10_000, 0];
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
+    assert_eq!(order, vec![1, 2, 0]);
+}
+
+#[test]
+fn topo_many_nodes_bucket_queue() {
+    // Enough nodes for a multi-level bucket queue; reversed keys on independent nodes.
+    let n = 5000;
+    let dag = vec![vec![]; n];
+    let key: Vec<usize> = (0..n).rev().collect();
+    let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
+    assert_eq!(order, (0..n).rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn topo_cycle_is_error() {
     // Contains a cycle: 0 -> 1 -> 0
     let dag = vec![vec![1], vec![0]];
     let key = vec![0, 1];
-    let order = topo_sort_with_tiebreak(&dag, &key);
-    // Should fall back to identity ordering
-    assert_eq!(order, vec![0, 1]);
+    let err = topo_sort_with_tiebreak(&dag, &key).unwrap_err();
+    assert_eq!(err, OrderingError::Cycle { nodes: vec![0, 1] });
 }
 
 #[test]
-fn topo_self_loop_fallback() {
+fn topo_self_loop_is_error() {
     // Self-loop at node 0
     let dag = vec![vec![0], vec![]];
     let key = vec![0, 1];
-    let order = topo_sort_with_tiebreak(&dag, &key);
-    // Should fall back to identity ordering
-    assert_eq!(order, vec![0, 1]);
+    // Node 1 is independent of the loop and still gets ordered.
+    let err = topo_sort_with_tiebreak(&dag, &key).unwrap_err();
+    assert_eq!(err, OrderingError::Cycle { nodes: vec![0] });
 }
 
 #[test]
@@ -219,3 +250,161 @@ fn col_order_all_columns_present() {
     sorted.sort();
     assert_eq!(sorted, vec![0, 1, 2]);
 }
+
+#[test]
+fn col_order_strict_accepts_consistent_input() {
+    let row_order = vec![2, 0, 1];
+    let row_to_col = vec![Some(1), None, Some(0)];
+    let strict = col_order_from_row_order_strict(&row_order, &row_to_col, 3).unwrap();
+    assert_eq!(strict, col_order_from_row_order(&row_order, &row_to_col, 3));
+    assert_eq!(strict, vec![0, 1, 2]);
+}
+
+#[test]
+fn col_order_strict_rejects_out_of_bounds_column() {
+    let row_order = vec![0, 1];
+    let row_to_col = vec![Some(0), Some(5)];
+    assert_eq!(
+        col_order_from_row_order_strict(&row_order, &row_to_col, 3),
+        Err(OrderingError::ColumnOutOfRange { row: 1, col: 5, ncols: 3 })
+    );
+}
+
+#[test]
+fn col_order_strict_rejects_duplicate_column() {
+    let row_order = vec![0, 1, 2];
+    let row_to_col = vec![Some(0), Some(0), Some(1)];
+    assert_eq!(
+        col_order_from_row_order_strict(&row_order, &row_to_col, 3),
+        Err(OrderingError::DuplicateColumn { col: 0, first_row: 0, second_row: 1 })
+    );
+}
+
+#[test]
+fn col_order_strict_rejects_bad_row_order() {
+    let row_to_col = vec![Some(0), Some(1)];
+    assert_eq!(
+        col_order_from_row_order_strict(&[0, 2], &row_to_col, 2),
+        Err(OrderingError::RowOutOfRange { row: 2, nrows: 2 })
+    );
+    assert_eq!(
+        col_order_from_row_order_strict(&[1, 1], &row_to_col, 2),
+        Err(OrderingError::DuplicateRow { row: 1 })
+    );
+    assert_eq!(
+        col_order_from_row_order_strict(&[1], &row_to_col, 2),
+        Err(OrderingError::MissingRow { row: 0 })
+    );
+}
+
+#[test]
+fn cuthill_mckee_path_in_scrambled_numbering() {
+    // Path 0 - 3 - 1 - 4 - 2.
+    let adj = vec![vec![3], vec![3, 4], vec![4], vec![0, 1], vec![1, 2]];
+    assert_eq!(cuthill_mckee(&adj), vec![0, 3, 1, 4, 2]);
+}
+
+#[test]
+fn cuthill_mckee_components_and_isolated_nodes() {
+    // Component {1, 2}, isolated 0 and 3: isolated nodes have degree 0 and come first.
+    let adj = vec![vec![], vec![2], vec![1], vec![]];
+    assert_eq!(cuthill_mckee(&adj), vec![0, 3, 1, 2]);
+    assert!(cuthill_mckee(&[]).is_empty());
+}
+
+#[test]
+fn minimum_degree_eliminates_star_center_late() {
+    // Star with center 0: eliminating the center first would join all leaves into a clique.
+    let adj = vec![vec![1, 2, 3], vec![0], vec![0], vec![0]];
+    assert_eq!(minimum_degree(&adj), vec![1, 2, 0, 3]);
+    assert!(minimum_degree(&[]).is_empty());
+}
+
+#[test]
+fn minimum_degree_tracks_fill_degrees() {
+    // Cycle 0 - 1 - 2 - 3 - 0 plus pendant 4 on 0: once 4 is gone all degrees are 2, and
+    // eliminating 0 adds the fill edge 1 - 3, so 1 keeps degree 2 (neighbors 2, 3).
+    let adj = vec![vec![1, 3, 4], vec![0, 2], vec![1, 3], vec![0, 2], vec![0]];
+    assert_eq!(minimum_degree(&adj), vec![4, 0, 1, 2, 3]);
+}
+
+#[test]
+fn min_coupling_pulls_heavy_edges_together() {
+    // Min-key order is 0, 1, 2, 3, leaving the heavy edge 0 -> 3 two positions apart.
+    let dag = vec![vec![(3, 10)], vec![(2, 1)], vec![], vec![]];
+    let key = vec![0, 1, 2, 3];
+    assert_eq!(topo_sort_with_tiebreak(&[vec![3], vec![2], vec![], vec![]], &key).unwrap(), vec![0, 1, 2, 3]);
+    assert_eq!(min_coupling_topo_order(&dag, &key).unwrap(), vec![1, 2, 0, 3]);
+}
+
+#[test]
+fn min_coupling_respects_edges_and_reports_cycles() {
+    let dag = vec![vec![(1, 1), (2, 5)], vec![(2, 1)], vec![]];
+    assert_eq!(min_coupling_topo_order(&dag, &[0, 1, 2]).unwrap(), vec![0, 1, 2]);
+    let cyclic = vec![vec![(1, 1)], vec![(0, 1)]];
+    assert!(matches!(
+        min_coupling_topo_order(&cyclic, &[0, 1]),
+        Err(OrderingError::Cycle { .. })
+    ));
+    assert!(min_coupling_topo_order(&[], &[]).unwrap().is_empty());
+}
+
+#[test]
+fn column_minimum_degree_orders_shared_columns_last() {
+    // Column 0 meets every row, so it is the center of the column intersection star.
+    let rows = vec![vec![0, 1], vec![0, 2], vec![0, 3]];
+    assert_eq!(column_minimum_degree(&rows, 4), vec![1, 2, 0, 3]);
+    assert!(column_minimum_degree(&[], 0).is_empty());
+}
+
+#[test]
+fn column_minimum_degree_ignores_dense_rows() {
+    let mut rows = vec![(0..20).collect::<Vec<usize>>()];
+    rows.extend((0..20).rev().map(|j| vec![j]));
+    assert_eq!(column_minimum_degree(&rows, 20), (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn adjacent_coupling_follows_the_heaviest_edge() {
+    // Min-key order is 0, 1, 2, 3; the heavy edge 0 -> 3 becomes consecutive instead.
+    let dag = vec![vec![(3, 10)], vec![(2, 1)], vec![], vec![]];
+    assert_eq!(max_adjacent_coupling_topo_order(&dag, &[0, 1, 2, 3]).unwrap(), vec![0, 3, 1, 2]);
+
+    // 0 -> 1 is light and 0 -> 2 heavy: 2 follows 0 though 1 has the smaller key.
+    let dag = vec![vec![(1, 1), (2, 5)], vec![], vec![]];
+    assert_eq!(max_adjacent_coupling_topo_order(&dag, &[0, 1, 2]).unwrap(), vec![0, 2, 1]);
+}
+
+#[test]
+fn adjacent_coupling_respects_edges_and_reports_cycles() {
+    let dag = vec![vec![(1, 1), (2, 5)], vec![(2, 1)], vec![]];
+    assert_eq!(max_adjacent_coupling_topo_order(&dag, &[0, 1, 2]).unwrap(), vec![0, 1, 2]);
+    let cyclic = vec![vec![(1, 1)], vec![(0, 1)]];
+    assert!(matches!(
+        max_adjacent_coupling_topo_order(&cyclic, &[0, 1]),
+        Err(OrderingError::Cycle { .. })
+    ));
+    assert!(max_adjacent_coupling_topo_order(&[], &[]).unwrap().is_empty());
+}
+
+#[test]
+fn adjacent_coupling_never_swaps_across_a_zero_weight_edge() {
+    let dag = vec![
+        vec![(2, 1), (4, 0)],
+        vec![(2, 2), (3, 0), (5, 0)],
+        vec![(4, 2)],
+        vec![(4, 0)],
+        vec![],
+        vec![],
+    ];
+    let order = max_adjacent_coupling_topo_order(&dag, &[4, 4, 8, 0, 3, 7]).unwrap();
+    let mut pos = vec![0; dag.len()];
+    for (p, &u) in order.iter().enumerate() {
+        pos[u] = p;
+    }
+    for (u, out) in dag.iter().enumerate() {
+        for &(v, _) in out {
+            assert!(pos[u] < pos[v], "edge {u} -> {v} violated by {order:?}");
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::orientation::{
+    block_triangular_structure_with_options, try_block_triangular_structure_with_options,
+};
+use nalgebra_block_triangularization::{
+    BtfError, BtfOptions, Orientation, Pin, UnmatchedPlacement, block_triangular_structure,
+    upper_block_triangular_structure,
+};
+
+fn three_blocks() -> DMatrix<u8> {
+    DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 1, 0,
+        1, 1, 0, 0, 0,
+        0, 0, 1, 1, 1,
+        0, 0, 0, 1, 0,
+        0, 0, 1, 0, 1,
+    ])
+}
+
+/// Whether every entry of `m` permuted by the orders lies on or below the block diagonal.
+fn is_block_lower(m: &DMatrix<u8>, rows: &[usize], cols: &[usize], sizes: &[usize]) -> bool {
+    let mut block_of = Vec::new();
+    for (b, &size) in sizes.iter().enumerate() {
+        block_of.extend(std::iter::repeat_n(b, size));
+    }
+    (0..rows.len()).all(|i| {
+        (0..cols.len()).all(|j| m[(rows[i], cols[j])] == 0 || block_of[j] <= block_of[i])
+    })
+}
+
+#[test]
+fn upper_orientation_is_the_upper_analysis() {
+    let m = three_blocks();
+    let s = block_triangular_structure(&m, Orientation::Upper);
+    let upper = upper_block_triangular_structure(&m);
+    assert_eq!(s.row_order(), upper.row_order);
+    assert_eq!(s.col_order(), upper.col_order);
+    assert_eq!(s.block_sizes(), upper.block_sizes);
+}
+
+#[test]
+fn lower_orientation_reverses_the_upper_form() {
+    let m = three_blocks();
+    let s = block_triangular_structure(&m, Orientation::Lower);
+    assert_eq!(s.orientation, Orientation::Lower);
+    let (rows, cols) = (s.row_order(), s.col_order());
+    assert_eq!(rows, s.upper.row_order.iter().rev().copied().collect::<Vec<_>>());
+    assert!(is_block_lower(&m, &rows, &cols, &s.block_sizes()));
+
+    let (pr, pc) = s.permutations();
+    let mut permuted = m.clone();
+    pr.permute_rows(&mut permuted);
+    pc.permute_columns(&mut permuted);
+    for i in 0..5 {
+        for j in 0..5 {
+            assert_eq!(permuted[(i, j)], m[(rows[i], cols[j])]);
+        }
+    }
+}
+
+#[test]
+fn options_apply_in_the_upper_convention() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let options = BtfOptions {
+        unmatched: UnmatchedPlacement::Start,
+        ..Default::default()
+    };
+    let s = try_block_triangular_structure_with_options(&m, Orientation::Lower, &options).unwrap();
+    assert_eq!(s.upper.col_order[0], 2);
+    assert_eq!(s.col_order()[2], 2);
+    let infallible = block_triangular_structure_with_options(&m, Orientation::Lower, &options);
+    assert_eq!(infallible.col_order(), s.col_order());
+    assert_eq!(infallible.row_order(), s.row_order());
+}
+
+#[test]
+fn option_errors_are_btf_errors() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let options = BtfOptions {
+        pin_last: vec![Pin::Col(2)],
+        ..Default::default()
+    };
+    assert!(matches!(
+        try_block_triangular_structure_with_options(&m, Orientation::Lower, &options),
+        Err(BtfError::InvalidPins(_))
+    ));
+}
+
+#[test]
+#[should_panic(expected = "pins in BtfOptions cannot be honored")]
+fn infallible_variant_panics_on_option_errors() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 0, 0,
+        0, 1, 0,
+    ]);
+    let options = BtfOptions {
+        pin_last: vec![Pin::Col(2)],
+        ..Default::default()
+    };
+    block_triangular_structure_with_options(&m, Orientation::Lower, &options);
+}
+
+#[test]
+fn mismatched_orientation_is_reported() {
+    let s = block_triangular_structure(&three_blocks(), Orientation::Lower);
+    assert!(s.expect_orientation(Orientation::Lower).is_ok());
+    let err = s.expect_orientation(Orientation::Upper).unwrap_err();
+    assert!(matches!(
+        err,
+        BtfError::OrientationMismatch {
+            expected: Orientation::Upper,
+            found: Orientation::Lower,
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "expected Upper block triangular form, found Lower"
+    );
+}
@@ -0,0 +1,66 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::BtfError;
+use nalgebra_block_triangularization::pencil::pencil_structure;
+
+#[test]
+fn identity_mass_matrix_has_only_finite_eigenvalues() {
+    let e = DMatrix::<u8>::identity(3, 3);
+    let a = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let p = pencil_structure(&e, &a).unwrap();
+    assert_eq!(p.rank, 3);
+    assert!(p.regular);
+    assert_eq!(p.degree, 3);
+    assert_eq!(p.infinite_eigenvalues, Some(0));
+    assert_eq!(p.structure.block_sizes, vec![3]);
+}
+
+#[test]
+fn semi_explicit_dae() {
+    // x0' = f(x0, x1), 0 = g(x0, x1): one differential, one algebraic equation.
+    let e = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        0, 0,
+    ]);
+    let a = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        1, 1,
+    ]);
+    let p = pencil_structure(&e, &a).unwrap();
+    assert!(p.regular);
+    assert_eq!(p.degree, 1);
+    assert_eq!(p.infinite_eigenvalues, Some(1));
+}
+
+#[test]
+fn degree_prefers_mass_entries_over_first_matching() {
+    // The diagonal is a perfect matching of A, but (0, 1), (1, 0) both carry λ.
+    let e = DMatrix::from_row_slice(2, 2, &[
+        0, 1,
+        1, 0,
+    ]);
+    let a = DMatrix::<u8>::identity(2, 2);
+    let p = pencil_structure(&e, &a).unwrap();
+    assert_eq!(p.degree, 2);
+    assert_eq!(p.infinite_eigenvalues, Some(0));
+    assert_eq!(p.structure.col_order, vec![1, 0]);
+}
+
+#[test]
+fn singular_and_mismatched_pencils() {
+    let e = DMatrix::from_row_slice(2, 2, &[
+        1, 0,
+        1, 0,
+    ]);
+    let a = DMatrix::<u8>::zeros(2, 2);
+    let p = pencil_structure(&e, &a).unwrap();
+    assert_eq!(p.rank, 1);
+    assert!(!p.regular);
+    assert_eq!(p.infinite_eigenvalues, None);
+
+    let err = pencil_structure(&e, &DMatrix::<u8>::zeros(2, 3)).unwrap_err();
+    assert!(matches!(err, BtfError::DimensionMismatch { .. }));
+}
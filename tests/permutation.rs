@@ -1,5 +1,9 @@
 use nalgebra::DMatrix;
-use nalgebra_block_triangularization::permutation::permutation_sequence_from_order;
+use nalgebra_block_triangularization::permutation::{
+    PermError, PermParseError, cycle_notation, is_valid_permutation, one_line_notation,
+    parse_cycle_notation, parse_one_line_notation, permutation_sequence_from_order,
+    validate_permutation,
+};
 
 fn apply_perm_to_vec(perm: &nalgebra::PermutationSequence<nalgebra::Dyn>, v: &[usize]) -> Vec<usize> {
     let n = v.len();
@@ -218,3 +222,84 @@ fn perm_deterministic() {
     
     assert_eq!(result1, result2);
 }
+
+#[test]
+fn validate_accepts_permutations() {
+    assert!(is_valid_permutation(&[]));
+    assert!(is_valid_permutation(&[0]));
+    assert!(is_valid_permutation(&[2, 0, 1]));
+    assert_eq!(validate_permutation(&[3, 1, 0, 2]), Ok(()));
+}
+
+#[test]
+fn validate_reports_out_of_range() {
+    assert!(!is_valid_permutation(&[0, 3, 1]));
+    assert_eq!(
+        validate_permutation(&[0, 3, 1]),
+        Err(PermError::OutOfRange { position: 1, value: 3, len: 3 })
+    );
+}
+
+#[test]
+fn validate_reports_duplicate() {
+    assert!(!is_valid_permutation(&[1, 0, 1]));
+    assert_eq!(
+        validate_permutation(&[1, 0, 1]),
+        Err(PermError::Duplicate { value: 1, first: 0, second: 2 })
+    );
+}
+
+#[test]
+fn perm_error_display() {
+    let err = validate_permutation(&[0, 0]).unwrap_err();
+    assert_eq!(err.to_string(), "index 0 appears at positions 0 and 1");
+}
+
+#[test]
+fn one_line_notation_round_trips() {
+    assert_eq!(one_line_notation(&[2, 0, 1]), "[2 0 1]");
+    assert_eq!(one_line_notation(&[]), "[]");
+    assert_eq!(parse_one_line_notation("[2 0 1]"), Ok(vec![2, 0, 1]));
+    assert_eq!(parse_one_line_notation(" 2, 0,1 "), Ok(vec![2, 0, 1]));
+    assert_eq!(parse_one_line_notation("[]"), Ok(vec![]));
+}
+
+#[test]
+fn one_line_notation_rejects_bad_input() {
+    assert_eq!(parse_one_line_notation("[1 0"), Err(PermParseError::Unbalanced));
+    assert_eq!(
+        parse_one_line_notation("[1 x]"),
+        Err(PermParseError::InvalidToken("x".into()))
+    );
+    assert_eq!(
+        parse_one_line_notation("[0 0]"),
+        Err(PermParseError::Invalid(PermError::Duplicate { value: 0, first: 0, second: 1 }))
+    );
+}
+
+#[test]
+fn cycle_notation_round_trips() {
+    let order = [2, 0, 1, 3];
+    assert_eq!(cycle_notation(&order), "(0 2 1)(3)");
+    assert_eq!(parse_cycle_notation("(0 2 1)(3)", 4), Ok(order.to_vec()));
+    assert_eq!(parse_cycle_notation("(0 2 1)", 4), Ok(order.to_vec()));
+    // Any rotation of a cycle is the same permutation.
+    assert_eq!(parse_cycle_notation(" (2 1 0) ", 4), Ok(order.to_vec()));
+    assert_eq!(cycle_notation(&[]), "");
+    assert_eq!(parse_cycle_notation("()", 2), Ok(vec![0, 1]));
+    assert_eq!(parse_cycle_notation("", 0), Ok(vec![]));
+}
+
+#[test]
+fn cycle_notation_rejects_bad_input() {
+    assert_eq!(parse_cycle_notation("(0 1", 2), Err(PermParseError::Unbalanced));
+    assert_eq!(parse_cycle_notation("(0 1)(1 2)", 3), Err(PermParseError::RepeatedElement(1)));
+    assert!(matches!(
+        parse_cycle_notation("(0 4)", 3),
+        Err(PermParseError::Invalid(PermError::OutOfRange { value: 4, .. }))
+    ));
+    assert_eq!(
+        PermParseError::RepeatedElement(1).to_string(),
+        "element 1 appears in more than one cycle"
+    );
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "plotters")]
+
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::plot::{
+    draw_block_heatmap, draw_permuted_spy, draw_spy, draw_spy_diff,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+use plotters::prelude::{IntoDrawingArea, SVGBackend};
+
+fn render(draw: impl FnOnce(&plotters::drawing::DrawingArea<SVGBackend, plotters::coord::Shift>)) -> String {
+    let mut svg = String::new();
+    {
+        let area = SVGBackend::with_string(&mut svg, (90, 90)).into_drawing_area();
+        draw(&area);
+        area.present().unwrap();
+    }
+    svg
+}
+
+fn sample() -> DMatrix<u8> {
+    DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 1, 1,
+        0, 1, 1,
+    ])
+}
+
+#[test]
+fn spy_draws_one_cell_per_nonzero() {
+    let m = sample();
+    let svg = render(|area| draw_spy(area, &m).unwrap());
+    // Background plus six nonzeros.
+    assert_eq!(svg.matches("<rect").count(), 1 + 6);
+    assert!(svg.contains("#224466"));
+}
+
+#[test]
+fn permuted_spy_outlines_blocks() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.block_sizes, vec![2, 1]);
+    let svg = render(|area| draw_permuted_spy(area, &m, &s).unwrap());
+    assert_eq!(svg.matches("<rect").count(), 1 + 6 + 2);
+    assert!(svg.contains("#DD6600") || svg.contains("#dd6600"));
+}
+
+#[test]
+fn heatmap_draws_nonempty_block_pairs() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    let svg = render(|area| draw_block_heatmap(area, &m, &s).unwrap());
+    // Background, blocks (0,0), (0,1), (1,1), and two outlines.
+    assert_eq!(svg.matches("<rect").count(), 1 + 3 + 2);
+}
+
+#[test]
+fn spy_diff_draws_both_halves() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    let svg = render(|area| draw_spy_diff(area, &m, &s).unwrap());
+    // Background, six entries per half, two outlines.
+    assert_eq!(svg.matches("<rect").count(), 1 + 12 + 2);
+}
+
+#[test]
+fn outlines_follow_block_columns_of_tall_patterns() {
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        0, 1,
+        1, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let svg = render(|area| draw_permuted_spy(area, &m, &s).unwrap());
+    // The surplus row's block has no columns and no outline; the matched blocks sit one row
+    // below their column position.
+    let outlines: Vec<&str> = svg.lines().filter(|line| line.contains("fill=\"none\"")).collect();
+    assert_eq!(outlines.len(), 2);
+    assert!(outlines[0].starts_with("<rect x=\"0\" y=\"30\" width=\"44\" height=\"29\""));
+    assert!(outlines[1].starts_with("<rect x=\"45\" y=\"60\" width=\"44\" height=\"29\""));
+}
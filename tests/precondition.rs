@@ -0,0 +1,118 @@
+use nalgebra::{DMatrix, DVector};
+use nalgebra_block_triangularization::precondition::{
+    BlockGaussSeidel, BlockJacobi, Preconditioner,
+};
+use nalgebra_block_triangularization::solve::{BlockLu, SolveError};
+use nalgebra_block_triangularization::{UpperBtfStructure, upper_block_triangular_structure};
+
+/// Diagonally dominant 5x5 matrix whose strong entries form a 2x2 and a 3x3 block with an
+/// upper coupling; the weak entries (0.1) couple everything.
+fn test_matrix() -> DMatrix<f64> {
+    DMatrix::from_row_slice(5, 5, &[
+        4.0, 1.0, 0.1, 0.0, 0.7,
+        1.0, 5.0, 0.0, 0.1, 0.0,
+        0.1, 0.0, 6.0, 1.0, 0.0,
+        0.0, 0.1, 0.0, 7.0, 1.0,
+        0.0, 0.0, 1.0, 0.0, 8.0,
+    ])
+}
+
+/// Structure of the strong entries only.
+fn strong_structure(a: &DMatrix<f64>) -> UpperBtfStructure {
+    upper_block_triangular_structure(&a.map(|v| u8::from(v.abs() > 0.5)))
+}
+
+/// `A` with the entries outside the diagonal blocks (`keep_upper`: below them) zeroed.
+fn truncated(a: &DMatrix<f64>, s: &UpperBtfStructure, keep_upper: bool) -> DMatrix<f64> {
+    let mut block_of_row = vec![0; a.nrows()];
+    let mut block_of_col = vec![0; a.ncols()];
+    let mut start = 0;
+    for (b, &size) in s.block_sizes.iter().enumerate() {
+        for p in start..start + size {
+            block_of_row[s.row_order[p]] = b;
+            block_of_col[s.col_order[p]] = b;
+        }
+        start += size;
+    }
+    DMatrix::from_fn(a.nrows(), a.ncols(), |i, j| {
+        let (bi, bj) = (block_of_row[i], block_of_col[j]);
+        if bi == bj || (keep_upper && bi < bj) { a[(i, j)] } else { 0.0 }
+    })
+}
+
+fn rhs() -> DVector<f64> {
+    DVector::from_fn(5, |i, _| 1.0 - 0.3 * i as f64)
+}
+
+#[test]
+fn jacobi_inverts_the_block_diagonal() {
+    let a = test_matrix();
+    let s = strong_structure(&a);
+    assert_eq!(s.num_blocks(), 2);
+    let m = BlockJacobi::factor(&a, &s).unwrap();
+    assert_eq!(m.dim(), 5);
+    let z = m.apply(&rhs()).unwrap();
+    let d = truncated(&a, &s, false);
+    assert!((d * z - rhs()).norm() < 1e-12);
+}
+
+#[test]
+fn gauss_seidel_inverts_the_block_upper_triangle() {
+    let a = test_matrix();
+    let s = strong_structure(&a);
+    let m = BlockGaussSeidel::factor(&a, &s).unwrap();
+    let z = m.apply(&rhs()).unwrap();
+    let u = truncated(&a, &s, true);
+    assert!((&u * &z - rhs()).norm() < 1e-12);
+    assert!((truncated(&a, &s, false) * z - rhs()).norm() > 1e-3);
+}
+
+#[test]
+fn gauss_seidel_on_the_full_pattern_is_the_exact_solve() {
+    let a = test_matrix();
+    let s = upper_block_triangular_structure(&a);
+    let gs = BlockGaussSeidel::factor(&a, &s).unwrap().apply(&rhs()).unwrap();
+    let lu = BlockLu::factor(&a, &s).unwrap();
+    assert!((&gs - lu.solve(&rhs()).unwrap()).norm() < 1e-12);
+    assert!((&gs - lu.apply(&rhs()).unwrap()).norm() < 1e-12);
+    // The exact solve rejects the sparser structure that the preconditioners accept.
+    assert!(matches!(
+        BlockLu::factor(&a, &strong_structure(&a)),
+        Err(SolveError::PatternMismatch { .. })
+    ));
+}
+
+fn richardson_error(m: &dyn Preconditioner<f64>, iterations: usize) -> f64 {
+    let a = test_matrix();
+    let b = rhs();
+    let mut x = DVector::zeros(5);
+    for _ in 0..iterations {
+        x += m.apply(&(&b - &a * &x)).unwrap();
+    }
+    (&a * x - b).norm()
+}
+
+#[test]
+fn preconditioned_iteration_converges() {
+    let a = test_matrix();
+    let s = strong_structure(&a);
+    let jacobi = BlockJacobi::factor(&a, &s).unwrap();
+    let gs = BlockGaussSeidel::factor(&a, &s).unwrap();
+    assert!(richardson_error(&jacobi, 40) < 1e-10);
+    assert!(richardson_error(&gs, 40) < 1e-10);
+    assert!(richardson_error(&gs, 5) < richardson_error(&jacobi, 5));
+}
+
+#[test]
+fn apply_checks_dimensions() {
+    let a = test_matrix();
+    let m = BlockJacobi::factor(&a, &strong_structure(&a)).unwrap();
+    assert_eq!(
+        m.apply(&DVector::zeros(4)).unwrap_err(),
+        SolveError::DimensionMismatch { expected: 5, found: 4 }
+    );
+    assert!(matches!(
+        BlockGaussSeidel::factor(&DMatrix::<f64>::zeros(2, 3), &strong_structure(&a)),
+        Err(SolveError::NotSquare { nrows: 2, ncols: 3 })
+    ));
+}
@@ -0,0 +1,58 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::profile::{
+    first_deficient, leading_rank_profile, nested_rank_profile, submatrix_rank,
+};
+
+#[test]
+fn lower_triangular_profile_is_full() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 1, 0,
+        0, 1, 1,
+    ]);
+    assert_eq!(leading_rank_profile(&m), vec![1, 2, 3]);
+    assert_eq!(first_deficient(&leading_rank_profile(&m)), None);
+}
+
+#[test]
+fn deficiency_located_and_recovered() {
+    // The leading 1x1 and 2x2 blocks are singular; the full matrix is not.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        0, 1, 0,
+        0, 0, 1,
+        1, 0, 0,
+    ]);
+    let profile = leading_rank_profile(&m);
+    assert_eq!(profile, vec![0, 1, 3]);
+    assert_eq!(first_deficient(&profile), Some(1));
+}
+
+#[test]
+fn growth_by_two_needs_column_search() {
+    // Adding row 1 and column 1 raises the rank from 0 to 2 via (1, 0) and (0, 1).
+    let m = DMatrix::from_row_slice(2, 2, &[
+        0, 1,
+        1, 1,
+    ]);
+    assert_eq!(leading_rank_profile(&m), vec![0, 2]);
+    assert_eq!(nested_rank_profile(&m, &[1, 0], &[1, 0]), vec![1, 2]);
+}
+
+#[test]
+fn rectangular_and_nested_orders() {
+    let m = DMatrix::from_row_slice(2, 4, &[
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ]);
+    assert_eq!(leading_rank_profile(&m), vec![0, 0]);
+    assert_eq!(nested_rank_profile(&m, &[0, 1], &[2, 3]), vec![1, 2]);
+    assert_eq!(submatrix_rank(&m, &[0, 1], &[1, 2, 3]), 2);
+    assert_eq!(submatrix_rank(&m, &[1], &[0, 2]), 0);
+}
+
+#[test]
+#[should_panic(expected = "listed twice")]
+fn repeated_index_panics() {
+    let m = DMatrix::<u8>::identity(2, 2);
+    nested_rank_profile(&m, &[0, 0], &[0, 1]);
+}
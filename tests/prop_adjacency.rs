@@ -1,7 +1,7 @@
 // Property-based tests for the adjacency module
 use nalgebra::DMatrix;
 use nalgebra_block_triangularization::adjacency::{
-    build_row_adjacency, build_row_dependency_graph,
+    build_col_dependency_graph, build_row_adjacency, build_row_dependency_graph,
 };
 use nalgebra_block_triangularization::matching::hopcroft_karp;
 use proptest::prelude::*;
@@ -192,4 +192,38 @@ proptest! {
             }
         }
     }
+
+    /// Property: The column dependency graph is the row graph reversed under the matching
+    /// Edge j -> l exists iff row(l) -> row(j) does, for matched columns j and l.
+    #[test]
+    fn col_dependency_mirrors_row_dependency((nrows, ncols, m) in arbitrary_matrix(20, 20)) {
+        let row_adj = build_row_adjacency(&m);
+        let col_adj: Vec<Vec<usize>> = (0..ncols)
+            .map(|j| (0..nrows).filter(|&i| m[(i, j)] != 0).collect())
+            .collect();
+        let matching = hopcroft_karp(&row_adj, ncols);
+        let row_graph = build_row_dependency_graph(&row_adj, &matching.col_to_row);
+        let col_graph = build_col_dependency_graph(&col_adj, &matching.row_to_col);
+
+        prop_assert_eq!(col_graph.len(), ncols);
+        for (j, edges) in col_graph.iter().enumerate() {
+            prop_assert!(edges.windows(2).all(|w| w[0] < w[1]));
+            prop_assert!(!edges.contains(&j));
+            for &l in edges {
+                let rl = matching.col_to_row[l].unwrap();
+                match matching.col_to_row[j] {
+                    Some(rj) => prop_assert!(row_graph[rl].contains(&rj)),
+                    None => prop_assert!(m[(rl, j)] != 0),
+                }
+            }
+        }
+        // Unmatched rows have no column counterpart.
+        for (rl, edges) in row_graph.iter().enumerate() {
+            let Some(l) = matching.row_to_col[rl] else { continue };
+            for &rj in edges {
+                let j = matching.row_to_col[rj].unwrap();
+                prop_assert!(col_graph[j].contains(&l));
+            }
+        }
+    }
 }
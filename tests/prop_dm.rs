@@ -0,0 +1,63 @@
+// Property-based tests for the Dulmage–Mendelsohn decomposition
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::adjacency::build_row_adjacency;
+use nalgebra_block_triangularization::dm::dulmage_mendelsohn;
+use nalgebra_block_triangularization::hall::hall_violator;
+use nalgebra_block_triangularization::matching::hopcroft_karp;
+use nalgebra_block_triangularization::permutation::is_valid_permutation;
+use proptest::prelude::*;
+
+proptest! {
+    /// Property: The coarse/fine order is a pair of permutations making the pattern block
+    /// upper triangular, and the parts account for the structural rank
+    #[test]
+    fn dm_order_is_block_upper_triangular(
+        (nrows, ncols, bits) in (1usize..8, 1usize..8)
+            .prop_flat_map(|(r, c)| (Just(r), Just(c), prop::collection::vec(prop::bool::weighted(0.3), r * c))),
+    ) {
+        let m = DMatrix::from_row_slice(nrows, ncols, &bits.iter().map(|&b| u8::from(b)).collect::<Vec<_>>());
+        let dm = dulmage_mendelsohn(&m);
+        prop_assert!(is_valid_permutation(&dm.row_order()) && dm.row_order().len() == nrows);
+        prop_assert!(is_valid_permutation(&dm.col_order()) && dm.col_order().len() == ncols);
+        prop_assert_eq!(dm.structural_rank(), hopcroft_karp(&build_row_adjacency(&m), ncols).size);
+        prop_assert!(dm.under.rows.len() < dm.under.cols.len() || dm.under.cols.is_empty());
+        prop_assert!(dm.over.cols.len() < dm.over.rows.len() || dm.over.rows.is_empty());
+
+        let last = dm.square.block_sizes.len() + 1;
+        let (mut row_part, mut col_part) = (vec![0; nrows], vec![0; ncols]);
+        for (b, (rows, cols)) in dm.square.blocks().into_iter().enumerate() {
+            rows.iter().for_each(|&i| row_part[i] = b + 1);
+            cols.iter().for_each(|&j| col_part[j] = b + 1);
+        }
+        dm.over.rows.iter().for_each(|&i| row_part[i] = last);
+        dm.over.cols.iter().for_each(|&j| col_part[j] = last);
+        for i in 0..nrows {
+            for j in 0..ncols {
+                if m[(i, j)] != 0 {
+                    prop_assert!(col_part[j] >= row_part[i], "entry ({}, {}) below the diagonal", i, j);
+                }
+            }
+        }
+    }
+
+    /// Property: A Hall violator exists iff some row is unmatched, verifies against the
+    /// pattern, and lies inside the overdetermined part
+    #[test]
+    fn hall_violator_certifies_unmatched_rows(
+        (nrows, ncols, bits) in (1usize..8, 1usize..8)
+            .prop_flat_map(|(r, c)| (Just(r), Just(c), prop::collection::vec(prop::bool::weighted(0.3), r * c))),
+    ) {
+        let m = DMatrix::from_row_slice(nrows, ncols, &bits.iter().map(|&b| u8::from(b)).collect::<Vec<_>>());
+        let rank = hopcroft_karp(&build_row_adjacency(&m), ncols).size;
+        let over = dulmage_mendelsohn(&m).over;
+        match hall_violator(&m) {
+            None => prop_assert_eq!(rank, nrows),
+            Some(v) => {
+                prop_assert!(rank < nrows);
+                prop_assert!(v.verify(&m));
+                prop_assert_eq!(v.deficiency(), 1);
+                prop_assert!(v.rows.iter().all(|i| over.rows.contains(i)));
+            }
+        }
+    }
+}
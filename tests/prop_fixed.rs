@@ -0,0 +1,29 @@
+// Property-based tests for the fixed-size, allocation-free analysis
+use nalgebra::SMatrix;
+use nalgebra_block_triangularization::fixed::upper_block_triangular_structure_fixed;
+use nalgebra_block_triangularization::{
+    BtfOptions, MatchingAlgorithm, upper_block_triangular_structure_with_options,
+};
+use proptest::prelude::*;
+
+proptest! {
+    /// Property: The fixed-size path reproduces the dynamic analysis with MC21 matching
+    #[test]
+    fn fixed_equals_dynamic_mc21(bits in prop::collection::vec(prop::bool::weighted(0.3), 49)) {
+        let m = SMatrix::<u8, 7, 7>::from_row_slice(
+            &bits.iter().map(|&b| u8::from(b)).collect::<Vec<_>>(),
+        );
+        let options = BtfOptions {
+            matching: MatchingAlgorithm::Mc21,
+            ..Default::default()
+        };
+        let fixed = upper_block_triangular_structure_fixed(&m).to_structure();
+        let dynamic = upper_block_triangular_structure_with_options(&m, &options);
+        prop_assert_eq!(fixed.row_order, dynamic.row_order);
+        prop_assert_eq!(fixed.col_order, dynamic.col_order);
+        prop_assert_eq!(fixed.block_sizes, dynamic.block_sizes);
+        prop_assert_eq!(fixed.matching_size, dynamic.matching_size);
+        prop_assert_eq!(fixed.block_dag, dynamic.block_dag);
+        prop_assert_eq!(fixed.row_to_col, dynamic.row_to_col);
+    }
+}
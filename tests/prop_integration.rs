@@ -1,7 +1,16 @@
 // Property-based integration tests for the main library
-use nalgebra::DMatrix;
+use nalgebra::{DMatrix, DVector};
+use nalgebra_block_triangularization::analysis::BtfAnalysis;
+use nalgebra_block_triangularization::arrowhead::arrowhead_structure;
+use nalgebra_block_triangularization::canonical::{
+    canonical_structure, canonical_structure_with_options,
+};
+use nalgebra_block_triangularization::session::BtfSession;
+use nalgebra_block_triangularization::supervariable::supervariable_block_triangular_structure;
 use nalgebra_block_triangularization::{
-    upper_block_triangular_structure, upper_triangular_permutations,
+    BlockOrder, BtfOptions, MatchingAlgorithm, UnmatchedPlacement, UpperBtfStructure, has_strong_hall_property, is_irreducible,
+    is_structurally_nonsingular, upper_block_triangular_structure,
+    upper_block_triangular_structure_with_options, upper_triangular_permutations,
 };
 use proptest::prelude::*;
 
@@ -23,6 +32,16 @@ fn arbitrary_matrix(
     })
 }
 
+/// Row sets of the diagonal blocks, each sorted, independent of the block order.
+fn sorted_block_rows(s: &UpperBtfStructure) -> Vec<Vec<usize>> {
+    let mut sets = s.solve_order_blocks().0;
+    for set in &mut sets {
+        set.sort_unstable();
+    }
+    sets.sort();
+    sets
+}
+
 proptest! {
     /// Property: Row and column orders are valid permutations
     /// This ensures the structure produces valid orderings.
@@ -79,7 +98,7 @@ proptest! {
         // Create rectangular matrix
         let total = bits.len();
         let nrows = ((total as f64) / 1.5).sqrt() as usize;
-        let ncols = if nrows > 0 { total / nrows } else { 0 };
+        let ncols = total.checked_div(nrows).unwrap_or(0);
         if nrows < 1 || ncols < 1 {
             return Ok(());
         }
@@ -217,4 +236,343 @@ proptest! {
             prop_assert!(!structure.block_sizes.is_empty(), "Positive matching but no blocks");
         }
     }
+
+    /// Property: The block-wise gemv agrees with the plain product for every placement of
+    /// the unmatched rows and columns.
+    #[test]
+    fn gemv_matches_dense_product(
+        (nrows, ncols, m) in arbitrary_matrix(15, 15),
+        placement in prop::sample::select(vec![
+            UnmatchedPlacement::End,
+            UnmatchedPlacement::Start,
+            UnmatchedPlacement::Adjacent,
+        ]),
+    ) {
+        let options = BtfOptions { unmatched: placement, ..Default::default() };
+        let structure = upper_block_triangular_structure_with_options(&m, &options);
+        let a = DMatrix::from_fn(nrows, ncols, |i, j| i64::from(m[(i, j)]) * (1 + i * ncols + j) as i64);
+        let x = DVector::from_fn(ncols, |j, _| 3 - j as i64);
+        prop_assert_eq!(structure.gemv(&a, &x), &a * &x);
+    }
+
+    /// Property: Merged blocks keep the permuted matrix block upper triangular, and every
+    /// merged block but the last reaches the requested size.
+    #[test]
+    fn merged_blocks_stay_upper_block_triangular(
+        (_nrows, _ncols, m) in arbitrary_matrix(15, 15),
+        min_block_size in 0..6usize,
+    ) {
+        let options = BtfOptions { min_block_size, ..Default::default() };
+        let merged = upper_block_triangular_structure_with_options(&m, &options);
+        let plain = upper_block_triangular_structure(&m);
+        prop_assert_eq!(&merged.row_order, &plain.row_order);
+        prop_assert_eq!(&merged.col_order, &plain.col_order);
+        prop_assert_eq!(merged.block_sizes.iter().sum::<usize>(), plain.block_sizes.iter().sum::<usize>());
+        if let Some((_, init)) = merged.block_sizes.split_last() {
+            prop_assert!(init.iter().all(|&s| s >= min_block_size));
+        }
+
+        // No entry reaches back into the column of a row in an earlier block.
+        let row_block = merged.row_blocks();
+        let col_to_row = merged.col_to_row();
+        for i in 0..m.nrows() {
+            for j in 0..m.ncols() {
+                if let Some(k) = col_to_row[j].filter(|_| m[(i, j)] != 0) {
+                    prop_assert!(row_block[i] <= row_block[k], "entry ({}, {})", i, j);
+                }
+            }
+        }
+        for (b, out) in merged.block_dag.iter().enumerate() {
+            prop_assert!(out.iter().all(|&c| c > b && c < merged.num_blocks()));
+        }
+    }
+
+    /// Property: Size-based block orders permute the irreducible blocks (same row sets) and
+    /// keep every block DAG edge forward.
+    #[test]
+    fn size_block_orders_permute_blocks(
+        (_nrows, _ncols, m) in arbitrary_matrix(15, 15),
+        largest in any::<bool>(),
+    ) {
+        let block_order = if largest { BlockOrder::LargestFirst } else { BlockOrder::SmallestFirst };
+        let options = BtfOptions { block_order, ..Default::default() };
+        let sized = upper_block_triangular_structure_with_options(&m, &options);
+        let plain = upper_block_triangular_structure(&m);
+        let row_sets = |s: &nalgebra_block_triangularization::UpperBtfStructure| {
+            let mut sets: Vec<Vec<usize>> = s.solve_order_blocks().0;
+            for set in &mut sets {
+                set.sort_unstable();
+            }
+            sets.sort();
+            sets
+        };
+        prop_assert_eq!(row_sets(&sized), row_sets(&plain));
+        for (b, out) in sized.block_dag.iter().enumerate() {
+            prop_assert!(out.iter().all(|&c| c > b));
+        }
+    }
+
+    /// Property: The minimal-displacement order permutes the irreducible blocks, keeps every
+    /// block DAG edge forward, and of two consecutive blocks without an edge between them puts
+    /// the one with the smaller mean original index (rows and matched columns) first.
+    #[test]
+    fn min_displacement_order_sorts_unconstrained_neighbours(
+        (_nrows, _ncols, m) in arbitrary_matrix(15, 15),
+    ) {
+        let options = BtfOptions { block_order: BlockOrder::MinDisplacement, ..Default::default() };
+        let displaced = upper_block_triangular_structure_with_options(&m, &options);
+        let plain = upper_block_triangular_structure(&m);
+        prop_assert_eq!(sorted_block_rows(&displaced), sorted_block_rows(&plain));
+
+        let blocks = displaced.solve_order_blocks().0;
+        // (sum of indices, count, smallest row) per block.
+        let mean: Vec<(usize, usize, usize)> = blocks
+            .iter()
+            .map(|rows| {
+                let cols: Vec<usize> =
+                    rows.iter().filter_map(|&r| displaced.row_to_col[r]).collect();
+                let sum = rows.iter().sum::<usize>() + cols.iter().sum::<usize>();
+                let key = *rows.iter().min().unwrap();
+                (sum, rows.len() + cols.len(), key)
+            })
+            .collect();
+        for (b, out) in displaced.block_dag.iter().enumerate() {
+            prop_assert!(out.iter().all(|&c| c > b));
+            if b + 1 < blocks.len() && !out.contains(&(b + 1)) {
+                let ((sa, na, ka), (sb, nb, kb)) = (mean[b], mean[b + 1]);
+                prop_assert!((sa * nb, ka) <= (sb * na, kb), "blocks {} and {}", b, b + 1);
+            }
+        }
+    }
+
+    /// Property: The adjacent-coupling order permutes the irreducible blocks and keeps every
+    /// block DAG edge forward.
+    #[test]
+    fn adjacent_coupling_order_permutes_blocks(
+        (_nrows, _ncols, m) in arbitrary_matrix(15, 15),
+    ) {
+        let options = BtfOptions { block_order: BlockOrder::AdjacentCoupling, ..Default::default() };
+        let clustered = upper_block_triangular_structure_with_options(&m, &options);
+        let plain = upper_block_triangular_structure(&m);
+        prop_assert_eq!(sorted_block_rows(&clustered), sorted_block_rows(&plain));
+        for (b, out) in clustered.block_dag.iter().enumerate() {
+            prop_assert!(out.iter().all(|&c| c > b));
+        }
+    }
+
+    /// Property: The canonical matching is the lexicographically smallest perfect matching,
+    /// whichever matching algorithm found the partition.
+    #[test]
+    fn canonical_matching_is_lexicographic_minimum(
+        bits in prop::collection::vec(any::<u8>(), 1..49)
+    ) {
+        // Square, two thirds dense, so most patterns are structurally nonsingular.
+        let n = (bits.len() as f64).sqrt() as usize;
+        let bits: Vec<u8> = bits.into_iter().take(n * n).map(|b| u8::from(b % 3 != 0)).collect();
+        let m = DMatrix::from_row_slice(n, n, &bits);
+        let hk = canonical_structure(&m).unwrap();
+        let mc21 = canonical_structure_with_options(
+            &m,
+            &BtfOptions { matching: MatchingAlgorithm::Mc21, ..Default::default() },
+        )
+        .unwrap();
+        if hk.matching_size < n {
+            return Ok(());
+        }
+        prop_assert_eq!(&hk.row_order, &mc21.row_order);
+        prop_assert_eq!(&hk.col_order, &mc21.col_order);
+
+        fn smallest(m: &DMatrix<u8>, r: usize, used: &mut [bool], out: &mut Vec<usize>) -> bool {
+            if r == m.nrows() {
+                return true;
+            }
+            for c in 0..m.ncols() {
+                if m[(r, c)] != 0 && !used[c] {
+                    used[c] = true;
+                    out.push(c);
+                    if smallest(m, r + 1, used, out) {
+                        return true;
+                    }
+                    out.pop();
+                    used[c] = false;
+                }
+            }
+            false
+        }
+        let mut expected = Vec::new();
+        prop_assert!(smallest(&m, 0, &mut vec![false; m.ncols()], &mut expected));
+        let found: Vec<usize> = hk.row_to_col.iter().map(|c| c.unwrap()).collect();
+        prop_assert_eq!(found, expected);
+    }
+
+    /// Property: Arrowhead blocks respect the size limit, and no entry couples two different
+    /// blocks outside the border.
+    #[test]
+    fn arrowhead_blocks_are_decoupled(
+        (nrows, ncols, m) in arbitrary_matrix(12, 12),
+        max_block_size in 1..5usize,
+    ) {
+        let a = arrowhead_structure(&m, max_block_size);
+        let mut rows = a.row_order.clone();
+        rows.sort_unstable();
+        prop_assert_eq!(rows, (0..nrows).collect::<Vec<_>>());
+        let mut cols = a.col_order.clone();
+        cols.sort_unstable();
+        prop_assert_eq!(cols, (0..ncols).collect::<Vec<_>>());
+
+        let mut row_block = vec![None; nrows];
+        let mut col_block = vec![None; ncols];
+        for (b, block) in a.blocks.iter().enumerate() {
+            prop_assert!(block.rows.len() <= max_block_size && block.cols.len() <= max_block_size);
+            for &i in &block.rows {
+                row_block[i] = Some(b);
+            }
+            for &j in &block.cols {
+                col_block[j] = Some(b);
+            }
+        }
+        for i in 0..nrows {
+            for j in 0..ncols {
+                if m[(i, j)] != 0 && row_block[i].is_some() && col_block[j].is_some() {
+                    prop_assert_eq!(row_block[i], col_block[j], "entry ({}, {})", i, j);
+                }
+            }
+        }
+    }
+
+    /// Property: Capping the block size keeps the block partition and the matching, and
+    /// every split block has core blocks within the cap.
+    #[test]
+    fn capped_blocks_are_bordered(
+        (_nrows, _ncols, m) in arbitrary_matrix(15, 15),
+        max_block_size in 1..5usize,
+    ) {
+        let options = BtfOptions { max_block_size, ..Default::default() };
+        let capped = upper_block_triangular_structure_with_options(&m, &options);
+        let plain = upper_block_triangular_structure(&m);
+        prop_assert_eq!(&capped.block_sizes, &plain.block_sizes);
+        prop_assert_eq!(&capped.row_blocks(), &plain.row_blocks());
+        prop_assert_eq!(&capped.row_to_col, &plain.row_to_col);
+        for split in &capped.block_splits {
+            prop_assert!(split.core_block_sizes.iter().all(|&b| b <= max_block_size));
+            prop_assert_eq!(split.core_size() + split.border_size, plain.block_sizes[split.block]);
+        }
+        let split_blocks: Vec<usize> = capped.block_splits.iter().map(|s| s.block).collect();
+        for (b, &size) in plain.block_sizes.iter().enumerate() {
+            if size > max_block_size && capped.matching_size == m.nrows() {
+                prop_assert!(split_blocks.contains(&b));
+            }
+        }
+        // Matched pairs stay aligned where the plain structure aligns them.
+        for (p, (&r, &c)) in plain.row_order.iter().zip(&plain.col_order).enumerate() {
+            if plain.row_to_col[r] == Some(c) {
+                prop_assert_eq!(capped.row_to_col[capped.row_order[p]], Some(capped.col_order[p]));
+            }
+        }
+    }
+
+    /// Property: The predicates agree with the full analysis
+    #[test]
+    fn predicates_match_the_structure((nrows, ncols, m) in arbitrary_matrix(8, 8)) {
+        let s = upper_block_triangular_structure(&m);
+        let nonsingular = nrows == ncols && s.matching_size == nrows;
+        prop_assert_eq!(is_structurally_nonsingular(&m), nonsingular);
+        prop_assert_eq!(is_irreducible(&m), nonsingular && s.block_sizes.len() == 1);
+    }
+
+    /// Property: The strong Hall test agrees with the definition on every column subset
+    #[test]
+    fn strong_hall_matches_the_definition((nrows, ncols, m) in arbitrary_matrix(7, 5)) {
+        let rows_of = |subset: usize| {
+            (0..nrows)
+                .filter(|&i| (0..ncols).any(|j| subset >> j & 1 == 1 && m[(i, j)] != 0))
+                .count()
+        };
+        let all = (1usize << ncols) - 1;
+        let expected = nrows >= ncols
+            && rows_of(all) >= ncols
+            && (1..all).all(|subset| rows_of(subset) > subset.count_ones() as usize);
+        prop_assert_eq!(has_strong_hall_property(&m), expected);
+        if nrows == ncols {
+            prop_assert_eq!(has_strong_hall_property(&m), is_irreducible(&m));
+        }
+    }
+
+    /// Property: Reordering a cached analysis gives the full pipeline's structure
+    #[test]
+    fn cached_analysis_reorders_like_the_pipeline(
+        (_nrows, _ncols, m) in arbitrary_matrix(12, 12),
+        block_order in prop_oneof![
+            Just(BlockOrder::MinRow),
+            Just(BlockOrder::MinCoupling),
+            Just(BlockOrder::LargestFirst),
+            Just(BlockOrder::AdjacentCoupling),
+        ],
+        unmatched in prop_oneof![
+            Just(UnmatchedPlacement::End),
+            Just(UnmatchedPlacement::Start),
+            Just(UnmatchedPlacement::Adjacent),
+        ],
+        max_block_size in 0..4usize,
+    ) {
+        let options = BtfOptions { block_order, unmatched, max_block_size, ..Default::default() };
+        let reordered = BtfAnalysis::new(&m, &BtfOptions::default()).structure(&options).unwrap();
+        let full = upper_block_triangular_structure_with_options(&m, &options);
+        prop_assert_eq!(reordered.row_order, full.row_order);
+        prop_assert_eq!(reordered.col_order, full.col_order);
+        prop_assert_eq!(reordered.block_sizes, full.block_sizes);
+        prop_assert_eq!(reordered.block_splits.len(), full.block_splits.len());
+    }
+
+    /// Property: A warm-started session step agrees with a fresh analysis
+    #[test]
+    fn session_update_matches_fresh_analysis(
+        (nrows, ncols, m) in arbitrary_matrix(10, 10),
+        toggles in prop::collection::vec((0..10usize, 0..10usize), 1..6),
+    ) {
+        let mut session = BtfSession::new(&m, BtfOptions::default()).unwrap();
+        let mut next = m.clone();
+        for (i, j) in toggles {
+            next[(i % nrows, j % ncols)] ^= 1;
+        }
+        let (structure, change) = session.update(&next).unwrap();
+        let fresh = upper_block_triangular_structure(&next);
+        prop_assert_eq!(structure.matching_size, fresh.matching_size);
+        if fresh.matching_size == nrows && nrows == ncols {
+            // The block partition of a nonsingular pattern does not depend on the matching.
+            let blocks = |s: &nalgebra_block_triangularization::UpperBtfStructure| {
+                let mut rows = s.solve_order_blocks().0;
+                rows.iter_mut().for_each(|b| b.sort_unstable());
+                rows.sort();
+                rows
+            };
+            prop_assert_eq!(blocks(structure), blocks(&fresh));
+        }
+        prop_assert!(change.unchanged_blocks <= structure.num_blocks());
+    }
+
+    /// Property: The supervariable-compressed matching is maximum and gives the same blocks
+    #[test]
+    fn supervariable_structure_matches_plain_analysis(
+        (_, _, base) in arbitrary_matrix(5, 5),
+        row_picks in prop::collection::vec(0..5usize, 1..10),
+        col_picks in prop::collection::vec(0..5usize, 1..10),
+    ) {
+        // Repeat rows and columns of a small pattern to create indistinguishable ones.
+        let m = DMatrix::from_fn(row_picks.len(), col_picks.len(), |i, j| {
+            base[(row_picks[i] % base.nrows(), col_picks[j] % base.ncols())]
+        });
+        let grouped = supervariable_block_triangular_structure(&m, &BtfOptions::default()).unwrap();
+        let plain = upper_block_triangular_structure(&m);
+        prop_assert_eq!(grouped.structure.matching_size, plain.matching_size);
+        if plain.matching_size == m.nrows() && m.nrows() == m.ncols() {
+            let blocks = |s: &nalgebra_block_triangularization::UpperBtfStructure| {
+                let mut rows = s.solve_order_blocks().0;
+                rows.iter_mut().for_each(|b| b.sort_unstable());
+                rows.sort();
+                rows
+            };
+            prop_assert_eq!(blocks(&grouped.structure), blocks(&plain));
+        }
+    }
 }
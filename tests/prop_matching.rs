@@ -1,8 +1,127 @@
 // Property-based tests for the matching module (Hopcroft-Karp algorithm)
-use nalgebra_block_triangularization::matching::hopcroft_karp;
+use nalgebra_block_triangularization::matching::{
+    enumerate_maximum_matchings, hopcroft_karp, maximum_independent_set, mc21,
+    minimum_vertex_cover, priority_matching,
+};
 use proptest::prelude::*;
 
+/// Number of matchings of exactly `size` edges, by brute force.
+fn count_matchings_of_size(adj: &[Vec<usize>], row: usize, size: usize, used: &mut Vec<bool>) -> usize {
+    if size == 0 {
+        return 1;
+    }
+    if adj.len() - row < size {
+        return 0;
+    }
+    let mut count = count_matchings_of_size(adj, row + 1, size, used);
+    for &j in &adj[row] {
+        if !used[j] {
+            used[j] = true;
+            count += count_matchings_of_size(adj, row + 1, size - 1, used);
+            used[j] = false;
+        }
+    }
+    count
+}
+
+/// Best (size, per-class counts from the highest class down) over all matchings, by brute force.
+fn best_priority_profile(adj: &[Vec<(usize, u8)>], row: usize, used: &mut Vec<bool>) -> (usize, [usize; 4]) {
+    if row == adj.len() {
+        return (0, [0; 4]);
+    }
+    let mut best = best_priority_profile(adj, row + 1, used);
+    for &(j, p) in &adj[row] {
+        if !used[j] {
+            used[j] = true;
+            let (size, mut counts) = best_priority_profile(adj, row + 1, used);
+            used[j] = false;
+            counts[3 - p as usize] += 1;
+            best = best.max((size + 1, counts));
+        }
+    }
+    best
+}
+
 proptest! {
+    /// Property: Enumeration yields every maximum matching exactly once
+    #[test]
+    fn enumeration_finds_all_maximum_matchings(
+        adj in prop::collection::vec(prop::collection::btree_set(0..5usize, 0..4), 0..6)
+    ) {
+        let adj: Vec<Vec<usize>> = adj.into_iter().map(|s| s.into_iter().collect()).collect();
+        let size = hopcroft_karp(&adj, 5).size;
+        let all = enumerate_maximum_matchings(&adj, 5, usize::MAX);
+
+        let mut seen: Vec<Vec<Option<usize>>> = Vec::new();
+        for m in &all {
+            prop_assert_eq!(m.size, size);
+            prop_assert_eq!(m.row_to_col.iter().flatten().count(), size);
+            for (i, c) in m.row_to_col.iter().enumerate() {
+                if let Some(j) = *c {
+                    prop_assert!(adj[i].contains(&j));
+                    prop_assert_eq!(m.col_to_row[j], Some(i));
+                }
+            }
+            seen.push(m.row_to_col.clone());
+        }
+        seen.sort();
+        seen.dedup();
+        prop_assert_eq!(seen.len(), all.len());
+        prop_assert_eq!(all.len(), count_matchings_of_size(&adj, 0, size, &mut vec![false; 5]));
+    }
+
+    /// Property: Priority matching is maximum, then lexicographically best per class
+    /// Checked against exhaustive enumeration on small graphs.
+    #[test]
+    fn priority_matching_matches_brute_force(
+        adj in prop::collection::vec(prop::collection::vec((0..5usize, 0..4u8), 0..4), 0..6)
+    ) {
+        let mut weighted: Vec<Vec<(usize, u8)>> = adj;
+        for edges in &mut weighted {
+            edges.sort_unstable();
+            edges.dedup_by_key(|e| e.0);
+        }
+        let plain: Vec<Vec<usize>> = weighted.iter().map(|e| e.iter().map(|&(j, _)| j).collect()).collect();
+        let class = |i: usize, j: usize| weighted[i].iter().find(|e| e.0 == j).unwrap().1;
+        let m = priority_matching(&plain, 5, class);
+
+        let mut counts = [0usize; 4];
+        for (i, c) in m.row_to_col.iter().enumerate() {
+            if let Some(j) = *c {
+                prop_assert_eq!(m.col_to_row[j], Some(i));
+                counts[3 - class(i, j) as usize] += 1;
+            }
+        }
+        let expected = best_priority_profile(&weighted, 0, &mut vec![false; 5]);
+        prop_assert_eq!((m.size, counts), expected);
+    }
+
+    /// Property: MC21 finds a valid maximum matching
+    /// Its transversal may differ from Hopcroft-Karp, but never its size.
+    #[test]
+    fn mc21_is_maximum_and_consistent(
+        (n_right, adj) in (1..20usize).prop_flat_map(|n_right| {
+            prop::collection::vec(prop::collection::vec(0..n_right, 0..6), 1..20)
+                .prop_map(move |mut adj| {
+                    for edges in &mut adj {
+                        edges.sort_unstable();
+                        edges.dedup();
+                    }
+                    (n_right, adj)
+                })
+        })
+    ) {
+        let m = mc21(&adj, n_right);
+        prop_assert_eq!(m.size, hopcroft_karp(&adj, n_right).size);
+        prop_assert_eq!(m.row_to_col.iter().flatten().count(), m.size);
+        for (i, &opt_j) in m.row_to_col.iter().enumerate() {
+            if let Some(j) = opt_j {
+                prop_assert!(adj[i].contains(&j));
+                prop_assert_eq!(m.col_to_row[j], Some(i));
+            }
+        }
+    }
+
     /// Property: Every matched edge must exist in the adjacency list
     /// This verifies the matching algorithm only selects edges that actually exist in the graph.
     #[test]
@@ -197,4 +316,25 @@ proptest! {
 
         prop_assert!(matching.size <= n_left.min(n_right));
     }
+
+    /// Property: Kőnig's cover touches every entry with as many vertices as the matching has
+    /// edges, and its complement is independent
+    #[test]
+    fn konig_cover_and_independent_set(
+        adj in prop::collection::vec(prop::collection::btree_set(0..6usize, 0..4), 0..8)
+    ) {
+        let adj: Vec<Vec<usize>> = adj.into_iter().map(|s| s.into_iter().collect()).collect();
+        for m in [hopcroft_karp(&adj, 6), mc21(&adj, 6)] {
+            let cover = minimum_vertex_cover(&m, &adj);
+            let independent = maximum_independent_set(&m, &adj);
+            prop_assert_eq!(cover.len(), m.size);
+            prop_assert_eq!(cover.len() + independent.len(), adj.len() + 6);
+            for (i, cols) in adj.iter().enumerate() {
+                for j in cols {
+                    prop_assert!(cover.rows.contains(&i) || cover.cols.contains(j));
+                    prop_assert!(!(independent.rows.contains(&i) && independent.cols.contains(j)));
+                }
+            }
+        }
+    }
 }
@@ -32,13 +32,29 @@ fn arbitrary_dag(size_range: std::ops::Range<usize>) -> impl Strategy<Value = Ve
 }
 
 proptest! {
+    /// Property: Bucket-queue and heap paths agree
+    /// Scaling keys preserves their order but forces the heap fallback.
+    #[test]
+    fn topo_bucket_matches_heap(
+        dag in arbitrary_dag(1..60),
+        raw_keys in prop::collection::vec(0usize..8, 60)
+    ) {
+        let n = dag.len();
+        let key: Vec<usize> = raw_keys[..n].to_vec();
+        let scaled: Vec<usize> = key.iter().map(|&k| k * 1_000_000).collect();
+        prop_assert_eq!(
+            topo_sort_with_tiebreak(&dag, &key),
+            topo_sort_with_tiebreak(&dag, &scaled)
+        );
+    }
+
     /// Property: Topological sort respects edge ordering
     /// For every edge u → v, u must appear before v in the topological order.
     #[test]
     fn topo_respects_edges(dag in arbitrary_dag(1..30)) {
         let n = dag.len();
         let key: Vec<usize> = (0..n).collect();
-        let order = topo_sort_with_tiebreak(&dag, &key);
+        let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
 
         // Order should contain all nodes
         prop_assert_eq!(order.len(), n, "Topo sort returned wrong number of nodes");
@@ -67,7 +83,7 @@ proptest! {
     fn topo_is_permutation(dag in arbitrary_dag(1..30)) {
         let n = dag.len();
         let key: Vec<usize> = (0..n).collect();
-        let order = topo_sort_with_tiebreak(&dag, &key);
+        let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
 
         // Should contain all nodes
         let mut sorted = order.clone();
@@ -82,14 +98,14 @@ proptest! {
         let n = dag.len();
         // Use reverse order as keys to test tie-breaking
         let key: Vec<usize> = (0..n).map(|i| n - i).collect();
-        let order = topo_sort_with_tiebreak(&dag, &key);
+        let order = topo_sort_with_tiebreak(&dag, &key).unwrap();
 
         prop_assert_eq!(order.len(), n, "Topo sort returned wrong number of nodes");
 
         // Build in-degree tracking to verify tie-breaking
         let mut indeg = vec![0; n];
-        for u in 0..n {
-            for &v in &dag[u] {
+        for out in &dag {
+            for &v in out {
                 indeg[v] += 1;
             }
         }
@@ -116,8 +132,7 @@ proptest! {
         let row_to_col: Vec<Option<usize>> = matching_data
             .iter()
             .take(n)
-            .enumerate()
-            .map(|(_i, &val)| {
+            .map(|&val| {
                 if val % 3 == 0 {
                     None // Some rows unmatched
                 } else {
@@ -165,10 +180,9 @@ proptest! {
         let num_matched = row_to_col.iter().filter(|x| x.is_some()).count();
 
         // First num_matched columns in col_order should be matched columns
-        for i in 0..num_matched.min(col_order.len()) {
-            let col = col_order[i];
+        for (i, &col) in col_order.iter().enumerate().take(num_matched) {
             // Check if this column is matched to some row
-            let is_matched = row_to_col.iter().any(|&mc| mc == Some(col));
+            let is_matched = row_to_col.contains(&Some(col));
             prop_assert!(
                 is_matched,
                 "Column {} at position {} is unmatched but appears before matched columns",
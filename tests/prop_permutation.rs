@@ -1,6 +1,9 @@
 // Property-based tests for the permutation module
 use nalgebra::DMatrix;
-use nalgebra_block_triangularization::permutation::permutation_sequence_from_order;
+use nalgebra_block_triangularization::permutation::{
+    cycle_notation, one_line_notation, parse_cycle_notation, parse_one_line_notation,
+    permutation_sequence_from_order,
+};
 use proptest::prelude::*;
 
 // Helper to apply permutation to vector
@@ -73,4 +76,11 @@ proptest! {
 
         prop_assert_eq!(result, input);
     }
+
+    /// Property: Both string notations parse back to the permutation they were printed from
+    #[test]
+    fn notations_round_trip(perm_vec in Just((0..12usize).collect::<Vec<usize>>()).prop_shuffle()) {
+        prop_assert_eq!(parse_one_line_notation(&one_line_notation(&perm_vec)), Ok(perm_vec.clone()));
+        prop_assert_eq!(parse_cycle_notation(&cycle_notation(&perm_vec), perm_vec.len()), Ok(perm_vec));
+    }
 }
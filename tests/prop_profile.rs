@@ -0,0 +1,21 @@
+// Property-based tests for incremental rank profiles
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::profile::{nested_rank_profile, submatrix_rank};
+use proptest::prelude::*;
+
+proptest! {
+    /// Property: Every prefix rank of the incremental profile equals a fresh maximum matching
+    #[test]
+    fn profile_matches_fresh_matchings(
+        bits in prop::collection::vec(prop::bool::weighted(0.25), 48),
+        rows in Just((0..6).collect::<Vec<usize>>()).prop_shuffle(),
+        cols in Just((0..8).collect::<Vec<usize>>()).prop_shuffle(),
+    ) {
+        let m = DMatrix::from_row_slice(6, 8, &bits.iter().map(|&b| u8::from(b)).collect::<Vec<_>>());
+        let profile = nested_rank_profile(&m, &rows, &cols);
+        prop_assert_eq!(profile.len(), 6);
+        for (k, &rank) in profile.iter().enumerate() {
+            prop_assert_eq!(rank, submatrix_rank(&m, &rows[..=k], &cols[..=k]));
+        }
+    }
+}
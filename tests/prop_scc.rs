@@ -1,5 +1,7 @@
 // Property-based tests for the SCC module (Tarjan's algorithm)
-use nalgebra_block_triangularization::scc::{condensation_dag, scc_id_map, tarjan_scc};
+use nalgebra_block_triangularization::scc::{
+    condensation_dag, scc_id_map, tarjan_scc, trimmed_tarjan_scc,
+};
 use proptest::prelude::*;
 
 /// Generate arbitrary directed graphs
@@ -172,4 +174,31 @@ proptest! {
             prop_assert!(!edges.contains(&i), "Self-loop at component {}", i);
         }
     }
+
+    /// Property: Trimming finds the same components as plain Tarjan, still in reverse
+    /// topological order (every edge leaving a component points to an earlier one).
+    #[test]
+    fn trimmed_matches_tarjan(graph in arbitrary_directed_graph(1..30)) {
+        let trimmed = trimmed_tarjan_scc(&graph);
+        let normalize = |sccs: &[Vec<usize>]| {
+            let mut sets: Vec<Vec<usize>> = sccs
+                .iter()
+                .map(|c| {
+                    let mut c = c.clone();
+                    c.sort_unstable();
+                    c
+                })
+                .collect();
+            sets.sort();
+            sets
+        };
+        prop_assert_eq!(normalize(&trimmed), normalize(&tarjan_scc(&graph)));
+
+        let comp_of = scc_id_map(&trimmed, graph.len());
+        for (u, out) in graph.iter().enumerate() {
+            for &v in out {
+                prop_assert!(comp_of[v] <= comp_of[u], "edge {} -> {} points forward", u, v);
+            }
+        }
+    }
 }
@@ -58,7 +58,7 @@ proptest! {
         // Create rectangular matrix
         let total = bits.len();
         let nrows = ((total as f64) / 1.5).sqrt() as usize;
-        let ncols = if nrows > 0 { total / nrows } else { 0 };
+        let ncols = total.checked_div(nrows).unwrap_or(0);
         if nrows < 1 || ncols < 1 {
             return Ok(());
         }
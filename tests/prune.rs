@@ -0,0 +1,52 @@
+use nalgebra::{Complex, DMatrix};
+use nalgebra_block_triangularization::prune::{
+    DropTolerance, PruneReport, pruned_block_triangular_structure, pruned_row_adjacency,
+};
+use nalgebra_block_triangularization::{BtfOptions, upper_block_triangular_structure};
+
+fn coupled() -> DMatrix<f64> {
+    // Rows 0 and 1 form a 2x2 block only through the tiny entry (1, 0).
+    DMatrix::from_row_slice(3, 3, &[
+        2.0, 1.0, 0.0,
+        1e-12, 3.0, 0.0,
+        0.0, -1e-9, 4.0,
+    ])
+}
+
+#[test]
+fn pruning_is_counted_and_changes_the_structure() {
+    let m = coupled();
+    assert_eq!(upper_block_triangular_structure(&m).block_sizes, vec![1, 2]);
+
+    let drop = DropTolerance { tolerance: 1e-8, record_entries: false };
+    let pruned = pruned_block_triangular_structure(&m, &drop, &BtfOptions::default()).unwrap();
+    assert_eq!(pruned.pruned, PruneReport { count: 2, entries: None });
+    assert_eq!(pruned.structure.block_sizes, vec![1, 1, 1]);
+}
+
+#[test]
+fn pruned_entries_are_listed_on_request() {
+    let m = coupled();
+    let drop = DropTolerance { tolerance: 1e-10, record_entries: true };
+    let (adj, report) = pruned_row_adjacency(&m, &drop);
+    assert_eq!(adj, vec![vec![0, 1], vec![1], vec![1, 2]]);
+    assert_eq!(report.count, 1);
+    assert_eq!(report.entries, Some(vec![(1, 0)]));
+}
+
+#[test]
+fn zero_tolerance_prunes_nothing() {
+    let m = coupled();
+    let (adj, report) = pruned_row_adjacency(&m, &DropTolerance::default());
+    assert_eq!(report, PruneReport { count: 0, entries: None });
+    assert_eq!(adj, nalgebra_block_triangularization::adjacency::build_row_adjacency(&m));
+}
+
+#[test]
+fn complex_entries_are_pruned_by_modulus() {
+    let m = DMatrix::from_row_slice(1, 2, &[Complex::new(3e-7, 4e-7), Complex::new(0.0, 1.0)]);
+    let drop = DropTolerance { tolerance: 6e-7, record_entries: true };
+    let (adj, report) = pruned_row_adjacency(&m, &drop);
+    assert_eq!(adj, vec![vec![1]]);
+    assert_eq!(report.entries, Some(vec![(0, 0)]));
+}
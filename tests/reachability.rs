@@ -0,0 +1,179 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::reachability::{
+    block_reachability, cols_influenced_by_row, inverse_entry_pattern, inverse_pattern,
+    rows_influenced_by_col, solution_support,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+/// Dense inverse of a 0/1 pattern with "generic" values, used as a structural oracle.
+fn generic_inverse_pattern(m: &DMatrix<u8>) -> DMatrix<bool> {
+    let n = m.nrows();
+    let a = DMatrix::from_fn(n, n, |i, j| {
+        if m[(i, j)] != 0 {
+            1.0 + ((i * 7 + j * 13) % 11) as f64 / 7.0
+        } else {
+            0.0
+        }
+    });
+    let inv = a.try_inverse().expect("generic values should be invertible");
+    inv.map(|x| x.abs() > 1e-12)
+}
+
+#[test]
+fn reachability_chain() {
+    // 0 -> 1 -> 2
+    let dag = vec![vec![1], vec![2], vec![]];
+    let reach = block_reachability(&dag);
+    assert_eq!(
+        reach,
+        vec![
+            vec![true, true, true],
+            vec![false, true, true],
+            vec![false, false, true],
+        ]
+    );
+}
+
+#[test]
+fn reachability_independent_blocks() {
+    let dag = vec![vec![], vec![]];
+    let reach = block_reachability(&dag);
+    assert_eq!(reach, vec![vec![true, false], vec![false, true]]);
+}
+
+#[test]
+fn inverse_pattern_of_block_diagonal_is_diagonal() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1u8, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let pattern = inverse_pattern(&structure);
+    assert_eq!(pattern, DMatrix::from_row_slice(2, 2, &[true, false, false, true]));
+
+    let entries = inverse_entry_pattern(&structure);
+    assert_eq!(entries, m.map(|x| x != 0));
+}
+
+#[test]
+fn inverse_pattern_of_triangular_is_upper_full() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1u8, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let pattern = inverse_pattern(&structure);
+    assert_eq!(structure.block_sizes, vec![1, 1, 1]);
+    for a in 0..3 {
+        for b in 0..3 {
+            assert_eq!(pattern[(a, b)], a <= b);
+        }
+    }
+}
+
+#[test]
+fn inverse_entry_pattern_matches_numeric_inverse() {
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1u8, 1, 0, 0, 0, 0,
+        1, 1, 0, 0, 0, 0,
+        1, 0, 1, 1, 0, 0,
+        0, 1, 1, 1, 0, 0,
+        0, 0, 1, 0, 1, 1,
+        0, 0, 0, 1, 1, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let entries = inverse_entry_pattern(&structure);
+    assert_eq!(entries, generic_inverse_pattern(&m));
+}
+
+#[test]
+fn inverse_pattern_empty() {
+    let m: DMatrix<u8> = DMatrix::zeros(0, 0);
+    let structure = upper_block_triangular_structure(&m);
+    assert_eq!(inverse_pattern(&structure).nrows(), 0);
+    assert_eq!(inverse_entry_pattern(&structure).shape(), (0, 0));
+}
+
+#[test]
+fn influence_queries_on_chain() {
+    // Row 0 uses x0, x1; row 1 uses x1, x2; row 2 uses x2.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1u8, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+
+    // x2 is solved from row 2 and consumed (transitively) by all rows.
+    assert_eq!(rows_influenced_by_col(&structure, 2), vec![0, 1, 2]);
+    // x0 is only involved in row 0.
+    assert_eq!(rows_influenced_by_col(&structure, 0), vec![0]);
+
+    // Equation 2 determines x2, which feeds x1 and x0.
+    assert_eq!(cols_influenced_by_row(&structure, 2), vec![0, 1, 2]);
+    assert_eq!(cols_influenced_by_row(&structure, 0), vec![0]);
+}
+
+#[test]
+fn influence_queries_agree_with_inverse_pattern() {
+    let m = DMatrix::from_row_slice(6, 6, &[
+        1u8, 1, 0, 0, 0, 0,
+        1, 1, 0, 0, 0, 0,
+        1, 0, 1, 1, 0, 0,
+        0, 1, 1, 1, 0, 0,
+        0, 0, 1, 0, 1, 1,
+        0, 0, 0, 1, 1, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let inv = inverse_entry_pattern(&structure);
+
+    for i in 0..6 {
+        let expected: Vec<usize> = (0..6).filter(|&j| inv[(j, i)]).collect();
+        assert_eq!(cols_influenced_by_row(&structure, i), expected);
+    }
+}
+
+#[test]
+fn influence_of_unmatched_column_is_empty() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1u8, 0, 1,
+        0, 1, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let unmatched = structure.col_order[2];
+    assert!(rows_influenced_by_col(&structure, unmatched).is_empty());
+}
+
+#[test]
+fn solution_support_is_union_of_influenced_cols() {
+    // Chain of singletons 0 -> 1 -> 2 plus an independent block {3}.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(solution_support(&s, &[2]), vec![0, 1, 2]);
+    assert_eq!(solution_support(&s, &[0]), vec![0]);
+    assert_eq!(solution_support(&s, &[0, 3]), vec![0, 3]);
+    assert!(solution_support(&s, &[]).is_empty());
+
+    let pattern = inverse_entry_pattern(&s);
+    for rows in [vec![1], vec![1, 3], vec![0, 2], vec![0, 1, 2, 3]] {
+        let mut expected: Vec<usize> = rows
+            .iter()
+            .flat_map(|&i| cols_influenced_by_row(&s, i))
+            .collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(solution_support(&s, &rows), expected);
+        for j in 0..4 {
+            let nonzero = rows.iter().any(|&i| pattern[(j, i)]);
+            assert_eq!(expected.contains(&j), nonzero);
+        }
+    }
+}
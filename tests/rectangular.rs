@@ -0,0 +1,68 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::rectangular::{RectangularStructure, analyze_rectangular};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+#[test]
+fn square_nonsingular_is_all_core() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 0,
+        0, 1, 1,
+    ]);
+    let r = analyze_rectangular(&m).unwrap();
+    let s = upper_block_triangular_structure(&m);
+    assert!(r.is_square_core());
+    assert_eq!(r.rank(), 3);
+    assert_eq!(r.core_rows, s.row_order);
+    assert_eq!(r.core_cols, s.col_order);
+    assert_eq!(r.block_sizes, s.block_sizes);
+    assert_eq!(r.block_dag, s.block_dag);
+}
+
+#[test]
+fn wide_pattern_has_column_tail() {
+    let m = DMatrix::from_row_slice(2, 4, &[
+        1, 0, 1, 0,
+        0, 1, 0, 1,
+    ]);
+    let r = analyze_rectangular(&m).unwrap();
+    assert_eq!(r.shape(), (2, 4));
+    assert_eq!(r.rank(), 2);
+    assert!(r.unmatched_rows.is_empty());
+    assert_eq!(r.unmatched_cols.len(), 2);
+    for (rows, cols) in r.blocks() {
+        assert_eq!(rows.len(), cols.len());
+        for (&i, &j) in rows.iter().zip(cols) {
+            assert_eq!(m[(i, j)], 1);
+        }
+    }
+}
+
+#[test]
+fn tall_pattern_drops_unmatched_row_blocks() {
+    // Rows 1 and 2 compete for column 1; the surplus row's block is not part of the core.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 1,
+        0, 1,
+        0, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let r = RectangularStructure::from(&s);
+    assert_eq!(s.num_blocks(), 3);
+    assert_eq!(r.block_sizes, vec![1, 1]);
+    assert_eq!(r.unmatched_rows.len(), 1);
+    assert!(r.unmatched_cols.is_empty());
+    assert_eq!(r.shape(), (3, 2));
+    // Row 0 depends on the row matched to column 1.
+    assert_eq!(r.core_rows[0], 0);
+    assert_eq!(r.core_cols, vec![0, 1]);
+    assert_eq!(r.block_dag, vec![vec![1], vec![]]);
+}
+
+#[test]
+fn patterns_without_columns_are_all_tail() {
+    let r = analyze_rectangular(&DMatrix::<u8>::zeros(3, 0)).unwrap();
+    assert_eq!(r.rank(), 0);
+    assert_eq!(r.unmatched_rows, vec![0, 1, 2]);
+    assert!(r.block_sizes.is_empty());
+}
@@ -0,0 +1,53 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::adjacency::build_row_adjacency;
+use nalgebra_block_triangularization::matching::hopcroft_karp;
+use nalgebra_block_triangularization::redundancy::minimal_row_deletion;
+
+fn rank_without(m: &DMatrix<u8>, rows: &[usize]) -> (usize, usize) {
+    let kept: Vec<usize> = (0..m.nrows()).filter(|i| !rows.contains(i)).collect();
+    let sub = m.select_rows(&kept);
+    (hopcroft_karp(&build_row_adjacency(&sub), sub.ncols()).size, kept.len())
+}
+
+#[test]
+fn full_row_rank_needs_no_deletion() {
+    let m = DMatrix::from_row_slice(2, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+    ]);
+    let d = minimal_row_deletion(&m);
+    assert_eq!(d.rank, 2);
+    assert!(d.rows.is_empty());
+    assert!(d.candidates.is_empty());
+}
+
+#[test]
+fn overdetermined_rows_are_candidates() {
+    // Rows 1 and 2 both only determine column 1; row 0 is essential.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        0, 1,
+        0, 1,
+    ]);
+    let d = minimal_row_deletion(&m);
+    assert_eq!(d.rank, 2);
+    assert_eq!(d.rows.len(), 1);
+    assert_eq!(d.candidates, vec![1, 2]);
+    assert!(d.candidates.contains(&d.rows[0]));
+    assert_eq!(rank_without(&m, &d.rows), (2, 2));
+}
+
+#[test]
+fn deficient_square_pattern() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 1, 1, 1,
+    ]);
+    let d = minimal_row_deletion(&m);
+    assert_eq!(d.rank, 3);
+    assert_eq!(d.rows.len(), 1);
+    assert_eq!(d.candidates, vec![0, 1, 2]);
+    assert_eq!(rank_without(&m, &d.rows), (3, 3));
+}
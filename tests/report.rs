@@ -0,0 +1,95 @@
+#![cfg(feature = "html")]
+
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::BtfError;
+use nalgebra_block_triangularization::report::{html_report, html_report_labeled};
+
+#[test]
+fn report_contains_all_sections() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 1,
+        0, 0, 1,
+    ]);
+    let html = html_report(&m, "model <A & B>");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>model &lt;A &amp; B&gt;</title>"));
+    for section in ["Summary", "Permuted pattern", "Blocks", "Block DAG", "Singularity diagnostics"] {
+        assert!(html.contains(&format!("<h2>{section}</h2>")), "missing {section}");
+    }
+    assert!(html.contains("Structurally full rank."));
+    // Two blocks in the table, one DAG edge.
+    assert!(html.contains("<tr><td>0</td><td>2</td><td>0, 1</td><td>0, 1</td><td>1</td><td></td></tr>"));
+    assert_eq!(html.matches("<path ").count(), 1);
+    // One spy cell per nonzero.
+    assert_eq!(html.matches("</rect>").count(), 6);
+}
+
+#[test]
+fn report_shows_singularity_diagnostics() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let html = html_report(&m, "singular");
+    assert!(html.contains("Structural rank 2 of 3."));
+    assert!(html.contains("<tr><th>Overdetermined rows</th><td>0, 1</td></tr>"));
+    assert!(html.contains("<tr><th>Underdetermined columns</th><td>1, 2</td></tr>"));
+}
+
+#[test]
+fn report_is_deterministic_and_handles_empty() {
+    let m = DMatrix::<u8>::zeros(0, 0);
+    let html = html_report(&m, "empty");
+    assert_eq!(html, html_report(&m, "empty"));
+    assert!(html.contains("Structurally full rank."));
+}
+
+#[test]
+fn labeled_report_uses_names() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let rows = ["energy", "mass", "momentum"];
+    let cols = ["T", "p", "v<1>"];
+    let html = html_report_labeled(&m, "labeled", &rows, &cols).unwrap();
+    assert!(html.contains("<title>mass, T</title>"));
+    assert!(html.contains("<tr><th>Overdetermined rows</th><td>energy, mass</td></tr>"));
+    assert!(html.contains("<tr><th>Underdetermined columns</th><td>p, v&lt;1&gt;</td></tr>"));
+
+    assert!(matches!(
+        html_report_labeled(&m, "labeled", &rows[..2], &cols),
+        Err(BtfError::DimensionMismatch { expected: (3, 3), found: (2, 3) })
+    ));
+}
+
+#[test]
+fn tall_matrix_report_lists_matched_columns_per_block() {
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        0, 1,
+        1, 1,
+    ]);
+    let html = html_report(&m, "tall");
+    assert!(html.contains("<tr><th>Shape</th><td>3 x 2</td></tr>"));
+    assert!(html.contains("<tr><th>Structural rank</th><td>2</td></tr>"));
+    // Every column shows up in exactly one block row of the table; the surplus row's block
+    // has none.
+    let table = &html[html.find("<h2>Blocks</h2>").unwrap()..html.find("<h2>Block DAG</h2>").unwrap()];
+    assert_eq!(table.matches("<tr><td>").count(), 3);
+    assert!(table.contains("<td>1</td><td>2</td><td></td>"));
+    // Block outlines advance by rows and columns separately.
+    let outlines: Vec<&str> = html.lines().filter(|line| line.contains("stroke=\"#d60\"")).collect();
+    let cell = 600 / 3;
+    assert_eq!(outlines.len(), 3);
+    assert!(outlines[1].starts_with(&format!("<rect x=\"0\" y=\"{cell}\" width=\"{cell}\"")));
+    assert!(outlines[2].starts_with(&format!("<rect x=\"{cell}\" y=\"{}\"", 2 * cell)));
+
+    let labeled = html_report_labeled(&m, "tall", &["a", "b", "c"], &["x", "y"]).unwrap();
+    let table = &labeled[labeled.find("<h2>Blocks</h2>").unwrap()..labeled.find("<h2>Block DAG</h2>").unwrap()];
+    assert_eq!(table.matches("<tr><td>").count(), 3);
+    assert!(table.contains("<td>1</td><td>c</td><td></td>"));
+}
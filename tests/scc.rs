@@ -1,4 +1,6 @@
-use nalgebra_block_triangularization::scc::{condensation_dag, scc_id_map, tarjan_scc};
+use nalgebra_block_triangularization::scc::{
+    condensation_dag, condense, condense_trimmed, scc_id_map, tarjan_scc, trimmed_tarjan_scc,
+};
 
 #[test]
 fn scc_empty_graph() {
@@ -89,16 +91,12 @@ fn scc_complex_graph() {
         .iter()
         .find(|scc| scc.contains(&0))
         .unwrap()
-        .iter()
-        .copied()
-        .collect();
+        .to_vec();
     let scc_with_2: Vec<_> = sccs
         .iter()
         .find(|scc| scc.contains(&2))
         .unwrap()
-        .iter()
-        .copied()
-        .collect();
+        .to_vec();
 
     let mut sorted_0 = scc_with_0.clone();
     sorted_0.sort();
@@ -258,3 +256,49 @@ fn scc_tarjan_order_independence() {
     scc_sizes.sort();
     assert_eq!(scc_sizes, vec![2, 3]);
 }
+
+#[test]
+fn condense_combines_stages() {
+    // 0 <-> 1 -> 2
+    let graph = vec![vec![1], vec![0, 2], vec![]];
+    let c = condense(&graph);
+    assert_eq!(c.sccs, tarjan_scc(&graph));
+    assert_eq!(c.comp_of, scc_id_map(&c.sccs, 3));
+    assert_eq!(c.dag, condensation_dag(&graph, &c.comp_of, c.sccs.len()));
+    assert_eq!(c.dag[c.comp_of[0]], vec![c.comp_of[2]]);
+}
+
+#[test]
+fn trimmed_scc_peels_sources_and_sinks() {
+    // 0 -> 1 <-> 2 -> 3, plus an isolated node 4.
+    let graph = vec![vec![1], vec![2], vec![1, 3], vec![], vec![]];
+    let sccs = trimmed_tarjan_scc(&graph);
+    assert_eq!(sccs.len(), 4);
+    assert_eq!(sccs[..2], [vec![4], vec![3]]);
+    let mut core = sccs[2].clone();
+    core.sort_unstable();
+    assert_eq!(core, vec![1, 2]);
+    assert_eq!(sccs[3], vec![0]);
+}
+
+#[test]
+fn trimmed_scc_keeps_self_loops_in_core() {
+    let graph = vec![vec![0, 1], vec![]];
+    assert_eq!(trimmed_tarjan_scc(&graph), vec![vec![1], vec![0]]);
+}
+
+#[test]
+fn condense_trimmed_matches_condense_up_to_relabeling() {
+    let graph = vec![vec![1], vec![0, 2], vec![3], vec![2], vec![0]];
+    let plain = condense(&graph);
+    let trimmed = condense_trimmed(&graph);
+    assert_eq!(plain.sccs.len(), trimmed.sccs.len());
+    for u in 0..graph.len() {
+        for v in 0..graph.len() {
+            assert_eq!(
+                plain.comp_of[u] == plain.comp_of[v],
+                trimmed.comp_of[u] == trimmed.comp_of[v]
+            );
+        }
+    }
+}
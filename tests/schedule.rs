@@ -0,0 +1,98 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::schedule::schedule;
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+fn example() -> DMatrix<u8> {
+    // Block {0,1} couples to {2} and {3}; {2} and {3} are independent.
+    DMatrix::from_row_slice(4, 4, &[
+        1, 1, 1, 0,
+        1, 1, 0, 1,
+        0, 0, 1, 0,
+        0, 0, 0, 1,
+    ])
+}
+
+#[test]
+fn schedule_blocks_and_levels() {
+    let s = upper_block_triangular_structure(&example());
+    let sched = schedule(&s);
+    assert_eq!(sched.blocks.len(), 3);
+    assert_eq!(sched.blocks[0].rows, vec![0, 1]);
+    assert_eq!(sched.blocks[0].depends_on, vec![1, 2]);
+    assert!(sched.blocks[1].depends_on.is_empty());
+    assert!(sched.blocks[2].depends_on.is_empty());
+    assert_eq!(sched.levels, vec![vec![1, 2], vec![0]]);
+    for (id, block) in sched.blocks.iter().enumerate() {
+        assert_eq!(block.id, id);
+        assert_eq!(block.rows.len(), block.cols.len());
+    }
+}
+
+#[test]
+fn schedule_levels_respect_dependencies() {
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let sched = schedule(&upper_block_triangular_structure(&m));
+    assert_eq!(sched.levels, vec![vec![3], vec![2], vec![1], vec![0]]);
+    let mut level_of = vec![0; sched.blocks.len()];
+    for (l, blocks) in sched.levels.iter().enumerate() {
+        for &b in blocks {
+            level_of[b] = l;
+        }
+    }
+    for block in &sched.blocks {
+        for &d in &block.depends_on {
+            assert!(level_of[d] < level_of[block.id]);
+        }
+    }
+}
+
+#[test]
+fn schedule_empty() {
+    let sched = schedule(&upper_block_triangular_structure(&DMatrix::<u8>::zeros(0, 0)));
+    assert!(sched.blocks.is_empty());
+    assert!(sched.levels.is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn schedule_json_round_trip() {
+    let sched = schedule(&upper_block_triangular_structure(&example()));
+    let json = serde_json::to_string(&sched).unwrap();
+    let back: nalgebra_block_triangularization::schedule::Schedule =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(back, sched);
+}
+
+#[test]
+fn schedule_cols_are_the_matched_columns_of_each_block() {
+    // Tall: row 2 is surplus and forms a block without columns.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        0, 1,
+        1, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let sched = schedule(&s);
+    let cols: Vec<Vec<usize>> = sched.blocks.iter().map(|b| b.cols.clone()).collect();
+    assert_eq!(cols, vec![vec![], vec![0], vec![1]]);
+
+    // Square but singular: column 2 is free and belongs to no block.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 1,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let sched = schedule(&s);
+    for block in &sched.blocks {
+        let matched: Vec<usize> = block.rows.iter().filter_map(|&r| s.row_to_col[r]).collect();
+        assert_eq!(block.cols, matched);
+    }
+    let scheduled: usize = sched.blocks.iter().map(|b| b.cols.len()).sum();
+    assert_eq!(scheduled, s.matching_size);
+}
@@ -0,0 +1,94 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::adjacency::build_row_adjacency;
+use nalgebra_block_triangularization::permutation::is_valid_permutation;
+use nalgebra_block_triangularization::scramble::{
+    ScrambleMismatch, random_permutation, scramble, scramble_row_adjacency, scramble_self_check,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+fn three_blocks() -> DMatrix<u8> {
+    DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 1, 0,
+        1, 1, 0, 0, 0,
+        0, 0, 1, 1, 1,
+        0, 0, 0, 1, 0,
+        0, 0, 1, 0, 1,
+    ])
+}
+
+#[test]
+fn permutations_are_deterministic() {
+    let p = random_permutation(20, 7);
+    assert!(is_valid_permutation(&p));
+    assert_eq!(p, random_permutation(20, 7));
+    assert_ne!(p, random_permutation(20, 8));
+    assert_eq!(random_permutation(0, 7), Vec::<usize>::new());
+}
+
+#[test]
+fn scrambled_entries_follow_the_returned_orders() {
+    let m = three_blocks();
+    let s = scramble(&m, 42);
+    assert!(is_valid_permutation(&s.row_order));
+    assert!(is_valid_permutation(&s.col_order));
+    for i in 0..5 {
+        for j in 0..5 {
+            assert_eq!(s.matrix[(i, j)], m[(s.row_order[i], s.col_order[j])]);
+        }
+    }
+}
+
+#[test]
+fn pattern_scrambling_matches_matrix_scrambling() {
+    let m = three_blocks();
+    let dense = scramble(&m, 3);
+    let pattern = scramble_row_adjacency(&build_row_adjacency(&m), 5, 3);
+    assert_eq!(pattern.row_order, dense.row_order);
+    assert_eq!(pattern.col_order, dense.col_order);
+    assert_eq!(pattern.matrix, build_row_adjacency(&dense.matrix));
+}
+
+#[test]
+fn unscrambled_structure_is_a_form_of_the_original() {
+    let m = three_blocks();
+    let s = scramble(&m, 11);
+    let structure = s.unscramble_structure(&upper_block_triangular_structure(&s.matrix));
+    assert_eq!(structure.block_sizes, upper_block_triangular_structure(&m).block_sizes);
+    for (r, c) in structure.matched_pairs() {
+        assert_ne!(m[(r, c)], 0);
+    }
+    // Permuted by the unscrambled orders, the original is block upper triangular.
+    let blocks = structure.row_blocks();
+    let col_blocks = structure.col_blocks();
+    for r in 0..5 {
+        for c in 0..5 {
+            if m[(r, c)] != 0 {
+                assert!(blocks[r] <= col_blocks[c].unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn self_check_passes_for_many_seeds() {
+    let m = three_blocks();
+    for seed in 0..20 {
+        assert_eq!(scramble_self_check(&m, seed), Ok(()));
+    }
+    let singular = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        0, 0, 1,
+        0, 0, 1,
+    ]);
+    assert_eq!(scramble_self_check(&singular, 5), Ok(()));
+}
+
+#[test]
+fn mismatch_messages() {
+    let rank = ScrambleMismatch::StructuralRank { original: 3, scrambled: 2 };
+    assert_eq!(rank.to_string(), "structural rank 2 after scrambling, 3 before");
+    assert_eq!(
+        ScrambleMismatch::Blocks.to_string(),
+        "scrambling changed the diagonal blocks"
+    );
+}
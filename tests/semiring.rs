@@ -0,0 +1,71 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::adjacency::build_row_adjacency;
+use nalgebra_block_triangularization::semiring::{
+    ExactZero, StructuralSemiring, Threshold, block_triangular_structure_over, row_adjacency_over,
+};
+use nalgebra_block_triangularization::{BtfOptions, upper_block_triangular_structure};
+
+#[test]
+fn exact_zero_matches_the_default_pattern() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1.0, 0.0, 2.0,
+        0.0, 3.0, 0.0,
+        4.0, 0.0, 0.0,
+    ]);
+    assert_eq!(row_adjacency_over(&m, &ExactZero), build_row_adjacency(&m));
+    let over = block_triangular_structure_over(&m, &ExactZero, &BtfOptions::default()).unwrap();
+    let plain = upper_block_triangular_structure(&m);
+    assert_eq!(over.row_order, plain.row_order);
+    assert_eq!(over.col_order, plain.col_order);
+    assert_eq!(over.block_sizes, plain.block_sizes);
+}
+
+#[test]
+fn probabilities_above_threshold() {
+    // Probability that each entry is nonzero; only (1, 0) is unlikely.
+    let p = DMatrix::from_row_slice(2, 2, &[
+        0.9, 0.6,
+        0.05, 1.0,
+    ]);
+    let semiring = Threshold { threshold: 0.5 };
+    assert_eq!(row_adjacency_over(&p, &semiring), vec![vec![0, 1], vec![1]]);
+    let s = block_triangular_structure_over(&p, &semiring, &BtfOptions::default()).unwrap();
+    assert_eq!(s.block_sizes, vec![1, 1]);
+}
+
+#[test]
+fn symbolic_entries_through_a_closure() {
+    // Nonzero iff the expression is not identically zero.
+    let e = |s: &str| s.to_string();
+    let m = DMatrix::from_row_slice(2, 2, &[
+        e("x - x"), e("y"),
+        e("2*x"), e("0"),
+    ]);
+    let not_identically_zero = |s: &String| s != "0" && s != "x - x";
+    assert!(not_identically_zero.is_nonzero(&e("y")));
+    assert_eq!(row_adjacency_over(&m, &not_identically_zero), vec![vec![1], vec![0]]);
+    let s = block_triangular_structure_over(&m, &not_identically_zero, &BtfOptions::default())
+        .unwrap();
+    assert_eq!(s.matching_size, 2);
+    assert!(s.is_fully_triangular());
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+#[test]
+fn intervals_under_exact_zero_and_a_custom_test() {
+    let iv = |lo, hi| Interval { lo, hi };
+    let m = DMatrix::from_row_slice(2, 2, &[
+        iv(1.0, 2.0), iv(-1.0, 1.0),
+        iv(0.0, 0.0), iv(0.5, 3.0),
+    ]);
+    // Only the degenerate [0, 0] is a structural zero.
+    assert_eq!(row_adjacency_over(&m, &ExactZero), vec![vec![0, 1], vec![1]]);
+    // Stricter: nonzero only if the interval excludes zero.
+    let excludes_zero = |x: &Interval| x.lo > 0.0 || x.hi < 0.0;
+    assert_eq!(row_adjacency_over(&m, &excludes_zero), vec![vec![0], vec![1]]);
+}
@@ -0,0 +1,134 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::session::{BlockEvent, BtfSession, StructureChange};
+use nalgebra_block_triangularization::{BtfError, BtfOptions};
+
+fn two_blocks() -> DMatrix<u8> {
+    // Blocks {0, 1} and {2, 3}, coupled only from the first to the second.
+    DMatrix::from_row_slice(4, 4, &[
+        1, 1, 1, 0,
+        1, 1, 0, 0,
+        0, 0, 1, 1,
+        0, 0, 1, 1,
+    ])
+}
+
+#[test]
+fn closing_a_cycle_merges_blocks_and_opening_it_splits_them() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    assert_eq!(session.structure().block_sizes, vec![2, 2]);
+
+    let mut coupled = two_blocks();
+    coupled[(2, 0)] = 1;
+    let (structure, change) = session.update(&coupled).unwrap();
+    assert_eq!(structure.block_sizes, vec![4]);
+    assert_eq!(change.added_entries, 1);
+    assert_eq!(change.removed_entries, 0);
+    assert_eq!(change.rematched_rows, 0);
+    assert_eq!(change.merged, vec![(0, vec![0, 1])]);
+    assert!(change.split.is_empty());
+    assert_eq!(change.unchanged_blocks, 0);
+    assert!(!change.is_partition_unchanged());
+
+    let (structure, change) = session.update(&two_blocks()).unwrap();
+    assert_eq!(structure.block_sizes, vec![2, 2]);
+    assert_eq!(change.removed_entries, 1);
+    assert_eq!(change.split, vec![(0, vec![0, 1])]);
+    assert!(change.merged.is_empty());
+}
+
+#[test]
+fn removing_a_matched_entry_repairs_the_matching() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    let before = session.matching().row_to_col.clone();
+
+    // Drop the entry row 0 is matched to; the matching must move, the blocks stay.
+    let mut next = two_blocks();
+    let (r, c) = (0, before[0].unwrap());
+    next[(r, c)] = 0;
+    let (structure, change) = session.update(&next).unwrap();
+    assert_eq!(structure.matching_size, 4);
+    assert!(change.rematched_rows >= 1);
+    assert_ne!(session.matching().row_to_col[0], Some(c));
+    assert!(session.structure().block_sizes.iter().sum::<usize>() == 4);
+}
+
+#[test]
+fn unchanged_pattern_reports_no_change() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    let values = two_blocks() * 3;
+    let (_, change) = session.update(&values).unwrap();
+    assert_eq!(change, StructureChange { unchanged_blocks: 2, ..Default::default() });
+    assert!(change.is_partition_unchanged());
+}
+
+#[test]
+fn losing_rank_leaves_rows_unmatched() {
+    let mut session = BtfSession::new(&DMatrix::<u8>::identity(3, 3), BtfOptions::default()).unwrap();
+    let mut next = DMatrix::<u8>::identity(3, 3);
+    next[(2, 2)] = 0;
+    next[(2, 0)] = 1;
+    let (structure, change) = session.update(&next).unwrap();
+    assert_eq!(structure.matching_size, 2);
+    assert_eq!(structure.unmatched_rows().len(), 1);
+    assert_eq!(change.rematched_rows, 1);
+}
+
+#[test]
+fn shape_change_is_an_error() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    let err = session.update(&DMatrix::<u8>::identity(3, 3)).unwrap_err();
+    assert!(matches!(
+        err,
+        BtfError::DimensionMismatch { expected: (4, 4), found: (3, 3) }
+    ));
+}
+
+#[test]
+fn merge_events_name_blocks_by_identifier() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    assert_eq!(session.structure().block_ids(), vec![0, 2]);
+
+    let mut coupled = two_blocks();
+    coupled[(2, 0)] = 1;
+    let (_, change) = session.update(&coupled).unwrap();
+    assert_eq!(change.events, vec![
+        BlockEvent::Merged { blocks: vec![0, 2], into: 0 },
+        BlockEvent::RowsMoved { rows: vec![0, 1], from: 0, to: 0 },
+        BlockEvent::RowsMoved { rows: vec![2, 3], from: 2, to: 0 },
+    ]);
+    assert_eq!(change.stale_blocks(), vec![0]);
+
+    let (_, change) = session.update(&two_blocks()).unwrap();
+    assert_eq!(change.events[0], BlockEvent::Split { block: 0, into: vec![0, 2] });
+    assert_eq!(change.stale_blocks(), vec![0, 2]);
+}
+
+#[test]
+fn entries_within_kept_blocks_only_invalidate_those_blocks() {
+    let mut session = BtfSession::new(&two_blocks(), BtfOptions::default()).unwrap();
+    let mut next = two_blocks();
+    next[(0, 3)] = 1;
+    let (_, change) = session.update(&next).unwrap();
+    assert!(change.is_partition_unchanged());
+    assert_eq!(change.unchanged_blocks, 2);
+    assert_eq!(change.events, vec![BlockEvent::Modified { block: 0 }]);
+    assert_eq!(change.stale_blocks(), vec![0]);
+}
+
+#[test]
+fn repaired_matching_avoids_forbidden_entries() {
+    let options = BtfOptions {
+        forbidden: vec![(0, 0), (2, 2)],
+        ..Default::default()
+    };
+    let mut session = BtfSession::new(&DMatrix::from_element(3, 3, 1u8), options).unwrap();
+    let mut next = DMatrix::from_element(3, 3, 1u8);
+    next[(1, 1)] = 0;
+    next[(0, 2)] = 0;
+    let (structure, _) = session.update(&next).unwrap();
+    assert_eq!(structure.matching_size, 3);
+    for (r, c) in structure.matched_pairs() {
+        assert_ne!(next[(r, c)], 0);
+        assert!(![(0, 0), (2, 2)].contains(&(r, c)));
+    }
+}
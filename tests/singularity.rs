@@ -0,0 +1,70 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::dm::DmPart;
+use nalgebra_block_triangularization::singularity::diagnose_singularity;
+
+#[test]
+fn nonsingular_pattern() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 1,
+    ]);
+    let report = diagnose_singularity(&m);
+    assert!(report.is_well_determined());
+    assert_eq!(report.structural_rank, 2);
+    assert!(report.unmatched_rows.is_empty() && report.unmatched_cols.is_empty());
+    assert_eq!(report.well_determined, DmPart { rows: vec![0, 1], cols: vec![0, 1] });
+    assert_eq!(report.to_string(), "structural rank 2 of a 2 x 2 pattern: structurally nonsingular");
+}
+
+#[test]
+fn over_and_underdetermined_subsystems() {
+    // Rows 0 and 1 only constrain column 0; row 2 involves columns 1 and 2.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 1, 1,
+    ]);
+    let report = diagnose_singularity(&m);
+    assert!(!report.is_well_determined());
+    assert_eq!(report.structural_rank, 2);
+    assert_eq!(report.overdetermined, DmPart { rows: vec![0, 1], cols: vec![0] });
+    assert_eq!(report.underdetermined, DmPart { rows: vec![2], cols: vec![1, 2] });
+    assert!(report.well_determined.rows.is_empty());
+    assert_eq!(report.unmatched_rows.len(), 1);
+    assert!(report.overdetermined.rows.contains(&report.unmatched_rows[0]));
+    assert_eq!(report.unmatched_cols.len(), 1);
+    assert!(report.underdetermined.cols.contains(&report.unmatched_cols[0]));
+
+    let text = report.to_string();
+    assert!(text.starts_with("structural rank 2 of a 3 x 3 pattern\n"));
+    assert!(text.contains("overdetermined: 2 equations (rows 0, 1) in 1 unknown (column 0)"));
+    assert!(text.contains("underdetermined: 1 equation (row 2) in 2 unknowns (columns 1, 2)"));
+    assert!(text.contains("unmatched rows: "));
+    assert!(text.contains("unmatched columns: "));
+}
+
+#[test]
+fn parts_cover_every_row_and_column() {
+    let m = DMatrix::from_row_slice(4, 5, &[
+        1, 1, 0, 0, 0,
+        0, 1, 0, 0, 0,
+        0, 0, 1, 1, 0,
+        0, 0, 0, 0, 0,
+    ]);
+    let report = diagnose_singularity(&m);
+    let mut rows: Vec<usize> = [&report.overdetermined, &report.underdetermined, &report.well_determined]
+        .iter()
+        .flat_map(|p| p.rows.iter().copied())
+        .collect();
+    rows.sort_unstable();
+    assert_eq!(rows, vec![0, 1, 2, 3]);
+    let mut cols: Vec<usize> = [&report.overdetermined, &report.underdetermined, &report.well_determined]
+        .iter()
+        .flat_map(|p| p.cols.iter().copied())
+        .collect();
+    cols.sort_unstable();
+    assert_eq!(cols, vec![0, 1, 2, 3, 4]);
+    // The empty row is an equation without unknowns.
+    assert_eq!(report.unmatched_rows, vec![3]);
+    assert!(report.to_string().contains("overdetermined: 1 equation (row 3) in 0 unknowns"));
+}
@@ -0,0 +1,103 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::snapshot::{
+    BtfSnapshot, SNAPSHOT_VERSION, SnapshotError, StructureClass,
+};
+use nalgebra_block_triangularization::{
+    BtfError, BtfOptions, upper_block_triangular_structure,
+    upper_block_triangular_structure_with_options,
+};
+
+fn example() -> DMatrix<u8> {
+    DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 1,
+        0, 0, 1,
+    ])
+}
+
+#[test]
+fn round_trips_through_the_structure() {
+    let s = upper_block_triangular_structure(&example());
+    let snap = BtfSnapshot::from_structure(&s);
+    assert_eq!(snap.version, SNAPSHOT_VERSION);
+    assert_eq!((snap.nrows, snap.ncols), (3, 3));
+    assert_eq!(snap.class, StructureClass::Reducible);
+    assert_eq!(snap, BtfSnapshot::from(&s));
+
+    let back = snap.to_structure().unwrap();
+    assert_eq!(back.row_order, s.row_order);
+    assert_eq!(back.col_order, s.col_order);
+    assert_eq!(back.block_sizes, s.block_sizes);
+    assert_eq!(back.block_dag, s.block_dag);
+    assert_eq!(back.row_to_col, s.row_to_col);
+    assert_eq!(back.matching_size, s.matching_size);
+}
+
+#[test]
+fn classifies_patterns() {
+    let class = |m: &DMatrix<u8>| BtfSnapshot::from_structure(&upper_block_triangular_structure(m)).class;
+    assert_eq!(class(&DMatrix::zeros(0, 0)), StructureClass::Empty);
+    assert_eq!(class(&DMatrix::zeros(2, 0)), StructureClass::Empty);
+    assert_eq!(class(&DMatrix::from_element(2, 3, 1)), StructureClass::Rectangular);
+    assert_eq!(class(&DMatrix::from_element(2, 2, 1)), StructureClass::Irreducible);
+    assert_eq!(class(&DMatrix::identity(3, 3)), StructureClass::Triangular);
+    assert_eq!(class(&example()), StructureClass::Reducible);
+    let singular = DMatrix::from_row_slice(2, 2, &[
+        1, 1,
+        0, 0,
+    ]);
+    assert_eq!(class(&singular), StructureClass::Singular);
+}
+
+#[test]
+fn amalgamated_blocks_are_stored() {
+    let options = BtfOptions { min_block_size: 3, ..Default::default() };
+    let s = upper_block_triangular_structure_with_options(&example(), &options);
+    let snap = BtfSnapshot::from_structure(&s);
+    assert_eq!(snap.block_sizes, vec![3]);
+    assert_eq!(snap.class, StructureClass::Irreducible);
+    snap.validate().unwrap();
+}
+
+#[test]
+fn rejects_unknown_versions_and_inconsistent_fields() {
+    let snap = BtfSnapshot::from_structure(&upper_block_triangular_structure(&example()));
+
+    let future = BtfSnapshot { version: SNAPSHOT_VERSION + 1, ..snap.clone() };
+    assert_eq!(
+        future.validate(),
+        Err(SnapshotError::UnsupportedVersion { found: SNAPSHOT_VERSION + 1, supported: SNAPSHOT_VERSION })
+    );
+
+    let bad_order = BtfSnapshot { row_order: vec![0, 0, 1], ..snap.clone() };
+    assert!(matches!(bad_order.validate(), Err(SnapshotError::RowOrder(_))));
+    let short = BtfSnapshot { col_order: vec![0, 1], ..snap.clone() };
+    assert!(matches!(short.validate(), Err(SnapshotError::Inconsistent(_))));
+    let miscounted = BtfSnapshot { matching_size: 2, ..snap.clone() };
+    assert!(matches!(miscounted.validate(), Err(SnapshotError::Inconsistent(_))));
+    let backward = BtfSnapshot { block_dag: vec![vec![], vec![0]], ..snap.clone() };
+    assert!(matches!(backward.validate(), Err(SnapshotError::Inconsistent(_))));
+    let relabeled = BtfSnapshot { class: StructureClass::Triangular, ..snap.clone() };
+    assert!(matches!(relabeled.to_structure(), Err(SnapshotError::Inconsistent(_))));
+
+    let err: BtfError = future.validate().unwrap_err().into();
+    assert!(matches!(err, BtfError::Snapshot(SnapshotError::UnsupportedVersion { .. })));
+}
+
+/// Pins the version 1 layout: a change here breaks archived snapshots.
+#[cfg(feature = "serde")]
+#[test]
+fn version_1_json_layout() {
+    let snap = BtfSnapshot::from_structure(&upper_block_triangular_structure(&example()));
+    let json = serde_json::to_string(&snap).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"version":1,"nrows":3,"ncols":3,"row_order":[0,1,2],"col_order":[0,1,2],"#,
+            r#""block_sizes":[2,1],"block_dag":[[1],[]],"row_to_col":[0,1,2],"#,
+            r#""matching_size":3,"class":"reducible"}"#
+        )
+    );
+    let back: BtfSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, snap);
+}
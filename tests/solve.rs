@@ -0,0 +1,285 @@
+use nalgebra::{DMatrix, DVector};
+use nalgebra_block_triangularization::solve::{
+    BlockFactorKind, BlockLu, BlockSolveOptions, BlockStatus, SolveError, solve_with_report,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+/// Deterministic test matrix: the 6x6 pattern from the integration tests with
+/// diagonally-dominant values, rows and columns scrambled.
+fn test_matrix() -> DMatrix<f64> {
+    let pattern: [[u8; 6]; 6] = [
+        [1, 1, 0, 0, 0, 0],
+        [1, 1, 0, 0, 0, 0],
+        [1, 0, 1, 1, 0, 0],
+        [0, 1, 1, 1, 0, 0],
+        [0, 0, 1, 0, 1, 1],
+        [0, 0, 0, 1, 1, 1],
+    ];
+    let rows = [3, 0, 5, 1, 4, 2];
+    let cols = [2, 5, 0, 4, 1, 3];
+    DMatrix::from_fn(6, 6, |i, j| {
+        let (r, c) = (rows[i], cols[j]);
+        if pattern[r][c] == 0 {
+            0.0
+        } else if r == c {
+            4.0 + r as f64
+        } else {
+            0.5 + 0.1 * (r * 6 + c) as f64
+        }
+    })
+}
+
+fn rhs(n: usize) -> DVector<f64> {
+    DVector::from_fn(n, |i, _| 1.0 + i as f64 * 0.25)
+}
+
+#[test]
+fn solve_matches_dense() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let lu = BlockLu::factor(&a, &structure).unwrap();
+    let b = rhs(6);
+    let x = lu.solve(&b).unwrap();
+    assert!((&a * &x - &b).norm() < 1e-10);
+}
+
+#[test]
+fn solve_transpose_matches_dense() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let lu = BlockLu::factor(&a, &structure).unwrap();
+    let b = rhs(6);
+    let x = lu.solve_transpose(&b).unwrap();
+    assert!((a.transpose() * &x - &b).norm() < 1e-10);
+}
+
+#[test]
+fn solve_transpose_of_triangular() {
+    let a = DMatrix::from_row_slice(3, 3, &[
+        2.0, 1.0, 0.0,
+        0.0, 3.0, 1.0,
+        0.0, 0.0, 4.0,
+    ]);
+    let structure = upper_block_triangular_structure(&a);
+    assert_eq!(structure.block_sizes, vec![1, 1, 1]);
+    let lu = BlockLu::factor(&a, &structure).unwrap();
+    let b = DVector::from_vec(vec![2.0, 4.0, 5.0]);
+    let x = lu.solve_transpose(&b).unwrap();
+    assert!((x - DVector::from_vec(vec![1.0, 1.0, 1.0])).norm() < 1e-12);
+}
+
+#[test]
+fn factor_rejects_rectangular() {
+    let a = DMatrix::from_element(2, 3, 1.0);
+    let structure = upper_block_triangular_structure(&a);
+    assert_eq!(
+        BlockLu::factor(&a, &structure).unwrap_err(),
+        SolveError::NotSquare { nrows: 2, ncols: 3 }
+    );
+}
+
+#[test]
+fn factor_rejects_structurally_singular() {
+    let a = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 0.0, 0.0]);
+    let structure = upper_block_triangular_structure(&a);
+    assert!(matches!(
+        BlockLu::factor(&a, &structure),
+        Err(SolveError::StructurallySingular { matching_size: 1, n: 2 })
+    ));
+}
+
+#[test]
+fn factor_rejects_numerically_singular_block() {
+    let a = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+    let structure = upper_block_triangular_structure(&a);
+    assert_eq!(
+        BlockLu::factor(&a, &structure).unwrap_err(),
+        SolveError::SingularBlock { block: 0 }
+    );
+}
+
+#[test]
+fn factor_detects_pattern_mismatch() {
+    let upper = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 0.0, 1.0]);
+    let structure = upper_block_triangular_structure(&upper);
+    let lower = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+    assert!(matches!(
+        BlockLu::factor(&lower, &structure),
+        Err(SolveError::PatternMismatch { .. })
+    ));
+}
+
+#[test]
+fn solve_rejects_wrong_rhs_length() {
+    let a: DMatrix<f64> = DMatrix::identity(3, 3);
+    let structure = upper_block_triangular_structure(&a);
+    let lu = BlockLu::factor(&a, &structure).unwrap();
+    assert_eq!(
+        lu.solve(&DVector::zeros(2)).unwrap_err(),
+        SolveError::DimensionMismatch { expected: 3, found: 2 }
+    );
+}
+
+fn qr_options() -> BlockSolveOptions<f64> {
+    BlockSolveOptions {
+        qr_fallback_threshold: 1e-6,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn default_options_use_lu_everywhere() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let lu = BlockLu::factor(&a, &structure).unwrap();
+    assert!(lu.block_factor_kinds().iter().all(|&k| k == BlockFactorKind::Lu));
+}
+
+#[test]
+fn ill_conditioned_block_falls_back_to_qr() {
+    // Block {0,1} is nearly singular; block {2} is benign.
+    let eps = 1e-9;
+    let a = DMatrix::from_row_slice(3, 3, &[
+        1.0, 1.0, 1.0,
+        1.0, 1.0 + eps, 0.0,
+        0.0, 0.0, 2.0,
+    ]);
+    let structure = upper_block_triangular_structure(&a);
+    assert_eq!(structure.block_sizes, vec![2, 1]);
+
+    let lu = BlockLu::factor_with_options(&a, &structure, &qr_options()).unwrap();
+    assert_eq!(
+        lu.block_factor_kinds(),
+        vec![BlockFactorKind::Qr { rank: 2 }, BlockFactorKind::Lu]
+    );
+
+    let b = DVector::from_vec(vec![3.0, 2.0 + eps, 2.0]);
+    let x = lu.solve(&b).unwrap();
+    assert!((&a * &x - &b).norm() < 1e-6);
+    let xt = lu.solve_transpose(&b).unwrap();
+    assert!((a.transpose() * &xt - &b).norm() < 1e-6);
+}
+
+#[test]
+fn rank_deficient_block_solves_consistent_system() {
+    let a = DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+    let structure = upper_block_triangular_structure(&a);
+    let lu = BlockLu::factor_with_options(&a, &structure, &qr_options()).unwrap();
+    assert_eq!(lu.block_factor_kinds(), vec![BlockFactorKind::Qr { rank: 1 }]);
+
+    let b = DVector::from_vec(vec![2.0, 2.0]);
+    let x = lu.solve(&b).unwrap();
+    assert!((&a * &x - &b).norm() < 1e-12);
+    let xt = lu.solve_transpose(&b).unwrap();
+    assert!((a.transpose() * &xt - &b).norm() < 1e-12);
+}
+
+#[test]
+fn qr_fallback_matches_lu_on_well_conditioned_input() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let options = BlockSolveOptions {
+        qr_fallback_threshold: 1.0,
+        ..Default::default()
+    };
+    let qr = BlockLu::factor_with_options(&a, &structure, &options).unwrap();
+    assert!(lu_kinds_all_qr(&qr));
+    let b = rhs(6);
+    assert!((&a * qr.solve(&b).unwrap() - &b).norm() < 1e-10);
+    assert!((a.transpose() * qr.solve_transpose(&b).unwrap() - &b).norm() < 1e-10);
+}
+
+fn lu_kinds_all_qr(lu: &BlockLu<f64>) -> bool {
+    lu.block_factor_kinds()
+        .iter()
+        .all(|k| matches!(k, BlockFactorKind::Qr { .. }))
+}
+
+#[test]
+fn report_of_successful_solve_matches_block_lu() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let b = rhs(6);
+    let report = solve_with_report(&a, &structure, &b, &BlockSolveOptions::default()).unwrap();
+    assert!(report.is_solved());
+    assert!(report.singular_blocks().is_empty());
+    let x = BlockLu::factor(&a, &structure).unwrap().solve(&b).unwrap();
+    assert!((&report.x - x).norm() < 1e-12);
+
+    let (rows, cols) = structure.solve_order_blocks();
+    assert_eq!(report.blocks.len(), structure.num_blocks());
+    for (k, block) in report.blocks.iter().enumerate() {
+        assert_eq!((&block.rows, &block.cols), (&rows[k], &cols[k]));
+        assert_eq!(block.kind, Some(BlockFactorKind::Lu));
+        assert!(block.pivot_growth.unwrap() > 0.0);
+    }
+}
+
+#[test]
+fn report_names_singular_and_blocked_blocks() {
+    // Blocks in solve order {2}, {0, 1}, {3}: {0, 1} is numerically singular, {2} needs its
+    // unknowns, {3} is independent.
+    let a = DMatrix::from_row_slice(4, 4, &[
+        1.0, 1.0, 0.0, 0.0,
+        1.0, 1.0, 0.0, 0.0,
+        1.0, 0.0, 2.0, 0.0,
+        0.0, 0.0, 0.0, 4.0,
+    ]);
+    let structure = upper_block_triangular_structure(&a);
+    assert_eq!(structure.row_order, vec![2, 0, 1, 3]);
+    assert!(matches!(
+        BlockLu::factor(&a, &structure),
+        Err(SolveError::SingularBlock { block: 1 })
+    ));
+
+    let b = DVector::from_vec(vec![1.0, 2.0, 3.0, 8.0]);
+    let report = solve_with_report(&a, &structure, &b, &BlockSolveOptions::default()).unwrap();
+    assert!(!report.is_solved());
+    assert_eq!(report.singular_blocks(), vec![1]);
+    let status: Vec<BlockStatus> = report.blocks.iter().map(|b| b.status).collect();
+    assert_eq!(
+        status,
+        vec![BlockStatus::Blocked { by: 1 }, BlockStatus::Singular, BlockStatus::Solved]
+    );
+    assert_eq!(report.blocks[1].rows, vec![0, 1]);
+    assert_eq!((report.blocks[1].kind, report.blocks[1].pivot_growth), (None, None));
+    assert_eq!(report.blocks[0].kind, Some(BlockFactorKind::Lu));
+    assert_eq!(report.x, DVector::from_vec(vec![0.0, 0.0, 0.0, 2.0]));
+}
+
+#[test]
+fn report_has_no_pivot_growth_for_an_all_zero_block() {
+    // The structure has an entry at (1, 1), but its value is zero; the QR fallback still
+    // factors the block (with rank 0).
+    let structure = upper_block_triangular_structure(&DMatrix::<f64>::identity(2, 2));
+    let a = DMatrix::from_row_slice(2, 2, &[
+        2.0, 0.0,
+        0.0, 0.0,
+    ]);
+    let options = BlockSolveOptions {
+        qr_fallback_threshold: 1e-6,
+        ..Default::default()
+    };
+    let b = DVector::from_vec(vec![2.0, 0.0]);
+    let report = solve_with_report(&a, &structure, &b, &options).unwrap();
+    let zero_block = &report.blocks[1];
+    assert_eq!(zero_block.kind, Some(BlockFactorKind::Qr { rank: 0 }));
+    assert_eq!(zero_block.pivot_growth, None);
+    assert_eq!(report.blocks[0].pivot_growth, Some(1.0));
+}
+
+#[test]
+fn report_rejects_unusable_input() {
+    let a = test_matrix();
+    let structure = upper_block_triangular_structure(&a);
+    let options = BlockSolveOptions::default();
+    assert!(matches!(
+        solve_with_report(&a, &structure, &rhs(5), &options),
+        Err(SolveError::DimensionMismatch { expected: 6, found: 5 })
+    ));
+    let wide = DMatrix::<f64>::zeros(2, 3);
+    assert!(matches!(
+        solve_with_report(&wide, &structure, &rhs(2), &options),
+        Err(SolveError::NotSquare { nrows: 2, ncols: 3 })
+    ));
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "numeric-diagnostics")]
+
+use nalgebra::{Complex, DMatrix};
+use nalgebra_block_triangularization::solve::SolveError;
+use nalgebra_block_triangularization::spectral::{
+    SpectralOptions, block_spectra, destabilizing_blocks,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+#[test]
+fn converged_blocks_report_their_dominant_eigenvalue() {
+    // Loop {0, 1} with eigenvalues 0.4 ± sqrt(0.03), feeding the unstable singleton 2.
+    let m: DMatrix<f64> = DMatrix::from_row_slice(3, 3, &[
+        0.5, 0.2, 0.0,
+        0.1, 0.3, 0.7,
+        0.0, 0.0, 1.5,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let spectra = block_spectra(&m, &s, &SpectralOptions::default()).unwrap();
+    assert_eq!(spectra.len(), 2);
+
+    let loop_block = spectra.iter().find(|b| b.rows.len() == 2).unwrap();
+    let expected = 0.4 + 0.03f64.sqrt();
+    assert!((loop_block.spectral_radius - expected).abs() < 1e-8);
+    assert!((loop_block.dominant_eigenvalue.unwrap() - expected).abs() < 1e-8);
+    assert!(loop_block.is_contractive());
+
+    let single = spectra.iter().position(|b| b.rows == vec![2]).unwrap();
+    assert_eq!(spectra[single].dominant_eigenvalue, Some(1.5));
+    assert_eq!(spectra[single].cols, vec![2]);
+    assert_eq!(destabilizing_blocks(&spectra), vec![single]);
+}
+
+#[test]
+fn eigenvalues_of_equal_modulus_still_give_the_radius() {
+    // Real block with eigenvalues 0.5 ± 0.9i: no convergence, but |Bx| / |x| is constant.
+    let rotation: DMatrix<f64> = DMatrix::from_row_slice(2, 2, &[
+        0.5, -0.9,
+        0.9, 0.5,
+    ]);
+    // Symmetric block with eigenvalues ±sqrt(2).
+    let reflection = DMatrix::from_row_slice(2, 2, &[
+        1.0, 1.0,
+        1.0, -1.0,
+    ]);
+    for (m, radius) in [(rotation, 1.06f64.sqrt()), (reflection, 2f64.sqrt())] {
+        let s = upper_block_triangular_structure(&m);
+        let options = SpectralOptions { max_iterations: 50, ..Default::default() };
+        let spectra = block_spectra(&m, &s, &options).unwrap();
+        assert_eq!(spectra.len(), 1);
+        assert_eq!(spectra[0].dominant_eigenvalue, None);
+        assert_eq!(spectra[0].iterations, 50);
+        assert!((spectra[0].spectral_radius - radius).abs() < 1e-12);
+        assert_eq!(destabilizing_blocks(&spectra), vec![0]);
+    }
+}
+
+#[test]
+fn complex_blocks() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        Complex::new(0.0, 0.5), Complex::new(1.0, 0.0),
+        Complex::new(0.0, 0.0), Complex::new(0.0, -0.25),
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let spectra = block_spectra(&m, &s, &SpectralOptions::default()).unwrap();
+    let eigenvalues: Vec<Complex<f64>> =
+        spectra.iter().map(|b| b.dominant_eigenvalue.unwrap()).collect();
+    assert!(eigenvalues.contains(&Complex::new(0.0, 0.5)));
+    assert!(eigenvalues.contains(&Complex::new(0.0, -0.25)));
+    assert!(destabilizing_blocks(&spectra).is_empty());
+}
+
+#[test]
+fn nilpotent_block_has_zero_radius() {
+    // Pattern with a 2-cycle whose values make the block nilpotent after one step.
+    let pattern = DMatrix::from_row_slice(2, 2, &[
+        1.0, 1.0,
+        1.0, 1.0,
+    ]);
+    let s = upper_block_triangular_structure(&pattern);
+    let values = DMatrix::from_row_slice(2, 2, &[
+        1.0, 1.0,
+        -1.0, -1.0,
+    ]);
+    let spectra = block_spectra(&values, &s, &SpectralOptions::default()).unwrap();
+    assert_eq!(spectra[0].spectral_radius, 0.0);
+    assert_eq!(spectra[0].dominant_eigenvalue, Some(0.0));
+}
+
+#[test]
+fn structurally_singular_structure_is_rejected() {
+    let m = DMatrix::from_row_slice(2, 2, &[
+        1.0, 0.0,
+        1.0, 0.0,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(
+        block_spectra(&m, &s, &SpectralOptions::default()).unwrap_err(),
+        SolveError::StructurallySingular { matching_size: 1, n: 2 }
+    );
+}
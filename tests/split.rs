@@ -0,0 +1,120 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::{
+    BtfOptions, UnmatchedPlacement, UpperBtfStructure, upper_block_triangular_structure,
+    upper_block_triangular_structure_with_options,
+};
+use nalgebra_block_triangularization::components::analyze_by_components;
+
+/// Two 3-cycles joined into one irreducible 6x6 block, plus a trailing singleton.
+fn linked_cycles() -> DMatrix<u8> {
+    DMatrix::from_row_slice(7, 7, &[
+        1, 1, 0, 0, 0, 0, 0,
+        0, 1, 1, 0, 0, 0, 0,
+        1, 0, 1, 1, 0, 0, 1,
+        0, 0, 0, 1, 1, 0, 0,
+        0, 0, 0, 0, 1, 1, 0,
+        1, 0, 0, 1, 0, 1, 0,
+        0, 0, 0, 0, 0, 0, 1,
+    ])
+}
+
+fn capped(max_block_size: usize) -> BtfOptions {
+    BtfOptions { max_block_size, ..Default::default() }
+}
+
+/// Checks that every split block is a bordered block triangular form with small core blocks.
+fn assert_bordered(m: &DMatrix<u8>, s: &UpperBtfStructure, max: usize) {
+    let starts: Vec<usize> = s.block_sizes.iter().scan(0, |acc, &n| { *acc += n; Some(*acc - n) }).collect();
+    for split in &s.block_splits {
+        let (start, n) = (starts[split.block], s.block_sizes[split.block]);
+        assert!(n > max);
+        assert_eq!(split.core_size() + split.border_size, n);
+        assert!(split.core_block_sizes.iter().all(|&b| b <= max));
+        // Positions stay aligned with the matching.
+        for p in start..start + n {
+            assert_eq!(s.row_to_col[s.row_order[p]], Some(s.col_order[p]));
+        }
+        // Core rows never reach back into an earlier core block's columns.
+        let mut core_block = Vec::new();
+        for (k, &size) in split.core_block_sizes.iter().enumerate() {
+            core_block.extend(std::iter::repeat_n(k, size));
+        }
+        for p in 0..split.core_size() {
+            for q in 0..split.core_size() {
+                if m[(s.row_order[start + p], s.col_order[start + q])] != 0 {
+                    assert!(core_block[p] <= core_block[q], "core entry ({p}, {q}) below the diagonal");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn no_cap_leaves_no_splits() {
+    let m = linked_cycles();
+    let plain = upper_block_triangular_structure(&m);
+    assert!(plain.block_splits.is_empty());
+    let s = upper_block_triangular_structure_with_options(&m, &capped(6));
+    assert!(s.block_splits.is_empty());
+    assert_eq!(s.row_order, plain.row_order);
+}
+
+#[test]
+fn oversized_block_is_bordered() {
+    let m = linked_cycles();
+    let plain = upper_block_triangular_structure(&m);
+    for max in 1..6 {
+        let s = upper_block_triangular_structure_with_options(&m, &capped(max));
+        assert_eq!(s.block_sizes, plain.block_sizes, "the block partition is unchanged");
+        assert_eq!(s.block_dag, plain.block_dag);
+        assert_eq!(s.block_splits.len(), 1, "max {max}");
+        assert_bordered(&m, &s, max);
+    }
+    // Tearing one link leaves the two 3-cycles.
+    let s = upper_block_triangular_structure_with_options(&m, &capped(3));
+    assert_eq!(s.block_splits[0].border_size, 1);
+    let mut sizes = s.block_splits[0].core_block_sizes.clone();
+    sizes.sort_unstable();
+    assert!(sizes.iter().all(|&b| b <= 3));
+}
+
+#[test]
+fn dense_block_keeps_cap() {
+    let m = DMatrix::from_element(6, 6, 1u8);
+    let s = upper_block_triangular_structure_with_options(&m, &capped(2));
+    assert_eq!(s.block_splits.len(), 1);
+    assert_eq!(s.block_splits[0].core_size(), 2);
+    assert_eq!(s.block_splits[0].border_size, 4);
+    assert_bordered(&m, &s, 2);
+}
+
+#[test]
+fn components_split_after_stitching() {
+    let m = linked_cycles();
+    let s = analyze_by_components(&m, &capped(2)).unwrap();
+    assert_eq!(s.block_splits.len(), 1);
+    assert_bordered(&m, &s, 2);
+}
+
+#[test]
+fn merged_groups_with_adjacent_unmatched_columns_stay_permutations() {
+    // Column 4 is unmatched; `Adjacent` puts it right after block {0}, inside the merged
+    // group of all four blocks.
+    let mut m = DMatrix::<u8>::zeros(4, 5);
+    m.fill_diagonal(1);
+    m[(0, 4)] = 1;
+    let options = BtfOptions {
+        unmatched: UnmatchedPlacement::Adjacent,
+        min_block_size: 4,
+        max_block_size: 1,
+        ..Default::default()
+    };
+    let s = upper_block_triangular_structure_with_options(&m, &options);
+    let mut cols = s.col_order.clone();
+    cols.sort_unstable();
+    assert_eq!(cols, vec![0, 1, 2, 3, 4]);
+    assert_eq!(s.block_sizes, vec![4]);
+    let matched: Vec<usize> = s.col_order.iter().copied().filter(|&c| c != 4).collect();
+    let rows: Vec<Option<usize>> = s.row_order.iter().map(|&r| s.row_to_col[r]).collect();
+    assert_eq!(rows, matched.into_iter().map(Some).collect::<Vec<_>>());
+}
@@ -0,0 +1,88 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::spy::{
+    block_color, entry_migration, spy_diff_svg, spy_diff_text,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+fn sample() -> DMatrix<u8> {
+    DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 1, 1,
+        0, 1, 1,
+    ])
+}
+
+#[test]
+fn text_view_shows_both_patterns_by_block() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    assert_eq!(s.block_sizes, vec![2, 1]);
+    assert_eq!(
+        spy_diff_text(&m, &s),
+        "1 . .   |   0 0 0\n\
+         0 0 0   |   0 0 .\n\
+         . 0 0   |   . . 1\n"
+    );
+}
+
+#[test]
+fn entries_move_with_their_rows() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    let moves = entry_migration(&m, &s);
+    assert_eq!(moves.len(), 6);
+    for mv in &moves {
+        assert_eq!(s.row_order[mv.to.0], mv.from.0);
+        assert_eq!(s.col_order[mv.to.1], mv.from.1);
+        assert_eq!(mv.block, s.row_blocks()[mv.from.0]);
+    }
+}
+
+#[test]
+fn svg_view_draws_each_entry_twice() {
+    let m = sample();
+    let s = upper_block_triangular_structure(&m);
+    let svg = spy_diff_svg(&m, &s);
+    // Two panel backgrounds, six entries per panel, two block outlines.
+    assert_eq!(svg.matches("<rect").count(), 2 + 12 + 2);
+    let (r, g, b) = block_color(1);
+    assert_eq!(svg.matches(&format!("#{r:02x}{g:02x}{b:02x}")).count(), 2);
+    assert!(svg.contains("<title>(0, 0) block 1</title>"));
+}
+
+#[test]
+fn svg_outlines_use_block_column_positions() {
+    // Tall: the surplus row 2 forms a block without columns, so the matched blocks sit one
+    // row below their column position.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 0,
+        0, 1,
+        1, 1,
+    ]);
+    let s = upper_block_triangular_structure(&m);
+    let svg = spy_diff_svg(&m, &s);
+    let attr = |line: &str, name: &str| -> usize {
+        let start = line.find(&format!(" {name}=\"")).unwrap() + name.len() + 3;
+        line[start..].split('"').next().unwrap().parse().unwrap()
+    };
+    let height = attr(svg.lines().next().unwrap(), "height");
+    let cell = height / 3;
+    let offset = 2 * cell + 2 * cell.max(10);
+    let outlines: Vec<(usize, usize, usize, usize)> = svg
+        .lines()
+        .filter(|line| line.contains("stroke=\"#000\""))
+        .map(|line| {
+            let x = attr(line, "x") - offset;
+            (x / cell, attr(line, "y") / cell, attr(line, "width") / cell, attr(line, "height") / cell)
+        })
+        .collect();
+    // (col, row, ncols, nrows) of each block in solve order.
+    assert_eq!(outlines, vec![(0, 0, 0, 1), (0, 1, 1, 1), (1, 2, 1, 1)]);
+}
+
+#[test]
+#[should_panic(expected = "pattern shape does not match")]
+fn mismatched_shape_panics() {
+    let s = upper_block_triangular_structure(&sample());
+    spy_diff_text(&DMatrix::<u8>::zeros(2, 2), &s);
+}
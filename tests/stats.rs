@@ -0,0 +1,97 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::stats::{reducibility_metrics, structural_symmetry};
+
+#[test]
+fn metrics_mixed_blocks() {
+    // Block {0,1} (2x2, 4 nonzeros), singletons {2}, {3}; coupling (1,3), (2,3).
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 1,
+        0, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let r = reducibility_metrics(&m);
+    assert_eq!(r.n, 4);
+    assert_eq!(r.nnz, 8);
+    assert_eq!(r.nonsingleton_row_fraction, 0.5);
+    assert_eq!(r.diagonal_nnz_fraction, 6.0 / 8.0);
+    assert_eq!(r.above_diagonal_nnz_fraction, 2.0 / 8.0);
+    assert_eq!(r.max_block_fraction, 0.5);
+}
+
+#[test]
+fn metrics_triangular_and_irreducible() {
+    let tri = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    let r = reducibility_metrics(&tri);
+    assert_eq!(r.nonsingleton_row_fraction, 0.0);
+    assert_eq!(r.diagonal_nnz_fraction, 0.5);
+    assert_eq!(r.max_block_fraction, 1.0 / 3.0);
+
+    let cyc = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        1, 0, 1,
+    ]);
+    let r = reducibility_metrics(&cyc);
+    assert_eq!(r.nonsingleton_row_fraction, 1.0);
+    assert_eq!(r.diagonal_nnz_fraction, 1.0);
+    assert_eq!(r.above_diagonal_nnz_fraction, 0.0);
+    assert_eq!(r.max_block_fraction, 1.0);
+}
+
+#[test]
+fn metrics_empty() {
+    let r = reducibility_metrics(&DMatrix::<u8>::zeros(0, 0));
+    assert_eq!(r.n, 0);
+    assert_eq!(r.nnz, 0);
+    assert_eq!(r.nonsingleton_row_fraction, 0.0);
+    assert_eq!(r.diagonal_nnz_fraction, 0.0);
+    assert_eq!(r.max_block_fraction, 0.0);
+}
+
+#[test]
+fn symmetry_of_symmetric_pattern() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 1,
+        0, 1, 1,
+    ]);
+    let r = structural_symmetry(&m);
+    assert_eq!(r.offdiag_nnz, 4);
+    assert_eq!(r.ratio, 1.0);
+    assert!(r.is_symmetric());
+}
+
+#[test]
+fn symmetry_lists_asymmetric_entries() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        1, 1, 0,
+        0, 1, 1,
+    ]);
+    let r = structural_symmetry(&m);
+    assert_eq!(r.offdiag_nnz, 4);
+    assert_eq!(r.asymmetric, vec![(0, 2), (2, 1)]);
+    assert_eq!(r.ratio, 0.5);
+    assert!(!r.is_symmetric());
+}
+
+#[test]
+fn symmetry_of_rectangular_and_diagonal_patterns() {
+    let wide = DMatrix::from_row_slice(2, 3, &[
+        1, 1, 1,
+        1, 0, 0,
+    ]);
+    let r = structural_symmetry(&wide);
+    assert_eq!(r.asymmetric, vec![(0, 2)]);
+    assert_eq!(r.ratio, 2.0 / 3.0);
+
+    let diag = DMatrix::<u8>::identity(3, 3);
+    let r = structural_symmetry(&diag);
+    assert_eq!(r.offdiag_nnz, 0);
+    assert_eq!(r.ratio, 1.0);
+}
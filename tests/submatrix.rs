@@ -0,0 +1,105 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::submatrix::{
+    upper_block_triangular_structure_of_submatrix,
+    upper_block_triangular_structure_of_submatrix_with_options,
+};
+use nalgebra_block_triangularization::{BtfError, BtfOptions, upper_block_triangular_structure};
+
+fn system() -> DMatrix<u8> {
+    DMatrix::from_row_slice(6, 6, &[
+        1, 0, 1, 0, 0, 1,
+        0, 1, 0, 0, 1, 0,
+        1, 0, 1, 0, 0, 0,
+        0, 0, 0, 1, 0, 1,
+        0, 1, 0, 1, 1, 0,
+        0, 0, 1, 0, 0, 1,
+    ])
+}
+
+/// Copy the selection out, as the in-place analysis is expected to behave.
+fn copy_selection(a: &DMatrix<u8>, rows: &[usize], cols: &[usize]) -> DMatrix<u8> {
+    DMatrix::from_fn(rows.len(), cols.len(), |i, j| a[(rows[i], cols[j])])
+}
+
+#[test]
+fn selection_matches_the_copied_submatrix() {
+    let a = system();
+    let (rows, cols) = ([5, 0, 2, 3], [5, 2, 0, 3]);
+    let sub = upper_block_triangular_structure_of_submatrix(&a, &rows, &cols).unwrap();
+    let copied = upper_block_triangular_structure(&copy_selection(&a, &rows, &cols));
+
+    assert_eq!(sub.structure.row_order, copied.row_order);
+    assert_eq!(sub.structure.col_order, copied.col_order);
+    assert_eq!(sub.block_sizes(), copied.block_sizes.as_slice());
+    let expected_rows: Vec<usize> = copied.row_order.iter().map(|&l| rows[l]).collect();
+    let expected_cols: Vec<usize> = copied.col_order.iter().map(|&l| cols[l]).collect();
+    assert_eq!(sub.row_order, expected_rows);
+    assert_eq!(sub.col_order, expected_cols);
+}
+
+#[test]
+fn orders_and_pairs_use_original_indices() {
+    let a = system();
+    let (rows, cols) = ([0, 2, 3, 5], [0, 2, 3, 5]);
+    let sub = upper_block_triangular_structure_of_submatrix(&a, &rows, &cols).unwrap();
+
+    let mut sorted_rows = sub.row_order.clone();
+    sorted_rows.sort_unstable();
+    assert_eq!(sorted_rows, rows);
+    for (r, c) in sub.matched_pairs() {
+        assert_ne!(a[(r, c)], 0, "({r}, {c}) is not an entry");
+        assert!(rows.contains(&r) && cols.contains(&c));
+    }
+    let (block_rows, block_cols) = sub.solve_order_blocks();
+    assert_eq!(block_rows.concat().len(), 4);
+    assert_eq!(block_cols.concat().len(), 4);
+    for (br, bc) in block_rows.iter().zip(&block_cols) {
+        assert_eq!(br.len(), bc.len());
+    }
+}
+
+#[test]
+fn unselected_entries_are_ignored() {
+    let a = system();
+    // Rows 1 and 4 with columns 1 and 4 form a 2x2 irreducible block; the rest is dropped.
+    let sub = upper_block_triangular_structure_of_submatrix(&a, &[1, 4], &[1, 4]).unwrap();
+    assert_eq!(sub.block_sizes(), &[2]);
+    assert_eq!(sub.structure.matching_size, 2);
+}
+
+#[test]
+fn rectangular_and_empty_selections() {
+    let a = system();
+    let sub = upper_block_triangular_structure_of_submatrix(&a, &[0, 2, 5], &[0, 2]).unwrap();
+    assert_eq!(sub.structure.matching_size, 2);
+    assert_eq!(sub.row_order.len(), 3);
+    assert_eq!(sub.col_order.len(), 2);
+
+    let empty = upper_block_triangular_structure_of_submatrix(&a, &[], &[]).unwrap();
+    assert!(empty.row_order.is_empty());
+    assert_eq!(empty.structure.num_blocks(), 0);
+}
+
+#[test]
+fn invalid_selections_are_rejected() {
+    let a = system();
+    let err = upper_block_triangular_structure_of_submatrix(&a, &[0, 6], &[0]).unwrap_err();
+    assert!(matches!(err, BtfError::IndexOutOfRange { index: 6, len: 6 }));
+    let err = upper_block_triangular_structure_of_submatrix(&a, &[0], &[1, 3, 1]).unwrap_err();
+    assert!(matches!(err, BtfError::DuplicateIndex { index: 1 }));
+}
+
+#[test]
+fn options_use_local_indices() {
+    let a = system();
+    let (rows, cols) = ([0, 2], [0, 2]);
+    // Forbid local (0, 0), i.e. original (0, 0): the matching must pair 0 with 2.
+    let options = BtfOptions {
+        forbidden: vec![(0, 0)],
+        ..Default::default()
+    };
+    let sub =
+        upper_block_triangular_structure_of_submatrix_with_options(&a, &rows, &cols, &options)
+            .unwrap();
+    assert!(sub.matched_pairs().contains(&(0, 2)));
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "download")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use nalgebra_block_triangularization::csr::analyze_csr;
+use nalgebra_block_triangularization::error::BtfError;
+use nalgebra_block_triangularization::suitesparse::{
+    MatrixMarketError, StoredZeros, fetch_suitesparse, read_matrix_market,
+    read_matrix_market_with, read_suitesparse_mirror, read_suitesparse_mirror_with,
+};
+
+const GENERAL: &str = "%%MatrixMarket matrix coordinate real general
+% column-major, as written by the collection
+3 4 5
+1 1 2.5
+3 1 -1
+2 2 0
+1 3 7
+3 4 1
+";
+
+const SYMMETRIC: &str = "%%MatrixMarket matrix coordinate pattern symmetric
+3 3 4
+1 1
+2 1
+3 2
+3 3
+";
+
+fn rows(pattern: &nalgebra_block_triangularization::csr::CsrPattern) -> Vec<Vec<u32>> {
+    (0..pattern.nrows()).map(|i| pattern.row(i).to_vec()).collect()
+}
+
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("btf-suitesparse-{}-{test}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_archive(path: &std::path::Path, matrix: &str, mtx: &str) {
+    let gz = flate2::write::GzEncoder::new(fs::File::create(path).unwrap(), Default::default());
+    let mut tar = tar::Builder::new(gz);
+    for (member, body) in [
+        (format!("{matrix}/{matrix}_b.mtx"), "%%MatrixMarket matrix array real general\n1 1\n1\n"),
+        (format!("{matrix}/{matrix}.mtx"), mtx),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, member, body.as_bytes()).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap();
+}
+
+#[test]
+fn general_file_is_read_in_row_major_order() {
+    let p = read_matrix_market(GENERAL.as_bytes()).unwrap();
+    assert_eq!((p.nrows(), p.ncols(), p.nnz()), (3, 4, 5));
+    // The explicit zero at (2, 2) is a stored entry and stays structural.
+    assert_eq!(rows(&p), vec![vec![0, 2], vec![1], vec![0, 3]]);
+}
+
+#[test]
+fn symmetric_file_is_mirrored() {
+    let p = read_matrix_market(SYMMETRIC.as_bytes()).unwrap();
+    assert_eq!(rows(&p), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+    let s = analyze_csr(&p);
+    assert_eq!(s.matching_size, 3);
+    assert_eq!(s.block_sizes, vec![3]);
+}
+
+#[test]
+fn stored_zeros_can_be_dropped_and_counted() {
+    let kept = read_matrix_market_with(GENERAL.as_bytes(), StoredZeros::Structural).unwrap();
+    assert_eq!((kept.pattern.nnz(), kept.dropped_zeros), (5, 0));
+
+    let dropped = read_matrix_market_with(GENERAL.as_bytes(), StoredZeros::Dropped).unwrap();
+    assert_eq!(dropped.dropped_zeros, 1);
+    assert_eq!(rows(&dropped.pattern), vec![vec![0, 2], vec![], vec![0, 3]]);
+    // Row 1 lost its only entry, so the structural rank drops.
+    assert_eq!(analyze_csr(&kept.pattern).matching_size, 3);
+    assert_eq!(analyze_csr(&dropped.pattern).matching_size, 2);
+}
+
+#[test]
+fn dropped_zeros_in_complex_and_symmetric_files() {
+    // A complex entry is zero only when both parts are; the zero off-diagonal entry of the
+    // symmetric file is counted once and not mirrored.
+    let complex = "%%MatrixMarket matrix coordinate complex symmetric
+2 2 3
+1 1 0 1
+2 1 0.0 -0
+2 2 1e-300 0
+";
+    let read = read_matrix_market_with(complex.as_bytes(), StoredZeros::Dropped).unwrap();
+    assert_eq!(read.dropped_zeros, 1);
+    assert_eq!(rows(&read.pattern), vec![vec![0], vec![1]]);
+    assert_eq!(read_matrix_market(complex.as_bytes()).unwrap().nnz(), 4);
+
+    // Pattern files store no values, so nothing can be dropped.
+    let read = read_matrix_market_with(SYMMETRIC.as_bytes(), StoredZeros::Dropped).unwrap();
+    assert_eq!((read.pattern.nnz(), read.dropped_zeros), (6, 0));
+}
+
+#[test]
+fn dropping_zeros_requires_values() {
+    let missing = "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n2 2\n";
+    assert_eq!(read_matrix_market(missing.as_bytes()).unwrap().nnz(), 2);
+    assert!(matches!(
+        read_matrix_market_with(missing.as_bytes(), StoredZeros::Dropped),
+        Err(BtfError::MatrixMarket(MatrixMarketError::Entry { line: 4 }))
+    ));
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let err = |text: &str| read_matrix_market(text.as_bytes()).unwrap_err();
+    assert!(matches!(
+        err("%%MatrixMarket matrix array real general\n1 1\n1\n"),
+        BtfError::MatrixMarket(MatrixMarketError::Header(_))
+    ));
+    assert!(matches!(
+        err("%%MatrixMarket matrix coordinate real general\n2 two 1\n1 1 1\n"),
+        BtfError::MatrixMarket(MatrixMarketError::Size { line: 2 })
+    ));
+    assert!(matches!(
+        err("%%MatrixMarket matrix coordinate real general\n%c\n2 2 1\n3 1 1\n"),
+        BtfError::MatrixMarket(MatrixMarketError::Entry { line: 4 })
+    ));
+    assert!(matches!(
+        err("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n"),
+        BtfError::MatrixMarket(MatrixMarketError::EntryCount { expected: 2, found: 1 })
+    ));
+    // An absurd entry count is reported, not preallocated.
+    let huge = format!(
+        "%%MatrixMarket matrix coordinate pattern symmetric\n2 2 {}\n1 1\n",
+        usize::MAX
+    );
+    assert!(matches!(
+        err(&huge),
+        BtfError::MatrixMarket(MatrixMarketError::EntryCount { expected: usize::MAX, found: 1 })
+    ));
+}
+
+#[test]
+fn mirror_prefers_extracted_file_then_archive() {
+    let mirror = scratch_dir("mirror");
+    fs::create_dir_all(mirror.join("Test")).unwrap();
+    write_archive(&mirror.join("Test/sym.tar.gz"), "sym", SYMMETRIC);
+    let from_archive = read_suitesparse_mirror(&mirror, "Test/sym").unwrap();
+    assert_eq!(rows(&from_archive), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+
+    fs::create_dir_all(mirror.join("Test/sym")).unwrap();
+    fs::write(mirror.join("Test/sym/sym.mtx"), GENERAL).unwrap();
+    let extracted = read_suitesparse_mirror(&mirror, "Test/sym").unwrap();
+    assert_eq!(extracted.nnz(), 5);
+    let without_zeros =
+        read_suitesparse_mirror_with(&mirror, "Test/sym", StoredZeros::Dropped).unwrap();
+    assert_eq!((without_zeros.pattern.nnz(), without_zeros.dropped_zeros), (4, 1));
+
+    // A mirror hit never reaches the network.
+    assert_eq!(fetch_suitesparse("Test/sym", Some(&mirror)).unwrap().nnz(), 5);
+    fs::remove_dir_all(&mirror).unwrap();
+}
+
+#[test]
+fn missing_mirror_entry_is_not_found() {
+    let mirror = scratch_dir("missing");
+    match read_suitesparse_mirror(&mirror, "Test/absent") {
+        Err(BtfError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("expected NotFound, got {other:?}"),
+    }
+    fs::remove_dir_all(&mirror).unwrap();
+}
+
+#[test]
+fn names_must_be_group_slash_name() {
+    for name in ["west0479", "HB/", "/west0479", "HB/a/b", "../west0479", "HB/.."] {
+        assert!(
+            matches!(
+                fetch_suitesparse(name, None),
+                Err(BtfError::MatrixMarket(MatrixMarketError::InvalidName(_)))
+            ),
+            "{name}"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::supervariable::{
+    SupervariableMap, supervariable_block_triangular_structure, supervariable_map,
+};
+use nalgebra_block_triangularization::{
+    BtfOptions, try_upper_block_triangular_structure_with_options,
+};
+
+fn two_unknowns_per_node() -> DMatrix<u8> {
+    // Nodes {0, 1} and {2, 3} coupled both ways, node {4, 5} only fed by the first two; every
+    // node's two equations and two unknowns share a pattern.
+    DMatrix::from_row_slice(6, 6, &[
+        1, 1, 1, 1, 0, 0,
+        1, 1, 1, 1, 0, 0,
+        1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1,
+        0, 0, 0, 0, 1, 1,
+        0, 0, 0, 0, 1, 1,
+    ])
+}
+
+#[test]
+fn identical_rows_and_columns_are_grouped() {
+    let map = supervariable_map(&two_unknowns_per_node());
+    assert_eq!(map.row_groups, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+    assert_eq!(map.col_groups, vec![vec![0, 1, 2, 3], vec![4, 5]]);
+    assert_eq!(map.row_group_of(), vec![0, 0, 1, 1, 2, 2]);
+    assert_eq!(map.col_group_of(), vec![0, 0, 0, 0, 1, 1]);
+    assert!(!map.is_trivial());
+}
+
+#[test]
+fn distinct_patterns_give_a_trivial_map() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        0, 1, 1,
+        0, 0, 1,
+    ]);
+    let map = supervariable_map(&m);
+    assert_eq!(map, SupervariableMap {
+        row_groups: vec![vec![0], vec![1], vec![2]],
+        col_groups: vec![vec![0], vec![1], vec![2]],
+    });
+    assert!(map.is_trivial());
+}
+
+#[test]
+fn grouped_analysis_matches_the_plain_one() {
+    let m = two_unknowns_per_node();
+    let options = BtfOptions::default();
+    let grouped = supervariable_block_triangular_structure(&m, &options).unwrap();
+    let plain = try_upper_block_triangular_structure_with_options(&m, &options).unwrap();
+    assert_eq!(grouped.structure.matching_size, 6);
+    assert_eq!(grouped.structure.block_sizes, plain.block_sizes);
+    assert_eq!(grouped.structure.block_sizes, vec![4, 2]);
+    assert_eq!(grouped.structure.solve_order_blocks(), plain.solve_order_blocks());
+    assert!(grouped.structure.matched_pairs().iter().all(|&(r, c)| m[(r, c)] != 0));
+}
+
+#[test]
+fn flow_is_rerouted_between_groups() {
+    // Row group {0, 1} can use either column group, row group {2} only the first; a greedy
+    // assignment of {0, 1} to columns {0, 1} must be undone for a perfect matching.
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 1,
+        1, 1, 1,
+        1, 1, 0,
+    ]);
+    let grouped = supervariable_block_triangular_structure(&m, &BtfOptions::default()).unwrap();
+    assert_eq!(grouped.map.row_groups, vec![vec![0, 1], vec![2]]);
+    assert_eq!(grouped.map.col_groups, vec![vec![0, 1], vec![2]]);
+    assert_eq!(grouped.structure.matching_size, 3);
+    assert!(grouped.structure.matched_pairs().iter().all(|&(r, c)| m[(r, c)] != 0));
+}
+
+#[test]
+fn rank_deficient_groups_leave_rows_unmatched() {
+    // Three identical rows over two columns.
+    let m = DMatrix::from_row_slice(3, 2, &[
+        1, 1,
+        1, 1,
+        1, 1,
+    ]);
+    let grouped = supervariable_block_triangular_structure(&m, &BtfOptions::default()).unwrap();
+    assert_eq!(grouped.map.row_groups, vec![vec![0, 1, 2]]);
+    assert_eq!(grouped.structure.matching_size, 2);
+    assert_eq!(grouped.structure.unmatched_rows(), vec![2]);
+}
+
+#[test]
+fn forbidden_entries_split_groups_and_stay_unmatched() {
+    let m = DMatrix::from_element(3, 3, 1u8);
+    let options = BtfOptions {
+        forbidden: vec![(0, 0), (1, 1), (2, 2)],
+        ..Default::default()
+    };
+    let grouped = supervariable_block_triangular_structure(&m, &options).unwrap();
+    assert!(grouped.map.is_trivial());
+    assert_eq!(grouped.structure.matching_size, 3);
+    assert!(grouped.structure.matched_pairs().iter().all(|&(r, c)| r != c));
+}
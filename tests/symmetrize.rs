@@ -0,0 +1,50 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::symmetrize::symmetrizing_permutation;
+
+fn permuted(m: &DMatrix<u8>, col_order: &[usize]) -> DMatrix<u8> {
+    m.select_columns(col_order)
+}
+
+#[test]
+fn recovers_shuffled_symmetric_pattern() {
+    // Tridiagonal pattern with its columns reversed.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        0, 0, 1, 1,
+        0, 1, 1, 1,
+        1, 1, 1, 0,
+        1, 1, 0, 0,
+    ]);
+    let s = symmetrizing_permutation(&m);
+    assert_eq!(s.matching_size, 4);
+    assert_eq!(s.symmetry.ratio, 1.0);
+    let b = permuted(&m, &s.col_order);
+    assert!((0..4).all(|i| b[(i, i)] != 0));
+}
+
+#[test]
+fn diagonal_stays_zero_free() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 0, 1,
+        0, 1, 1,
+    ]);
+    let s = symmetrizing_permutation(&m);
+    let b = permuted(&m, &s.col_order);
+    assert!((0..3).all(|i| b[(i, i)] != 0));
+    let mut cols = s.col_order.clone();
+    cols.sort_unstable();
+    assert_eq!(cols, vec![0, 1, 2]);
+}
+
+#[test]
+fn singular_pattern_fills_free_columns() {
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 0, 0,
+        1, 0, 0,
+        0, 0, 1,
+    ]);
+    let s = symmetrizing_permutation(&m);
+    assert_eq!(s.matching_size, 2);
+    assert_eq!(s.col_order, vec![0, 1, 2]);
+    assert_eq!(s.symmetry.asymmetric, vec![(1, 0)]);
+}
@@ -0,0 +1,171 @@
+use nalgebra::DMatrix;
+use nalgebra_block_triangularization::tearing::{
+    TearingHeuristic, TearingOptions, feedback_arc_set, feedback_vertex_set, tear,
+    torn_block_triangular_structure,
+};
+use nalgebra_block_triangularization::upper_block_triangular_structure;
+
+/// Whether removing `fvs` leaves `graph` acyclic (DFS colouring).
+fn acyclic_without(graph: &[Vec<usize>], fvs: &[usize]) -> bool {
+    fn visit(u: usize, g: &[Vec<usize>], removed: &[bool], state: &mut [u8]) -> bool {
+        state[u] = 1;
+        for &v in &g[u] {
+            if removed[v] || state[v] == 2 {
+                continue;
+            }
+            if state[v] == 1 || !visit(v, g, removed, state) {
+                return false;
+            }
+        }
+        state[u] = 2;
+        true
+    }
+    let mut removed = vec![false; graph.len()];
+    for &u in fvs {
+        removed[u] = true;
+    }
+    let mut state = vec![0u8; graph.len()];
+    (0..graph.len()).all(|u| removed[u] || state[u] != 0 || visit(u, graph, &removed, &mut state))
+}
+
+fn without_arcs(graph: &[Vec<usize>], arcs: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    (0..graph.len())
+        .map(|u| graph[u].iter().copied().filter(|&v| !arcs.contains(&(u, v))).collect())
+        .collect()
+}
+
+fn heuristics() -> Vec<TearingHeuristic> {
+    vec![
+        TearingHeuristic::Cellier,
+        TearingHeuristic::GreedyMfvs,
+        #[cfg(feature = "exact-tearing")]
+        TearingHeuristic::ExhaustiveSearch,
+    ]
+}
+
+#[test]
+fn fvs_of_simple_cycle_is_one_node() {
+    let g = vec![vec![1], vec![2], vec![0]];
+    for h in heuristics() {
+        let fvs = feedback_vertex_set(&g, h);
+        assert_eq!(fvs.len(), 1, "{h:?}");
+        assert!(acyclic_without(&g, &fvs));
+    }
+}
+
+#[test]
+fn fvs_of_dag_is_empty() {
+    let g = vec![vec![1, 2], vec![2], vec![]];
+    for h in heuristics() {
+        assert!(feedback_vertex_set(&g, h).is_empty());
+    }
+}
+
+#[test]
+fn fvs_hub_node() {
+    // Three 2-cycles through hub 0: tearing the hub alone breaks all of them.
+    let g = vec![vec![1, 2, 3], vec![0], vec![0], vec![0]];
+    for h in heuristics() {
+        assert_eq!(feedback_vertex_set(&g, h), vec![0], "{h:?}");
+    }
+}
+
+#[test]
+fn fvs_always_breaks_all_cycles() {
+    // Dense-ish graph: every i -> (i+1), (i+3) mod 7.
+    let g: Vec<Vec<usize>> = (0..7).map(|i| vec![(i + 1) % 7, (i + 3) % 7]).collect();
+    for h in heuristics() {
+        let fvs = feedback_vertex_set(&g, h);
+        assert!(acyclic_without(&g, &fvs), "{h:?}");
+    }
+}
+
+#[cfg(feature = "exact-tearing")]
+#[test]
+fn exhaustive_search_is_no_larger_than_heuristics() {
+    let g: Vec<Vec<usize>> = (0..8).map(|i| vec![(i + 1) % 8, (i + 5) % 8]).collect();
+    let exact = feedback_vertex_set(&g, TearingHeuristic::ExhaustiveSearch);
+    assert!(acyclic_without(&g, &exact));
+    for h in [TearingHeuristic::Cellier, TearingHeuristic::GreedyMfvs] {
+        assert!(exact.len() <= feedback_vertex_set(&g, h).len());
+    }
+}
+
+#[test]
+fn options_select_by_block_size() {
+    let options = TearingOptions {
+        by_size: vec![(3, TearingHeuristic::GreedyMfvs)],
+        default: TearingHeuristic::Cellier,
+    };
+    assert_eq!(options.heuristic_for(2), TearingHeuristic::GreedyMfvs);
+    assert_eq!(options.heuristic_for(3), TearingHeuristic::GreedyMfvs);
+    assert_eq!(options.heuristic_for(4), TearingHeuristic::Cellier);
+}
+
+#[test]
+fn tear_reports_residuals_and_matched_tear_vars() {
+    // Block {0,1,2} (3-cycle) followed by singleton {3}.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        0, 1, 1, 0,
+        1, 0, 1, 1,
+        0, 0, 0, 1,
+    ]);
+    let structure = upper_block_triangular_structure(&m);
+    let tearing = tear(&m, &TearingOptions::default());
+    assert_eq!(tearing.len(), 1);
+    let t = &tearing[0];
+    assert_eq!(structure.block_sizes[t.block], 3);
+    assert_eq!(t.heuristic, TearingHeuristic::Cellier);
+    assert_eq!(t.residuals.len(), 1);
+    assert_eq!(t.tear_vars.len(), 1);
+    assert!(t.residuals[0] < 3);
+    assert!(t.tear_vars[0] < 3);
+    assert_ne!(m[(t.residuals[0], t.tear_vars[0])], 0);
+}
+
+#[test]
+fn tear_triangular_matrix_is_empty() {
+    let m: DMatrix<u8> = DMatrix::identity(4, 4);
+    assert!(tear(&m, &TearingOptions::default()).is_empty());
+}
+
+#[test]
+fn fas_breaks_all_cycles() {
+    assert_eq!(feedback_arc_set(&[vec![1], vec![2], vec![0]]).len(), 1);
+    assert!(feedback_arc_set(&[vec![1, 2], vec![2], vec![]]).is_empty());
+    for (n, a, b) in [(7, 1, 3), (8, 1, 5), (9, 2, 4)] {
+        let g: Vec<Vec<usize>> = (0..n).map(|i| vec![(i + a) % n, (i + b) % n]).collect();
+        let fas = feedback_arc_set(&g);
+        assert!(fas.windows(2).all(|w| w[0] < w[1]));
+        assert!(fas.iter().all(|&(u, v)| g[u].contains(&v)));
+        assert!(acyclic_without(&without_arcs(&g, &fas), &[]), "n = {n}");
+    }
+}
+
+#[test]
+fn cut_entries_make_every_block_triangular() {
+    // 3-cycle {0,1,2} coupled to 2-cycle {3,4}.
+    let m = DMatrix::from_row_slice(5, 5, &[
+        1, 1, 0, 0, 0,
+        0, 1, 1, 0, 1,
+        1, 0, 1, 0, 0,
+        0, 0, 0, 1, 1,
+        0, 0, 0, 1, 1,
+    ]);
+    let torn = torn_block_triangular_structure(&m, &TearingOptions::default());
+    assert_eq!(torn.structure.row_order, upper_block_triangular_structure(&m).row_order);
+    assert_eq!(torn.blocks, tear(&m, &TearingOptions::default()));
+    assert_eq!(torn.blocks.len(), 2);
+
+    let mut cut = m.clone();
+    for t in &torn.blocks {
+        assert!(!t.cut_entries.is_empty());
+        for &(i, j) in &t.cut_entries {
+            assert_ne!(cut[(i, j)], 0);
+            assert!(!torn.structure.matched_pairs().contains(&(i, j)));
+            cut[(i, j)] = 0;
+        }
+    }
+    assert!(upper_block_triangular_structure(&cut).is_fully_triangular());
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "metrics")]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use nalgebra::DMatrix;
+use std::sync::atomic::AtomicBool;
+
+use nalgebra_block_triangularization::analysis::BtfAnalysis;
+use nalgebra_block_triangularization::approximate::{
+    approximate_block_triangular_structure, cancellable_block_triangular_structure,
+};
+use nalgebra_block_triangularization::components::analyze_by_components;
+use nalgebra_block_triangularization::csr::{CsrPattern, analyze_csr};
+use nalgebra_block_triangularization::matching::MatchingBudget;
+use nalgebra_block_triangularization::nested::nested_block_triangular_structure;
+use nalgebra_block_triangularization::session::BtfSession;
+use nalgebra_block_triangularization::supervariable::supervariable_block_triangular_structure;
+use nalgebra_block_triangularization::telemetry::{
+    ANALYSES, BLOCKS, LARGEST_BLOCK, MATCHING_PHASES, MATCHING_SECONDS, STRUCTURAL_DEFICIENCY,
+};
+use nalgebra_block_triangularization::tearing::TearingOptions;
+use nalgebra_block_triangularization::{
+    BtfOptions, MatchingAlgorithm, upper_block_triangular_structure,
+    upper_block_triangular_structure_with_options,
+};
+
+#[test]
+fn analysis_emits_metrics() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 0,
+        0, 0, 0,
+    ]);
+    metrics::with_local_recorder(&recorder, || {
+        upper_block_triangular_structure(&m);
+        let options = BtfOptions {
+            matching: MatchingAlgorithm::Mc21,
+            ..Default::default()
+        };
+        upper_block_triangular_structure_with_options(&m, &options);
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let find = |name: &str| {
+        snapshot
+            .iter()
+            .filter(|(key, ..)| key.key().name() == name)
+            .map(|(key, _, _, value)| {
+                let labels: Vec<String> = key.key().labels().map(|l| l.value().to_string()).collect();
+                (labels, value)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let analyses = find(ANALYSES);
+    assert_eq!(analyses.len(), 1);
+    assert!(matches!(analyses[0].1, DebugValue::Counter(2)));
+    let matching = find(MATCHING_SECONDS);
+    let mut algorithms: Vec<_> = matching.iter().map(|(labels, _)| labels[0].clone()).collect();
+    algorithms.sort();
+    assert_eq!(algorithms, vec!["hopcroft_karp", "mc21"]);
+
+    let values = |name: &str| match find(name)[0].1 {
+        DebugValue::Histogram(v) => v.iter().map(|x| x.into_inner()).collect::<Vec<f64>>(),
+        other => panic!("{name} is not a histogram: {other:?}"),
+    };
+    assert_eq!(values(STRUCTURAL_DEFICIENCY), vec![1.0, 1.0]);
+    assert_eq!(values(BLOCKS), vec![2.0, 2.0]);
+    assert_eq!(values(LARGEST_BLOCK), vec![2.0, 2.0]);
+}
+
+#[test]
+fn every_analysis_entry_point_emits_metrics() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 0,
+        0, 0, 1,
+    ]);
+    let options = BtfOptions::default();
+    metrics::with_local_recorder(&recorder, || {
+        let mut session = BtfSession::new(&m, options.clone()).unwrap();
+        let mut next = m.clone();
+        next[(2, 0)] = 1;
+        session.update(&next).unwrap();
+        BtfAnalysis::new(&m, &options).structure(&options).unwrap();
+        analyze_csr(&CsrPattern::from_matrix(&m).unwrap());
+        supervariable_block_triangular_structure(&m, &options).unwrap();
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let analyses: Vec<_> = snapshot
+        .iter()
+        .filter(|(key, ..)| key.key().name() == ANALYSES)
+        .collect();
+    assert!(matches!(analyses[0].3, DebugValue::Counter(5)));
+
+    let mut algorithms: Vec<String> = snapshot
+        .iter()
+        .filter(|(key, ..)| key.key().name() == MATCHING_SECONDS)
+        .map(|(key, ..)| key.key().labels().next().unwrap().value().to_string())
+        .collect();
+    algorithms.sort();
+    assert_eq!(algorithms, vec!["hopcroft_karp", "mc21", "repair", "supervariable"]);
+}
+
+fn analyses_counted(run: impl FnOnce()) -> u64 {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, run);
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .find_map(|(key, _, _, value)| match value {
+            DebugValue::Counter(n) if key.key().name() == ANALYSES => Some(n),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+#[test]
+fn internal_sub_analyses_are_not_counted() {
+    // Two components, each analyzed on its own internally.
+    let m = DMatrix::from_row_slice(4, 4, &[
+        1, 1, 0, 0,
+        1, 1, 0, 0,
+        0, 0, 1, 1,
+        0, 0, 1, 1,
+    ]);
+    let options = BtfOptions::default();
+    assert_eq!(analyses_counted(|| drop(analyze_by_components(&m, &options).unwrap())), 1);
+    // Not an instrumented entry point; the analyses it runs internally stay uncounted.
+    assert_eq!(
+        analyses_counted(|| drop(nested_block_triangular_structure(&m, 1, &TearingOptions::default()))),
+        0
+    );
+}
+
+#[test]
+fn budgeted_analyses_emit_metrics_and_phases() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let m = DMatrix::from_row_slice(3, 3, &[
+        1, 1, 0,
+        1, 1, 0,
+        0, 0, 1,
+    ]);
+    metrics::with_local_recorder(&recorder, || {
+        approximate_block_triangular_structure(&m, &MatchingBudget::default());
+        cancellable_block_triangular_structure(&m, &BtfOptions::default(), &AtomicBool::new(false))
+            .unwrap();
+        let cancelled = AtomicBool::new(true);
+        assert!(cancellable_block_triangular_structure(&m, &BtfOptions::default(), &cancelled).is_err());
+    });
+
+    let snapshot = snapshotter.snapshot().into_vec();
+    let value = |name: &str| {
+        snapshot
+            .iter()
+            .find(|(key, ..)| key.key().name() == name)
+            .map(|(.., value)| value)
+            .unwrap_or_else(|| panic!("{name} not recorded"))
+    };
+    assert!(matches!(value(ANALYSES), DebugValue::Counter(2)));
+    match value(MATCHING_PHASES) {
+        DebugValue::Histogram(phases) => assert_eq!(phases.len(), 3),
+        other => panic!("{MATCHING_PHASES} is not a histogram: {other:?}"),
+    }
+}
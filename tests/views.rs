@@ -0,0 +1,114 @@
+use nalgebra::{DMatrix, DMatrixView, DVector};
+use nalgebra_block_triangularization::components::analyze_by_components;
+use nalgebra_block_triangularization::csr::{CsrPattern, analyze_csr};
+use nalgebra_block_triangularization::dm::dulmage_mendelsohn;
+use nalgebra_block_triangularization::solve::BlockLu;
+use nalgebra_block_triangularization::stats::reducibility_metrics;
+use nalgebra_block_triangularization::{
+    BtfOptions, condensation, row_dependency_graph, submatrix_structure,
+    submatrix_structure_with_options, upper_block_triangular_structure,
+    upper_triangular_permutations,
+};
+
+/// 8x8 system whose rows and columns 2..6 hold the 4x4 block {2, 3} -> {4, 5} coupling.
+fn system() -> DMatrix<f64> {
+    DMatrix::from_row_slice(8, 8, &[
+        5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+        1.0, 5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 4.0, 1.0, 2.0, 0.0, 0.0, 1.0,
+        0.0, 0.0, 1.0, 4.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 0.0, 3.0, 1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 1.0, 3.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0,
+        1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0,
+    ])
+}
+
+#[test]
+fn submatrix_is_analyzed_in_place() {
+    let a = system();
+    let copied = a.view((2, 2), (4, 4)).clone_owned();
+    let in_place = submatrix_structure(&a, (2, 2), (4, 4));
+    let expected = upper_block_triangular_structure(&copied);
+    assert_eq!(in_place.row_order, expected.row_order);
+    assert_eq!(in_place.col_order, expected.col_order);
+    assert_eq!(in_place.block_sizes, vec![2, 2]);
+    assert_eq!(in_place.block_dag, vec![vec![1], vec![]]);
+
+    let with_options = submatrix_structure_with_options(
+        &a,
+        (2, 2),
+        (4, 4),
+        &BtfOptions { min_block_size: 4, ..Default::default() },
+    )
+    .unwrap();
+    assert_eq!(with_options.block_sizes, vec![4]);
+}
+
+#[test]
+#[should_panic]
+fn submatrix_out_of_range_panics() {
+    submatrix_structure(&system(), (6, 6), (4, 4));
+}
+
+#[test]
+fn strided_views_match_copies() {
+    let a = system();
+    // Every other row and column: a view with steps, so neither stride is contiguous.
+    let view = a.view_with_steps((0, 0), (4, 4), (1, 1));
+    let copy = view.clone_owned();
+    assert_eq!(view.strides(), (2, 16));
+
+    let (s_view, s_copy) = (upper_block_triangular_structure(&view), upper_block_triangular_structure(&copy));
+    assert_eq!(s_view.row_order, s_copy.row_order);
+    assert_eq!(s_view.col_order, s_copy.col_order);
+    assert_eq!(s_view.block_sizes, s_copy.block_sizes);
+    assert_eq!(row_dependency_graph(&view), row_dependency_graph(&copy));
+    assert_eq!(condensation(&view).sccs, condensation(&copy).sccs);
+    let (pv, pc) = (upper_triangular_permutations(&view), upper_triangular_permutations(&copy));
+    let (mut uv, mut uc) = (copy.clone(), copy.clone());
+    pv.0.permute_rows(&mut uv);
+    pv.1.permute_columns(&mut uv);
+    pc.0.permute_rows(&mut uc);
+    pc.1.permute_columns(&mut uc);
+    assert_eq!(uv, uc);
+    assert_eq!(reducibility_metrics(&view), reducibility_metrics(&copy));
+    assert_eq!(dulmage_mendelsohn(&view), dulmage_mendelsohn(&copy));
+    assert_eq!(
+        analyze_by_components(&view, &BtfOptions::default()).unwrap().row_order,
+        analyze_by_components(&copy, &BtfOptions::default()).unwrap().row_order
+    );
+    assert_eq!(CsrPattern::from_matrix(&view).unwrap(), CsrPattern::from_matrix(&copy).unwrap());
+}
+
+#[test]
+fn views_into_foreign_row_major_memory() {
+    // Row-major storage owned by someone else, read through a column-major view with swapped
+    // strides: element (i, j) sits at `i * 3 + j`.
+    let data: Vec<f64> = vec![
+        2.0, 0.0, 1.0,
+        1.0, 3.0, 0.0,
+        0.0, 0.0, 4.0,
+    ];
+    let view = DMatrixView::from_slice_with_strides(&data, 3, 3, 3, 1);
+    assert_eq!(view[(1, 0)], 1.0);
+    let copy = view.clone_owned();
+
+    let structure = upper_block_triangular_structure(&view);
+    assert_eq!(structure.row_order, upper_block_triangular_structure(&copy).row_order);
+    assert_eq!(analyze_csr(&CsrPattern::from_matrix(&view).unwrap()).row_order, structure.row_order);
+
+    let b = DVector::from_vec(vec![3.0, 4.0, 4.0]);
+    let x = BlockLu::factor(&view, &structure).unwrap().solve(&b).unwrap();
+    assert!((&copy * &x - &b).norm() < 1e-12);
+}
+
+#[test]
+fn block_solve_on_a_diagonal_sub_block() {
+    let a = system();
+    let block = a.view((2, 2), (4, 4));
+    let structure = submatrix_structure(&a, (2, 2), (4, 4));
+    let b = DVector::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+    let x = BlockLu::factor(&block, &structure).unwrap().solve(&b).unwrap();
+    assert!((block * &x - &b).norm() < 1e-12);
+}